@@ -1,15 +1,81 @@
 import test from 'ava'
-import { readFileSync, readdirSync } from 'node:fs'
+import { copyFileSync, mkdtempSync, readFileSync, readdirSync, rmSync, writeFileSync } from 'node:fs'
+import { tmpdir } from 'node:os'
 import { join, dirname } from 'node:path'
 import { fileURLToPath } from 'node:url'
 import {
+  BackgroundCoordinateSpace,
+  bboxFromCenter,
+  bboxIntersection,
+  bboxUnion,
+  benchmark,
+  cannyEdges,
+  classifyTargetShape,
+  clearBackgroundCache,
+  ColorMode,
+  computeDifferenceMap,
+  computeImageStats,
+  CoordinateMode,
+  CoordinateSpace,
+  CoordinateUnit,
+  cropTransparent,
+  DatasetAlgorithm,
+  doubleSlideMatch,
+  EdgeMode,
+  ErrorCode,
+  evaluateDataset,
+  findAllMatches,
+  generateBezierDragPath,
+  generateDragPath,
+  improvedSimpleSlideMatch,
+  improvedSimpleSlideMatchWithPath,
+  improvedSimpleSlideMatchWithPathAsync,
   improvedSlideMatch,
+  improvedSlideMatchAsync,
+  improvedSlideMatchVerbose,
+  improvedSlideMatchWithPath,
+  improvedSlideMatchWithPathAsync,
+  improvedSlideXOffset,
+  InvertMode,
+  LowConfidenceAction,
+  matchAtPosition,
+  MatchBackend,
+  MatchStrategy,
+  matchTemplateRaw,
+  OversizePolicy,
+  PieceRegionPreset,
+  previewPlacement,
+  renderMatchOverlay,
+  restoreBackground,
+  rotationMatch,
+  setBackend,
+  simpleSlideMatch,
+  SlideAxis,
+  slideComparison,
   slideMatch,
+  type SlideMatchConfig,
+  slideMatchCrossCheck,
+  slideMatchEmbedded,
+  slideMatchMultiTarget,
+  slideMatchNormalized,
+  slideMatchScoreMatrix,
+  slideMatchShuffled,
+  slideMatchSigned,
+  slideMatchStreamResults,
+  slideMatchWithMask,
+  SliderShape,
+  slideXOffset,
+  targetQualityScore,
+  TemplateMatchMethod,
+  ThresholdStrategy,
+  tryImprovedSlideMatch,
+  trySlideMatch,
 } from '../index'
 
 const __filename = fileURLToPath(import.meta.url)
 const TEST_DIR = dirname(__filename)
 const IMAGES_DIR = join(TEST_DIR, 'images')
+const SYNTHETIC_DIR = join(IMAGES_DIR, 'synthetic')
 
 // 解析pos.txt文件
 function parsePosFile(): Map<number, any> {
@@ -153,3 +219,2194 @@ test('算法准确性统计', (t) => {
 
   t.pass()
 })
+
+// CLAHE 预处理配置
+test('改进版算法 - CLAHE 预处理', (t) => {
+  const { cut, bg } = testCases[0]
+  const targetBuffer = readFileSync(cut)
+  const backgroundBuffer = readFileSync(bg)
+
+  const bbox = improvedSlideMatch(targetBuffer, backgroundBuffer, 0.3, { claheTileSize: 32, claheClipLimit: 2.0 })
+  t.truthy(bbox)
+  t.true(bbox.x2 > bbox.x1)
+
+  t.throws(() => improvedSlideMatch(targetBuffer, backgroundBuffer, 0.3, { claheTileSize: 0 }), {
+    message: /CLAHE 分块大小必须大于 0/,
+  })
+})
+
+// 显示尺寸坐标换算
+test('改进版算法 - 换算到显示尺寸', (t) => {
+  const { cut, bg } = testCases[0]
+  const targetBuffer = readFileSync(cut)
+  const backgroundBuffer = readFileSync(bg)
+  // PNG 头部 IHDR 中的宽度
+  const naturalWidth = backgroundBuffer.readUInt32BE(16)
+
+  const natural = improvedSlideMatch(targetBuffer, backgroundBuffer)
+
+  // 非整数比例，例如 320/344
+  const ratio = 320 / 344
+  const display = improvedSlideMatch(targetBuffer, backgroundBuffer, 0.3, {
+    backgroundDisplayWidth: naturalWidth * ratio,
+  })
+
+  t.is(display.x1, Math.round(natural.x1 * ratio))
+  t.is(display.y1, Math.round(natural.y1 * ratio))
+  t.is(display.x2, Math.round(natural.x2 * ratio))
+  t.is(display.y2, Math.round(natural.y2 * ratio))
+
+  t.throws(() => improvedSlideMatch(targetBuffer, backgroundBuffer, 0.3, { backgroundDisplayWidth: 0 }), {
+    message: /显示宽度必须是大于 0 的有限数值/,
+  })
+})
+
+test('coordinateMode center 返回匹配区域的中心点', (t) => {
+  const { cut, bg } = testCases[0]
+  const targetBuffer = readFileSync(cut)
+  const backgroundBuffer = readFileSync(bg)
+  const naturalWidth = backgroundBuffer.readUInt32BE(16)
+
+  const topLeft = slideMatch(targetBuffer, backgroundBuffer)
+  t.is(topLeft.coordinateMode, CoordinateMode.TopLeft)
+
+  const center = slideMatch(targetBuffer, backgroundBuffer, { coordinateMode: CoordinateMode.Center })
+  t.is(center.coordinateMode, CoordinateMode.Center)
+  t.is(center.x1, topLeft.x1 + Math.floor((topLeft.x2 - topLeft.x1) / 2))
+  t.is(center.y1, topLeft.y1 + Math.floor((topLeft.y2 - topLeft.y1) / 2))
+  t.is(center.x2, topLeft.x2)
+  t.is(center.y2, topLeft.y2)
+
+  // 先换算到显示尺寸再取中心
+  const ratio = 320 / 344
+  const backgroundDisplayWidth = naturalWidth * ratio
+  const display = slideMatch(targetBuffer, backgroundBuffer, { backgroundDisplayWidth })
+  const displayCenter = slideMatch(targetBuffer, backgroundBuffer, {
+    backgroundDisplayWidth,
+    coordinateMode: CoordinateMode.Center,
+  })
+  t.is(displayCenter.x1, display.x1 + Math.floor((display.x2 - display.x1) / 2))
+  t.is(displayCenter.y1, display.y1 + Math.floor((display.y2 - display.y1) / 2))
+})
+
+test('coordinateUnit ratio 返回按图片尺寸归一化的坐标', (t) => {
+  const { cut, bg } = testCases[0]
+  const targetBuffer = readFileSync(cut)
+  const backgroundBuffer = readFileSync(bg)
+  // PNG 头部 IHDR 中的宽高
+  const [backgroundWidth, backgroundHeight] = [backgroundBuffer.readUInt32BE(16), backgroundBuffer.readUInt32BE(20)]
+  const [targetWidth, targetHeight] = [targetBuffer.readUInt32BE(16), targetBuffer.readUInt32BE(20)]
+
+  t.is(slideMatch(targetBuffer, backgroundBuffer).ratio, undefined)
+
+  for (const coordinateMode of [CoordinateMode.TopLeft, CoordinateMode.Center]) {
+    const pixel = slideMatch(targetBuffer, backgroundBuffer, { coordinateMode })
+    // 像素坐标不受 coordinateUnit 影响；显示尺寸只影响像素坐标，不影响比例
+    const result = slideMatch(targetBuffer, backgroundBuffer, {
+      coordinateMode,
+      coordinateUnit: CoordinateUnit.Ratio,
+    })
+    t.is(result.x1, pixel.x1)
+    const display = slideMatch(targetBuffer, backgroundBuffer, {
+      coordinateMode,
+      coordinateUnit: CoordinateUnit.Ratio,
+      backgroundDisplayWidth: backgroundWidth / 2,
+    })
+    t.deepEqual(display.ratio, result.ratio)
+
+    // 比例 × 原始尺寸还原像素坐标
+    const ratio = result.ratio!
+    t.is(Math.round(ratio.x1 * backgroundWidth), pixel.x1)
+    t.is(Math.round(ratio.y1 * backgroundHeight), pixel.y1)
+    t.is(Math.round(ratio.x2 * backgroundWidth), pixel.x2)
+    t.is(Math.round(ratio.y2 * backgroundHeight), pixel.y2)
+    t.is(Math.round(ratio.targetX * targetWidth), pixel.targetX)
+    t.is(Math.round(ratio.targetY * targetHeight), pixel.targetY)
+    t.true(ratio.x2 > 0 && ratio.x2 <= 1 && ratio.y2 > 0 && ratio.y2 <= 1)
+  }
+})
+
+// 边缘形态学后处理：目标轮廓比缺口大 1px，单像素边缘无法完全重合
+test('改进版算法 - 边缘膨胀改善轮廓错位', (t) => {
+  const targetBuffer = readFileSync(join(SYNTHETIC_DIR, 'misaligned_cut.png'))
+  const backgroundBuffer = readFileSync(join(SYNTHETIC_DIR, 'misaligned_bg.png'))
+  const expectedX = 178
+
+  const plain = improvedSlideMatch(targetBuffer, backgroundBuffer)
+  const dilated = improvedSlideMatch(targetBuffer, backgroundBuffer, 0.3, {
+    edgeMorphology: { op: 'dilate', radius: 1 },
+  })
+
+  console.log(
+    `\n轮廓错位: 默认 x1=${plain.x1} (${plain.confidence}), 膨胀后 x1=${dilated.x1} (${dilated.confidence}), 预期 x1=${expectedX}`,
+  )
+  t.true(Math.abs(dilated.x1 - expectedX) <= 2)
+  t.true(Math.abs(dilated.x1 - expectedX) <= Math.abs(plain.x1 - expectedX))
+  // 膨胀后的得分明显高于未处理时（约 0.69 对 0.41）
+  t.true(dilated.confidence! > plain.confidence! + 0.2)
+
+  t.throws(
+    () => improvedSlideMatch(targetBuffer, backgroundBuffer, 0.3, { edgeMorphology: { op: 'close', radius: 256 } }),
+    { message: /形态学操作半径必须在 0-255 范围内/ },
+  )
+})
+
+// 边缘膨胀：错开 1 像素的轮廓
+test('dilateEdges 提高轮廓错位时的匹配得分', (t) => {
+  const targetBuffer = readFileSync(join(SYNTHETIC_DIR, 'misaligned_cut.png'))
+  const backgroundBuffer = readFileSync(join(SYNTHETIC_DIR, 'misaligned_bg.png'))
+  const expectedX = 178
+  // 自适应阈值只取决于图片本身，两次匹配只在是否膨胀上不同
+  const plain = improvedSlideMatch(targetBuffer, backgroundBuffer, 0)
+  const dilated = improvedSlideMatch(targetBuffer, backgroundBuffer, 0, { dilateEdges: 1 })
+
+  t.deepEqual(dilated.metadata?.targetThresholds, plain.metadata?.targetThresholds)
+  t.true(dilated.confidence! > plain.confidence!)
+  t.true(Math.abs(dilated.x1 - expectedX) <= 1)
+
+  // 0 表示不膨胀
+  t.deepEqual(improvedSlideMatch(targetBuffer, backgroundBuffer, 0, { dilateEdges: 0 }), plain)
+  t.throws(() => slideMatch(targetBuffer, backgroundBuffer, { dilateEdges: 256 }), {
+    message: /边缘膨胀半径必须在 0-255 范围内/,
+  })
+})
+
+// 只沿 x 方向搜索固定行
+test('fixedY 只在固定行上搜索', (t) => {
+  testCases.forEach(({ index, cut, bg, expected }) => {
+    const targetBuffer = readFileSync(cut)
+    const backgroundBuffer = readFileSync(bg)
+
+    const start = performance.now()
+    const full = slideMatch(targetBuffer, backgroundBuffer)
+    const fullTime = performance.now() - start
+
+    const rowStart = performance.now()
+    const row = slideMatch(targetBuffer, backgroundBuffer, { fixedY: expected.y1 })
+    const rowTime = performance.now() - rowStart
+
+    console.log(`\n测试 ${index} fixedY: 完整匹配 ${fullTime.toFixed(2)}ms, 单行搜索 ${rowTime.toFixed(2)}ms`)
+    t.is(row.y1, expected.y1)
+    t.is(row.x1, full.x1)
+
+    // 允许上下偏差
+    const banded = improvedSlideMatch(targetBuffer, backgroundBuffer, null, {
+      fixedY: expected.y1 + 1,
+      fixedYTolerance: 2,
+    })
+    t.is(banded.y1, expected.y1)
+  })
+
+  // fixedY + 目标高度超出背景图
+  const { cut, bg } = testCases[0]
+  t.throws(() => slideMatch(readFileSync(cut), readFileSync(bg), { fixedY: 10000 }), {
+    message: /fixedY 超出范围/,
+  })
+})
+
+// 置信度阈值为 1.0 时必然触发回退，回退结果应与原版算法完全一致
+// 回退时不重新转换灰度图、只重新执行边缘检测，由 tests/stages.rs 统计各阶段的执行次数
+test('改进版算法 - 回退路径结果与原版一致', (t) => {
+  testCases.forEach(({ cut, bg }) => {
+    const targetBuffer = readFileSync(cut)
+    const backgroundBuffer = readFileSync(bg)
+
+    const primary = improvedSlideMatch(targetBuffer, backgroundBuffer, 0)
+    const fallback = improvedSlideMatch(targetBuffer, backgroundBuffer, 1.0)
+
+    // 置信度阈值为 0 时，只有得分不大于 0 才会回退
+    t.is(primary.metadata?.usedFallback, !(primary.metadata!.primaryConfidence > 0))
+    t.true(fallback.metadata?.usedFallback)
+    t.deepEqual(fallback.metadata?.targetThresholds, [100, 200])
+    t.deepEqual(fallback.metadata?.backgroundThresholds, [100, 200])
+    // 回退时仍保留首次尝试的得分
+    t.is(fallback.metadata?.primaryConfidence, primary.metadata?.primaryConfidence)
+
+    const { targetX, targetY, x1, y1, x2, y2 } = fallback
+    const original = slideMatch(targetBuffer, backgroundBuffer)
+    t.deepEqual(
+      { targetX, targetY, x1, y1, x2, y2 },
+      {
+        targetX: original.targetX,
+        targetY: original.targetY,
+        x1: original.x1,
+        y1: original.y1,
+        x2: original.x2,
+        y2: original.y2,
+      },
+    )
+  })
+})
+
+// 近乎纯色图片的自适应阈值
+test('纯色和低对比度图片的自适应阈值保证低阈值小于高阈值', (t) => {
+  for (const name of ['uniform_black', 'uniform_white', 'low_contrast']) {
+    const targetBuffer = readFileSync(join(SYNTHETIC_DIR, `${name}_cut.png`))
+    const backgroundBuffer = readFileSync(join(SYNTHETIC_DIR, `${name}_bg.png`))
+
+    // 纯色图片没有边缘，得分为 0，可能回退到固定阈值；无论哪种情况阈值都必须有效
+    // 关闭边缘密度检查，否则纯色背景会直接报错
+    const bbox = improvedSimpleSlideMatch(targetBuffer, backgroundBuffer, 0, { edgeDensityThreshold: 0 })
+    const [targetLow, targetHigh] = bbox.metadata!.targetThresholds
+    const [backgroundLow, backgroundHigh] = bbox.metadata!.backgroundThresholds
+
+    t.true(targetLow < targetHigh, name)
+    t.true(backgroundLow < backgroundHigh, name)
+    t.true(targetHigh <= 255, name)
+    t.true(backgroundHigh <= 255, name)
+  }
+})
+
+// 异形滑块：透明区域的边缘不参与匹配
+test('异形滑块透明区域掩码', (t) => {
+  // 拼图块四周有一圈半透明（alpha 40）的杂色，裁剪时会被保留下来
+  const targetBuffer = readFileSync(join(SYNTHETIC_DIR, 'puzzle_cut.png'))
+  const backgroundBuffer = readFileSync(join(SYNTHETIC_DIR, 'puzzle_bg.png'))
+
+  const bbox = slideMatch(targetBuffer, backgroundBuffer)
+  // 缺口位于 (200, 60)，目标图片中拼图块距左上角 10 像素
+  t.is(bbox.x1 + 10, 200)
+  t.is(bbox.y1 + 10, 60)
+})
+
+// 目标图片大于背景图片
+test('oversizePolicy 缩放后匹配，坐标仍在原始背景图中', (t) => {
+  // 背景为 bg1.png 第 94-139 行（301x46），目标为放大 2 倍的 cut1.png（110x90）；缺口 x1 = 149
+  const targetBuffer = readFileSync(join(SYNTHETIC_DIR, 'oversize_cut.png'))
+  const backgroundBuffer = readFileSync(join(SYNTHETIC_DIR, 'oversize_bg.png'))
+
+  const message = /背景图片的尺寸必须大于等于目标图片的尺寸（目标图片 110x90，背景图片 301x46）/
+  t.throws(() => slideMatch(targetBuffer, backgroundBuffer), { message })
+  t.throws(() => improvedSlideMatch(targetBuffer, backgroundBuffer, null, { oversizePolicy: OversizePolicy.Error }), {
+    message,
+  })
+
+  for (const oversizePolicy of [OversizePolicy.ScaleTarget, OversizePolicy.ScaleBackground]) {
+    for (const bbox of [
+      slideMatch(targetBuffer, backgroundBuffer, { oversizePolicy }),
+      improvedSlideMatch(targetBuffer, backgroundBuffer, null, { oversizePolicy }),
+    ]) {
+      t.true(Math.abs(bbox.x1 - 149) <= 2, `${oversizePolicy}: x1 = ${bbox.x1}`)
+      t.true(bbox.x2 <= 301 && bbox.y2 <= 46, oversizePolicy)
+      // 缩放比例为 46 / 90
+      t.true(Math.abs(bbox.targetScale! - 46 / 90) < 1e-9, oversizePolicy)
+    }
+  }
+
+  // 尺寸合适时不缩放
+  const cut = readFileSync(join(IMAGES_DIR, 'cut1.png'))
+  const bg = readFileSync(join(IMAGES_DIR, 'bg1.png'))
+  t.is(slideMatch(cut, bg, { oversizePolicy: OversizePolicy.ScaleTarget }).targetScale, undefined)
+})
+
+// 背景图按渲染宽度重采样
+test('backgroundTargetWidth 把背景图重采样到滑块对应的宽度', (t) => {
+  // bg1.png（301x199）放大 398 / 301 倍得到的“原始尺寸”背景图，滑块 cut1.png 对应 301px 宽的背景；缺口 x1 = 149
+  const targetBuffer = readFileSync(join(IMAGES_DIR, 'cut1.png'))
+  const backgroundBuffer = readFileSync(join(SYNTHETIC_DIR, 'bg1_natural.png'))
+
+  for (const bbox of [
+    slideMatch(targetBuffer, backgroundBuffer, { backgroundTargetWidth: 301 }),
+    improvedSlideMatch(targetBuffer, backgroundBuffer, null, { backgroundTargetWidth: 301 }),
+  ]) {
+    // 默认返回重采样后背景图中的坐标
+    t.true(Math.abs(bbox.x1 - 149) <= 2, `x1 = ${bbox.x1}`)
+    t.is(bbox.targetScale, undefined)
+  }
+  t.true(improvedSlideMatch(targetBuffer, backgroundBuffer, null, { backgroundTargetWidth: 301 }).confidence! > 0.5)
+
+  // 换算回原始背景图：149 × 398 / 301 ≈ 197
+  const natural = slideMatch(targetBuffer, backgroundBuffer, {
+    backgroundTargetWidth: 301,
+    backgroundCoordinateSpace: BackgroundCoordinateSpace.Natural,
+  })
+  t.true(Math.abs(natural.x1 - 197) <= 3, `x1 = ${natural.x1}`)
+  t.true(natural.x2 <= 398 && natural.y2 <= 263)
+  t.true(Math.abs(natural.targetScale! - 398 / 301) < 1e-9)
+
+  t.throws(() => slideMatch(targetBuffer, backgroundBuffer, { backgroundTargetWidth: 0 }), {
+    message: /backgroundTargetWidth 必须大于 0/,
+  })
+})
+
+// 背景图中画有滑块本身
+test('ignorePieceRegion 排除起始位置的滑块', (t) => {
+  // piece_start_bg.png 是在 bg1.png 的 (0, 95) 处画上 cut1.png 的背景图，缺口位于 x1 = 149
+  const targetBuffer = readFileSync(join(IMAGES_DIR, 'cut1.png'))
+  const backgroundBuffer = readFileSync(join(SYNTHETIC_DIR, 'piece_start_bg.png'))
+
+  // 默认匹配到滑块本身
+  t.is(slideMatch(targetBuffer, backgroundBuffer).x1, 0)
+
+  for (const ignorePieceRegion of [PieceRegionPreset.AutoLeft, { x: 0, y: 90, width: 60, height: 50 }]) {
+    for (const bbox of [
+      slideMatch(targetBuffer, backgroundBuffer, { ignorePieceRegion }),
+      simpleSlideMatch(targetBuffer, backgroundBuffer, { ignorePieceRegion }),
+      improvedSlideMatch(targetBuffer, backgroundBuffer, null, { ignorePieceRegion }),
+    ]) {
+      t.deepEqual([bbox.x1, bbox.y1], [149, 95])
+    }
+  }
+
+  const empty = { x: 0, y: 0, width: 0, height: 10 }
+  t.throws(() => slideMatch(targetBuffer, backgroundBuffer, { ignorePieceRegion: empty }), {
+    message: /ignorePieceRegion 的宽度和高度必须大于 0/,
+  })
+})
+
+// 原版函数的置信度阈值
+test('原版函数设置 confidenceThreshold 后返回置信度，过低时报错', (t) => {
+  const targetBuffer = readFileSync(join(IMAGES_DIR, 'cut1.png'))
+  const backgroundBuffer = readFileSync(join(IMAGES_DIR, 'bg1.png'))
+
+  // 默认不检查，也不返回置信度
+  t.is(slideMatch(targetBuffer, backgroundBuffer).confidence, undefined)
+
+  for (const bbox of [
+    slideMatch(targetBuffer, backgroundBuffer, { confidenceThreshold: 0.3 }),
+    simpleSlideMatch(targetBuffer, backgroundBuffer, { confidenceThreshold: 0.3 }),
+  ]) {
+    t.is(bbox.x1, 149)
+    t.true(bbox.confidence! > 0.8)
+  }
+
+  // 固定阈值 (100, 200) 下 bright_sky_bg.png 的缺口边缘全部丢失，得分峰值为 0
+  const skyTarget = readFileSync(join(SYNTHETIC_DIR, 'bright_sky_cut.png'))
+  const skyBackground = readFileSync(join(SYNTHETIC_DIR, 'bright_sky_bg.png'))
+  const message = /匹配置信度 0\.000 不高于阈值 0\.300/
+  t.throws(() => slideMatch(skyTarget, skyBackground, { confidenceThreshold: 0.3 }), { message })
+  t.throws(() => simpleSlideMatch(skyTarget, skyBackground, { confidenceThreshold: 0.3 }), { message })
+
+  t.throws(() => slideMatch(targetBuffer, backgroundBuffer, { confidenceThreshold: 1.5 }), {
+    message: /置信度阈值必须在 0\.0-1\.0 范围内/,
+  })
+})
+
+// 小目标快速路径
+test('fastSmall 对小于 2500 像素的目标图片跳过边缘检测', (t) => {
+  // cut1.png 为 55x45（2475 像素）
+  const targetBuffer = readFileSync(join(IMAGES_DIR, 'cut1.png'))
+  const backgroundBuffer = readFileSync(join(IMAGES_DIR, 'bg1.png'))
+
+  const fast = slideMatch(targetBuffer, backgroundBuffer, { fastSmall: true, confidenceThreshold: 0 })
+  t.deepEqual(fast, slideMatch(targetBuffer, backgroundBuffer, { edgeMode: EdgeMode.None, confidenceThreshold: 0 }))
+  t.deepEqual([fast.x1, fast.y1], [149, 95])
+
+  // puzzle_cut.png 裁剪后为 69x60，不走快速路径
+  const puzzleTarget = readFileSync(join(SYNTHETIC_DIR, 'puzzle_cut.png'))
+  const puzzleBackground = readFileSync(join(SYNTHETIC_DIR, 'puzzle_bg.png'))
+  t.deepEqual(slideMatch(puzzleTarget, puzzleBackground, { fastSmall: true }), slideMatch(puzzleTarget, puzzleBackground))
+})
+
+// 次优峰值与模棱两可的匹配
+test('ambiguityMargin 检测得分接近的第二个缺口', (t) => {
+  // two_notch_bg.png 在 bg1.png 的 x = 30 处按 60% 不透明度复制了缺口 (149, 95)，得分约为真实缺口的 88%
+  const targetBuffer = readFileSync(join(IMAGES_DIR, 'cut1.png'))
+  const backgroundBuffer = readFileSync(join(SYNTHETIC_DIR, 'two_notch_bg.png'))
+
+  t.is(slideMatch(targetBuffer, backgroundBuffer).isAmbiguous, undefined)
+
+  for (const match of [
+    (options: SlideMatchConfig) => slideMatch(targetBuffer, backgroundBuffer, options),
+    (options: SlideMatchConfig) => simpleSlideMatch(targetBuffer, backgroundBuffer, options),
+    (options: SlideMatchConfig) => improvedSlideMatch(targetBuffer, backgroundBuffer, null, options),
+  ]) {
+    const strict = match({ ambiguityMargin: 0.05 })
+    t.is(strict.x1, 149)
+    t.false(strict.isAmbiguous)
+    t.is(strict.secondBestX, 30)
+    t.true(strict.secondBestScore! < 0.95 * match({ confidenceThreshold: 0 }).confidence!)
+
+    t.true(match({ ambiguityMargin: 0.15 }).isAmbiguous)
+  }
+
+  // 次优峰值的 x 与 x1 一样换算到显示尺寸
+  const scaled = slideMatch(targetBuffer, backgroundBuffer, { ambiguityMargin: 0.05, backgroundDisplayWidth: 150.5 })
+  t.deepEqual([scaled.x1, scaled.secondBestX], [75, 15])
+
+  t.throws(() => slideMatch(targetBuffer, backgroundBuffer, { ambiguityMargin: 2 }), {
+    message: /ambiguityMargin 必须在 0\.0-1\.0 范围内/,
+  })
+})
+
+test('onLowConfidence 控制改进版函数置信度过低时的处理方式', (t) => {
+  // 夜景背景的描边与背景灰度相同，自适应阈值下目标图片没有边缘，置信度为 0
+  const targetBuffer = readFileSync(join(SYNTHETIC_DIR, 'night_cut.png'))
+  const backgroundBuffer = readFileSync(join(SYNTHETIC_DIR, 'night_bg.png'))
+
+  const fallback = improvedSimpleSlideMatch(targetBuffer, backgroundBuffer, 0.3)
+  t.true(fallback.metadata!.usedFallback)
+  t.true(fallback.metadata!.lowConfidence)
+  t.is(fallback.metadata!.lowConfidenceAction, LowConfidenceAction.Fallback)
+  t.is(fallback.metadata!.primaryConfidence, 0)
+  t.deepEqual(
+    improvedSimpleSlideMatch(targetBuffer, backgroundBuffer, 0.3, { onLowConfidence: LowConfidenceAction.Fallback }),
+    fallback,
+  )
+
+  const best = improvedSimpleSlideMatch(targetBuffer, backgroundBuffer, 0.3, {
+    onLowConfidence: LowConfidenceAction.ReturnBest,
+  })
+  t.false(best.metadata!.usedFallback)
+  t.true(best.metadata!.lowConfidence)
+  t.is(best.metadata!.lowConfidenceAction, LowConfidenceAction.ReturnBest)
+  t.is(best.confidence, 0)
+
+  t.throws(
+    () =>
+      improvedSimpleSlideMatch(targetBuffer, backgroundBuffer, 0.3, { onLowConfidence: LowConfidenceAction.Error }),
+    { message: /匹配置信度 0\.000 不高于阈值 0\.300/ },
+  )
+
+  // 置信度足够时三种处理方式的结果相同
+  const cut = readFileSync(join(IMAGES_DIR, 'cut1.png'))
+  const bg = readFileSync(join(IMAGES_DIR, 'bg1.png'))
+  const strict = improvedSimpleSlideMatch(cut, bg, 0.3, { onLowConfidence: LowConfidenceAction.Error })
+  t.deepEqual(strict, improvedSimpleSlideMatch(cut, bg, 0.3))
+  t.false(strict.metadata!.lowConfidence)
+  t.is(strict.metadata!.lowConfidenceAction, undefined)
+})
+
+// 峰值旁瓣比
+test('returnPsr 返回峰值旁瓣比，正确匹配明显高于错误匹配', (t) => {
+  const read = (dir: string, name: string) => readFileSync(join(dir, name))
+
+  // 默认不返回
+  t.is(slideMatch(read(IMAGES_DIR, 'cut1.png'), read(IMAGES_DIR, 'bg1.png')).psr, undefined)
+
+  // 自带的 4 组图片都匹配正确，PSR 在 17-32 之间（cut1 约 24、cut2 约 20、cut3 约 32、cut4 约 17）
+  for (const i of [1, 2, 3, 4]) {
+    const bbox = slideMatch(read(IMAGES_DIR, `cut${i}.png`), read(IMAGES_DIR, `bg${i}.png`), { returnPsr: true })
+    t.true(bbox.psr! > 15, `cut${i}: psr=${bbox.psr}`)
+  }
+
+  // 错误匹配：轮廓错位时改进版函数偏到 x1=218（预期 178），PSR 约 8.5
+  const misaligned = improvedSlideMatch(
+    read(SYNTHETIC_DIR, 'misaligned_cut.png'),
+    read(SYNTHETIC_DIR, 'misaligned_bg.png'),
+    0.3,
+    { returnPsr: true },
+  )
+  t.true(Math.abs(misaligned.x1 - 178) > 20)
+  t.true(misaligned.psr! < 10)
+
+  // 错误匹配：天空背景下自适应阈值丢失缺口边缘，匹配到 x1=18（预期 207），PSR 约 6.8
+  const sky = improvedSlideMatch(
+    read(SYNTHETIC_DIR, 'bright_sky_cut.png'),
+    read(SYNTHETIC_DIR, 'bright_sky_bg.png'),
+    0,
+    { returnPsr: true },
+  )
+  t.not(sky.x1, 207)
+  t.true(sky.psr! < 10)
+
+  // 局限：背景左侧渲染了与滑块相同的图案时，错误位置同样是一个尖锐的峰，PSR 约 21
+  const pieceStart = slideMatch(read(IMAGES_DIR, 'cut1.png'), read(SYNTHETIC_DIR, 'piece_start_bg.png'), {
+    returnPsr: true,
+  })
+  t.is(pieceStart.x1, 0)
+  t.true(pieceStart.psr! > 15)
+
+  // 得分图全为 0 时无法计算
+  const night = slideMatch(read(SYNTHETIC_DIR, 'night_cut.png'), read(SYNTHETIC_DIR, 'night_bg.png'), {
+    returnPsr: true,
+  })
+  t.is(night.psr, undefined)
+})
+
+// 匹配尝试详情
+test('improvedSlideMatchVerbose 返回每次匹配尝试的得分和位置', (t) => {
+  const targetBuffer = readFileSync(join(IMAGES_DIR, 'cut1.png'))
+  const backgroundBuffer = readFileSync(join(IMAGES_DIR, 'bg1.png'))
+
+  // 置信度足够时只有自适应阈值一次尝试
+  const { bbox, attempts } = improvedSlideMatchVerbose(targetBuffer, backgroundBuffer, 0.3)
+  t.deepEqual(bbox, improvedSlideMatch(targetBuffer, backgroundBuffer, 0.3))
+  t.deepEqual(attempts, [{ strategy: 'adaptive', score: bbox.confidence!, location: [149, 95] }])
+
+  // 尝试的位置与结果一样换算到显示尺寸
+  const scaled = improvedSlideMatchVerbose(targetBuffer, backgroundBuffer, 0.3, { backgroundDisplayWidth: 150.5 })
+  t.deepEqual(scaled.attempts[0].location, [scaled.bbox.x1, scaled.bbox.y1])
+
+  // 夜景背景自适应阈值下置信度为 0，回退到固定阈值，两次尝试都会记录
+  const night = improvedSlideMatchVerbose(
+    readFileSync(join(SYNTHETIC_DIR, 'night_cut.png')),
+    readFileSync(join(SYNTHETIC_DIR, 'night_bg.png')),
+    0.3,
+  )
+  t.true(night.bbox.metadata!.usedFallback)
+  t.deepEqual(night.attempts.map((attempt) => attempt.strategy), ['adaptive', 'fixed'])
+  t.is(night.attempts[0].score, night.bbox.metadata!.primaryConfidence)
+  t.is(night.attempts[1].score, night.bbox.confidence)
+})
+
+// 轮廓形状匹配
+test("strategy: 'contour' 在杂乱纹理背景中按轮廓形状定位缺口", (t) => {
+  // 2x2 随机噪点纹理背景，缺口在 (200, 51)，内部平涂并带 2 像素浅色描边；滑块为 44x52 的拼图形状
+  const targetBuffer = readFileSync(join(SYNTHETIC_DIR, 'noisy_cut.png'))
+  const backgroundBuffer = readFileSync(join(SYNTHETIC_DIR, 'noisy_bg.png'))
+
+  // 滑块内部的噪点边缘与背景各处的噪点同样相关，边缘互相关定位失败
+  t.not(slideMatch(targetBuffer, backgroundBuffer).x1, 200)
+
+  const bbox = slideMatch(targetBuffer, backgroundBuffer, { strategy: MatchStrategy.Contour, confidenceThreshold: 0.9 })
+  t.deepEqual([bbox.x1, bbox.y1, bbox.x2, bbox.y2], [200, 51, 244, 103])
+  t.true(bbox.confidence! > 0.95)
+
+  // 自带的图片上与边缘互相关结果一致
+  const cut = readFileSync(join(IMAGES_DIR, 'cut1.png'))
+  const bg = readFileSync(join(IMAGES_DIR, 'bg1.png'))
+  t.is(slideMatch(cut, bg, { strategy: MatchStrategy.Contour }).x1, 149)
+
+  // 没有尺寸接近的闭合轮廓时报错
+  t.throws(
+    () =>
+      slideMatch(targetBuffer, readFileSync(join(SYNTHETIC_DIR, 'bright_sky_bg.png')), {
+        strategy: MatchStrategy.Contour,
+      }),
+    { message: /背景图中没有与滑块尺寸接近的闭合轮廓/ },
+  )
+})
+
+// 有符号坐标
+test('slideMatchSigned 返回与 slideMatch 相同的有符号坐标', (t) => {
+  const targetBuffer = readFileSync(join(IMAGES_DIR, 'cut1.png'))
+  const backgroundBuffer = readFileSync(join(IMAGES_DIR, 'bg1.png'))
+
+  const bbox = slideMatch(targetBuffer, backgroundBuffer, { confidenceThreshold: 0.3 })
+  const signed = slideMatchSigned(targetBuffer, backgroundBuffer, { confidenceThreshold: 0.3 })
+  t.deepEqual(signed, {
+    targetX: bbox.targetX,
+    targetY: bbox.targetY,
+    x1: bbox.x1,
+    y1: bbox.y1,
+    x2: bbox.x2,
+    y2: bbox.y2,
+    confidence: bbox.confidence,
+  })
+
+  // 未设置 confidenceThreshold 时不返回得分
+  t.is(slideMatchSigned(targetBuffer, backgroundBuffer).confidence, undefined)
+})
+
+// 坐标参照点
+test('coordinateSpace 为 backgroundRelativeToTarget 时坐标相对目标图片的裁剪起点', (t) => {
+  // 夜景图片的缺口在 (210, 60)，目标图片含 10 像素透明边
+  const targetBuffer = readFileSync(join(SYNTHETIC_DIR, 'night_cut.png'))
+  const backgroundBuffer = readFileSync(join(SYNTHETIC_DIR, 'night_bg.png'))
+  const match = (options: SlideMatchConfig) =>
+    improvedSlideMatch(targetBuffer, backgroundBuffer, 0.3, { linearLight: true, ambiguityMargin: 0.05, ...options })
+
+  const absolute = match({})
+  const relative = match({ coordinateSpace: CoordinateSpace.BackgroundRelativeToTarget })
+  t.deepEqual([absolute.targetX, absolute.targetY, absolute.x1, absolute.y1], [10, 10, 210, 60])
+  t.deepEqual([relative.targetX, relative.targetY], [10, 10])
+  t.deepEqual([relative.x1, relative.y1, relative.x2, relative.y2], [200, 50, 250, 100])
+  // 次优峰值同样平移
+  t.is(relative.secondBestX, absolute.secondBestX! - 10)
+
+  // 显示尺寸下减去换算后的裁剪起点，ratio 减去原始尺寸下的裁剪起点
+  const scaled = match({
+    coordinateSpace: CoordinateSpace.BackgroundRelativeToTarget,
+    coordinateUnit: CoordinateUnit.Ratio,
+    backgroundDisplayWidth: 160,
+  })
+  t.deepEqual([scaled.targetX, scaled.x1, scaled.y1], [5, 100, 25])
+  t.is(scaled.ratio!.x1, 200 / 320)
+})
+
+// 梯度方向匹配
+test('strategy: orientation 只统计方向相同的边缘', (t) => {
+  // text_bg.png 顶部的“口”字与缺口形状相同但走向杂乱，方向匹配不会被误导，缺口位于 (220, 90)
+  const targetBuffer = readFileSync(join(SYNTHETIC_DIR, 'text_cut.png'))
+  const backgroundBuffer = readFileSync(join(SYNTHETIC_DIR, 'text_bg.png'))
+  t.true(slideMatch(targetBuffer, backgroundBuffer).y1 < 30)
+
+  const bbox = slideMatch(targetBuffer, backgroundBuffer, { strategy: MatchStrategy.Orientation })
+  t.deepEqual([bbox.x1, bbox.y1], [220, 90])
+
+  // 真实样本上与默认方式定位一致
+  const options = { strategy: MatchStrategy.Orientation }
+  for (const { cut, bg, expected } of testCases) {
+    const [target, background] = [readFileSync(cut), readFileSync(bg)]
+    t.true(Math.abs(slideMatch(target, background, options).x1 - expected.x1) <= 5)
+    t.true(Math.abs(improvedSlideMatch(target, background, 0.3, options).x1 - expected.x1) <= 5)
+  }
+
+  t.throws(() => slideMatch(targetBuffer, backgroundBuffer, { ...options, orientationBins: 0 }), {
+    message: /orientationBins 必须在 1-64 范围内/,
+  })
+})
+
+// 倒角匹配
+test('strategy: chamfer 容忍滑块与缺口轮廓错开', (t) => {
+  // sloppy_bg.png 的缺口轮廓比滑块轮廓整体外扩 2 像素，缺口位于 (198, 49)；
+  // 左侧 x = 60 处的诱饵与滑块轮廓的左侧部分完全重合、但缺少右侧，边缘互相关会被它吸引
+  const targetBuffer = readFileSync(join(SYNTHETIC_DIR, 'sloppy_cut.png'))
+  const backgroundBuffer = readFileSync(join(SYNTHETIC_DIR, 'sloppy_bg.png'))
+  t.true(slideMatch(targetBuffer, backgroundBuffer).x1 < 100)
+
+  const options = { strategy: MatchStrategy.Chamfer }
+  const bbox = slideMatch(targetBuffer, backgroundBuffer, { ...options, ambiguityMargin: 0.1 })
+  t.true(Math.abs(bbox.x1 - 198) <= 2 && Math.abs(bbox.y1 - 49) <= 2)
+  t.true(bbox.confidence! > 0 && bbox.confidence! <= 1)
+  t.true(bbox.secondBestX! < 100)
+  t.false(bbox.isAmbiguous)
+  t.true(Math.abs(improvedSlideMatch(targetBuffer, backgroundBuffer, 0.3, options).x1 - 198) <= 2)
+
+  // 真实样本上与默认方式定位一致
+  for (const { cut, bg, expected } of testCases) {
+    t.true(Math.abs(slideMatch(readFileSync(cut), readFileSync(bg), options).x1 - expected.x1) <= 5)
+  }
+})
+
+// 设备像素比
+test('devicePixelRatio 把坐标换算为 CSS 像素', (t) => {
+  const { cut, bg } = testCases[0]
+  const targetBuffer = readFileSync(cut)
+  const backgroundBuffer = readFileSync(bg)
+  const naturalWidth = backgroundBuffer.readUInt32BE(16)
+
+  const natural = slideMatch(targetBuffer, backgroundBuffer)
+  const css = slideMatch(targetBuffer, backgroundBuffer, { devicePixelRatio: 2 })
+  for (const key of ['x1', 'y1', 'x2', 'y2', 'targetX', 'targetY'] as const) {
+    t.is(css[key], Math.round(natural[key] / 2))
+  }
+  t.is(improvedSlideMatch(targetBuffer, backgroundBuffer, 0.3, { devicePixelRatio: 2 }).x1, css.x1)
+
+  // 先按显示宽度换算，再除以 DPR
+  const ratio = 320 / 344
+  const display = slideMatch(targetBuffer, backgroundBuffer, {
+    backgroundDisplayWidth: naturalWidth * ratio,
+    devicePixelRatio: 1.5,
+  })
+  t.is(display.x1, Math.round((natural.x1 * ratio) / 1.5))
+
+  for (const devicePixelRatio of [0, -2, Infinity]) {
+    t.throws(() => slideMatch(targetBuffer, backgroundBuffer, { devicePixelRatio }), {
+      message: /设备像素比必须是大于 0 的有限数值/,
+    })
+  }
+})
+
+// 中值滤波
+test('medianFilterRadius 去除 JPEG 块噪声', (t) => {
+  // bg1_q40.jpg 为 bg1.png 以 JPEG 质量 40 重新编码的结果
+  const targetBuffer = readFileSync(join(IMAGES_DIR, 'cut1.png'))
+  const backgroundBuffer = readFileSync(join(SYNTHETIC_DIR, 'bg1_q40.jpg'))
+  const filtered = { medianFilterRadius: 2 }
+
+  // 边缘图的灰度均值 / 255 即边缘像素占比
+  const edgeDensity = (config?: SlideMatchConfig) =>
+    computeImageStats(cannyEdges(backgroundBuffer, null, config).data).mean / 255
+  t.true(edgeDensity(filtered) < edgeDensity() * 0.5)
+
+  // 定位不变
+  t.is(slideMatch(targetBuffer, backgroundBuffer).x1, 149)
+  t.is(slideMatch(targetBuffer, backgroundBuffer, filtered).x1, 149)
+  t.is(slideMatch(targetBuffer, backgroundBuffer, { ...filtered, medianFilterTarget: true }).x1, 149)
+  const improved = improvedSlideMatch(targetBuffer, backgroundBuffer, 0.3, filtered)
+  t.is(improved.x1, 149)
+
+  // 自适应阈值在滤波后的灰度图上计算
+  const { thresholds } = cannyEdges(backgroundBuffer, { adaptive: true }, filtered)
+  t.deepEqual(thresholds, improved.metadata!.backgroundThresholds)
+  t.notDeepEqual(thresholds, cannyEdges(backgroundBuffer, { adaptive: true }).thresholds)
+
+  // 0 表示不滤波
+  t.deepEqual(
+    slideMatch(targetBuffer, backgroundBuffer, { medianFilterRadius: 0 }),
+    slideMatch(targetBuffer, backgroundBuffer),
+  )
+  t.throws(() => slideMatch(targetBuffer, backgroundBuffer, { medianFilterRadius: 256 }), {
+    message: /中值滤波半径必须在 0-255 范围内/,
+  })
+})
+
+// 多个目标图片
+test('slideMatchMultiTarget 返回置信度最高的目标图片', (t) => {
+  const backgroundBuffer = readFileSync(join(IMAGES_DIR, 'bg1.png'))
+  const targetBuffer = readFileSync(join(IMAGES_DIR, 'cut1.png'))
+  const invalid = Buffer.from('not an image')
+  const targets = [readFileSync(join(IMAGES_DIR, 'cut2.png')), targetBuffer, invalid]
+
+  const { bestBbox, targetIndex, allResults } = slideMatchMultiTarget(targets, backgroundBuffer)
+  t.is(targetIndex, 1)
+  t.deepEqual(bestBbox, slideMatch(targetBuffer, backgroundBuffer, { confidenceThreshold: 0 }))
+  t.is(allResults.length, 3)
+  t.true(allResults[0]!.confidence! < bestBbox.confidence!)
+  t.deepEqual(allResults[1], bestBbox)
+  t.is(allResults[2], null)
+
+  t.throws(() => slideMatchMultiTarget([invalid, invalid], backgroundBuffer), { message: /所有目标图片均匹配失败/ })
+  t.throws(() => slideMatchMultiTarget([], backgroundBuffer), { message: /目标图片列表不能为空/ })
+})
+
+// CLAHE：低对比度渐变与纯色图片
+test('CLAHE 增强渐变图的局部对比度', (t) => {
+  // 水平渐变，灰度 96-127
+  const gradient = readFileSync(join(SYNTHETIC_DIR, 'gradient_bg.png'))
+  const raw = computeImageStats(gradient)
+  const enhanced = computeImageStats(gradient, { claheTileSize: 32 })
+  t.true(enhanced.max - enhanced.min > raw.max - raw.min)
+  t.true(enhanced.stdDev > raw.stdDev)
+
+  // 对比度限制越大，增强越明显
+  const strong = computeImageStats(gradient, { claheTileSize: 32, claheClipLimit: 40 })
+  t.true(strong.stdDev > enhanced.stdDev * 1.5)
+
+  // 纯色图片的直方图被对比度限制削平，保持纯色
+  const uniform = computeImageStats(readFileSync(join(SYNTHETIC_DIR, 'uniform_white_bg.png')), {
+    claheTileSize: 32,
+    claheClipLimit: 40,
+  })
+  t.is(uniform.stdDev, 0)
+})
+
+// CLAHE：缺口位于背景左下角的阴影中
+test('CLAHE 找到阴影中的缺口', (t) => {
+  const targetBuffer = readFileSync(join(SYNTHETIC_DIR, 'shadow_cut.png'))
+  const backgroundBuffer = readFileSync(join(SYNTHETIC_DIR, 'shadow_bg.png'))
+
+  // 阴影压低了缺口描边的对比度，Canny 丢失缺口轮廓，匹配到右上方的色块
+  t.not(slideMatch(targetBuffer, backgroundBuffer).x1, 49)
+
+  const config: SlideMatchConfig = { claheTileSize: 32 }
+  const bbox = slideMatch(targetBuffer, backgroundBuffer, config)
+  t.is(bbox.x1, 49)
+  t.is(bbox.y1, 92)
+  t.is(improvedSlideMatch(targetBuffer, backgroundBuffer, 0.3, config).x1, 49)
+})
+
+// 深色主题：近乎全黑的背景上画浅色轮廓
+test('invert 修正深色主题背景的自适应阈值', (t) => {
+  const targetBuffer = readFileSync(join(SYNTHETIC_DIR, 'dark_theme_cut.png'))
+  const backgroundBuffer = readFileSync(join(SYNTHETIC_DIR, 'dark_theme_bg.png'))
+
+  // 自适应阈值过低，暗色的干扰轮廓和纹理都被检测为边缘，匹配到左侧的干扰缺口
+  const plain = improvedSlideMatch(targetBuffer, backgroundBuffer)
+  t.not(plain.x1, 192)
+  t.false(plain.metadata!.backgroundInverted)
+
+  for (const invert of [true, InvertMode.Auto]) {
+    const bbox = improvedSlideMatch(targetBuffer, backgroundBuffer, 0.3, { invert })
+    t.is(bbox.x1, 192)
+    t.is(bbox.y1, 59)
+    t.false(bbox.metadata!.usedFallback)
+    t.true(bbox.metadata!.targetInverted)
+    t.true(bbox.metadata!.backgroundInverted)
+  }
+
+  // 明亮的背景在 auto 模式下不反相
+  const { cut, bg } = testCases[0]
+  const bright = improvedSlideMatch(readFileSync(cut), readFileSync(bg), 0.3, { invert: InvertMode.Auto })
+  t.false(bright.metadata!.backgroundInverted)
+
+  t.throws(() => slideMatch(targetBuffer, backgroundBuffer, { invert: InvertMode.Auto, invertCutoff: 300 }), {
+    message: /反相灰度均值阈值必须在 0-255 范围内/,
+  })
+})
+
+// 内置基准测试
+test('benchmark 返回各阶段耗时', (t) => {
+  const result = benchmark({ iterations: 3 })
+  t.true(result.matched)
+  t.is(result.iterations, 3)
+  t.deepEqual(result.backgroundSize, [320, 160])
+  t.is(result.targetSize, 53)
+  t.is(typeof result.simd, 'boolean')
+  t.true(result.threads >= 1)
+  for (const stage of [result.decode, result.canny, result.matching, result.total]) {
+    t.true(stage.medianMs > 0)
+    t.true(stage.p95Ms >= stage.medianMs)
+  }
+  t.true(result.total.meanMs >= result.canny.meanMs)
+
+  t.true(benchmark({ backgroundSize: [600, 300], targetSize: 60, iterations: 1 }).matched)
+  t.throws(() => benchmark({ iterations: 0 }), { message: /iterations 必须在 1-1000 范围内/ })
+  t.throws(() => benchmark({ targetSize: 200 }), { message: /滑块边长必须在 8-160 范围内/ })
+})
+
+// 归一化坐标
+test('slideMatchNormalized 返回相对于背景图片尺寸的坐标', (t) => {
+  const { cut, bg } = testCases[0]
+  const targetBuffer = readFileSync(cut)
+  const backgroundBuffer = readFileSync(bg)
+
+  const bbox = slideMatch(targetBuffer, backgroundBuffer, { confidenceThreshold: 0 })
+  const normalized = slideMatchNormalized(targetBuffer, backgroundBuffer)
+  // bg1.png 为 301x199
+  t.deepEqual(normalized, {
+    x1: bbox.x1 / 301,
+    y1: bbox.y1 / 199,
+    x2: bbox.x2 / 301,
+    y2: bbox.y2 / 199,
+    confidence: bbox.confidence!,
+  })
+  for (const value of [normalized.x1, normalized.y1, normalized.x2, normalized.y2]) {
+    t.true(value >= 0 && value <= 1)
+  }
+
+  // 显示尺寸不影响归一化坐标
+  t.deepEqual(slideMatchNormalized(targetBuffer, backgroundBuffer, { backgroundDisplayWidth: 150 }), normalized)
+})
+
+// 差异图
+test('computeDifferenceMap 返回匹配区域的灰度差异图', (t) => {
+  const { cut, bg } = testCases[0]
+  const targetBuffer = readFileSync(cut)
+  const backgroundBuffer = readFileSync(bg)
+  const bbox = slideMatch(targetBuffer, backgroundBuffer)
+
+  const difference = computeDifferenceMap(targetBuffer, backgroundBuffer, bbox)
+  // PNG 头部 IHDR 中的宽高与匹配区域一致
+  t.is(difference.readUInt32BE(16), bbox.x2 - bbox.x1)
+  t.is(difference.readUInt32BE(20), bbox.y2 - bbox.y1)
+  t.true(computeImageStats(difference).max > 0)
+
+  t.throws(() => computeDifferenceMap(targetBuffer, backgroundBuffer, { ...bbox, x2: 400 }), {
+    message: /超出背景图片/,
+  })
+  t.throws(
+    () => computeDifferenceMap(targetBuffer, backgroundBuffer, { ...bbox, coordinateMode: CoordinateMode.Center }),
+    { message: /coordinateMode 必须为 topLeft/ },
+  )
+})
+
+test('renderMatchOverlay 把目标图片叠加到匹配位置', (t) => {
+  const { cut, bg } = testCases[0]
+  const targetBuffer = readFileSync(cut)
+  const backgroundBuffer = readFileSync(bg)
+  const bbox = slideMatch(targetBuffer, backgroundBuffer)
+
+  const overlay = renderMatchOverlay(targetBuffer, backgroundBuffer, bbox, 0.5)
+  // PNG 头部 IHDR 中的宽高与背景图片（301x199）一致
+  t.is(overlay.readUInt32BE(16), 301)
+  t.is(overlay.readUInt32BE(20), 199)
+
+  t.throws(() => renderMatchOverlay(targetBuffer, backgroundBuffer, bbox, 1.5), {
+    message: /alpha 必须在 0.0-1.0 范围内/,
+  })
+})
+
+test('previewPlacement 与不透明的 renderMatchOverlay 结果一致', (t) => {
+  // sloppy_cut.png 四周有透明边框，裁剪起点不为 (0, 0)
+  const targetBuffer = readFileSync(join(SYNTHETIC_DIR, 'sloppy_cut.png'))
+  const backgroundBuffer = readFileSync(join(SYNTHETIC_DIR, 'sloppy_bg.png'))
+  const bbox = slideMatch(targetBuffer, backgroundBuffer)
+  t.not(bbox.targetX, 0)
+
+  const preview = previewPlacement(targetBuffer, backgroundBuffer, bbox.x1, bbox.y1)
+  t.deepEqual(preview, renderMatchOverlay(targetBuffer, backgroundBuffer, bbox, 1))
+})
+
+test('matchAtPosition 的得分与 slideMatch 在同一位置的置信度一致', (t) => {
+  const { cut, bg } = testCases[0]
+  const targetBuffer = readFileSync(cut)
+  const backgroundBuffer = readFileSync(bg)
+
+  const bbox = slideMatch(targetBuffer, backgroundBuffer, { confidenceThreshold: 0 })
+  const result = matchAtPosition(targetBuffer, backgroundBuffer, bbox.x1, bbox.y1)
+  t.is(result.score, bbox.confidence!)
+  t.is(result.metadata.primaryConfidence, result.score)
+  t.false(result.metadata.usedFallback)
+  t.deepEqual(result.metadata.targetThresholds, [100, 200])
+
+  // 偏离缺口的位置得分更低
+  t.true(matchAtPosition(targetBuffer, backgroundBuffer, bbox.x1 + 5, bbox.y1).score < result.score)
+  // 目标图片超出背景图（bg1.png 为 301x199）
+  t.throws(() => matchAtPosition(targetBuffer, backgroundBuffer, 300, 0), { message: /位置超出范围/ })
+})
+
+test('slideMatchCrossCheck 同时返回改进版和原版算法的结果', (t) => {
+  const { cut, bg } = testCases[0]
+  const targetBuffer = readFileSync(cut)
+  const backgroundBuffer = readFileSync(bg)
+
+  const result = slideMatchCrossCheck(targetBuffer, backgroundBuffer)
+  const improved = improvedSlideMatch(targetBuffer, backgroundBuffer)
+  const original = slideMatch(targetBuffer, backgroundBuffer, { confidenceThreshold: 0 })
+  t.is(result.improved.bbox.x1, improved.x1)
+  t.is(result.improved.confidence, improved.confidence!)
+  t.is(result.original.bbox.x1, original.x1)
+  t.is(result.original.confidence, original.confidence!)
+  t.true(result.agrees)
+  t.is(result.deltaX, 0)
+})
+
+test('slideMatchCrossCheck 报告两种算法的分歧', (t) => {
+  const targetBuffer = readFileSync(join(SYNTHETIC_DIR, 'dark_theme_cut.png'))
+  const backgroundBuffer = readFileSync(join(SYNTHETIC_DIR, 'dark_theme_bg.png'))
+
+  // 改进版落在诱饵轮廓上，原版找到真实缺口
+  const result = slideMatchCrossCheck(targetBuffer, backgroundBuffer)
+  t.is(result.original.bbox.x1, 192)
+  t.is(result.improved.bbox.x1, 102)
+  t.is(result.deltaX, -90)
+  t.false(result.agrees)
+  t.true(slideMatchCrossCheck(targetBuffer, backgroundBuffer, { tolerance: 90 }).agrees)
+})
+
+test('evaluateDataset 统计标注数据集的准确率', (t) => {
+  const dir = mkdtempSync(join(tmpdir(), 'slide-match-dataset-'))
+  try {
+    // 001 正确；002 标注错误；003 缺少背景图；004 标注无法解析
+    copyFileSync(join(IMAGES_DIR, 'cut1.png'), join(dir, '001_target.png'))
+    copyFileSync(join(IMAGES_DIR, 'bg1.png'), join(dir, '001_bg.png'))
+    writeFileSync(join(dir, '001.json'), JSON.stringify({ x: 149 }))
+    copyFileSync(join(IMAGES_DIR, 'cut2.png'), join(dir, '002_target.png'))
+    copyFileSync(join(IMAGES_DIR, 'bg2.png'), join(dir, '002_bg.png'))
+    writeFileSync(join(dir, '002.json'), JSON.stringify({ x: 10 }))
+    copyFileSync(join(IMAGES_DIR, 'cut3.png'), join(dir, '003_target.png'))
+    writeFileSync(join(dir, '003.json'), JSON.stringify({ x: 81 }))
+    copyFileSync(join(IMAGES_DIR, 'cut4.png'), join(dir, '004_target.png'))
+    copyFileSync(join(IMAGES_DIR, 'bg4.png'), join(dir, '004_bg.png'))
+    writeFileSync(join(dir, '004.json'), 'not json')
+
+    const result = evaluateDataset(dir, { algorithm: DatasetAlgorithm.ImprovedSlideMatch })
+    t.is(result.total, 4)
+    t.is(result.correct, 1)
+    t.is(result.accuracy, 0.25)
+    t.deepEqual(
+      result.failures.map((failure) => failure.name),
+      ['002', '003', '004'],
+    )
+
+    const [wrong, missing, unparsable] = result.failures
+    t.is(wrong.expectedX, 10)
+    t.is(wrong.gotX, 67)
+    t.true(wrong.confidence! > 0)
+    t.is(wrong.error, undefined)
+    t.is(missing.expectedX, 81)
+    t.regex(missing.error!, /无法读取背景图片/)
+    t.is(unparsable.expectedX, undefined)
+    t.regex(unparsable.error!, /无法解析标注文件/)
+
+    // 放宽容差后 002 也视为正确
+    t.is(evaluateDataset(dir, { tolerancePx: 60 }).correct, 2)
+  } finally {
+    rmSync(dir, { recursive: true, force: true })
+  }
+})
+
+// 竖直滑动：vertical_bg / vertical_cut 为 bg1 / cut1 转置（交换 x、y）后的图片
+test('axis: y 与水平方向的结果对应', (t) => {
+  const { cut, bg } = testCases[0]
+  const options = { returnScoreProfile: true, confidenceThreshold: 0 }
+  const horizontal = slideMatch(readFileSync(cut), readFileSync(bg), { ...options, axis: SlideAxis.X })
+  const verticalCut = readFileSync(join(SYNTHETIC_DIR, 'vertical_cut.png'))
+  const verticalBg = readFileSync(join(SYNTHETIC_DIR, 'vertical_bg.png'))
+  const vertical = slideMatch(verticalCut, verticalBg, { ...options, axis: SlideAxis.Y })
+
+  t.is(vertical.x1, horizontal.y1)
+  t.is(vertical.y1, horizontal.x1)
+  t.is(vertical.confidence, horizontal.confidence)
+  t.deepEqual(vertical.scoreProfile, horizontal.scoreProfile)
+  t.is(horizontal.slideDistance, horizontal.x1 - horizontal.targetX)
+  t.is(vertical.slideDistance, vertical.y1 - vertical.targetY)
+  t.is(vertical.slideDistance, horizontal.slideDistance)
+
+  // fixedX 只在该列附近沿 y 方向搜索
+  const banded = improvedSlideMatch(verticalCut, verticalBg, null, {
+    axis: SlideAxis.Y,
+    fixedX: vertical.x1 + 1,
+    fixedXTolerance: 2,
+  })
+  t.is(banded.x1, vertical.x1)
+  t.is(banded.y1, vertical.y1)
+
+  t.throws(() => slideMatch(verticalCut, verticalBg, { axis: SlideAxis.Y, fixedX: 10000 }), {
+    message: /fixedX 超出范围/,
+  })
+  t.throws(() => slideMatch(verticalCut, verticalBg, { axis: SlideAxis.Y, fixedY: 10 }), {
+    message: /不能使用 fixedY/,
+  })
+  t.throws(() => slideMatch(readFileSync(cut), readFileSync(bg), { fixedX: 10 }), {
+    message: /fixedX 只在 axis 为 y 时可用/,
+  })
+})
+
+// 背景图片带透明圆角和透明边框（透明像素为黑色）
+test('backgroundFill 合成背景图片的透明区域', (t) => {
+  const target = readFileSync(join(SYNTHETIC_DIR, 'rounded_cut.png'))
+  const background = readFileSync(join(SYNTHETIC_DIR, 'rounded_bg.png'))
+  const options = { ambiguityMargin: 0.1, returnPsr: true }
+
+  // 默认合成到白色，圆角处没有假边缘
+  const flattened = slideMatch(target, background, options)
+  t.deepEqual([flattened.x1, flattened.y1], [190, 60])
+  t.is(flattened.secondBestScore, 0)
+
+  // 合成到黑色相当于直接转灰度：圆角处出现接近缺口得分的次优峰值
+  const black = slideMatch(target, background, { ...options, backgroundFill: [0, 0, 0] })
+  t.true(black.secondBestScore! > 0.3)
+  t.true(black.psr! < flattened.psr!)
+
+  t.throws(() => slideMatch(target, background, { backgroundFill: [255, 255] }), {
+    message: /backgroundFill 必须是 3 个 0-255 范围内的整数/,
+  })
+})
+
+test('slideXOffset 只返回水平拖动距离', (t) => {
+  testCases.forEach(({ cut, bg }) => {
+    const targetBuffer = readFileSync(cut)
+    const backgroundBuffer = readFileSync(bg)
+
+    const bbox = slideMatch(targetBuffer, backgroundBuffer)
+    t.is(slideXOffset(targetBuffer, backgroundBuffer), bbox.x1 - bbox.targetX)
+    // coordinateMode / coordinateSpace 不影响结果
+    t.is(
+      slideXOffset(targetBuffer, backgroundBuffer, {
+        coordinateMode: CoordinateMode.Center,
+        coordinateSpace: CoordinateSpace.BackgroundRelativeToTarget,
+      }),
+      bbox.x1 - bbox.targetX,
+    )
+
+    const improved = improvedSlideMatch(targetBuffer, backgroundBuffer)
+    t.is(improvedSlideXOffset(targetBuffer, backgroundBuffer), improved.x1 - improved.targetX)
+  })
+
+  const { cut, bg } = testCases[0]
+  t.throws(() => improvedSlideXOffset(readFileSync(cut), readFileSync(bg), 1.5), {
+    message: /置信度阈值必须在 0.0-1.0 范围内/,
+  })
+})
+
+// 按步长粗搜索后逐像素细化，配合 dilateEdges 加宽得分峰值
+test('stride 粗搜索后细化到逐像素的位置', (t) => {
+  testCases.forEach(({ cut, bg, expected }) => {
+    const targetBuffer = readFileSync(cut)
+    const backgroundBuffer = readFileSync(bg)
+    const full = slideMatch(targetBuffer, backgroundBuffer, { dilateEdges: 1, confidenceThreshold: 0 })
+
+    for (const stride of [2, 4, 8]) {
+      const strided = slideMatch(targetBuffer, backgroundBuffer, { dilateEdges: 1, confidenceThreshold: 0, stride })
+      t.deepEqual([strided.x1, strided.y1], [expected.x1, expected.y1])
+      t.is(strided.confidence, full.confidence)
+    }
+  })
+
+  const { cut, bg } = testCases[0]
+  t.throws(() => slideMatch(readFileSync(cut), readFileSync(bg), { stride: 0 }), {
+    message: /stride 必须在 1-16 范围内/,
+  })
+})
+
+// 双峰直方图阈值策略
+test('bimodal 阈值策略按两个主峰之间的灰度差计算阈值', (t) => {
+  testCases.forEach(({ cut, bg, expected }) => {
+    const targetBuffer = readFileSync(cut)
+    const backgroundBuffer = readFileSync(bg)
+    const options = { thresholdStrategy: ThresholdStrategy.Bimodal }
+
+    const result = improvedSlideMatch(targetBuffer, backgroundBuffer, 0, options)
+    t.deepEqual([result.x1, result.y1], [expected.x1, expected.y1])
+    t.is(result.metadata?.thresholdStrategy, ThresholdStrategy.Bimodal)
+    t.notDeepEqual(
+      result.metadata?.backgroundThresholds,
+      improvedSlideMatch(targetBuffer, backgroundBuffer, 0).metadata?.backgroundThresholds,
+    )
+
+    const original = slideMatch(targetBuffer, backgroundBuffer, options)
+    t.deepEqual([original.x1, original.y1], [expected.x1, expected.y1])
+  })
+})
+
+// 透明目标图片
+test('目标图片没有或只有极少不透明像素时报错，allowDegenerateTarget 时标记结果', (t) => {
+  const backgroundBuffer = readFileSync(join(IMAGES_DIR, 'bg1.png'))
+  // transparent_cut.png 完全透明；tiny_alpha_cut.png 只有 3 个不透明像素
+  const transparent = readFileSync(join(SYNTHETIC_DIR, 'transparent_cut.png'))
+  const tiny = readFileSync(join(SYNTHETIC_DIR, 'tiny_alpha_cut.png'))
+
+  t.throws(() => slideMatch(transparent, backgroundBuffer), { message: /目标图片没有不透明像素/ })
+  t.throws(() => simpleSlideMatch(transparent, backgroundBuffer), { message: /目标图片没有不透明像素/ })
+  t.throws(() => improvedSlideMatch(transparent, backgroundBuffer), { message: /目标图片没有不透明像素/ })
+
+  // 默认只拒绝完全透明的图片，minOpaquePixels 提高下限
+  t.is(slideMatch(tiny, backgroundBuffer).degenerateTarget, undefined)
+  t.throws(() => slideMatch(tiny, backgroundBuffer, { minOpaquePixels: 50 }), {
+    message: /目标图片只有 3 个不透明像素，少于 minOpaquePixels（50）/,
+  })
+
+  const options = { minOpaquePixels: 50, allowDegenerateTarget: true }
+  for (const target of [transparent, tiny]) {
+    t.true(slideMatch(target, backgroundBuffer, options).degenerateTarget)
+    t.true(simpleSlideMatch(target, backgroundBuffer, options).degenerateTarget)
+    t.true(improvedSlideMatch(target, backgroundBuffer, 0.3, options).degenerateTarget)
+  }
+  t.notThrows(() => slideMatch(transparent, backgroundBuffer, { minOpaquePixels: 0 }))
+
+  // 正常的滑块不受影响
+  const { cut, bg } = testCases[0]
+  t.is(slideMatch(readFileSync(cut), readFileSync(bg), { minOpaquePixels: 50 }).degenerateTarget, undefined)
+})
+
+// 不抛出异常的匹配
+test('trySlideMatch 以错误码返回可预期的失败', (t) => {
+  const target = readFileSync(testCases[0].cut)
+  const background = readFileSync(testCases[0].bg)
+
+  const ok = trySlideMatch(target, background, { confidenceThreshold: 0.5 })
+  t.true(ok.ok)
+  t.deepEqual([ok.bbox!.x1, ok.bbox!.y1], [149, 95])
+  t.is(ok.confidence, ok.bbox!.confidence)
+  t.is(ok.code, undefined)
+
+  const expectFailure = (result: ReturnType<typeof trySlideMatch>, code: ErrorCode, message: RegExp) => {
+    t.false(result.ok)
+    t.is(result.bbox, undefined)
+    t.is(result.code, code)
+    t.regex(result.message!, message)
+  }
+  expectFailure(trySlideMatch(Buffer.from('not an image'), background), ErrorCode.DecodeFailed, /无法加载目标图片/)
+  expectFailure(trySlideMatch(background, target), ErrorCode.TargetLargerThanBackground, /背景图片的尺寸必须大于等于/)
+  expectFailure(
+    trySlideMatch(target, background, { confidenceThreshold: 0.99 }),
+    ErrorCode.LowConfidence,
+    /^滑块匹配失败: 匹配置信度/,
+  )
+  expectFailure(
+    trySlideMatch(readFileSync(join(SYNTHETIC_DIR, 'transparent_cut.png')), background),
+    ErrorCode.DegenerateTarget,
+    /目标图片没有不透明像素/,
+  )
+  expectFailure(
+    tryImprovedSlideMatch(target, background, 0.99, { onLowConfidence: LowConfidenceAction.Error }),
+    ErrorCode.LowConfidence,
+    /^改进版滑块匹配失败: 匹配置信度/,
+  )
+
+  // 与抛出异常的函数的错误信息一致
+  const thrown = t.throws(() => slideMatch(target, background, { confidenceThreshold: 0.99 }))
+  t.is(thrown!.message, trySlideMatch(target, background, { confidenceThreshold: 0.99 }).message)
+
+  // 调用方的错误仍抛出异常
+  t.throws(() => trySlideMatch(target, background, { cannyLow: 300 }), { message: /cannyLow/ })
+  t.throws(() => tryImprovedSlideMatch(target, background, 2), { message: /置信度阈值必须在 0.0-1.0 范围内/ })
+})
+
+test('抛出的异常以错误码作为 code，与 trySlideMatch 结果中的 code 相同', async (t) => {
+  const target = readFileSync(testCases[0].cut)
+  const background = readFileSync(testCases[0].bg)
+  const codeOf = (error: unknown) => (error as Error & { code: string }).code
+
+  t.is(codeOf(t.throws(() => slideMatch(Buffer.from('not an image'), background))), ErrorCode.DecodeFailed)
+  t.is(codeOf(t.throws(() => slideMatch(target, background, { confidenceThreshold: 0.99 }))), ErrorCode.LowConfidence)
+  t.is(
+    codeOf(t.throws(() => improvedSlideMatch(target, background, 0.99, { onLowConfidence: LowConfidenceAction.Error }))),
+    ErrorCode.LowConfidence,
+  )
+  t.is(
+    codeOf(t.throws(() => improvedSlideMatchWithPath(join(SYNTHETIC_DIR, 'missing.png'), testCases[0].bg))),
+    ErrorCode.ReadFailed,
+  )
+  // 异步函数在 JS 线程上设置 code
+  t.is(
+    codeOf(await t.throwsAsync(improvedSlideMatchAsync(Buffer.from('not an image'), background))),
+    ErrorCode.DecodeFailed,
+  )
+
+  // 调用方的错误没有错误码
+  t.is(codeOf(t.throws(() => improvedSlideMatch(target, background, 2))), 'GenericFailure')
+})
+
+// 匹配耗时
+test('measureTime 在结果中返回匹配耗时', async (t) => {
+  const target = readFileSync(testCases[0].cut)
+  const background = readFileSync(testCases[0].bg)
+  const options = { measureTime: true }
+
+  t.is(slideMatch(target, background).elapsedMs, undefined)
+  t.is(slideMatch(target, background, { measureTime: false }).elapsedMs, undefined)
+
+  const results = [
+    slideMatch(target, background, options),
+    simpleSlideMatch(target, background, options),
+    improvedSlideMatch(target, background, 0.3, options),
+    improvedSimpleSlideMatch(target, background, 0.3, options),
+    improvedSlideMatchVerbose(target, background, 0.3, options).bbox,
+    await improvedSlideMatchAsync(target, background, 0.3, options),
+    trySlideMatch(target, background, options).bbox!,
+  ]
+  for (const result of results) {
+    t.true(result.elapsedMs! > 0)
+    t.true(result.elapsedMs! < 10_000)
+  }
+  // 不影响匹配结果
+  t.deepEqual([results[0].x1, results[0].y1], [149, 95])
+})
+
+// 背景补边
+test('backgroundPadding 允许目标图片超出背景右侧边缘', (t) => {
+  // right_edge_bg.png 为 bg1.png 的左侧 190 像素，缺口 (149, 95)-(204, 140) 超出右侧边缘 14 像素
+  const targetBuffer = readFileSync(join(IMAGES_DIR, 'cut1.png'))
+  const backgroundBuffer = readFileSync(join(SYNTHETIC_DIR, 'right_edge_bg.png'))
+
+  t.not(slideMatch(targetBuffer, backgroundBuffer).x1, 149)
+  t.not(slideMatch(targetBuffer, backgroundBuffer, { backgroundPadding: 10 }).x1, 149)
+
+  const padded = slideMatch(targetBuffer, backgroundBuffer, { backgroundPadding: 20, returnScoreProfile: true })
+  t.deepEqual([padded.x1, padded.y1, padded.x2, padded.y2], [149, 95, 204, 140])
+  // 背景宽 190，目标宽 55：不补边时有 136 个候选 x，补 20 像素后多出 20 个
+  t.is(padded.scoreProfile?.length, 156)
+  t.is(improvedSlideMatch(targetBuffer, backgroundBuffer, 0, { backgroundPadding: 20 }).x1, 149)
+
+  // 完全位于背景内的缺口不受影响
+  testCases.forEach(({ cut, bg, expected }) => {
+    const result = slideMatch(readFileSync(cut), readFileSync(bg), { backgroundPadding: 20 })
+    t.deepEqual([result.x1, result.y1], [expected.x1, expected.y1])
+  })
+})
+
+// 阈值策略
+test('每种阈值策略在同一组灰度图上都能找到缺口，并记录在 metadata 中', (t) => {
+  const targetBuffer = readFileSync(join(SYNTHETIC_DIR, 'cut1_gray.png'))
+  const strategies = [
+    ThresholdStrategy.Fixed,
+    ThresholdStrategy.MeanStdDev,
+    ThresholdStrategy.Otsu,
+    ThresholdStrategy.Bimodal,
+    ThresholdStrategy.Median,
+  ]
+
+  // 8 位与 16 位灰度背景的结果一致
+  for (const background of ['bg1_gray.png', 'bg1_gray16.png']) {
+    const backgroundBuffer = readFileSync(join(SYNTHETIC_DIR, background))
+    for (const thresholdStrategy of strategies) {
+      const original = slideMatch(targetBuffer, backgroundBuffer, { thresholdStrategy })
+      t.is(original.x1, 149)
+      t.is(original.y1, 95)
+
+      const improved = improvedSlideMatch(targetBuffer, backgroundBuffer, 0, { thresholdStrategy })
+      t.is(improved.x1, 149)
+      t.is(improved.y1, 95)
+      t.is(improved.metadata?.thresholdStrategy, thresholdStrategy)
+      t.is(improved.metadata?.targetThresholds.length, 2)
+      t.true(improved.metadata!.targetThresholds[0] < improved.metadata!.targetThresholds[1])
+    }
+  }
+
+  const backgroundBuffer = readFileSync(join(SYNTHETIC_DIR, 'bg1_gray.png'))
+  // 原版函数默认固定阈值，改进版函数默认 meanStdDev
+  t.deepEqual(
+    slideMatch(targetBuffer, backgroundBuffer),
+    slideMatch(targetBuffer, backgroundBuffer, { thresholdStrategy: ThresholdStrategy.Fixed }),
+  )
+  t.is(matchAtPosition(targetBuffer, backgroundBuffer, 149, 95).metadata.thresholdStrategy, ThresholdStrategy.Fixed)
+  t.is(improvedSlideMatch(targetBuffer, backgroundBuffer, 0).metadata?.thresholdStrategy, ThresholdStrategy.MeanStdDev)
+
+  // fixed 使用 (100, 200)，显式指定的阈值仍然覆盖
+  const fixed = improvedSlideMatch(targetBuffer, backgroundBuffer, 0, { thresholdStrategy: ThresholdStrategy.Fixed })
+  t.deepEqual(fixed.metadata?.backgroundThresholds, [100, 200])
+  const explicit = improvedSlideMatch(targetBuffer, backgroundBuffer, 0, {
+    thresholdStrategy: ThresholdStrategy.Fixed,
+    cannyLow: 60,
+    cannyHigh: 120,
+  })
+  t.deepEqual(explicit.metadata?.backgroundThresholds, [60, 120])
+
+  // 明亮天空的双峰灰度分布下，otsu 与 median 一样能找到缺口，原版的固定阈值找不到
+  const skyTarget = readFileSync(join(SYNTHETIC_DIR, 'bright_sky_cut.png'))
+  const skyBackground = readFileSync(join(SYNTHETIC_DIR, 'bright_sky_bg.png'))
+  t.not(slideMatch(skyTarget, skyBackground).x1, 207)
+  const otsu = slideMatch(skyTarget, skyBackground, { thresholdStrategy: ThresholdStrategy.Otsu })
+  t.is(otsu.x1, 207)
+  t.is(otsu.y1, 92)
+})
+
+// 16 位灰度图
+test('16 位灰度图在 16 位灰度值上计算自适应阈值', (t) => {
+  // bg1_gray.png 扩展为 16 位并在低字节加入抖动，cut1.png 转为 16 位灰度 + 透明通道
+  const targetBuffer = readFileSync(join(SYNTHETIC_DIR, 'cut1_la16.png'))
+  const backgroundBuffer = readFileSync(join(SYNTHETIC_DIR, 'bg1_gray16.png'))
+
+  const result = improvedSlideMatch(targetBuffer, backgroundBuffer)
+  t.true(Math.abs(result.x1 - 149) <= 5)
+
+  // 阈值与 8 位图片的结果接近（16 位灰度值 / 257 换算到 0-255）
+  const expected = improvedSlideMatch(
+    readFileSync(join(IMAGES_DIR, 'cut1.png')),
+    readFileSync(join(SYNTHETIC_DIR, 'bg1_gray.png')),
+  )
+  const [low, high] = result.metadata!.backgroundThresholds
+  const [expectedLow, expectedHigh] = expected.metadata!.backgroundThresholds
+  t.true(Math.abs(low - expectedLow) < 1)
+  t.true(Math.abs(high - expectedHigh) < 1)
+})
+
+// 不支持的图片格式
+test('AVIF 图片报错说明格式不受支持', (t) => {
+  // bg1.png 以 AVIF 重新编码；image 库解码 AVIF 依赖 libdav1d，预编译包未包含
+  const targetBuffer = readFileSync(join(IMAGES_DIR, 'cut1.png'))
+  const backgroundBuffer = readFileSync(join(SYNTHETIC_DIR, 'bg1.avif'))
+
+  t.throws(() => slideMatch(targetBuffer, backgroundBuffer), { message: /不支持 AVIF 格式的图片/ })
+  t.throws(() => improvedSlideMatch(backgroundBuffer, readFileSync(join(IMAGES_DIR, 'bg1.png'))), {
+    message: /不支持 AVIF 格式的图片/,
+  })
+})
+
+// 图片像素上限
+test('图片像素数超过上限时在解码前报错', (t) => {
+  const targetBuffer = readFileSync(join(SYNTHETIC_DIR, 'misaligned_cut.png'))
+  const backgroundBuffer = readFileSync(join(SYNTHETIC_DIR, 'misaligned_bg.png'))
+
+  // 背景图 320x160 = 51200 像素
+  t.throws(() => slideMatch(targetBuffer, backgroundBuffer, { maxImagePixels: 50000 }), {
+    message: /图片像素数 51200 超过上限 50000/,
+  })
+  t.throws(() => improvedSlideMatch(targetBuffer, backgroundBuffer, null, { maxImagePixels: 50000 }), {
+    message: /图片像素数 51200 超过上限 50000/,
+  })
+  t.notThrows(() => slideMatch(targetBuffer, backgroundBuffer, { maxImagePixels: 51200 }))
+
+  t.throws(() => slideMatch(targetBuffer, backgroundBuffer, { maxImagePixels: 0 }), {
+    message: /图片像素上限必须大于 0/,
+  })
+})
+
+// 边缘密度检查
+test('背景边缘过少时报错', (t) => {
+  const targetBuffer = readFileSync(join(SYNTHETIC_DIR, 'uniform_white_cut.png'))
+  const backgroundBuffer = readFileSync(join(SYNTHETIC_DIR, 'uniform_white_bg.png'))
+
+  t.throws(() => slideMatch(targetBuffer, backgroundBuffer), {
+    message: /背景图片边缘过少/,
+  })
+  t.throws(() => improvedSimpleSlideMatch(targetBuffer, backgroundBuffer), {
+    message: /背景图片边缘过少/,
+  })
+  // 不做边缘检测时不检查
+  t.notThrows(() => slideMatch(targetBuffer, backgroundBuffer, { edgeMode: EdgeMode.None }))
+
+  t.throws(() => slideMatch(targetBuffer, backgroundBuffer, { edgeDensityThreshold: 1.5 }), {
+    message: /边缘密度下限必须在 0.0-1.0 范围内/,
+  })
+
+  // 真实验证码背景的边缘像素占比不到 1%，默认下限（0.1%）不拒绝
+  const realTarget = readFileSync(testCases[0].cut)
+  const realBackground = readFileSync(testCases[0].bg)
+  t.notThrows(() => slideMatch(realTarget, realBackground))
+  t.throws(() => slideMatch(realTarget, realBackground, { edgeDensityThreshold: 0.01 }), {
+    message: /背景图片边缘过少（边缘像素占比 0\.72%）/,
+  })
+})
+
+// 得分曲线
+test('returnScoreProfile 返回每个 x 上的最大得分', (t) => {
+  const targetBuffer = readFileSync(join(SYNTHETIC_DIR, 'misaligned_cut.png'))
+  const backgroundBuffer = readFileSync(join(SYNTHETIC_DIR, 'misaligned_bg.png'))
+
+  // 默认不返回
+  t.is(slideMatch(targetBuffer, backgroundBuffer).scoreProfile, undefined)
+
+  const bbox = improvedSlideMatch(targetBuffer, backgroundBuffer, null, { returnScoreProfile: true })
+  const profile = bbox.scoreProfile!
+  // 背景图宽 320
+  t.is(profile.length, 320 - (bbox.x2 - bbox.x1) + 1)
+  t.is(profile[bbox.x1], Math.max(...profile))
+  t.true(Math.abs(profile[bbox.x1] - bbox.confidence!) < 1e-6)
+
+  // 只搜索固定行时长度不变
+  const fixed = slideMatch(targetBuffer, backgroundBuffer, { returnScoreProfile: true, fixedY: bbox.y1 })
+  t.is(fixed.scoreProfile!.length, profile.length)
+})
+
+// 得分图
+test('returnHeatmap 返回完整的匹配得分图', (t) => {
+  const targetBuffer = readFileSync(join(SYNTHETIC_DIR, 'misaligned_cut.png'))
+  const backgroundBuffer = readFileSync(join(SYNTHETIC_DIR, 'misaligned_bg.png'))
+
+  t.is(improvedSlideMatch(targetBuffer, backgroundBuffer).heatmap, undefined)
+
+  const bbox = improvedSlideMatch(targetBuffer, backgroundBuffer, null, { returnHeatmap: true })
+  const { data, width, height } = bbox.heatmap!
+  t.true(data instanceof Float32Array)
+  // 背景图 320x160
+  t.is(width, 320 - (bbox.x2 - bbox.x1) + 1)
+  t.is(height, 160 - (bbox.y2 - bbox.y1) + 1)
+  t.is(data.length, width * height)
+  t.is(data[bbox.y1 * width + bbox.x1], Math.max(...data))
+})
+
+test('slideMatchScoreMatrix 返回完整得分矩阵，最大值位置与 slideMatch 一致', (t) => {
+  const targetBuffer = readFileSync(join(SYNTHETIC_DIR, 'misaligned_cut.png'))
+  const backgroundBuffer = readFileSync(join(SYNTHETIC_DIR, 'misaligned_bg.png'))
+
+  const bbox = slideMatch(targetBuffer, backgroundBuffer)
+  const matrix = slideMatchScoreMatrix(targetBuffer, backgroundBuffer)
+  const { data, rows, cols } = matrix
+  t.true(data instanceof Float32Array)
+  // 背景图 320x160
+  t.is(cols, 320 - (bbox.x2 - bbox.x1) + 1)
+  t.is(rows, 160 - (bbox.y2 - bbox.y1) + 1)
+  t.is(data.length, rows * cols)
+
+  const best = data.indexOf(Math.max(...data))
+  t.deepEqual([best % cols, Math.floor(best / cols)], [bbox.x1, bbox.y1])
+  t.is(matrix.at(bbox.x1, bbox.y1), data[best])
+  t.throws(() => matrix.at(cols, 0), { message: /超出得分矩阵范围/ })
+})
+
+// 背景边缘图缓存
+test('cacheBackground 缓存背景边缘图，结果与不缓存一致', (t) => {
+  const targetBuffer = readFileSync(join(SYNTHETIC_DIR, 'misaligned_cut.png'))
+  const backgroundBuffer = readFileSync(join(SYNTHETIC_DIR, 'misaligned_bg.png'))
+
+  clearBackgroundCache()
+  const expected = improvedSlideMatch(targetBuffer, backgroundBuffer)
+  // 第一次写入缓存，第二次命中缓存
+  for (let i = 0; i < 2; i++) {
+    const bbox = improvedSlideMatch(targetBuffer, backgroundBuffer, null, { cacheBackground: true })
+    t.is(bbox.x1, expected.x1)
+    t.is(bbox.y1, expected.y1)
+    t.true(Math.abs(bbox.confidence! - expected.confidence!) < 1e-6)
+  }
+
+  // 预处理配置不同时不会误用缓存
+  const dilated = improvedSlideMatch(targetBuffer, backgroundBuffer, null, { dilateEdges: 1 })
+  const dilatedCached = improvedSlideMatch(targetBuffer, backgroundBuffer, null, { dilateEdges: 1, cacheBackground: true })
+  t.true(Math.abs(dilatedCached.confidence! - dilated.confidence!) < 1e-6)
+
+  // 只搜索固定行时使用缓存的整张边缘图
+  const fixed = slideMatch(targetBuffer, backgroundBuffer, { fixedY: expected.y1 })
+  const fixedCached = slideMatch(targetBuffer, backgroundBuffer, { fixedY: expected.y1, cacheBackground: true })
+  t.deepEqual(fixedCached, fixed)
+  clearBackgroundCache()
+})
+
+// 多目标匹配
+test('findAllMatches 返回所有达到阈值的位置', (t) => {
+  // 同一个十字形在背景中出现 3 次：(30, 20)、(150, 90)、(250, 40)
+  const targetBuffer = readFileSync(join(SYNTHETIC_DIR, 'multi_cut.png'))
+  const backgroundBuffer = readFileSync(join(SYNTHETIC_DIR, 'multi_bg.png'))
+
+  const matches = findAllMatches(targetBuffer, backgroundBuffer)
+  t.deepEqual(
+    matches.map(({ bbox }) => [bbox.x1, bbox.y1]).sort((a, b) => a[0] - b[0]),
+    [
+      [30, 20],
+      [150, 90],
+      [250, 40],
+    ],
+  )
+  // 按得分从高到低排列
+  for (let i = 1; i < matches.length; i++) {
+    t.true(matches[i - 1].confidence >= matches[i].confidence)
+  }
+
+  t.is(findAllMatches(targetBuffer, backgroundBuffer, { maxResults: 2 }).length, 2)
+  // 没有位置达到阈值时返回空数组
+  t.deepEqual(findAllMatches(targetBuffer, backgroundBuffer, { scoreThreshold: 0.99 }), [])
+  // 抑制半径较小时，同一位置附近的次高点不再被抑制
+  t.true(
+    findAllMatches(targetBuffer, backgroundBuffer, { scoreThreshold: 0.3, nmsRadius: 1 }).length >
+      findAllMatches(targetBuffer, backgroundBuffer, { scoreThreshold: 0.3 }).length,
+  )
+})
+
+// 异步匹配
+test('improvedSlideMatchAsync 处理大尺寸 Buffer', async (t) => {
+  const { cut, bg } = testCases[0]
+  const targetBuffer = readFileSync(cut)
+  // PNG 解码会忽略 IEND 之后的数据，末尾追加 16 MB 填充模拟大尺寸背景图片
+  const backgroundBuffer = Buffer.concat([readFileSync(bg), Buffer.alloc(16 * 1024 * 1024)])
+
+  const expected = improvedSlideMatch(targetBuffer, backgroundBuffer)
+  t.deepEqual(await improvedSlideMatchAsync(targetBuffer, backgroundBuffer), expected)
+  t.deepEqual(expected, improvedSlideMatch(targetBuffer, readFileSync(bg)))
+})
+
+test('improvedSlideMatchAsync 并发调用结果与同步版一致', async (t) => {
+  const cases = ['misaligned', 'puzzle', 'multi'].map((name) => ({
+    target: readFileSync(join(SYNTHETIC_DIR, `${name}_cut.png`)),
+    background: readFileSync(join(SYNTHETIC_DIR, `${name}_bg.png`)),
+  }))
+  const expected = cases.map(({ target, background }) => improvedSlideMatch(target, background))
+
+  // 每组图片同时发起多次调用
+  const results = await Promise.all(
+    cases.flatMap(({ target, background }) =>
+      Array.from({ length: 4 }, () => improvedSlideMatchAsync(target, background)),
+    ),
+  )
+  results.forEach((bbox, i) => {
+    t.deepEqual(bbox, expected[Math.floor(i / 4)])
+  })
+
+  await t.throwsAsync(() => improvedSlideMatchAsync(cases[0].target, cases[0].background, 1.5), {
+    message: /置信度阈值必须在 0.0-1.0 范围内/,
+  })
+})
+
+// 流式多目标匹配
+test('slideMatchStreamResults 逐行回调每个结果并返回总数', async (t) => {
+  const targetBuffer = readFileSync(join(SYNTHETIC_DIR, 'multi_cut.png'))
+  const backgroundBuffer = readFileSync(join(SYNTHETIC_DIR, 'multi_bg.png'))
+
+  const received: Array<[number, number]> = []
+  const count = await slideMatchStreamResults(targetBuffer, backgroundBuffer, 0.5, (err, candidate) => {
+    t.falsy(err)
+    received.push([candidate.bbox.x1, candidate.bbox.y1])
+  })
+
+  // 按扫描顺序（从上到下）回调
+  t.is(count, 3)
+  t.deepEqual(received, [
+    [30, 20],
+    [250, 40],
+    [150, 90],
+  ])
+
+  await t.throwsAsync(() => slideMatchStreamResults(targetBuffer, backgroundBuffer, 2, () => {}), {
+    message: /最低匹配得分必须在 0.0-1.0 范围内/,
+  })
+})
+
+// 切片打乱的背景图
+test('restoreBackground / slideMatchShuffled 还原切片打乱的背景图', (t) => {
+  // shuffled_bg.png 由 misaligned_bg.png 按 10 像素切片、splitY = 80 上下分别打乱得到
+  const targetBuffer = readFileSync(join(SYNTHETIC_DIR, 'misaligned_cut.png'))
+  const backgroundBuffer = readFileSync(join(SYNTHETIC_DIR, 'misaligned_bg.png'))
+  const shuffledBuffer = readFileSync(join(SYNTHETIC_DIR, 'shuffled_bg.png'))
+  const sliceOrder = [
+    ...Array.from({ length: 32 }, (_, i) => (i * 7) % 32),
+    ...Array.from({ length: 32 }, (_, i) => (i * 11) % 32),
+  ]
+
+  const expected = slideMatch(targetBuffer, backgroundBuffer)
+  const restored = restoreBackground(shuffledBuffer, sliceOrder, 10, 80)
+  t.deepEqual(slideMatch(targetBuffer, restored), expected)
+  t.deepEqual(slideMatchShuffled(targetBuffer, shuffledBuffer, sliceOrder, 10, 80), expected)
+
+  t.throws(() => restoreBackground(shuffledBuffer, sliceOrder, 10), {
+    message: /切片数量与图片宽度不一致/,
+  })
+  t.throws(() => restoreBackground(shuffledBuffer, sliceOrder.map((i) => i + 1), 10, 80), {
+    message: /切片下标 32 超出范围/,
+  })
+})
+
+// 双背景图对比
+test('slideComparison 通过完整背景图与缺口背景图的差异定位缺口', (t) => {
+  // 两张图片均为 JPEG，缺口为 (170, 70) 处 40x40 的区域
+  const fullBuffer = readFileSync(join(SYNTHETIC_DIR, 'comparison_full.jpg'))
+  const gappedBuffer = readFileSync(join(SYNTHETIC_DIR, 'comparison_gapped.jpg'))
+
+  const result = slideComparison(fullBuffer, gappedBuffer)
+  t.deepEqual([result.x1, result.y1, result.x2, result.y2], [170, 70, 210, 110])
+  t.true(result.confidence! > 0.9)
+
+  // 不过滤压缩噪声时差异区域会扩大
+  const noisy = slideComparison(fullBuffer, gappedBuffer, { diffThreshold: 0, minBlobArea: 1 })
+  t.true(noisy.x2 - noisy.x1 > 40)
+
+  t.throws(() => slideComparison(fullBuffer, fullBuffer), { message: /没有面积不小于 50 像素的差异区域/ })
+  t.throws(() => slideComparison(fullBuffer, readFileSync(join(SYNTHETIC_DIR, 'misaligned_cut.png'))), {
+    message: /两张背景图片的尺寸必须一致/,
+  })
+})
+
+// 双滑块匹配
+test('doubleSlideMatch 分别匹配两个滑块且结果互不重叠', (t) => {
+  // 40x40 的缺口位于 (200, 20)，30x30 的缺口位于 (30, 100)
+  const target1 = readFileSync(join(SYNTHETIC_DIR, 'double_cut1.png'))
+  const target2 = readFileSync(join(SYNTHETIC_DIR, 'double_cut2.png'))
+  const backgroundBuffer = readFileSync(join(SYNTHETIC_DIR, 'double_bg.png'))
+
+  const { first, second } = doubleSlideMatch(target1, target2, backgroundBuffer)
+  t.deepEqual([first.x1, first.y1, first.x2, first.y2], [200, 20, 240, 60])
+  t.deepEqual([second.x1, second.y1, second.x2, second.y2], [30, 100, 60, 130])
+
+  // 两个滑块相同时返回得分最高的两个不重叠位置，与多目标匹配的前两个结果一致
+  const multiTarget = readFileSync(join(SYNTHETIC_DIR, 'multi_cut.png'))
+  const multiBackground = readFileSync(join(SYNTHETIC_DIR, 'multi_bg.png'))
+  const same = doubleSlideMatch(multiTarget, multiTarget, multiBackground)
+  const [best, next] = findAllMatches(multiTarget, multiBackground)
+  t.deepEqual([same.first.x1, same.first.y1], [best.bbox.x1, best.bbox.y1])
+  t.deepEqual([same.second.x1, same.second.y1], [next.bbox.x1, next.bbox.y1])
+})
+
+// 内嵌滑块
+test('slideMatchEmbedded 从背景图中提取滑块并寻找缺口', (t) => {
+  // 40x40 的滑块贴在 (6, 60)，缺口位于 (200, 60)
+  const backgroundBuffer = readFileSync(join(SYNTHETIC_DIR, 'embedded_bg.png'))
+
+  // 自动检测：滑块区域可能包含 1 像素的轮廓边缘，但拖动距离不受影响
+  const detected = slideMatchEmbedded(backgroundBuffer)
+  t.true(Math.abs(detected.piece.x - 6) <= 1)
+  t.true(Math.abs(detected.piece.y - 60) <= 1)
+  t.true(Math.abs(detected.piece.width - 40) <= 2)
+  t.is(detected.bbox.targetX, detected.piece.x)
+  t.is(detected.bbox.x1 - detected.bbox.targetX, 194)
+
+  // 指定滑块区域
+  const hinted = slideMatchEmbedded(backgroundBuffer, { x: 6, y: 60, width: 40, height: 40 })
+  t.deepEqual(hinted.piece, { x: 6, y: 60, width: 40, height: 40 })
+  t.is(hinted.bbox.x1, 200)
+  t.true(Math.abs(hinted.bbox.y1 - 60) <= 1)
+
+  t.throws(() => slideMatchEmbedded(backgroundBuffer, { x: 300, y: 60, width: 40, height: 40 }), {
+    message: /滑块区域超出图片范围/,
+  })
+})
+
+// 从文件路径匹配
+test('从文件路径匹配失败时错误信息包含文件路径', (t) => {
+  const targetPath = join(SYNTHETIC_DIR, 'misaligned_cut.png')
+  const backgroundPath = join(SYNTHETIC_DIR, 'misaligned_bg.png')
+  const missingPath = join(SYNTHETIC_DIR, 'does_not_exist.png')
+
+  const bbox = improvedSlideMatchWithPath(targetPath, backgroundPath)
+  t.deepEqual(bbox, improvedSlideMatch(readFileSync(targetPath), readFileSync(backgroundPath)))
+
+  const targetError = t.throws(() => improvedSlideMatchWithPath(missingPath, backgroundPath))
+  t.true(targetError.message.includes(`无法读取目标图片 ${missingPath}`))
+
+  const backgroundError = t.throws(() => improvedSimpleSlideMatchWithPath(targetPath, missingPath))
+  t.true(backgroundError.message.includes(`无法读取背景图片 ${missingPath}`))
+
+  // 匹配过程中的错误同样附带两张图片的路径
+  const matchError = t.throws(() =>
+    improvedSlideMatchWithPath(targetPath, backgroundPath, null, { maxImagePixels: 100 }),
+  )
+  t.true(matchError.message.includes(targetPath))
+  t.true(matchError.message.includes(backgroundPath))
+  t.regex(matchError.message, /超过上限 100/)
+})
+
+// 裁剪透明边框
+test('从文件路径异步匹配，读取失败与匹配失败的错误码不同', async (t) => {
+  const targetPath = join(SYNTHETIC_DIR, 'misaligned_cut.png')
+  const backgroundPath = join(SYNTHETIC_DIR, 'misaligned_bg.png')
+  const missingPath = join(SYNTHETIC_DIR, 'does_not_exist.png')
+
+  t.deepEqual(
+    await improvedSlideMatchWithPathAsync(targetPath, backgroundPath),
+    improvedSlideMatchWithPath(targetPath, backgroundPath),
+  )
+  t.deepEqual(
+    await improvedSimpleSlideMatchWithPathAsync(targetPath, backgroundPath),
+    improvedSimpleSlideMatchWithPath(targetPath, backgroundPath),
+  )
+
+  const readError = await t.throwsAsync(improvedSlideMatchWithPathAsync(missingPath, backgroundPath))
+  t.is((readError as Error & { code: string }).code, ErrorCode.ReadFailed)
+  t.true(readError.message.includes(`无法读取目标图片 ${missingPath}`))
+
+  // 文本文件可以读取但无法解码
+  const textPath = join(IMAGES_DIR, 'pos.txt')
+  const decodeError = await t.throwsAsync(improvedSimpleSlideMatchWithPathAsync(targetPath, textPath))
+  t.is((decodeError as Error & { code: string }).code, ErrorCode.DecodeFailed)
+  t.true(decodeError.message.includes(textPath))
+})
+
+test('cropTransparent 与匹配函数的裁剪结果一致', (t) => {
+  // 44x44 的滑块四周有 3 像素透明边框
+  const targetBuffer = readFileSync(join(SYNTHETIC_DIR, 'misaligned_cut.png'))
+  const backgroundBuffer = readFileSync(join(SYNTHETIC_DIR, 'misaligned_bg.png'))
+
+  const bbox = slideMatch(targetBuffer, backgroundBuffer)
+  t.deepEqual([bbox.targetX, bbox.targetY], [3, 3])
+  const cropped = cropTransparent(targetBuffer)
+  t.false(cropped.fullyTransparent)
+  t.deepEqual([cropped.x, cropped.y], [bbox.targetX, bbox.targetY])
+  t.deepEqual([cropped.width, cropped.height], [bbox.x2 - bbox.x1, bbox.y2 - bbox.y1])
+
+  // 裁剪后的图片不再有透明边框
+  const again = slideMatch(cropped.data, backgroundBuffer)
+  t.deepEqual([again.targetX, again.targetY, again.x1, again.y1], [0, 0, bbox.x1, bbox.y1])
+
+  // 所有像素都视为透明时返回原图
+  const transparent = cropTransparent(targetBuffer, 255)
+  t.true(transparent.fullyTransparent)
+  t.deepEqual([transparent.x, transparent.y], [0, 0])
+
+  t.throws(() => cropTransparent(targetBuffer, 256), { message: /透明度阈值必须在 0-255 范围内/ })
+})
+
+// Canny 边缘检测
+test('cannyEdges 使用与匹配函数相同的阈值', (t) => {
+  const targetBuffer = readFileSync(join(IMAGES_DIR, 'cut1.png'))
+  const backgroundBuffer = readFileSync(join(IMAGES_DIR, 'bg1.png'))
+
+  const fixed = cannyEdges(backgroundBuffer)
+  t.deepEqual(fixed.thresholds, [100, 200])
+  t.deepEqual([...fixed.data.subarray(1, 4)], [...Buffer.from('PNG')])
+
+  // 自适应阈值与改进版函数的背景图阈值一致
+  const { metadata } = improvedSlideMatch(targetBuffer, backgroundBuffer)
+  t.deepEqual(cannyEdges(backgroundBuffer, { adaptive: true }).thresholds, metadata!.backgroundThresholds)
+
+  // 显式指定的阈值优先
+  t.deepEqual(cannyEdges(backgroundBuffer, { adaptive: true, low: 30, high: 90 }).thresholds, [30, 90])
+  t.throws(() => cannyEdges(backgroundBuffer, { low: 210 }), { message: /低阈值必须小于高阈值/ })
+})
+
+// 图片统计信息
+test('computeImageStats 计算灰度统计信息', (t) => {
+  // 纯色图片：标准差和熵均为 0
+  const uniform = computeImageStats(readFileSync(join(SYNTHETIC_DIR, 'uniform_white_bg.png')))
+  t.is(uniform.stdDev, 0)
+  t.is(uniform.entropy, 0)
+  t.is(uniform.min, uniform.max)
+  t.is(uniform.mean, uniform.min)
+
+  const stats = computeImageStats(readFileSync(join(IMAGES_DIR, 'bg1.png')))
+  t.true(stats.min <= stats.mean && stats.mean <= stats.max)
+  t.true(stats.stdDev > 0)
+  t.true(stats.entropy > 0 && stats.entropy <= 8)
+
+  t.throws(() => computeImageStats(Buffer.from('not an image')), { message: /计算图片统计信息失败/ })
+})
+
+// 原始图片模板匹配
+test('matchTemplateRaw 在单通道原始图片上匹配', (t) => {
+  // 20x10 的背景中 (12, 4) 处有一个 4x3 的图案
+  const pattern = [10, 200, 30, 90, 250, 0, 120, 60, 40, 180, 220, 5]
+  const background = Buffer.alloc(20 * 10, 100)
+  pattern.forEach((v, i) => {
+    background[(4 + Math.floor(i / 4)) * 20 + 12 + (i % 4)] = v
+  })
+  const template = { data: Buffer.from(pattern), width: 4, height: 3 }
+
+  const result = matchTemplateRaw(template, { data: background, width: 20, height: 10 }, undefined, true)
+  t.deepEqual([result.bbox.x1, result.bbox.y1, result.bbox.x2, result.bbox.y2], [12, 4, 16, 7])
+  t.true(Math.abs(result.score - 1) < 1e-4)
+  t.deepEqual([result.heatmap!.width, result.heatmap!.height], [17, 8])
+
+  // 差值平方和取最小值
+  const sse = matchTemplateRaw(template, { data: background, width: 20, height: 10 }, TemplateMatchMethod.SumOfSquaredErrors)
+  t.deepEqual([sse.bbox.x1, sse.bbox.y1, sse.score], [12, 4, 0])
+  t.is(sse.heatmap, undefined)
+
+  t.throws(() => matchTemplateRaw(template, { data: background, width: 20, height: 9 }), {
+    message: /背景图片数据长度 200 与尺寸 20x9 不一致/,
+  })
+  t.throws(() => matchTemplateRaw({ data: background, width: 20, height: 10 }, template), {
+    message: /背景图片的宽度必须大于等于模板图片的宽度/,
+  })
+})
+
+// 背景图直接转换为灰度图
+test('灰度背景图与彩色背景图的匹配结果一致', (t) => {
+  const { cut, bg } = testCases[0]
+  const targetBuffer = readFileSync(cut)
+  // bg1_gray.png 是 bg1.png 转换为 8 位灰度图后保存的，解码后无需再做颜色转换
+  const grayBuffer = readFileSync(join(SYNTHETIC_DIR, 'bg1_gray.png'))
+  const colorBuffer = readFileSync(bg)
+
+  t.deepEqual(slideMatch(targetBuffer, grayBuffer), slideMatch(targetBuffer, colorBuffer))
+  t.deepEqual(simpleSlideMatch(targetBuffer, grayBuffer), simpleSlideMatch(targetBuffer, colorBuffer))
+  t.deepEqual(improvedSlideMatch(targetBuffer, grayBuffer), improvedSlideMatch(targetBuffer, colorBuffer))
+  t.deepEqual(
+    improvedSimpleSlideMatch(targetBuffer, grayBuffer),
+    improvedSimpleSlideMatch(targetBuffer, colorBuffer),
+  )
+})
+
+// 灰度滑块图
+test('8 位灰度滑块图直接作为灰度图匹配', (t) => {
+  // cut1_gray.png 是 cut1.png 合成到黑色背景后转换的 8 位灰度图（没有透明通道）
+  const targetBuffer = readFileSync(join(SYNTHETIC_DIR, 'cut1_gray.png'))
+  const backgroundBuffer = readFileSync(join(SYNTHETIC_DIR, 'bg1_gray.png'))
+
+  const result = slideMatch(targetBuffer, backgroundBuffer)
+  t.deepEqual([result.targetX, result.targetY, result.x1, result.y1], [0, 0, 149, 95])
+  // 没有透明通道时不裁剪、不使用透明度掩码，与 simpleSlideMatch 的结果一致
+  t.deepEqual(result, simpleSlideMatch(targetBuffer, backgroundBuffer))
+})
+
+// 清除背景中的文字区域
+test('maskTextRegions 清除文字条带的边缘', (t) => {
+  // text_bg.png 顶部有一行与缺口形状相同的“口”字，缺口位于 (220, 90)
+  const targetBuffer = readFileSync(join(SYNTHETIC_DIR, 'text_cut.png'))
+  const backgroundBuffer = readFileSync(join(SYNTHETIC_DIR, 'text_bg.png'))
+
+  const fooled = slideMatch(targetBuffer, backgroundBuffer)
+  t.true(fooled.y1 < 30)
+
+  const masked = slideMatch(targetBuffer, backgroundBuffer, { maskTextRegions: true })
+  t.deepEqual([masked.x1, masked.y1], [220, 90])
+  const improved = improvedSlideMatch(targetBuffer, backgroundBuffer, 0.3, { maskTextRegions: true })
+  t.deepEqual([improved.x1, improved.y1], [220, 90])
+
+  // 没有文字的背景不受影响
+  const { cut, bg } = testCases[0]
+  t.deepEqual(
+    slideMatch(readFileSync(cut), readFileSync(bg), { maskTextRegions: true }),
+    slideMatch(readFileSync(cut), readFileSync(bg)),
+  )
+})
+
+// 旋转验证码
+test('rotationMatch 找出目标图片的旋转角度', (t) => {
+  // rotation_cut.png 是背景图 (100, 30) 处的圆形区域逆时针旋转 30 度得到的
+  const targetBuffer = readFileSync(join(SYNTHETIC_DIR, 'rotation_cut.png'))
+  const backgroundBuffer = readFileSync(join(SYNTHETIC_DIR, 'rotation_bg.png'))
+
+  // 两次插值会带来少量偏差，结果与正确角度相差不超过一个步长
+  const result = rotationMatch(targetBuffer, backgroundBuffer)
+  t.true(Math.abs(result.angleDeg - 30) <= 5)
+  t.true(result.confidence > 0.5)
+  t.is(rotationMatch(targetBuffer, backgroundBuffer, 15).angleDeg, 30)
+
+  t.throws(() => rotationMatch(targetBuffer, backgroundBuffer, 0), {
+    message: /旋转角度步长必须大于 0 且不超过 360/,
+  })
+})
+
+test('GPU 后端与 CPU 的匹配结果一致，GPU 不可用时回退到 CPU', (t) => {
+  const targetBuffer = readFileSync(join(SYNTHETIC_DIR, 'misaligned_cut.png'))
+  const backgroundBuffer = readFileSync(join(SYNTHETIC_DIR, 'misaligned_bg.png'))
+  const options = { returnHeatmap: true }
+
+  const cpu = slideMatch(targetBuffer, backgroundBuffer, options)
+  try {
+    setBackend(MatchBackend.Gpu)
+    const gpu = slideMatch(targetBuffer, backgroundBuffer, options)
+    t.is(gpu.x1, cpu.x1)
+    t.is(gpu.y1, cpu.y1)
+    const cpuData = cpu.heatmap!.data
+    const gpuData = gpu.heatmap!.data
+    t.is(gpuData.length, cpuData.length)
+    t.true(gpuData.every((score, i) => Math.abs(score - cpuData[i]) < 1e-4))
+  } finally {
+    setBackend(MatchBackend.Cpu)
+  }
+})
+
+test('matchTemplateRaw 归一化互相关与逐窗口计算结果一致', (t) => {
+  // 稀疏的伪随机图片，模拟边缘图
+  let seed = 7
+  const next = () => (seed = (seed * 1103515245 + 12345) % 2147483648) / 2147483648
+  const sparse = (length: number) => Buffer.from(Array.from({ length }, () => (next() < 0.2 ? Math.floor(next() * 256) : 0)))
+
+  // 大于 64x64 时使用积分图实现，小图使用 imageproc 的实现；
+  // 200 宽的背景图每行 189 个位置，覆盖 SIMD 分块（每次 16 个像素）和标量尾部
+  for (const [width, height] of [[96, 80], [200, 100], [30, 20]]) {
+    const background = { data: sparse(width * height), width, height }
+    const template = { data: sparse(12 * 9), width: 12, height: 9 }
+    const { heatmap, bbox } = matchTemplateRaw(template, background, undefined, true)
+
+    let best = { score: -Infinity, x: 0, y: 0 }
+    for (let y = 0; y + 9 <= height; y++) {
+      for (let x = 0; x + 12 <= width; x++) {
+        let it = 0
+        let ii = 0
+        let tt = 0
+        for (let j = 0; j < 9; j++) {
+          for (let i = 0; i < 12; i++) {
+            const v = background.data[(y + j) * width + x + i]
+            const w = template.data[j * 12 + i]
+            it += v * w
+            ii += v * v
+            tt += w * w
+          }
+        }
+        const norm = Math.sqrt(ii * tt)
+        const score = norm > 0 ? it / norm : it
+        t.true(Math.abs(heatmap!.data[y * heatmap!.width + x] - score) < 1e-4)
+        if (score > best.score) best = { score, x, y }
+      }
+    }
+    t.deepEqual([bbox.x1, bbox.y1], [best.x, best.y])
+  }
+})
+
+// 背景掩码
+test('背景掩码限制参与匹配的区域', (t) => {
+  const targetBuffer = readFileSync(join(SYNTHETIC_DIR, 'misaligned_cut.png'))
+  const backgroundBuffer = readFileSync(join(SYNTHETIC_DIR, 'misaligned_bg.png'))
+
+  // 不使用掩码时匹配到 x = 180 附近的缺口
+  const unmasked = slideMatch(targetBuffer, backgroundBuffer)
+  t.is(unmasked.x1, 180)
+
+  // 掩码只保留 x 在 40-130 之间的区域，右侧的缺口不再参与匹配
+  const mask = readFileSync(join(SYNTHETIC_DIR, 'misaligned_mask_left.png'))
+  const masked = slideMatchWithMask(targetBuffer, backgroundBuffer, mask)
+  t.true(masked.x1 < 130)
+  t.is(masked.targetX, unmasked.targetX)
+
+  // 掩码尺寸必须与背景一致
+  t.throws(
+    () => slideMatchWithMask(targetBuffer, backgroundBuffer, readFileSync(join(SYNTHETIC_DIR, 'mask_100x100.png'))),
+    { message: /掩码图片的尺寸必须与背景图片一致/ },
+  )
+})
+
+// RGB 颜色模式
+test('colorMode rgb 区分只有颜色不同的假缺口', (t) => {
+  // 两个描边相同的缺口，内部各有一块与背景亮度相同、只有颜色不同的标记；
+  // 真缺口在 (200, 60)，目标图片含 2 像素外边距
+  const targetBuffer = readFileSync(join(SYNTHETIC_DIR, 'color_decoy_cut.png'))
+  const backgroundBuffer = readFileSync(join(SYNTHETIC_DIR, 'color_decoy_bg.png'))
+
+  // 灰度图中两个缺口完全相同，匹配到左侧的假缺口
+  t.is(slideMatch(targetBuffer, backgroundBuffer).x1, 58)
+
+  const bbox = slideMatch(targetBuffer, backgroundBuffer, { colorMode: ColorMode.Rgb })
+  t.is(bbox.x1, 198)
+  t.is(bbox.y1, 58)
+
+  const improved = improvedSlideMatch(targetBuffer, backgroundBuffer, null, { colorMode: ColorMode.Rgb })
+  t.is(improved.x1, 198)
+  t.true(improved.confidence! > 0 && improved.confidence! <= 1)
+})
+
+// 线性光强灰度转换
+test('linearLight 恢复暗背景上饱和色的缺口描边', (t) => {
+  // 暗绿色夜景，缺口在 (210, 60)，3 像素红色描边与背景的 sRGB 加权灰度相同；
+  // 目标图片含 10 像素透明边
+  const targetBuffer = readFileSync(join(SYNTHETIC_DIR, 'night_cut.png'))
+  const backgroundBuffer = readFileSync(join(SYNTHETIC_DIR, 'night_bg.png'))
+
+  // 默认的灰度转换中描边消失，目标图片没有边缘
+  const gray = improvedSlideMatch(targetBuffer, backgroundBuffer)
+  t.is(gray.confidence, 0)
+  t.not(gray.x1, 210)
+
+  const bbox = improvedSlideMatch(targetBuffer, backgroundBuffer, null, { linearLight: true })
+  t.is(bbox.x1, 210)
+  t.is(bbox.y1, 60)
+  t.false(bbox.metadata!.usedFallback)
+
+  // 统计信息使用同样的灰度图：描边与背景的灰度不再相同，均值和熵都变大
+  const stats = computeImageStats(backgroundBuffer)
+  const linear = computeImageStats(backgroundBuffer, { linearLight: true })
+  t.true(linear.mean > stats.mean)
+  t.true(linear.entropy > stats.entropy)
+})
+
+// 跳过边缘检测的灰度匹配
+test('edgeMode none 在灰度图上直接匹配暗色遮罩缺口', (t) => {
+  // 缺口是没有描边的半透明暗色遮罩，位于 (150, 50)；目标图片四周有 4 像素透明边
+  const targetBuffer = readFileSync(join(SYNTHETIC_DIR, 'shaded_cut.png'))
+  const backgroundBuffer = readFileSync(join(SYNTHETIC_DIR, 'shaded_bg.png'))
+
+  // 背景过渡平滑，几乎没有边缘，默认的边缘匹配无法使用
+  t.throws(() => slideMatch(targetBuffer, backgroundBuffer), { message: /背景图片边缘过少/ })
+  t.not(slideMatch(targetBuffer, backgroundBuffer, { edgeDensityThreshold: 0 }).x1, 150)
+
+  const bbox = slideMatch(targetBuffer, backgroundBuffer, { edgeMode: EdgeMode.None })
+  t.is(bbox.targetX, 4)
+  t.is(bbox.targetY, 4)
+  t.is(bbox.x1, 150)
+  t.is(bbox.y1, 50)
+
+  // 改进版：不使用阈值，也不会回退
+  const improved = improvedSlideMatch(targetBuffer, backgroundBuffer, 0.3, { edgeMode: EdgeMode.None })
+  t.is(improved.x1, 150)
+  t.is(improved.y1, 50)
+  t.true(improved.confidence! > 0.9)
+  t.false(improved.metadata?.usedFallback)
+  t.deepEqual(improved.metadata?.targetThresholds, [])
+  t.deepEqual(improved.metadata?.backgroundThresholds, [])
+})
+
+// Canny 前的高斯模糊
+test('autoSigma 按背景图分辨率选择高斯模糊 σ，cannySigma 优先', (t) => {
+  const targetBuffer = readFileSync(join(IMAGES_DIR, 'cut1.png'))
+  const backgroundBuffer = readFileSync(join(IMAGES_DIR, 'bg1.png'))
+
+  // 301x199 的背景图 sqrt(宽 × 高) / 500 < 1，取下限 1.0
+  const auto = slideMatch(targetBuffer, backgroundBuffer, { autoSigma: true, returnScoreProfile: true })
+  t.deepEqual(auto, slideMatch(targetBuffer, backgroundBuffer, { cannySigma: 1, returnScoreProfile: true }))
+  t.deepEqual([auto.x1, auto.y1], [149, 95])
+
+  t.deepEqual(
+    slideMatch(targetBuffer, backgroundBuffer, { autoSigma: true, cannySigma: 1.2, returnScoreProfile: true }),
+    slideMatch(targetBuffer, backgroundBuffer, { cannySigma: 1.2, returnScoreProfile: true }),
+  )
+
+  t.throws(() => slideMatch(targetBuffer, backgroundBuffer, { cannySigma: 0 }), {
+    message: /高斯模糊 σ 必须是大于 0 的有限数值/,
+  })
+})
+
+// 中位数自适应阈值策略
+test('中位数阈值策略在灰度分布偏斜的图片上边缘更充分', (t) => {
+  // 上方大片明亮天空，下方是低对比度的暗色场景，缺口位于 (210, 95)
+  const targetBuffer = readFileSync(join(SYNTHETIC_DIR, 'bright_sky_cut.png'))
+  const backgroundBuffer = readFileSync(join(SYNTHETIC_DIR, 'bright_sky_bg.png'))
+
+  // 默认策略：天空拉高了均值和标准差，背景高阈值被钳制到 250，缺口边缘丢失
+  const meanBased = improvedSimpleSlideMatch(targetBuffer, backgroundBuffer, 0)
+  t.deepEqual(
+    meanBased,
+    improvedSimpleSlideMatch(targetBuffer, backgroundBuffer, 0, {
+      thresholdStrategy: ThresholdStrategy.MeanStdDev,
+    }),
+  )
+  t.is(meanBased.metadata?.backgroundThresholds[1], 250)
+  t.not(meanBased.x1, 207)
+
+  // 中位数策略：阈值跟随暗色场景，能找到缺口（目标图片含 3 像素外边距）
+  const medianBased = improvedSimpleSlideMatch(targetBuffer, backgroundBuffer, 0, {
+    thresholdStrategy: ThresholdStrategy.Median,
+  })
+  t.false(medianBased.metadata?.usedFallback)
+  t.true(medianBased.metadata!.backgroundThresholds[1] < 100)
+  t.is(medianBased.x1, 207)
+  t.is(medianBased.y1, 92)
+  t.true(medianBased.confidence! > meanBased.confidence!)
+})
+
+// 滑块形状识别
+test('滑块形状识别', (t) => {
+  const shapeOf = (file: string) => classifyTargetShape(readFileSync(file))
+
+  for (const { cut } of testCases) {
+    t.is(shapeOf(cut), SliderShape.PuzzlePiece)
+  }
+  t.is(shapeOf(join(SYNTHETIC_DIR, 'puzzle_cut.png')), SliderShape.PuzzlePiece)
+  t.is(shapeOf(join(SYNTHETIC_DIR, 'misaligned_cut.png')), SliderShape.Rectangle)
+  t.is(shapeOf(join(SYNTHETIC_DIR, 'circle_cut.png')), SliderShape.Circle)
+  t.is(shapeOf(join(SYNTHETIC_DIR, 'ring_cut.png')), SliderShape.Unknown)
+
+  t.throws(() => classifyTargetShape(Buffer.from('not an image')), { message: /滑块形状识别失败/ })
+})
+
+// 滑块质量评估
+test('targetQualityScore 评估滑块质量', (t) => {
+  const qualityOf = (file: string) => targetQualityScore(readFileSync(file))
+
+  for (const { cut } of testCases) {
+    const quality = qualityOf(cut)
+    t.true(quality.overallScore > 0.8)
+    t.true(quality.edgeDensity > 0 && quality.edgeDensity <= 1)
+    t.true(quality.opaqueFraction > 0 && quality.opaqueFraction <= 1)
+    t.true(Math.abs(quality.aspectRatio - 1.22) < 0.01)
+  }
+
+  // 没有不透明像素、只有几个不透明像素或没有边缘的滑块评分为 0
+  t.deepEqual(qualityOf(join(SYNTHETIC_DIR, 'transparent_cut.png')), {
+    edgeDensity: 0,
+    opaqueFraction: 0,
+    aspectRatio: 0,
+    overallScore: 0,
+  })
+  t.is(qualityOf(join(SYNTHETIC_DIR, 'tiny_alpha_cut.png')).overallScore, 0)
+  t.is(qualityOf(join(SYNTHETIC_DIR, 'low_contrast_cut.png')).overallScore, 0)
+
+  t.throws(() => targetQualityScore(Buffer.from('not an image')), { message: /目标图片质量评估失败/ })
+})
+
+// 由中心点构造结果
+test('bboxFromCenter 由中心点和尺寸构造结果', (t) => {
+  const bbox = bboxFromCenter(100, 50, 40, 30)
+  t.deepEqual([bbox.targetX, bbox.targetY, bbox.x1, bbox.y1, bbox.x2, bbox.y2], [0, 0, 80, 35, 120, 65])
+
+  // 靠近左上角时不小于 0
+  const clamped = bboxFromCenter(10, 5, 40, 30)
+  t.deepEqual([clamped.x1, clamped.y1, clamped.x2, clamped.y2], [0, 0, 30, 20])
+
+  // 靠近 u32 上限时不溢出
+  const max = 0xffffffff
+  const saturated = bboxFromCenter(max, max - 5, 40, 30)
+  t.deepEqual([saturated.x1, saturated.y1, saturated.x2, saturated.y2], [max - 20, max - 20, max, max])
+})
+
+// 拖动轨迹生成
+test('bboxUnion / bboxIntersection 合并匹配区域', (t) => {
+  const a = { ...bboxFromCenter(50, 50, 20, 20), targetX: 1, targetY: 2, confidence: 0.4 }
+  const b = { ...bboxFromCenter(65, 45, 20, 20), targetX: 3, targetY: 4, confidence: 0.8 }
+
+  const union = bboxUnion(a, b)
+  t.deepEqual([union.x1, union.y1, union.x2, union.y2], [40, 35, 75, 60])
+  t.deepEqual([union.targetX, union.targetY, union.confidence], [3, 4, 0.8])
+
+  const intersection = bboxIntersection(a, b)
+  t.truthy(intersection)
+  t.deepEqual([intersection!.x1, intersection!.y1, intersection!.x2, intersection!.y2], [55, 40, 60, 55])
+  t.deepEqual([intersection!.targetX, intersection!.targetY], [3, 4])
+
+  // 只有边相接时不算重叠
+  t.is(bboxIntersection(a, bboxFromCenter(70, 50, 20, 20)), null)
+})
+
+test('生成拖动轨迹', (t) => {
+  const path = generateDragPath(10, 100, 210, 100, 20, { totalDurationMs: 800, jitterAmplitude: 2 })
+
+  t.is(path.length, 20)
+  t.deepEqual(path[0], { x: 10, y: 100, tMs: 0 })
+  t.deepEqual(path[path.length - 1], { x: 210, y: 100, tMs: 800 })
+
+  for (let i = 1; i < path.length; i++) {
+    t.true(path[i].x >= path[i - 1].x)
+    t.true(path[i].tMs >= path[i - 1].tMs)
+    t.true(Math.abs(path[i].y - 100) <= 2)
+  }
+
+  // 先加速后减速：中段的位移大于首尾两段
+  const first = path[1].x - path[0].x
+  const middle = path[10].x - path[9].x
+  const last = path[19].x - path[18].x
+  t.true(middle > first)
+  t.true(middle > last)
+
+  t.throws(() => generateDragPath(0, 0, 10, 0, 1), { message: /轨迹点数量必须大于等于 2/ })
+})
+
+// 贝塞尔曲线拖动轨迹
+test('生成贝塞尔曲线拖动轨迹', (t) => {
+  const start = { x: 10, y: 100, tMs: 50 }
+  const end = { x: 210, y: 100, tMs: 0 }
+
+  // 指定控制点：对称的上拱曲线
+  const path = generateBezierDragPath(start, end, [[70, 40], [150, 40]], 21, 1000)
+  t.is(path.length, 21)
+  t.deepEqual(path[0], { x: 10, y: 100, tMs: 50 })
+  t.deepEqual(path[20], { x: 210, y: 100, tMs: 1050 })
+  t.deepEqual(path[10], { x: 110, y: 55, tMs: 550 })
+  for (let i = 1; i < path.length; i++) {
+    t.is(path[i].tMs - path[i - 1].tMs, 50)
+  }
+
+  // 自动生成控制点：首尾准确，中段偏离直线
+  const auto = generateBezierDragPath(start, end, [], 30, 1200)
+  t.is(auto.length, 30)
+  t.deepEqual(auto[0], { x: 10, y: 100, tMs: 50 })
+  t.deepEqual(auto[29], { x: 210, y: 100, tMs: 1250 })
+  t.true(auto.some((p) => p.y !== 100))
+
+  t.throws(() => generateBezierDragPath(start, end, [[0, 0]], 10, 1000), {
+    message: /需要 2 个控制点/,
+  })
+  t.throws(() => generateBezierDragPath(start, end, [], 1, 1000), { message: /轨迹点数量必须大于等于 2/ })
+})
+
+// 显式指定 Canny 阈值
+test('显式指定 Canny 阈值', (t) => {
+  const { cut, bg } = testCases[0]
+  const targetBuffer = readFileSync(cut)
+  const backgroundBuffer = readFileSync(bg)
+
+  // 与原版固定阈值相同时结果不变
+  const fixed = slideMatch(targetBuffer, backgroundBuffer, { cannyLow: 100, cannyHigh: 200 })
+  t.deepEqual(fixed, slideMatch(targetBuffer, backgroundBuffer))
+
+  // 目标图和背景图分别指定
+  const bbox = improvedSlideMatch(targetBuffer, backgroundBuffer, 0.3, {
+    cannyLow: 80,
+    cannyHigh: 180,
+    backgroundCannyHigh: 220,
+  })
+  t.false(bbox.metadata?.usedFallback)
+  t.deepEqual(bbox.metadata?.targetThresholds, [80, 180])
+  t.deepEqual(bbox.metadata?.backgroundThresholds, [80, 220])
+
+  t.throws(() => slideMatch(targetBuffer, backgroundBuffer, { cannyLow: 200, cannyHigh: 100 }), {
+    message: /低阈值必须小于高阈值/,
+  })
+  t.throws(() => slideMatch(targetBuffer, backgroundBuffer, { targetCannyHigh: 300 }), {
+    message: /targetCannyHigh 必须在 0-255 范围内/,
+  })
+  // 只指定一侧时与另一侧的默认值比较
+  t.throws(() => slideMatch(targetBuffer, backgroundBuffer, { cannyLow: 220 }), {
+    message: /低阈值必须小于高阈值/,
+  })
+})
@@ -0,0 +1,175 @@
+use std::io::Cursor;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use slide_match::cache::BackgroundCache;
+use slide_match::ncc::match_template_normalized;
+use slide_match::test_utils::generate_slider_pair;
+use slide_match::{
+  improved_slide_match_internal, simple_slide_match_internal, slide_match_internal,
+  SlideMatchConfig,
+};
+
+// 背景图尺寸：小、中、大
+const SIZES: [(&str, u32, u32); 3] = [
+  ("small", 300, 150),
+  ("medium", 600, 300),
+  ("large", 1200, 600),
+];
+
+// 改进版匹配的置信度阈值（与 JS 端默认值一致）
+const CONFIDENCE_THRESHOLD: f32 = 0.3;
+
+// 各尺寸的 (名称, 滑块 PNG, 背景图 PNG)，缺口位于背景图宽度的 2/3 处
+fn fixtures() -> Vec<(&'static str, Vec<u8>, Vec<u8>)> {
+  SIZES
+    .iter()
+    .map(|&(name, width, height)| {
+      let (target, background) = generate_slider_pair(width, height, width * 2 / 3);
+      (name, target, background)
+    })
+    .collect()
+}
+
+fn bench_slide_match(c: &mut Criterion) {
+  let config = SlideMatchConfig::default();
+  let mut group = c.benchmark_group("slide_match");
+  for (name, target, background) in fixtures() {
+    group.bench_with_input(BenchmarkId::from_parameter(name), &name, |b, _| {
+      b.iter(|| slide_match_internal(&target, &background, None, &config).unwrap())
+    });
+  }
+  group.finish();
+}
+
+fn bench_improved_slide_match(c: &mut Criterion) {
+  let config = SlideMatchConfig::default();
+  let mut group = c.benchmark_group("improved_slide_match");
+  for (name, target, background) in fixtures() {
+    group.bench_with_input(BenchmarkId::from_parameter(name), &name, |b, _| {
+      b.iter(|| {
+        improved_slide_match_internal(&target, &background, CONFIDENCE_THRESHOLD, &config).unwrap()
+      })
+    });
+  }
+  group.finish();
+}
+
+fn bench_simple_slide_match(c: &mut Criterion) {
+  let config = SlideMatchConfig::default();
+  let mut group = c.benchmark_group("simple_slide_match");
+  for (name, target, background) in fixtures() {
+    group.bench_with_input(BenchmarkId::from_parameter(name), &name, |b, _| {
+      b.iter(|| simple_slide_match_internal(&target, &background, &config).unwrap())
+    });
+  }
+  group.finish();
+}
+
+// 单通道灰度 PNG 输入：目标图片跳过透明裁剪和 RGBA -> 灰度转换
+fn bench_gray_input(c: &mut Criterion) {
+  let to_gray = |bytes: &[u8]| {
+    let mut png = Vec::new();
+    image::load_from_memory(bytes)
+      .unwrap()
+      .to_luma8()
+      .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+      .unwrap();
+    png
+  };
+  let config = SlideMatchConfig::default();
+  let mut group = c.benchmark_group("slide_match_gray_input");
+  for (name, target, background) in fixtures() {
+    let (target, background) = (to_gray(&target), to_gray(&background));
+    group.bench_with_input(BenchmarkId::from_parameter(name), &name, |b, _| {
+      b.iter(|| slide_match_internal(&target, &background, None, &config).unwrap())
+    });
+  }
+  group.finish();
+}
+
+// 启用 `cacheBackground`：预热后每次匹配都命中缓存，只剩解码、目标图预处理和模板匹配
+fn bench_cached_background(c: &mut Criterion) {
+  let config = SlideMatchConfig {
+    cache_background: Some(true),
+    ..Default::default()
+  };
+  let mut group = c.benchmark_group("slide_match_cached_background");
+  for (name, target, background) in fixtures() {
+    BackgroundCache::global().clear();
+    slide_match_internal(&target, &background, None, &config).unwrap();
+    group.bench_with_input(BenchmarkId::from_parameter(name), &name, |b, _| {
+      b.iter(|| slide_match_internal(&target, &background, None, &config).unwrap())
+    });
+  }
+  group.finish();
+}
+
+// 只测边缘图上的归一化互相关（600×300），对比 `simd` 特性开关前后的耗时：
+// cargo bench --features test-utils -- correlation --save-baseline scalar
+// cargo bench --features test-utils,simd -- correlation --baseline scalar
+fn bench_correlation(c: &mut Criterion) {
+  let (target, background) = generate_slider_pair(600, 300, 400);
+  let edges = |bytes: &[u8]| {
+    let gray = image::load_from_memory(bytes).unwrap().to_luma8();
+    imageproc::edges::canny(&gray, 50.0, 150.0)
+  };
+  let (target, background) = (edges(&target), edges(&background));
+
+  c.bench_function("correlation/medium", |b| {
+    b.iter(|| match_template_normalized(&background, &target))
+  });
+}
+
+// 在同一份构建中对比互相关逐行累加的标量实现和 SIMD 实现（600×300 边缘图，按 `ncc` 中的方式遍历模板的
+// 非零像素和输出行）：cargo bench --features test-utils,simd -- row_kernels
+#[cfg(feature = "simd")]
+fn bench_row_kernels(c: &mut Criterion) {
+  use slide_match::ncc::row_kernels::{add_scaled_row, add_scaled_row_scalar};
+
+  let (target, background) = generate_slider_pair(600, 300, 400);
+  let edges = |bytes: &[u8]| {
+    let gray = image::load_from_memory(bytes).unwrap().to_luma8();
+    imageproc::edges::canny(&gray, 50.0, 150.0)
+  };
+  let (target, background) = (edges(&target), edges(&background));
+  let template_pixels: Vec<_> = target
+    .enumerate_pixels()
+    .filter(|(_, _, t)| t[0] != 0)
+    .map(|(dx, dy, t)| (dx as usize, dy as usize, t[0] as u16))
+    .collect();
+  let image_width = background.width() as usize;
+  let out_width = image_width - target.width() as usize + 1;
+  let out_height = background.height() as usize - target.height() as usize + 1;
+  let pixels = background.as_raw();
+
+  let correlate = |kernel: fn(&mut [u32], &[u8], u16)| {
+    let mut correlation = vec![0u32; out_width * out_height];
+    for &(dx, dy, t) in &template_pixels {
+      for (y, row) in correlation.chunks_exact_mut(out_width).enumerate() {
+        let start = (y + dy) * image_width + dx;
+        kernel(row, &pixels[start..start + out_width], t);
+      }
+    }
+    correlation
+  };
+
+  let mut group = c.benchmark_group("row_kernels");
+  group.bench_function("scalar", |b| b.iter(|| correlate(add_scaled_row_scalar)));
+  group.bench_function("simd", |b| b.iter(|| correlate(add_scaled_row)));
+  group.finish();
+}
+
+#[cfg(not(feature = "simd"))]
+fn bench_row_kernels(_: &mut Criterion) {}
+
+criterion_group!(
+  benches,
+  bench_slide_match,
+  bench_improved_slide_match,
+  bench_simple_slide_match,
+  bench_gray_input,
+  bench_cached_background,
+  bench_correlation,
+  bench_row_kernels
+);
+criterion_main!(benches);
@@ -1,5 +1,462 @@
 /* auto-generated by NAPI-RS */
 /* eslint-disable */
+/**
+ * 完整的模板匹配得分矩阵（归一化互相关，按行存储）
+ *
+ * `data[row * cols + col]` 为目标图片（裁剪透明边框后）左上角位于背景图 (col, row) 时的得分。
+ * 指定 `fixedY` 时只包含搜索的行，第 0 行对应 `y = fixedY - fixedYTolerance`（不小于 0）。
+ */
+export declare class ScoreMatrix {
+  /** 按行存储的全部得分，每次读取都会复制一份，需要多次使用时先保存到变量中 */
+  get data(): Float32Array
+  /** 行数，即目标图片左上角可取的 y 的个数 */
+  readonly rows: number
+  /** 列数，即目标图片左上角可取的 x 的个数 */
+  readonly cols: number
+  /** 第 `y` 行、第 `x` 列的得分，超出范围时报错 */
+  at(x: number, y: number): number
+}
+/** 指定 `backgroundTargetWidth` 时返回坐标所在的背景图坐标系 */
+export declare enum BackgroundCoordinateSpace {
+  /** 重采样后的背景图（默认），与目标图片匹配时所用的尺寸一致 */
+  Resized = 'resized',
+  /** 原始背景图 */
+  Natural = 'natural'
+}
+
+/**
+ * 由中心点和尺寸构造 `SlideBBox`，见 `SlideBBox::from_center`
+ *
+ * # 参数
+ * - cx / cy: 中心点坐标
+ * - w / h: 宽度和高度
+ */
+export declare function bboxFromCenter(cx: number, cy: number, w: number, h: number): SlideBBox
+
+/**
+ * 两个匹配区域的重叠部分，不重叠时返回 null，见 `SlideBBox::intersection`
+ *
+ * `targetX/targetY` 和 `confidence` 取自得分较高的一个
+ */
+export declare function bboxIntersection(a: SlideBBox, b: SlideBBox): SlideBBox | null
+
+/**
+ * 同时包含两个匹配区域的最小矩形，见 `SlideBBox::union`
+ *
+ * 用于合并多种算法的结果，`targetX/targetY` 和 `confidence` 取自得分较高的一个
+ */
+export declare function bboxUnion(a: SlideBBox, b: SlideBBox): SlideBBox
+
+/**
+ * 内置基准测试：合成一张带缺口的背景图和对应的滑块，按 `slideMatch` 的默认流程重复匹配，
+ * 返回解码、Canny 边缘检测和模板匹配各阶段的耗时统计
+ *
+ * 用于评估当前机器（或容器镜像）上的匹配速度，同时确认原生模块能够正常工作（见 `matched`）。
+ * 计时前先完整执行一次 `slideMatch` 作为预热。
+ *
+ * # 参数
+ * - options: 可选配置，见 `BenchmarkOptions`
+ */
+export declare function benchmark(options?: BenchmarkOptions | undefined | null): BenchmarkResult
+
+/** `benchmark` 的可选配置 */
+export interface BenchmarkOptions {
+  /** 合成背景图的尺寸 `[宽, 高]`，默认 `[320, 160]`，像素数不超过 4000000 */
+  backgroundSize?: [number, number]
+  /** 正方形滑块的边长，默认为背景图高度的 1/3（至少 8），不能超过背景图的宽和高 */
+  targetSize?: number
+  /** 重复次数，范围 1-1000，默认 20 */
+  iterations?: number
+}
+
+/** `benchmark` 的返回值 */
+export interface BenchmarkResult {
+  /** 解码目标图片和背景图片 */
+  decode: StageTimings
+  /** 裁剪透明边框、灰度转换和两张图片的 Canny 边缘检测（固定阈值 100, 200） */
+  canny: StageTimings
+  /** 边缘图模板匹配（归一化互相关）和峰值查找 */
+  matching: StageTimings
+  /** 以上三个阶段之和 */
+  total: StageTimings
+  /** 实际的重复次数 */
+  iterations: number
+  /** 合成背景图的尺寸 `[宽, 高]` */
+  backgroundSize: [number, number]
+  /** 滑块边长 */
+  targetSize: number
+  /** 是否以 `simd` 特性编译（灰度转换和互相关使用 SIMD 加速） */
+  simd: boolean
+  /** rayon 线程池的线程数，多目标图片匹配等并行路径使用 */
+  threads: number
+  /** `slideMatch` 是否在合成图片上找到了缺口，可用于确认原生模块在当前环境中工作正常 */
+  matched: boolean
+}
+
+/**
+ * 执行与匹配函数相同的 Canny 边缘检测，用于离线调参
+ *
+ * 彩色图片自动转换为灰度图，并按 `config` 执行相同的预处理（CLAHE、高斯模糊）和边缘后处理
+ * （形态学操作、边缘膨胀）。`colorMode` 为 `rgb` 时也只输出灰度图的边缘。
+ *
+ * # 参数
+ * - image: 图片 Buffer
+ * - options: 可选，阈值选项，见 `CannyEdgesOptions`
+ * - config: 可选配置，见 `SlideMatchConfig`
+ */
+export declare function cannyEdges(image: Buffer, options?: CannyEdgesOptions | undefined | null, config?: SlideMatchConfig | undefined | null): CannyEdgesResult
+
+/** Canny 边缘检测选项 */
+export interface CannyEdgesOptions {
+  /** 低阈值，范围 0-255，优先级高于自适应阈值和 `SlideMatchConfig.cannyLow` */
+  low?: number
+  /** 高阈值，范围 0-255，优先级高于自适应阈值和 `SlideMatchConfig.cannyHigh` */
+  high?: number
+  /**
+   * 是否使用改进版函数的自适应阈值（按 `thresholdStrategy` 计算），默认 false，
+   * 即使用原版函数的阈值（未指定 `thresholdStrategy` 时为固定阈值 (100, 200)）
+   */
+  adaptive?: boolean
+}
+
+/** Canny 边缘检测结果 */
+export interface CannyEdgesResult {
+  /** 边缘图（PNG 灰度图，边缘像素为 255） */
+  data: Buffer
+  /** 实际使用的 Canny 阈值 [low, high] */
+  thresholds: Array<number>
+}
+
+/**
+ * 识别滑块（目标图片）的形状
+ *
+ * 以透明度不低于 128 的像素为不透明像素，按其占外接矩形面积的比例分类：
+ * - 大于 90%：矩形
+ * - 60%-90%：拼图块或圆形（外接矩形接近正方形且与内切圆高度重合时为圆形）
+ * - 小于 60%：无法识别
+ *
+ * # 参数
+ * - target: 目标图片 Buffer
+ */
+export declare function classifyTargetShape(target: Buffer): SliderShape
+
+/** 清空背景边缘图缓存（见 `cacheBackground` 选项） */
+export declare function clearBackgroundCache(): void
+
+/** 参与匹配的颜色通道 */
+export declare enum ColorMode {
+  /** 转换为灰度图后匹配（默认） */
+  Gray = 'gray',
+  /** 分别在 R、G、B 三个通道上匹配，适合只靠颜色区分真假缺口的验证码 */
+  Rgb = 'rgb'
+}
+
+/**
+ * 计算目标图片与背景图片中匹配区域的逐像素灰度差异，用于排查错误的匹配结果
+ *
+ * 返回与匹配区域同尺寸的 PNG 图片，R 通道为灰度差的绝对值（越红差异越大），G、B 通道为 0；
+ * 目标图片的透明像素差异记为 0。`bbox` 通常是 `slideMatch` 的返回值，必须是背景图片原始尺寸下
+ * 以左上角表示的坐标（未设置 `backgroundDisplayWidth`、`devicePixelRatio`、`coordinateMode: 'center'` 等选项）。
+ *
+ * # 参数
+ * - target: 目标图片 Buffer
+ * - background: 背景图片 Buffer
+ * - bbox: 匹配结果
+ */
+export declare function computeDifferenceMap(target: Buffer, background: Buffer, bbox: SlideBBox): Buffer
+
+/**
+ * 计算图片的灰度统计信息（均值、标准差、最值、熵），用于匹配前评估图片质量和调试自适应阈值
+ *
+ * 统计的是匹配函数实际使用的灰度图：彩色图片自动转换为灰度图，并按 `config` 执行 CLAHE 等预处理。
+ *
+ * # 参数
+ * - image: 图片 Buffer
+ * - config: 可选配置，见 `SlideMatchConfig`
+ */
+export declare function computeImageStats(image: Buffer, config?: SlideMatchConfig | undefined | null): ImageStats
+
+/** 匹配结果中 `x1/y1` 的含义 */
+export declare enum CoordinateMode {
+  /** 匹配区域的左上角（默认） */
+  TopLeft = 'topLeft',
+  /**
+   * 匹配区域的中心点：`x1 = x1 + 宽度 / 2`、`y1 = y1 + 高度 / 2`（整数除法），`x2/y2` 仍为右下角，
+   * 适合以元素中心为坐标的拖动工具（如 Playwright 的 `page.mouse.move`）
+   */
+  Center = 'center'
+}
+
+/** 匹配结果中位置坐标的参照点 */
+export declare enum CoordinateSpace {
+  /** 背景图片左上角（默认） */
+  BackgroundAbsolute = 'backgroundAbsolute',
+  /**
+   * 目标图片的裁剪起点：匹配区域整体平移 `(-target_x, -target_y)`，`x1` 即滑块需要拖动的距离，
+   * 结果小于 0 时取 0
+   */
+  BackgroundRelativeToTarget = 'backgroundRelativeToTarget'
+}
+
+/** 匹配结果中坐标的单位 */
+export declare enum CoordinateUnit {
+  /** 像素（默认） */
+  Pixel = 'pixel',
+  /** 图片尺寸的比例，额外在 `SlideBBox.ratio` 中返回 */
+  Ratio = 'ratio'
+}
+
+/** 裁剪透明边框的结果 */
+export interface CroppedImage {
+  /** 裁剪后的 PNG 图片 */
+  data: Buffer
+  /** 裁剪起点，即匹配结果中的 `targetX/targetY` */
+  x: number
+  y: number
+  width: number
+  height: number
+  /** 图片中没有不透明的像素，此时返回原图，`x/y` 为 0 */
+  fullyTransparent: boolean
+}
+
+/**
+ * 裁剪图片四周的透明边框，与匹配函数裁剪目标图片的逻辑相同
+ *
+ * 可用于预先裁剪滑块图片，或在不执行匹配的情况下得到 `targetX/targetY`。
+ *
+ * # 参数
+ * - image: 图片 Buffer
+ * - alpha_threshold: 可选，透明度不大于该值的像素视为透明，范围 0-255，默认 0（与匹配函数一致）
+ */
+export declare function cropTransparent(image: Buffer, alphaThreshold?: number | undefined | null): CroppedImage
+
+/** `slideMatchCrossCheck` 的选项 */
+export interface CrossCheckOptions {
+  /** 两种算法的 `x1` 相差不超过该值（像素）时视为一致，默认 2 */
+  tolerance?: number
+  /** 改进版算法的置信度阈值，范围 0.0-1.0，默认 0.3，与 `improvedSlideMatch` 相同 */
+  confidenceThreshold?: number
+}
+
+/** 同时运行改进版和原版算法的结果，见 `slideMatchCrossCheck` */
+export interface CrossCheckResult {
+  /** 改进版算法（与 `improvedSlideMatch` 相同）的结果 */
+  improved: MatchCandidate
+  /** 原版算法（与 `slideMatch` 相同，固定 Canny 阈值）的结果 */
+  original: MatchCandidate
+  /** 两个结果的 `x1` 相差不超过 `tolerance` */
+  agrees: boolean
+  /** `improved.bbox.x1 - original.bbox.x1` */
+  deltaX: number
+}
+
+/** `evaluateDataset` 使用的匹配函数 */
+export declare enum DatasetAlgorithm {
+  /** `slideMatch`（默认） */
+  SlideMatch = 'slideMatch',
+  /** `improvedSlideMatch`，置信度阈值为默认的 0.3 */
+  ImprovedSlideMatch = 'improvedSlideMatch',
+  /** `simpleSlideMatch` */
+  SimpleSlideMatch = 'simpleSlideMatch',
+  /** `improvedSimpleSlideMatch`，置信度阈值为默认的 0.3 */
+  ImprovedSimpleSlideMatch = 'improvedSimpleSlideMatch'
+}
+
+/** `evaluateDataset` 的返回值 */
+export interface DatasetEvaluation {
+  /** 样本总数 */
+  total: number
+  /** 匹配结果在容差内的样本数 */
+  correct: number
+  /** `correct / total`，没有样本时为 0 */
+  accuracy: number
+  /** 未通过的样本，按名称排序 */
+  failures: Array<DatasetFailure>
+}
+
+/** 数据集中未通过的样本 */
+export interface DatasetFailure {
+  /** 样本名称，即文件名中的 `NNN` */
+  name: string
+  /** 标注的 x，标注文件缺失或无法解析时为空 */
+  expectedX?: number
+  /** 匹配结果的 `x1`，匹配失败时为空 */
+  gotX?: number
+  /** 匹配得分，匹配失败时为空 */
+  confidence?: number
+  /** 文件缺失、无法读取或匹配失败时的错误信息，匹配结果超出容差时为空 */
+  error?: string
+}
+
+/**
+ * 双滑块匹配：背景图中有两个缺口，分别对应两个滑块
+ *
+ * 先匹配第一个滑块，再在去掉第一个缺口区域的背景图中匹配第二个滑块，
+ * 保证两个结果互不重叠。两个滑块相同时返回得分最高和次高的两个不重叠位置。
+ * 预处理方式与 `slideMatch` 一致，`confidence` 为各自的匹配得分。
+ *
+ * # 参数
+ * - target1: 第一个目标图片 Buffer
+ * - target2: 第二个目标图片 Buffer，可以与第一个相同
+ * - background: 背景图片 Buffer
+ * - options: 可选配置，见 `SlideMatchConfig`
+ */
+export declare function doubleSlideMatch(target1: Buffer, target2: Buffer, background: Buffer, options?: SlideMatchConfig | undefined | null): DoubleSlideResult
+
+/** 双滑块匹配结果 */
+export interface DoubleSlideResult {
+  /** 第一个滑块（`target1`）的缺口位置 */
+  first: SlideBBox
+  /** 第二个滑块（`target2`）的缺口位置，与第一个缺口不重叠 */
+  second: SlideBBox
+}
+
+/** 拖动轨迹配置 */
+export interface DragPathConfig {
+  /** 拖动总时长（毫秒），默认 1000 */
+  totalDurationMs: number
+  /** 竖直方向随机抖动的最大幅度（像素），默认 1.0，0 表示不抖动 */
+  jitterAmplitude: number
+}
+
+/** 拖动轨迹上的一个点 */
+export interface DragPoint {
+  x: number
+  y: number
+  /** 相对于拖动开始的时间（毫秒） */
+  tMs: number
+}
+
+/** 模板匹配前的边缘处理方式 */
+export declare enum EdgeMode {
+  /** Canny 边缘检测后在边缘图上匹配（默认） */
+  Canny = 'canny',
+  /**
+   * 跳过边缘检测，直接在灰度图上做归一化互相关匹配，
+   * 适合缺口为半透明暗色遮罩（而非描边）的背景
+   */
+  None = 'none'
+}
+
+/** 内嵌滑块匹配结果 */
+export interface EmbeddedMatchResult {
+  /** 滑块在图片中的区域（原始尺寸像素） */
+  piece: PieceRegion
+  /** 缺口位置；`targetX/targetY` 为滑块在图片中的左上角 */
+  bbox: SlideBBox
+}
+
+/**
+ * 可预期的匹配失败原因，由 `trySlideMatch` 等不抛出异常的函数在结果的 `code` 中返回，
+ * 抛出异常的函数以同样的值作为异常的 `code`
+ *
+ * 与抛出异常的函数共用同一套错误类型（[`SlideMatchError`]），同样的输入在两类函数中对应同样的失败原因。
+ */
+export declare enum ErrorCode {
+  /** 图片无法解码（数据损坏、不是图片等） */
+  DecodeFailed = 'decodeFailed',
+  /** 无法读取图片文件（路径不存在、没有权限等） */
+  ReadFailed = 'readFailed',
+  /** 当前构建未包含该格式的解码器（如 AVIF） */
+  UnsupportedFormat = 'unsupportedFormat',
+  /** 图片像素数超过 `maxImagePixels` 上限 */
+  ImageTooLarge = 'imageTooLarge',
+  /** 目标图片的宽或高大于背景图片 */
+  TargetLargerThanBackground = 'targetLargerThanBackground',
+  /** 背景图片边缘过少（如纯色占位图） */
+  InsufficientEdges = 'insufficientEdges',
+  /** 匹配置信度不高于阈值（`confidenceThreshold` 或 `onLowConfidence` 为 `error`） */
+  LowConfidence = 'lowConfidence',
+  /** 目标图片的不透明像素数少于 `minOpaquePixels` */
+  DegenerateTarget = 'degenerateTarget'
+}
+
+/**
+ * 在带标注的验证码目录上评估匹配准确率，用于在调整算法或参数后做回归测试
+ *
+ * 目录中每个样本由 `NNN_target.png`、`NNN_bg.png` 和 `NNN.json`（`{ "x": 123 }`）组成，
+ * 匹配结果的 `x1` 与标注的 x 相差不超过 `tolerancePx` 时视为正确。单个样本出错时记入 `failures`，不中断评估。
+ *
+ * # 参数
+ * - dir: 数据集目录
+ * - options: 可选，评估选项
+ * - config: 可选配置，见 `SlideMatchConfig`
+ */
+export declare function evaluateDataset(dir: string, options?: EvaluateDatasetOptions | undefined | null, config?: SlideMatchConfig | undefined | null): DatasetEvaluation
+
+/** `evaluateDataset` 的选项 */
+export interface EvaluateDatasetOptions {
+  /** 结果的 `x1` 与标注的 x 相差不超过该值（像素）时视为正确，默认 5 */
+  tolerancePx?: number
+  /** 使用的匹配函数，默认 `slideMatch` */
+  algorithm?: DatasetAlgorithm
+}
+
+/**
+ * 多目标匹配：返回背景图中所有得分不低于阈值的位置
+ *
+ * 适合同一形状在背景图中出现多次的验证码。对得分图做非极大值抑制后按得分从高到低返回，
+ * 没有位置达到阈值时返回空数组。预处理方式与 `slideMatch` 一致（裁剪透明区域、固定 Canny 阈值）。
+ *
+ * # 参数
+ * - target: 目标图片 Buffer
+ * - background: 背景图片 Buffer
+ * - options: 多目标匹配选项
+ * - config: 可选配置，见 `SlideMatchConfig`
+ */
+export declare function findAllMatches(target: Buffer, background: Buffer, options?: FindAllMatchesOptions | undefined | null, config?: SlideMatchConfig | undefined | null): Array<MatchCandidate>
+
+/** 多目标匹配选项 */
+export interface FindAllMatchesOptions {
+  /** 最低匹配得分，范围 0.0-1.0，默认 0.5 */
+  scoreThreshold?: number
+  /** 最多返回的结果数，默认 10 */
+  maxResults?: number
+  /**
+   * 非极大值抑制半径（像素）：与得分更高的结果在 x、y 方向的距离都不超过该值时被抑制；
+   * 默认按目标图片尺寸抑制所有互相重叠的结果
+   */
+  nmsRadius?: number
+}
+
+/**
+ * 生成基于三次贝塞尔曲线的拖动轨迹
+ * 在参数 t 上等间隔取 `steps` 个点，映射为像素坐标和时间戳
+ *
+ * # 参数
+ * - start / end: 起点和终点，时间戳以 `start.tMs` 为起始
+ * - control_points: 两个控制点 `[[x1, y1], [x2, y2]]`；传空数组时自动生成两个控制点，使轨迹形成轻微弧线
+ * - steps: 轨迹点数量（包含起点和终点），必须大于等于 2
+ * - total_ms: 拖动总时长（毫秒）
+ */
+export declare function generateBezierDragPath(start: DragPoint, end: DragPoint, controlPoints: Array<[number, number]>, steps: number, totalMs: number): Array<DragPoint>
+
+/**
+ * 根据起点和终点生成拖动滑块的轨迹
+ * 轨迹在起步阶段加速、接近终点时减速，并在竖直方向加入少量随机抖动
+ *
+ * # 参数
+ * - start_x / start_y: 起点坐标
+ * - end_x / end_y: 终点坐标
+ * - steps: 轨迹点数量（包含起点和终点），必须大于等于 2
+ * - config: 可选配置，见 `DragPathConfig`
+ */
+export declare function generateDragPath(startX: number, startY: number, endX: number, endY: number, steps: number, config?: DragPathConfig | undefined | null): Array<DragPoint>
+
+/** 灰度图的统计信息 */
+export interface ImageStats {
+  /** 灰度均值 */
+  mean: number
+  /** 灰度标准差（总体标准差） */
+  stdDev: number
+  /** 最小灰度值 */
+  min: number
+  /** 最大灰度值 */
+  max: number
+  /** 灰度直方图的香农熵（比特），范围 0-8，纯色图片为 0 */
+  entropy: number
+}
+
 /**
  * 改进版简单滑块匹配（无透明背景裁剪 + 自适应阈值 + 置信度验证）
  * 接受 Buffer 参数（支持 base64 解码后的 u8 数组）
@@ -8,11 +465,18 @@
  * - target_image: 目标图片 Buffer
  * - background_image: 背景图片 Buffer
  * - confidence_threshold: 置信度阈值，范围 0.0-1.0，默认 0.3
+ * - options: 可选配置，见 `SlideMatchConfig`
  */
-export declare function improvedSimpleSlideMatch(targetImage: Buffer, backgroundImage: Buffer, confidenceThreshold?: number | undefined | null): SlideBBox
+export declare function improvedSimpleSlideMatch(targetImage: Buffer, backgroundImage: Buffer, confidenceThreshold?: number | undefined | null, options?: SlideMatchConfig | undefined | null): SlideBBox
 
 /** 改进版简单滑块匹配 - 从文件路径 */
-export declare function improvedSimpleSlideMatchWithPath(targetImagePath: string, backgroundImagePath: string, confidenceThreshold?: number | undefined | null): SlideBBox
+export declare function improvedSimpleSlideMatchWithPath(targetImagePath: string, backgroundImagePath: string, confidenceThreshold?: number | undefined | null, options?: SlideMatchConfig | undefined | null): SlideBBox
+
+/**
+ * 改进版简单滑块匹配 - 从文件路径（异步），参数与 `improvedSimpleSlideMatchWithPath` 相同
+ * 读取文件和匹配都在后台线程中执行，不阻塞 JS 主线程；错误的 `code` 同 `improvedSlideMatchWithPathAsync`
+ */
+export declare function improvedSimpleSlideMatchWithPathAsync(targetImagePath: string, backgroundImagePath: string, confidenceThreshold?: number | undefined | null, options?: SlideMatchConfig | undefined | null): Promise<SlideBBox>
 
 /**
  * 改进版滑块匹配（带透明背景裁剪 + 自适应阈值 + 置信度验证）
@@ -22,17 +486,330 @@ export declare function improvedSimpleSlideMatchWithPath(targetImagePath: string
  * - target_image: 目标图片 Buffer
  * - background_image: 背景图片 Buffer
  * - confidence_threshold: 置信度阈值，范围 0.0-1.0，默认 0.3
+ * - options: 可选配置，见 `SlideMatchConfig`
  */
-export declare function improvedSlideMatch(targetImage: Buffer, backgroundImage: Buffer, confidenceThreshold?: number | undefined | null): SlideBBox
+export declare function improvedSlideMatch(targetImage: Buffer, backgroundImage: Buffer, confidenceThreshold?: number | undefined | null, options?: SlideMatchConfig | undefined | null): SlideBBox
+
+/**
+ * 改进版滑块匹配（异步），参数与 `improvedSlideMatch` 相同
+ * 匹配在后台线程中执行，不阻塞 JS 主线程；图片 Buffer 不会被复制，匹配完成前不要修改
+ */
+export declare function improvedSlideMatchAsync(targetImage: Buffer, backgroundImage: Buffer, confidenceThreshold?: number | undefined | null, options?: SlideMatchConfig | undefined | null): Promise<SlideBBox>
+
+/**
+ * 改进版滑块匹配，同时返回每次匹配尝试（自适应阈值、回退的固定阈值）的得分和位置，
+ * 参数与 `improvedSlideMatch` 相同
+ */
+export declare function improvedSlideMatchVerbose(targetImage: Buffer, backgroundImage: Buffer, confidenceThreshold?: number | undefined | null, options?: SlideMatchConfig | undefined | null): SlideMatchVerbose
 
 /** 改进版滑块匹配 - 从文件路径 */
-export declare function improvedSlideMatchWithPath(targetImagePath: string, backgroundImagePath: string, confidenceThreshold?: number | undefined | null): SlideBBox
+export declare function improvedSlideMatchWithPath(targetImagePath: string, backgroundImagePath: string, confidenceThreshold?: number | undefined | null, options?: SlideMatchConfig | undefined | null): SlideBBox
+
+/**
+ * 改进版滑块匹配 - 从文件路径（异步），参数与 `improvedSlideMatchWithPath` 相同
+ * 读取文件和匹配都在后台线程中执行，不阻塞 JS 主线程；
+ * 读取文件失败时错误的 `code` 为 `ErrorCode.ReadFailed`，解码或匹配失败时为对应的错误码
+ */
+export declare function improvedSlideMatchWithPathAsync(targetImagePath: string, backgroundImagePath: string, confidenceThreshold?: number | undefined | null, options?: SlideMatchConfig | undefined | null): Promise<SlideBBox>
+
+/**
+ * 改进版滑块匹配，只返回滑块需要水平拖动的距离（`x1 - targetX`，小于 0 时为 0），
+ * 参数与 `improvedSlideMatch` 相同；`coordinateMode` 和 `coordinateSpace` 不生效
+ */
+export declare function improvedSlideXOffset(target: Buffer, background: Buffer, confidenceThreshold?: number | undefined | null, options?: SlideMatchConfig | undefined | null): number
+
+/** `invert` 的自动模式 */
+export declare enum InvertMode {
+  /** 灰度均值低于 `invertCutoff` 时反相 */
+  Auto = 'auto'
+}
+
+/**
+ * 计算目标图片放在背景图指定位置时的匹配得分，不搜索其他位置，
+ * 适合低成本地验证外部预测或缓存的坐标；参数与 `slideMatch` 相同
+ */
+export declare function matchAtPosition(targetImage: Buffer, backgroundImage: Buffer, x: number, y: number, options?: SlideMatchConfig | undefined | null): PositionScore
+
+/** 改进版匹配中一次匹配尝试的结果 */
+export interface MatchAttempt {
+  /** 匹配策略：`adaptive` 为自适应 Canny 阈值，`fixed` 为回退使用的固定阈值 (100, 200) */
+  strategy: string
+  /** 该次尝试的匹配得分（归一化互相关的最大值） */
+  score: number
+  /** 该次尝试的最佳位置 `[x1, y1]`，与结果的 `x1/y1` 同一坐标系 */
+  location: [number, number]
+}
+
+/** 模板匹配（归一化互相关）的计算后端 */
+export declare enum MatchBackend {
+  /** 始终在 CPU 上计算（默认） */
+  Cpu = 'cpu',
+  /** 在 GPU 上计算；需要启用 `gpu` 特性，GPU 不可用时回退到 CPU */
+  Gpu = 'gpu',
+  /** 背景图较大时使用 GPU，其余情况和 GPU 不可用时使用 CPU */
+  Auto = 'auto'
+}
+
+/** 多目标匹配的单个结果 */
+export interface MatchCandidate {
+  bbox: SlideBBox
+  /** 匹配得分（归一化互相关） */
+  confidence: number
+}
+
+/** 匹配过程的诊断信息 */
+export interface MatchMetadata {
+  /** 是否因置信度过低回退到了固定阈值（100, 200） */
+  usedFallback: boolean
+  /** 最终结果所用的 Canny 阈值策略，回退时为 `fixed` */
+  thresholdStrategy: ThresholdStrategy
+  /** 最终结果所用的目标图 Canny 阈值 [low, high]，`edgeMode` 为 `none` 时为空 */
+  targetThresholds: Array<number>
+  /** 最终结果所用的背景图 Canny 阈值 [low, high]，`edgeMode` 为 `none` 时为空 */
+  backgroundThresholds: Array<number>
+  /** 自适应阈值（首次尝试）的匹配得分，发生回退时也会保留 */
+  primaryConfidence: number
+  /** 首次尝试的得分是否不高于置信度阈值 */
+  lowConfidence: boolean
+  /** `lowConfidence` 为 true 时按 `onLowConfidence` 采取的处理方式（`fallback` 或 `returnBest`），否则不返回 */
+  lowConfidenceAction?: LowConfidenceAction
+  /** 目标图片的灰度图是否被反相（见 `invert`） */
+  targetInverted: boolean
+  /** 背景图片的灰度图是否被反相（见 `invert`） */
+  backgroundInverted: boolean
+}
+
+/** 缺口定位方式 */
+export declare enum MatchStrategy {
+  /** 边缘图模板匹配（归一化互相关，默认） */
+  Correlation = 'correlation',
+  /**
+   * 轮廓形状匹配：在背景边缘图的闭合轮廓中寻找 Hu 不变矩与滑块轮廓最接近的一个，
+   * 适合背景纹理过于杂乱、边缘互相关失效但缺口轮廓仍然清晰的情况
+   */
+  Contour = 'contour',
+  /**
+   * 梯度方向匹配：按 Sobel 梯度方向（忽略明暗方向）把边缘像素分箱，只统计方向相同的重合边缘，
+   * 对明暗对比变化更不敏感
+   */
+  Orientation = 'orientation',
+  /**
+   * 倒角匹配：按模板边缘像素到最近背景边缘的平均距离评分（距离变换），
+   * 滑块轮廓与缺口轮廓错开 1-3 像素（如服务端缩放）时仍能定位
+   */
+  Chamfer = 'chamfer'
+}
+
+/**
+ * 在自行处理好的单通道图片（如边缘图、灰度图）上直接执行模板匹配
+ *
+ * 不做任何预处理，只计算得分图并取最佳位置，可以配合 `cannyEdges` 或自定义的边缘检测使用。
+ *
+ * # 参数
+ * - template: 模板图片
+ * - background: 背景图片，宽度和高度都不能小于模板图片
+ * - method: 可选，得分计算方式，默认 `crossCorrelationNormalized`
+ * - return_heatmap: 可选，是否返回完整的匹配得分图，默认 false
+ */
+export declare function matchTemplateRaw(template: RawImage, background: RawImage, method?: TemplateMatchMethod | undefined | null, returnHeatmap?: boolean | undefined | null): RawMatchResult
+
+/**
+ * 边缘图形态学操作，参数为结构元素半径（像素），范围 0-255
+ *
+ * JavaScript 中写作 `{ op: 'dilate', radius: 1 }`。imageproc 的结构元素半径为 `u8`，超过 255 时报错。
+ */
+export type MorphOp =
+  | { op: 'dilate', radius: number }
+  | { op: 'erode', radius: number }
+  | { op: 'open', radius: number }
+  | { op: 'close', radius: number }
+
+/** 多目标图片匹配结果 */
+export interface MultiTargetResult {
+  /** 置信度最高的匹配结果 */
+  bestBbox: SlideBBox
+  /** `best_bbox` 对应的目标图片在 `targets` 中的下标 */
+  targetIndex: number
+  /** 每个目标图片的匹配结果，与 `targets` 一一对应，匹配失败的为 null */
+  allResults: Array<SlideBBox | undefined | null>
+}
+
+/** 改进版函数首次匹配置信度不高于阈值时的处理方式 */
+export declare enum LowConfidenceAction {
+  /** 回退到固定阈值 (100, 200) 重新匹配（默认） */
+  Fallback = 'fallback',
+  /** 不回退，直接返回自适应阈值的匹配结果 */
+  ReturnBest = 'returnBest',
+  /** 报错，由调用方换用其他策略 */
+  Error = 'error'
+}
+
+/** 目标图片大于背景图片时的处理方式 */
+export declare enum OversizePolicy {
+  /** 报错（默认），错误信息包含两张图片的尺寸 */
+  Error = 'error',
+  /** 按比例缩小目标图片直到能放进背景图片，适合滑块按 2 倍图、背景按 1 倍图下发的情况 */
+  ScaleTarget = 'scaleTarget',
+  /** 按比例放大背景图片直到能放下目标图片 */
+  ScaleBackground = 'scaleBackground'
+}
+
+/** 图片中的矩形区域（原始尺寸像素） */
+export interface PieceRegion {
+  x: number
+  y: number
+  width: number
+  height: number
+}
+
+/** `ignorePieceRegion` 的预设区域 */
+export declare enum PieceRegionPreset {
+  /** 背景图左侧从 x = 0 开始、与（裁剪透明边缘后的）目标图片等宽的整列区域 */
+  AutoLeft = 'autoLeft'
+}
+
+/** 指定位置的匹配得分，见 `matchAtPosition` */
+export interface PositionScore {
+  /** 目标图片放在该位置时的匹配得分，与 `slideMatch` 在同一位置的 `confidence` 一致 */
+  score: number
+  /** 预处理的诊断信息，`primaryConfidence` 与 `score` 相同，`usedFallback` 恒为 false */
+  metadata: MatchMetadata
+}
+
+/**
+ * 把目标图片放到背景图片的指定位置，返回与背景图片同尺寸的 PNG 图片，用于在不执行匹配的情况下
+ * 预览某个拖动位置的效果
+ *
+ * `(x, y)` 为裁剪透明边框后的目标图片左上角（与 `slideMatch` 返回的 `x1/y1` 含义相同，背景图片原始尺寸像素），
+ * 透明边框按与 `slideMatch` 相同的规则判断；超出背景图片的部分被截掉。
+ *
+ * # 参数
+ * - target: 目标图片 Buffer
+ * - background: 背景图片 Buffer
+ * - x: 目标图片左上角的 x 坐标
+ * - y: 目标图片左上角的 y 坐标
+ */
+export declare function previewPlacement(target: Buffer, background: Buffer, x: number, y: number): Buffer
+
+/** 以图片原始尺寸比例表示的坐标，乘以图片原始尺寸即得到（未换算到显示尺寸的）像素坐标 */
+export interface RatioCoordinates {
+  /** `target_x / 目标图片宽度` */
+  targetX: number
+  /** `target_y / 目标图片高度` */
+  targetY: number
+  /** `x1 / 背景图片宽度`，`x1` 的含义同样由 `coordinateMode` 决定 */
+  x1: number
+  /** `y1 / 背景图片高度` */
+  y1: number
+  /** `x2 / 背景图片宽度` */
+  x2: number
+  /** `y2 / 背景图片高度` */
+  y2: number
+}
+
+/** 单通道原始图片（每个像素 1 字节，按行存储） */
+export interface RawImage {
+  /** 像素数据，长度必须等于 `width * height` */
+  data: Buffer
+  width: number
+  height: number
+}
+
+/** 原始图片模板匹配结果 */
+export interface RawMatchResult {
+  /** 最佳匹配位置，`targetX/targetY` 为 0，`confidence` 与 `score` 相同 */
+  bbox: SlideBBox
+  /** 最佳匹配位置的得分（含义取决于 `method`） */
+  score: number
+  /** 完整的匹配得分图，仅在 `returnHeatmap` 为 true 时返回 */
+  heatmap?: ScoreHeatmap
+}
+
+/**
+ * 把目标图片半透明地叠加到背景图片上匹配到的位置，返回与背景图片同尺寸的 PNG 图片，用于快速目视检查匹配结果
+ *
+ * `bbox` 通常是 `slideMatch` 的返回值，要求与 `computeDifferenceMap` 相同。
+ *
+ * # 参数
+ * - target: 目标图片 Buffer（未裁剪的原始图片）
+ * - background: 背景图片 Buffer
+ * - bbox: 匹配结果
+ * - alpha: 目标图片的不透明度，范围 0.0-1.0
+ */
+export declare function renderMatchOverlay(target: Buffer, background: Buffer, bbox: SlideBBox, alpha: number): Buffer
+
+/**
+ * 还原切片打乱的背景图，返回 PNG 图片
+ *
+ * 背景图被切成宽度为 `sliceWidth` 的竖条并打乱顺序，`sliceOrder[i]` 为还原后第 i 条在打乱图片中的下标。
+ * 指定 `splitY` 时上下两部分分别打乱：`sliceOrder` 前一半对应上半部分（第 0 到 splitY - 1 行），
+ * 后一半对应下半部分，下标都是同一部分内的下标。
+ *
+ * # 参数
+ * - image: 打乱的背景图片 Buffer
+ * - slice_order: 切片顺序
+ * - slice_width: 切片宽度（像素）
+ * - split_y: 可选，上下两部分的分界行
+ */
+export declare function restoreBackground(image: Buffer, sliceOrder: Array<number>, sliceWidth: number, splitY?: number | undefined | null): Buffer
+
+/**
+ * 旋转验证码匹配：找出目标图片需要旋转的角度
+ *
+ * 以 `angleStepDeg` 为步长把目标图片从 0 度旋转到 360 度，每个角度都在背景图上做边缘模板匹配
+ * （预处理方式与 `slideMatch` 一致，只在灰度图上匹配），返回得分最高的角度。
+ *
+ * # 参数
+ * - target: 目标图片 Buffer，旋转后超出原尺寸的部分会被裁掉，建议使用圆形透明背景的图片
+ * - background: 背景图片 Buffer
+ * - angle_step_deg: 可选，角度步长（度），默认 5
+ * - options: 可选配置，见 `SlideMatchConfig`
+ */
+export declare function rotationMatch(target: Buffer, background: Buffer, angleStepDeg?: number | undefined | null, options?: SlideMatchConfig | undefined | null): RotationResult
+
+/** 旋转匹配结果 */
+export interface RotationResult {
+  /** 目标图片顺时针旋转该角度（度，0 到 360 之间）后与背景图最匹配 */
+  angleDeg: number
+  /** 该角度下的匹配得分（归一化互相关） */
+  confidence: number
+}
+
+/**
+ * 模板匹配的得分图（归一化互相关，按行存储）
+ *
+ * `data[y * width + x]` 为目标图片左上角位于背景图 (x, y) 时的得分（原始尺寸像素）。
+ * 指定 `fixedY` 时只包含搜索的行，第 0 行对应 `y = fixedY - fixedYTolerance`（不小于 0）。
+ * 指定 `fixedX` 时同理只包含搜索的列，第 0 列对应 `x = fixedX - fixedXTolerance`（不小于 0）。
+ */
+export interface ScoreHeatmap {
+  data: Float32Array
+  width: number
+  height: number
+}
+
+/**
+ * 选择模板匹配的计算后端，对进程内之后的所有匹配生效
+ *
+ * 选择 `gpu` 或 `auto` 时，首次匹配会初始化 GPU 设备并在之后的调用中复用；没有启用 `gpu` 特性、
+ * 找不到可用的 GPU 或初始化失败时通过 `tracing` 输出一次警告并回退到 CPU，匹配结果不受影响。
+ */
+export declare function setBackend(backend: MatchBackend): void
 
 /**
  * 简单滑块匹配（无透明背景裁剪）
  * 接受 Buffer 参数（支持 base64 解码后的 u8 数组）
+ * 可选参数 options 见 `SlideMatchConfig`
  */
-export declare function simpleSlideMatch(targetImage: Buffer, backgroundImage: Buffer): SlideBBox
+export declare function simpleSlideMatch(targetImage: Buffer, backgroundImage: Buffer, options?: SlideMatchConfig | undefined | null): SlideBBox
+
+/** 滑块的滑动方向 */
+export declare enum SlideAxis {
+  /** 水平滑动，缺口与滑块在同一行（默认） */
+  X = 'x',
+  /** 竖直滑动，缺口在滑块正上方或正下方 */
+  Y = 'y'
+}
 
 export interface SlideBBox {
   targetX: number
@@ -41,10 +818,555 @@ export interface SlideBBox {
   y1: number
   x2: number
   y2: number
+  /** 匹配得分（归一化互相关的最大值），改进版函数及设置了 `confidenceThreshold` 的原版函数返回 */
+  confidence?: number
+  /** 匹配过程的诊断信息，仅改进版函数返回 */
+  metadata?: MatchMetadata
+  /**
+   * 每个候选 x（原始尺寸像素，下标即 `x1`）上所有 y 的最大匹配得分，
+   * 长度为 `背景宽度 - 目标宽度 + 1`；`axis` 为 `y` 时为每个候选 y 上所有 x 的最大匹配得分，
+   * 长度为 `背景高度 - 目标高度 + 1`。仅在 `returnScoreProfile` 为 true 时返回
+   */
+  scoreProfile?: Array<number>
+  /** 完整的匹配得分图，仅在 `returnHeatmap` 为 true 时返回 */
+  heatmap?: ScoreHeatmap
+  /** `x1/y1` 的含义（见 `SlideMatchConfig.coordinateMode`），接受 `SlideMatchConfig` 的匹配函数返回 */
+  coordinateMode?: CoordinateMode
+  /** 以图片尺寸比例表示的坐标，仅在 `coordinateUnit` 为 `ratio` 时返回 */
+  ratio?: RatioCoordinates
+  /**
+   * 目标图片与返回坐标所在的背景图尺度不一致时（按 `oversizePolicy` 缩放，或 `backgroundTargetWidth`
+   * 配合 `natural` 坐标系），目标图片相对背景图片的缩放比例（目标图片像素 × `targetScale` = 背景图片像素），
+   * 未缩放时不返回
+   */
+  targetScale?: number
+  /**
+   * 次优峰值的得分是否与最优峰值相差不到 `ambiguityMargin`（匹配结果接近二选一），
+   * 仅在设置了 `ambiguityMargin` 时返回
+   */
+  isAmbiguous?: boolean
+  /** 次优峰值（与最优匹配区域不重叠的位置中的最高得分）的得分，仅在设置了 `ambiguityMargin` 时返回 */
+  secondBestScore?: number
+  /** 次优峰值的 `x1`（与 `x1` 同一坐标系），仅在设置了 `ambiguityMargin` 时返回 */
+  secondBestX?: number
+  /**
+   * 峰值旁瓣比（PSR）：(峰值 - 旁瓣均值) / 旁瓣标准差，旁瓣为得分图中以峰值为中心的 11x11 窗口之外的位置；
+   * 比原始得分更适合跨背景比较匹配质量，仅在 `returnPsr` 为 true 时返回，没有旁瓣或旁瓣得分全部相同时不返回
+   */
+  psr?: number
+  /**
+   * 沿 `axis` 方向的滑动距离：`x1 - targetX`，`axis` 为 `y` 时为 `y1 - targetY`；与 `x1` 同一尺度，
+   * 按左上角计算、不受 `coordinateMode` 和 `coordinateSpace` 影响，可能为负。仅在设置了 `axis` 时返回
+   */
+  slideDistance?: number
+  /**
+   * 目标图片的不透明像素少于 `minOpaquePixels`、因 `allowDegenerateTarget` 继续匹配时为 true，
+   * 此时结果没有意义；其他情况不返回
+   */
+  degenerateTarget?: boolean
+  /** 匹配耗时（毫秒，从开始解码图片到得到结果），仅在 `measureTime` 为 true 时返回 */
+  elapsedMs?: number
+}
+
+/** 以背景图片原始尺寸比例（0.0-1.0）表示的匹配结果，见 `slideMatchNormalized` */
+export interface SlideBBoxNormalized {
+  /** `x1 / 背景图片宽度`，`x1` 的含义由 `coordinateMode` 决定 */
+  x1: number
+  /** `y1 / 背景图片高度` */
+  y1: number
+  /** `x2 / 背景图片宽度` */
+  x2: number
+  /** `y2 / 背景图片高度` */
+  y2: number
+  /** 匹配得分 */
+  confidence: number
+}
+
+/** 有符号坐标的匹配结果，字段含义与 `SlideBBox` 相同 */
+export interface SlideBBoxSigned {
+  targetX: number
+  targetY: number
+  x1: number
+  y1: number
+  x2: number
+  y2: number
+  /** 匹配得分，设置了 `confidenceThreshold` 时返回 */
+  confidence?: number
+}
+
+/**
+ * 双背景图对比：同时提供完整背景图和带缺口的背景图时，通过逐像素差异定位缺口
+ *
+ * 对两张图片的灰度差做阈值化，返回面积最大的差异区域的外接矩形。`targetX/targetY` 固定为 0，
+ * `confidence` 为该区域面积占外接矩形的比例，形状越紧凑越接近 1。
+ *
+ * # 参数
+ * - full_background: 完整背景图片 Buffer
+ * - gapped_background: 带缺口的背景图片 Buffer，尺寸必须与完整背景图片一致
+ * - options: 可选，见 `SlideComparisonOptions`
+ */
+export declare function slideComparison(fullBackground: Buffer, gappedBackground: Buffer, options?: SlideComparisonOptions | undefined | null): SlideBBox
+
+/** 双背景图对比选项 */
+export interface SlideComparisonOptions {
+  /** 差异阈值，灰度差大于该值的像素视为不同，范围 0-255，默认 30；用于抑制 JPEG 压缩噪声 */
+  diffThreshold?: number
+  /** 最小连通区域面积（像素），更小的差异区域视为噪声，默认 50 */
+  minBlobArea?: number
 }
 
 /**
  * 滑块匹配（带透明背景裁剪）
  * 接受 Buffer 参数（支持 base64 解码后的 u8 数组）
+ * 可选参数 options 见 `SlideMatchConfig`
+ */
+export declare function slideMatch(targetImage: Buffer, backgroundImage: Buffer, options?: SlideMatchConfig | undefined | null): SlideBBox
+
+/** 滑块匹配可选配置 */
+export interface SlideMatchConfig {
+  /** CLAHE 分块边长（像素），设置后在阈值计算和边缘检测前启用 CLAHE 预处理 */
+  claheTileSize?: number
+  /** CLAHE 对比度限制，默认 2.0 */
+  claheClipLimit?: number
+  /** 背景图片在页面上的显示宽度（CSS 像素），设置后返回的坐标换算到显示尺寸 */
+  backgroundDisplayWidth?: number
+  /** 目标图片在页面上的显示宽度（CSS 像素），未设置时沿用背景的缩放比例 */
+  targetDisplayWidth?: number
+  /** 对目标和背景的边缘图执行形态学后处理（在模板匹配之前） */
+  edgeMorphology?: MorphOp
+  /**
+   * 边缘膨胀半径（像素），0 表示不膨胀；在 `edge_morphology` 之后对两张边缘图执行膨胀，
+   * 使抗锯齿差异导致错开 1 像素的轮廓仍能重合
+   */
+  dilateEdges?: number
+  /** Canny 低阈值，同时覆盖目标图和背景图的固定阈值与自适应阈值，范围 0-255 */
+  cannyLow?: number
+  /** Canny 高阈值，同时覆盖目标图和背景图的固定阈值与自适应阈值，范围 0-255 */
+  cannyHigh?: number
+  /** 目标图 Canny 低阈值，优先级高于 `canny_low` */
+  targetCannyLow?: number
+  /** 目标图 Canny 高阈值，优先级高于 `canny_high` */
+  targetCannyHigh?: number
+  /** 背景图 Canny 低阈值，优先级高于 `canny_low` */
+  backgroundCannyLow?: number
+  /** 背景图 Canny 高阈值，优先级高于 `canny_high` */
+  backgroundCannyHigh?: number
+  /**
+   * Canny 阈值策略，原版函数默认 `fixed`，改进版函数默认 `meanStdDev`；显式指定的 `cannyLow/cannyHigh`
+   * 等阈值仍会覆盖策略计算的结果
+   */
+  thresholdStrategy?: ThresholdStrategy
+  /** 边缘处理方式，默认 `canny`；为 `none` 时 Canny 阈值和形态学配置不生效 */
+  edgeMode?: EdgeMode
+  /** 颜色模式，默认 `gray`；为 `rgb` 时分别在 R、G、B 三个通道上匹配并取平均得分 */
+  colorMode?: ColorMode
+  /**
+   * 是否在线性光强下计算灰度（先把 sRGB 解码为线性值再按亮度权重求和，再编码回 8 位），默认 false；
+   * 暗背景上饱和色的缺口轮廓在默认的灰度转换中容易与背景混在一起，开启后自适应阈值统计和
+   * Canny 都使用该灰度图。`colorMode` 为 `rgb` 时只影响用于计算阈值的灰度图
+   */
+  linearLight?: boolean
+  /**
+   * 缺口（匹配区域左上角）的 y 坐标，即结果中的 `y1`（原始尺寸像素）；
+   * 指定后只在该行附近沿 x 方向搜索，背景图较宽时速度明显快于完整的二维匹配
+   */
+  fixedY?: number
+  /** `fixed_y` 上下允许的偏差（像素），默认 0 */
+  fixedYTolerance?: number
+  /** 单张图片允许的最大像素数（宽 × 高），默认 4000000，超过时在解码像素数据前报错 */
+  maxImagePixels?: number
+  /**
+   * 背景边缘图中边缘像素占比的下限，范围 0.0-1.0，默认 0.001；
+   * 低于该值（如纯色占位图）时报错而不是返回不可靠的匹配结果，设为 0 可关闭检查。
+   * 真实验证码背景的边缘像素占比通常只有 0.3%-1%，默认值不能取 1%，否则会拒绝正常图片。
+   * `edgeMode` 为 `none` 时不检查
+   */
+  edgeDensityThreshold?: number
+  /** 是否在结果中返回每个 x（`axis` 为 `y` 时为每个 y）上的最大匹配得分（`scoreProfile`），默认 false */
+  returnScoreProfile?: boolean
+  /** 是否在结果中返回完整的匹配得分图（`heatmap`），默认 false */
+  returnHeatmap?: boolean
+  /**
+   * 是否在进程内缓存背景图的边缘图，默认 false；
+   * 同一张背景图（图片数据、预处理配置和阈值都相同）重复匹配时跳过背景的边缘检测，
+   * 可调用 `clearBackgroundCache` 清空
+   */
+  cacheBackground?: boolean
+  /** Canny 边缘检测前额外执行的高斯模糊 σ，默认不模糊；优先级高于 `auto_sigma` */
+  cannySigma?: number
+  /**
+   * 是否按背景图分辨率自动选择 Canny 前的高斯模糊 σ，默认 false；
+   * σ = max(1.0, sqrt(宽 × 高) / 500)，目标图和背景图使用相同的 σ
+   */
+  autoSigma?: boolean
+  /**
+   * 是否清除背景边缘图中疑似文字（站点名称、标签等）的水平条带，默认 false；
+   * 边缘密度明显高于整张图、高度不超过 24 像素的连续行视为文字区域
+   */
+  maskTextRegions?: boolean
+  /**
+   * 返回结果中 `x1/y1` 的含义，默认 `topLeft`（左上角）；为 `center` 时为匹配区域的中心点，
+   * 换算到显示尺寸后再取中心
+   */
+  coordinateMode?: CoordinateMode
+  /**
+   * 返回结果的坐标单位，默认 `pixel`；为 `ratio` 时在 `SlideBBox.ratio` 中额外返回
+   * 按图片原始尺寸归一化的坐标（0-1），像素坐标字段保持不变
+   */
+  coordinateUnit?: CoordinateUnit
+  /**
+   * 目标图片大于背景图片时的处理方式，默认 `error`；缩放后返回的坐标仍在原始背景图的坐标系中，
+   * 结果的 `targetScale` 记录实际的缩放比例。只对 `slideMatch`、`simpleSlideMatch` 和改进版函数生效
+   */
+  oversizePolicy?: OversizePolicy
+  /**
+   * 匹配前把背景图片重采样（Lanczos3）到该宽度，高度按比例缩放，用于目标图片对应的是按该宽度
+   * 渲染的背景图、而不是背景图原始尺寸的情况。只对 `slideMatch`、`simpleSlideMatch` 和改进版函数生效
+   */
+  backgroundTargetWidth?: number
+  /**
+   * 指定 `backgroundTargetWidth` 时返回坐标所在的坐标系，默认 `resized`（重采样后的背景图）；
+   * `fixedY` 和 `fixedYTolerance` 同样按该坐标系解释
+   */
+  backgroundCoordinateSpace?: BackgroundCoordinateSpace
+  /**
+   * 背景图中画有滑块本身（起始位置）的区域，该区域的背景边缘在匹配前清零，避免匹配到滑块本身
+   * 而返回接近 0 的滑动距离；可以是原始尺寸像素的矩形，或 `autoLeft`（左侧与目标图片等宽的整列）
+   */
+  ignorePieceRegion?: PieceRegion | PieceRegionPreset
+  /**
+   * 原版函数（`slideMatch`、`simpleSlideMatch`）的置信度阈值，范围 0.0-1.0，默认不检查；
+   * 设置后结果包含 `confidence`（匹配得分峰值），峰值不高于阈值时报错而不是返回不可靠的位置。
+   * 改进版函数使用各自的 `confidenceThreshold` 参数
+   */
+  confidenceThreshold?: number
+  /**
+   * `slideMatch` 的小目标快速路径，默认 false；开启后裁剪后的目标图片像素数小于 2500（约 50×50）时
+   * 跳过 Canny 边缘检测，直接在灰度图上做归一化互相关（相当于 `edgeMode: 'none'`）。
+   * 互相关只遍历模板中的非零像素，灰度图比边缘图稠密得多，因此总耗时不一定更短；
+   * 匹配效果与 `edgeMode: 'none'` 相同，只适合缺口与滑块外观接近的背景
+   */
+  fastSmall?: boolean
+  /**
+   * 判断匹配是否模棱两可的相对差距，范围 0.0-1.0，默认不检查；设置后结果包含 `isAmbiguous`、
+   * `secondBestScore` 和 `secondBestX`，次优峰值得分不低于 最优得分 × (1 - `ambiguityMargin`) 时
+   * `isAmbiguous` 为 true。只对 `slideMatch`、`simpleSlideMatch` 和改进版函数生效
+   */
+  ambiguityMargin?: number
+  /** 改进版函数首次匹配（自适应阈值）的置信度不高于 `confidenceThreshold` 时的处理方式，默认 `fallback` */
+  onLowConfidence?: LowConfidenceAction
+  /** 是否在结果中返回峰值旁瓣比（`psr`），默认 false。只对 `slideMatch`、`simpleSlideMatch` 和改进版函数生效 */
+  returnPsr?: boolean
+  /**
+   * 缺口定位方式，默认 `correlation`。`contour` 只对 `slideMatch` 及其路径、掩码版本生效，
+   * `confidence` 为形状相似度，得分图相关的选项（`returnScoreProfile`、`returnHeatmap`、
+   * `ambiguityMargin`、`returnPsr`、`fixedY`）不生效；`orientation` 和 `chamfer` 替换所有边缘图模板匹配的
+   * 得分计算，`edgeMode` 为 `none` 时不生效
+   */
+  strategy?: MatchStrategy
+  /**
+   * `x1/y1/x2/y2` 的参照点，默认 `backgroundAbsolute`；为 `backgroundRelativeToTarget` 时
+   * 减去（换算到显示尺寸的）`target_x/target_y`，`coordinateUnit` 为 `ratio` 时同样减去原始尺寸下的裁剪起点
+   */
+  coordinateSpace?: CoordinateSpace
+  /** `strategy` 为 `orientation` 时梯度方向（0°-180°）的分箱数，范围 1-64，默认 8 */
+  orientationBins?: number
+  /**
+   * 截图的设备像素比（如高分屏上的 2），设置后返回的 `x1/y1/x2/y2/target_x/target_y` 在显示尺寸换算后
+   * 再除以 DPR，得到可直接用于 `page.mouse.move()` 的 CSS 像素坐标；必须是大于 0 的有限数值
+   */
+  devicePixelRatio?: number
+  /**
+   * 背景灰度图的中值滤波半径（像素），0 表示不滤波，范围 0-255；在 CLAHE、自适应阈值计算和边缘检测之前执行，
+   * 用于去除低质量 JPEG 的 8×8 块噪声
+   */
+  medianFilterRadius?: number
+  /** 是否同时对目标图片做中值滤波，默认 false */
+  medianFilterTarget?: boolean
+  /**
+   * 是否在中值滤波、CLAHE、阈值计算和边缘检测之前把灰度图反相（255 - 灰度值），默认 false；
+   * 近乎全黑的背景上画浅色轮廓的深色主题验证码，灰度直方图挤在 0 附近，自适应阈值过低。
+   * 为 `auto` 时目标图片和背景图片分别判断，灰度均值低于 `invertCutoff` 的才反相
+   */
+  invert?: boolean | InvertMode
+  /** `invert` 为 `auto` 时的灰度均值阈值，范围 0-255，默认 64 */
+  invertCutoff?: number
+  /**
+   * 滑块的滑动方向，默认 `x`；为 `y` 时在转置后的图片上沿 y 方向匹配，`fixedX`、`scoreProfile` 和
+   * `ignorePieceRegion: 'autoLeft'`（此时为顶部与目标图片等高的整行）都按 y 方向处理，`maskTextRegions`
+   * 清除的是竖直条带。只对 `slideMatch`、`simpleSlideMatch` 和改进版函数生效，其他函数设置为 `y` 时报错
+   */
+  axis?: SlideAxis
+  /**
+   * 缺口（匹配区域左上角）的 x 坐标，即结果中的 `x1`（原始尺寸像素），只在 `axis` 为 `y` 时可用；
+   * 指定后只在该列附近沿 y 方向搜索，与水平滑动时的 `fixedY` 对应
+   */
+  fixedX?: number
+  /** `fixed_x` 左右允许的偏差（像素），默认 0 */
+  fixedXTolerance?: number
+  /**
+   * 背景图片带透明度通道（圆角、透明边框等）时，灰度转换前把它合成到的纯色 `[r, g, b]`，默认白色
+   * `[255, 255, 255]`；透明像素直接转灰度时通常为黑色，会在透明区域边界产生大片假边缘
+   */
+  backgroundFill?: Array<number>
+  /**
+   * 模板匹配的滑动步长（像素），范围 1-16，默认 1（逐像素）；大于 1 时先按步长粗搜索，
+   * 再在最优位置周围逐像素细化，速度更快但可能错过不在网格上的缺口，见 `ncc::match_template_strided`。
+   * 只对默认的 `correlation` 策略生效
+   */
+  stride?: number
+  /**
+   * 模板匹配前在背景边缘图四周补 0 的宽度（像素），默认 0；缺口靠近背景右侧或底部边缘、
+   * 目标图片（含透明边距）放到正确位置时会超出背景的情况下使用。结果的 `x2/y2` 可能大于背景尺寸，
+   * 超出左侧和上方的位置无法用无符号坐标表示，不参与比较；只搜索固定行（`fixedY`）时不在上下补
+   */
+  backgroundPadding?: number
+  /**
+   * 目标图片至少需要的不透明像素数（透明度大于 0），默认 1，即只拒绝完全透明的目标图片；0 表示不检查。
+   * 不足时报错“目标图片没有不透明像素”或“目标图片只有 N 个不透明像素”
+   */
+  minOpaquePixels?: number
+  /**
+   * 目标图片的不透明像素不足 `minOpaquePixels` 时不报错，继续匹配并在结果中设置 `degenerateTarget: true`，
+   * 默认 false。只有返回 `SlideBBox` 的 `slideMatch`、`simpleSlideMatch` 和改进版函数会设置该标记
+   */
+  allowDegenerateTarget?: boolean
+  /**
+   * 在结果的 `elapsedMs` 中返回匹配耗时（毫秒），默认 false；不含 JavaScript 与原生模块之间传递参数的开销。
+   * 只对 `slideMatch`、`simpleSlideMatch` 和改进版函数（含按路径读取、异步和 `try` 前缀的变体）生效
+   */
+  measureTime?: boolean
+}
+
+/**
+ * 交叉验证：同时运行改进版（`improvedSlideMatch`）和原版（`slideMatch`）算法，返回两个结果及是否一致，
+ * 便于在切换算法前用线上流量做对比
+ *
+ * # 参数
+ * - target: 目标图片 Buffer
+ * - background: 背景图片 Buffer
+ * - options: 交叉验证选项
+ * - config: 可选配置，见 `SlideMatchConfig`
+ */
+export declare function slideMatchCrossCheck(target: Buffer, background: Buffer, options?: CrossCheckOptions | undefined | null, config?: SlideMatchConfig | undefined | null): CrossCheckResult
+
+/**
+ * 内嵌滑块匹配：滑块直接画在背景图中（通常位于左侧起始位置），没有单独的目标图片
+ *
+ * 先根据 `pieceRegionHint` 或自动检测（图片左侧 1/3 内边缘对比度最高的矩形）确定滑块区域，
+ * 以该区域为模板，在排除滑块区域的背景图中寻找缺口。
+ *
+ * # 参数
+ * - background: 包含滑块的背景图片 Buffer
+ * - piece_region_hint: 可选，滑块区域（原始尺寸像素）
+ * - options: 可选配置，见 `SlideMatchConfig`
+ */
+export declare function slideMatchEmbedded(background: Buffer, pieceRegionHint?: PieceRegion | undefined | null, options?: SlideMatchConfig | undefined | null): EmbeddedMatchResult
+
+/**
+ * 多目标图片匹配：同一个滑块有多个版本（不同分辨率、略有差异的裁剪）时，并行尝试每一个，
+ * 返回置信度最高的结果
+ *
+ * 每个目标图片的匹配方式与 `slideMatch` 相同。未设置 `confidenceThreshold` 时以 0 为阈值，
+ * 保证每个结果都带有 `confidence`；匹配失败（包括置信度不高于阈值）的目标图片在 `allResults`
+ * 中为 null，全部失败时报错。
+ *
+ * # 参数
+ * - targets: 目标图片 Buffer 列表，不能为空
+ * - background: 背景图片 Buffer
+ * - options: 可选配置，见 `SlideMatchConfig`
+ */
+export declare function slideMatchMultiTarget(targets: Array<Buffer>, background: Buffer, options?: SlideMatchConfig | undefined | null): MultiTargetResult
+
+/**
+ * 滑块匹配（带透明背景裁剪），返回 0.0-1.0 范围内、相对于背景图片原始尺寸的坐标，
+ * 便于在不同视口尺寸下换算；参数与 `slideMatch` 相同
+ */
+export declare function slideMatchNormalized(targetImage: Buffer, backgroundImage: Buffer, options?: SlideMatchConfig | undefined | null): SlideBBoxNormalized
+
+/**
+ * 返回完整的模板匹配得分矩阵，供调用方自行实现寻峰逻辑
+ *
+ * 预处理方式与 `slideMatch` 一致（裁剪透明区域、固定 Canny 阈值），`slideMatch` 的结果即矩阵中
+ * 得分最大的位置。矩阵坐标为处理尺寸下的像素，不做 `backgroundDisplayWidth` 换算。
+ *
+ * # 参数
+ * - target: 目标图片 Buffer
+ * - background: 背景图片 Buffer
+ * - options: 可选配置，见 `SlideMatchConfig`
+ */
+export declare function slideMatchScoreMatrix(target: Buffer, background: Buffer, options?: SlideMatchConfig | undefined | null): ScoreMatrix
+
+/**
+ * 还原切片打乱的背景图后执行滑块匹配（带透明背景裁剪），切片参数见 `restoreBackground`
+ *
+ * # 参数
+ * - target: 目标图片 Buffer
+ * - background: 打乱的背景图片 Buffer
+ * - slice_order: 切片顺序
+ * - slice_width: 切片宽度（像素）
+ * - split_y: 可选，上下两部分的分界行
+ * - options: 可选配置，见 `SlideMatchConfig`
+ */
+export declare function slideMatchShuffled(target: Buffer, background: Buffer, sliceOrder: Array<number>, sliceWidth: number, splitY?: number | undefined | null, options?: SlideMatchConfig | undefined | null): SlideBBox
+
+/** 滑块匹配（带透明背景裁剪），返回有符号坐标，参数与 `slideMatch` 相同 */
+export declare function slideMatchSigned(targetImage: Buffer, backgroundImage: Buffer, options?: SlideMatchConfig | undefined | null): SlideBBoxSigned
+
+/**
+ * 流式多目标匹配（异步）
+ *
+ * 逐行扫描匹配得分图，每发现一个得分不低于 `threshold` 的局部最大值就以 `(err, candidate)`
+ * 非阻塞地调用一次 `callback`，全部扫描完成后返回结果总数。预处理方式与 `slideMatch` 一致。
+ *
+ * # 参数
+ * - target: 目标图片 Buffer，不会被复制，匹配完成前不要修改
+ * - background: 背景图片 Buffer，不会被复制，匹配完成前不要修改
+ * - threshold: 最低匹配得分，范围 0.0-1.0
+ * - callback: 接收每个结果的回调函数
+ * - options: 可选配置，见 `SlideMatchConfig`
  */
-export declare function slideMatch(targetImage: Buffer, backgroundImage: Buffer): SlideBBox
+export declare function slideMatchStreamResults(target: Buffer, background: Buffer, threshold: number, callback: ((err: Error | null, arg: MatchCandidate) => any), options?: SlideMatchConfig | undefined | null): Promise<number>
+
+/** `improvedSlideMatchVerbose` 的返回值 */
+export interface SlideMatchVerbose {
+  /** 与 `improvedSlideMatch` 相同的匹配结果 */
+  bbox: SlideBBox
+  /** 按执行顺序排列的每次匹配尝试，未回退时只有 `adaptive` 一项 */
+  attempts: Array<MatchAttempt>
+}
+
+/**
+ * 带背景掩码的滑块匹配（带透明背景裁剪）
+ * 匹配前将背景边缘图中掩码值为 0 的像素清零，只在掩码内的区域搜索缺口
+ *
+ * # 参数
+ * - target_image: 目标图片 Buffer
+ * - background_image: 背景图片 Buffer
+ * - mask: 掩码图片 Buffer，单通道灰度图，尺寸必须与背景图片一致
+ * - options: 可选配置，见 `SlideMatchConfig`
+ */
+export declare function slideMatchWithMask(targetImage: Buffer, backgroundImage: Buffer, mask: Buffer, options?: SlideMatchConfig | undefined | null): SlideBBox
+
+/** 滑块形状 */
+export declare enum SliderShape {
+  /** 矩形（几乎没有透明区域） */
+  Rectangle = 'rectangle',
+  /** 拼图块等不规则形状 */
+  PuzzlePiece = 'puzzlePiece',
+  /** 圆形 */
+  Circle = 'circle',
+  /** 无法识别（没有不透明像素或不透明区域过少） */
+  Unknown = 'unknown'
+}
+
+/**
+ * 只返回滑块需要水平拖动的距离（`x1 - targetX`，小于 0 时为 0），参数与 `slideMatch` 相同
+ *
+ * 按 `slideMatch` 匹配，`coordinateMode` 和 `coordinateSpace` 不生效；设置了显示尺寸时返回显示尺寸下的距离
+ */
+export declare function slideXOffset(target: Buffer, background: Buffer, options?: SlideMatchConfig | undefined | null): number
+
+/** 单个阶段的耗时统计（毫秒） */
+export interface StageTimings {
+  /** 中位数 */
+  medianMs: number
+  /** 平均值 */
+  meanMs: number
+  /** 95 分位数（最近秩法） */
+  p95Ms: number
+}
+
+/** 目标图片（滑块）的质量评估 */
+export interface TargetQuality {
+  /** 裁剪透明边框后，按 `slideMatch` 的预处理和固定 Canny 阈值 (100, 200) 得到的边缘像素占比（不含透明区域内的边缘） */
+  edgeDensity: number
+  /** 不透明像素（透明度大于 0）占整张图片的比例 */
+  opaqueFraction: number
+  /** 裁剪透明边框后的宽高比（宽 / 高），没有不透明像素时为 0 */
+  aspectRatio: number
+  /**
+   * 综合评分，范围 0.0-1.0：边缘占比（达到 10% 为满分）、不透明像素数（达到 1024 为满分）和
+   * 宽高比（在 1:2 到 2:1 之间为满分）三项得分按 0.5、0.3、0.2 的权重取加权几何平均，
+   * 任何一项为 0（如没有边缘）时综合评分为 0
+   */
+  overallScore: number
+}
+
+/**
+ * 评估目标图片（滑块）是否适合匹配
+ *
+ * 在匹配之前检查滑块的边缘是否丰富、不透明像素是否足够、宽高比是否正常，返回各项指标和综合评分
+ * （`overallScore`，范围 0.0-1.0）。综合评分低于 0.3 时通过 `tracing` 输出警告，此时匹配结果很可能不可靠，
+ * 可以换一张验证码。
+ *
+ * # 参数
+ * - target: 目标图片 Buffer
+ */
+export declare function targetQualityScore(target: Buffer): TargetQuality
+
+/** 模板匹配的得分计算方式 */
+export declare enum TemplateMatchMethod {
+  /** 差值平方和，越小越匹配 */
+  SumOfSquaredErrors = 'sumOfSquaredErrors',
+  /** 归一化差值平方和，越小越匹配 */
+  SumOfSquaredErrorsNormalized = 'sumOfSquaredErrorsNormalized',
+  /** 互相关，越大越匹配 */
+  CrossCorrelation = 'crossCorrelation',
+  /** 归一化互相关，越大越匹配（默认，与匹配函数一致） */
+  CrossCorrelationNormalized = 'crossCorrelationNormalized'
+}
+
+/** Canny 阈值的计算策略，除 `Fixed` 外都按每张图片的灰度分布计算 */
+export declare enum ThresholdStrategy {
+  /** 固定阈值 low = 100，high = 200（原版函数的默认值），可用 `cannyLow/cannyHigh` 等选项指定其他值 */
+  Fixed = 'fixed',
+  /** 均值和标准差：low = 均值 - 标准差，high = 均值 + 2 * 标准差（改进版函数的默认值） */
+  MeanStdDev = 'meanStdDev',
+  /** Otsu 法：high = 使前景和背景类间方差最大的灰度值，low = 0.5 * high，适合双峰灰度分布的图片 */
+  Otsu = 'otsu',
+  /**
+   * 双峰直方图：找出灰度直方图中被谷底分开的两个主峰，low = 谷底 - 暗峰，high = 亮峰 - 暗峰
+   * （即两块区域分界处的灰度差），适合两块区域分明的图片；找不到第二个峰时与 `MeanStdDev` 相同
+   */
+  Bimodal = 'bimodal',
+  /**
+   * 中位数：low = 0.66 * 中位数，high = 1.33 * 中位数，
+   * 对大片明亮天空等灰度分布偏斜的图片更稳定
+   */
+  Median = 'median'
+}
+
+/**
+ * 不抛出异常的 `improvedSlideMatch`，参数与 `improvedSlideMatch` 相同，失败时的处理见 `trySlideMatch`
+ *
+ * `onLowConfidence` 为 `error` 时，置信度过低返回 `ok: false` 和 `lowConfidence` 错误码。
+ */
+export declare function tryImprovedSlideMatch(targetImage: Buffer, backgroundImage: Buffer, confidenceThreshold?: number | undefined | null, options?: SlideMatchConfig | undefined | null): TrySlideMatchResult
+
+/**
+ * 不抛出异常的 `slideMatch`，参数与 `slideMatch` 相同
+ *
+ * 图片无法解码、目标图片大于背景图片、置信度不高于 `confidenceThreshold` 等可预期的失败不抛出异常，
+ * 而是返回 `ok: false` 和错误码，适合大批量匹配时避免构造异常的开销；
+ * 选项类型错误、配置值无效等调用方的错误仍会抛出异常。
+ */
+export declare function trySlideMatch(targetImage: Buffer, backgroundImage: Buffer, options?: SlideMatchConfig | undefined | null): TrySlideMatchResult
+
+/**
+ * 不抛出异常的匹配函数（`trySlideMatch` 等）的返回值
+ *
+ * `ok` 为 true 时返回 `bbox` 和 `confidence`，否则返回 `code` 和 `message`
+ */
+export interface TrySlideMatchResult {
+  /** 是否匹配成功 */
+  ok: boolean
+  /** 匹配结果，与对应的抛出异常的函数的返回值相同 */
+  bbox?: SlideBBox
+  /** 匹配得分，即 `bbox.confidence` */
+  confidence?: number
+  /** 失败原因 */
+  code?: ErrorCode
+  /** 错误信息，与对应的函数抛出的异常信息相同 */
+  message?: string
+}
@@ -572,9 +572,69 @@ if (!nativeBinding) {
 }
 
 module.exports = nativeBinding
+module.exports.BackgroundCoordinateSpace = nativeBinding.BackgroundCoordinateSpace
+module.exports.bboxFromCenter = nativeBinding.bboxFromCenter
+module.exports.bboxIntersection = nativeBinding.bboxIntersection
+module.exports.bboxUnion = nativeBinding.bboxUnion
+module.exports.benchmark = nativeBinding.benchmark
+module.exports.cannyEdges = nativeBinding.cannyEdges
+module.exports.classifyTargetShape = nativeBinding.classifyTargetShape
+module.exports.clearBackgroundCache = nativeBinding.clearBackgroundCache
+module.exports.ColorMode = nativeBinding.ColorMode
+module.exports.computeDifferenceMap = nativeBinding.computeDifferenceMap
+module.exports.computeImageStats = nativeBinding.computeImageStats
+module.exports.CoordinateMode = nativeBinding.CoordinateMode
+module.exports.CoordinateSpace = nativeBinding.CoordinateSpace
+module.exports.CoordinateUnit = nativeBinding.CoordinateUnit
+module.exports.cropTransparent = nativeBinding.cropTransparent
+module.exports.DatasetAlgorithm = nativeBinding.DatasetAlgorithm
+module.exports.doubleSlideMatch = nativeBinding.doubleSlideMatch
+module.exports.EdgeMode = nativeBinding.EdgeMode
+module.exports.ErrorCode = nativeBinding.ErrorCode
+module.exports.evaluateDataset = nativeBinding.evaluateDataset
+module.exports.findAllMatches = nativeBinding.findAllMatches
+module.exports.generateBezierDragPath = nativeBinding.generateBezierDragPath
+module.exports.generateDragPath = nativeBinding.generateDragPath
 module.exports.improvedSimpleSlideMatch = nativeBinding.improvedSimpleSlideMatch
 module.exports.improvedSimpleSlideMatchWithPath = nativeBinding.improvedSimpleSlideMatchWithPath
+module.exports.improvedSimpleSlideMatchWithPathAsync = nativeBinding.improvedSimpleSlideMatchWithPathAsync
 module.exports.improvedSlideMatch = nativeBinding.improvedSlideMatch
+module.exports.improvedSlideMatchAsync = nativeBinding.improvedSlideMatchAsync
+module.exports.improvedSlideMatchVerbose = nativeBinding.improvedSlideMatchVerbose
 module.exports.improvedSlideMatchWithPath = nativeBinding.improvedSlideMatchWithPath
+module.exports.improvedSlideMatchWithPathAsync = nativeBinding.improvedSlideMatchWithPathAsync
+module.exports.improvedSlideXOffset = nativeBinding.improvedSlideXOffset
+module.exports.InvertMode = nativeBinding.InvertMode
+module.exports.LowConfidenceAction = nativeBinding.LowConfidenceAction
+module.exports.matchAtPosition = nativeBinding.matchAtPosition
+module.exports.MatchBackend = nativeBinding.MatchBackend
+module.exports.MatchStrategy = nativeBinding.MatchStrategy
+module.exports.matchTemplateRaw = nativeBinding.matchTemplateRaw
+module.exports.OversizePolicy = nativeBinding.OversizePolicy
+module.exports.PieceRegionPreset = nativeBinding.PieceRegionPreset
+module.exports.previewPlacement = nativeBinding.previewPlacement
+module.exports.renderMatchOverlay = nativeBinding.renderMatchOverlay
+module.exports.restoreBackground = nativeBinding.restoreBackground
+module.exports.rotationMatch = nativeBinding.rotationMatch
+module.exports.ScoreMatrix = nativeBinding.ScoreMatrix
+module.exports.setBackend = nativeBinding.setBackend
 module.exports.simpleSlideMatch = nativeBinding.simpleSlideMatch
+module.exports.SlideAxis = nativeBinding.SlideAxis
+module.exports.slideComparison = nativeBinding.slideComparison
 module.exports.slideMatch = nativeBinding.slideMatch
+module.exports.slideMatchCrossCheck = nativeBinding.slideMatchCrossCheck
+module.exports.slideMatchEmbedded = nativeBinding.slideMatchEmbedded
+module.exports.slideMatchMultiTarget = nativeBinding.slideMatchMultiTarget
+module.exports.slideMatchNormalized = nativeBinding.slideMatchNormalized
+module.exports.slideMatchScoreMatrix = nativeBinding.slideMatchScoreMatrix
+module.exports.slideMatchShuffled = nativeBinding.slideMatchShuffled
+module.exports.slideMatchSigned = nativeBinding.slideMatchSigned
+module.exports.slideMatchStreamResults = nativeBinding.slideMatchStreamResults
+module.exports.slideMatchWithMask = nativeBinding.slideMatchWithMask
+module.exports.SliderShape = nativeBinding.SliderShape
+module.exports.slideXOffset = nativeBinding.slideXOffset
+module.exports.targetQualityScore = nativeBinding.targetQualityScore
+module.exports.TemplateMatchMethod = nativeBinding.TemplateMatchMethod
+module.exports.ThresholdStrategy = nativeBinding.ThresholdStrategy
+module.exports.tryImprovedSlideMatch = nativeBinding.tryImprovedSlideMatch
+module.exports.trySlideMatch = nativeBinding.trySlideMatch
@@ -0,0 +1,37 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use napi_derive::napi;
+
+/// 模板匹配（归一化互相关）的计算后端
+#[napi(string_enum = "camelCase")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MatchBackend {
+  /// 始终在 CPU 上计算（默认）
+  #[default]
+  Cpu,
+  /// 在 GPU 上计算；需要启用 `gpu` 特性，GPU 不可用时回退到 CPU
+  Gpu,
+  /// 背景图较大时使用 GPU，其余情况和 GPU 不可用时使用 CPU
+  Auto,
+}
+
+// 当前后端，存储 `MatchBackend` 的判别值
+static BACKEND: AtomicU8 = AtomicU8::new(MatchBackend::Cpu as u8);
+
+/// 当前选择的计算后端
+pub fn current() -> MatchBackend {
+  match BACKEND.load(Ordering::Relaxed) {
+    x if x == MatchBackend::Gpu as u8 => MatchBackend::Gpu,
+    x if x == MatchBackend::Auto as u8 => MatchBackend::Auto,
+    _ => MatchBackend::Cpu,
+  }
+}
+
+/// 选择模板匹配的计算后端，对进程内之后的所有匹配生效
+///
+/// 选择 `gpu` 或 `auto` 时，首次匹配会初始化 GPU 设备并在之后的调用中复用；没有启用 `gpu` 特性、
+/// 找不到可用的 GPU 或初始化失败时通过 `tracing` 输出一次警告并回退到 CPU，匹配结果不受影响。
+#[napi]
+pub fn set_backend(backend: MatchBackend) {
+  BACKEND.store(backend as u8, Ordering::Relaxed);
+}
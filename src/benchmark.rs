@@ -0,0 +1,195 @@
+use std::time::{Duration, Instant};
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::decode::{self, DEFAULT_MAX_IMAGE_PIXELS};
+use crate::error::{napi_error, ErrorStatus};
+use crate::preprocess::{self, MatchImage};
+use crate::test_utils::{generate_slider_pair_sized, MIN_SLIDER_SIZE};
+use crate::{
+  crop_transparent, ncc, slide_match_internal, SlideMatchConfig, FIXED_CANNY_THRESHOLDS,
+};
+
+// 默认的合成背景图尺寸
+const DEFAULT_BACKGROUND_SIZE: (u32, u32) = (320, 160);
+// 默认重复次数
+const DEFAULT_ITERATIONS: u32 = 20;
+// 重复次数上限
+const MAX_ITERATIONS: u32 = 1000;
+
+/// `benchmark` 的可选配置
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct BenchmarkOptions {
+  /// 合成背景图的尺寸 `[宽, 高]`，默认 `[320, 160]`，像素数不超过 4000000
+  pub background_size: Option<(u32, u32)>,
+  /// 正方形滑块的边长，默认为背景图高度的 1/3（至少 8），不能超过背景图的宽和高
+  pub target_size: Option<u32>,
+  /// 重复次数，范围 1-1000，默认 20
+  pub iterations: Option<u32>,
+}
+
+/// 单个阶段的耗时统计（毫秒）
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct StageTimings {
+  /// 中位数
+  pub median_ms: f64,
+  /// 平均值
+  pub mean_ms: f64,
+  /// 95 分位数（最近秩法）
+  pub p95_ms: f64,
+}
+
+/// `benchmark` 的返回值
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct BenchmarkResult {
+  /// 解码目标图片和背景图片
+  pub decode: StageTimings,
+  /// 裁剪透明边框、灰度转换和两张图片的 Canny 边缘检测（固定阈值 100, 200）
+  pub canny: StageTimings,
+  /// 边缘图模板匹配（归一化互相关）和峰值查找
+  pub matching: StageTimings,
+  /// 以上三个阶段之和
+  pub total: StageTimings,
+  /// 实际的重复次数
+  pub iterations: u32,
+  /// 合成背景图的尺寸 `[宽, 高]`
+  pub background_size: (u32, u32),
+  /// 滑块边长
+  pub target_size: u32,
+  /// 是否以 `simd` 特性编译（灰度转换和互相关使用 SIMD 加速）
+  pub simd: bool,
+  /// rayon 线程池的线程数，多目标图片匹配等并行路径使用
+  pub threads: u32,
+  /// `slideMatch` 是否在合成图片上找到了缺口，可用于确认原生模块在当前环境中工作正常
+  pub matched: bool,
+}
+
+// 一次流水线各阶段的耗时
+struct StageDurations {
+  decode: Duration,
+  canny: Duration,
+  matching: Duration,
+}
+
+// 按 `slideMatch` 的默认流程分阶段执行一次匹配，返回各阶段耗时和匹配到的 x1
+fn run_stages(
+  target: &[u8],
+  background: &[u8],
+  config: &SlideMatchConfig,
+) -> anyhow::Result<(StageDurations, u32)> {
+  let start = Instant::now();
+  let target_image = decode::load_image(target, "目标图片", config)?;
+  let background_image = decode::load_background(background, config)?;
+  let decoded = Instant::now();
+
+  let (cropped_image, _) = crop_transparent(target_image.into_rgba8());
+  let target = MatchImage::from_rgba(&cropped_image, config);
+  let background = MatchImage::from_dynamic(background_image, config);
+  let target_edges = preprocess::detect_edges(target.gray, FIXED_CANNY_THRESHOLDS, config);
+  let background_edges =
+    preprocess::detect_background_edges(background.gray, FIXED_CANNY_THRESHOLDS, config);
+  let detected = Instant::now();
+
+  let scores = ncc::match_template_normalized(&background_edges, &target_edges);
+  let (x1, _) = ncc::find_extremes(&scores).max_value_location;
+  let matched = Instant::now();
+
+  Ok((
+    StageDurations {
+      decode: decoded - start,
+      canny: detected - decoded,
+      matching: matched - detected,
+    },
+    x1,
+  ))
+}
+
+// 中位数、平均值和 95 分位数，`samples` 不能为空
+fn summarize(mut samples: Vec<f64>) -> StageTimings {
+  samples.sort_by(f64::total_cmp);
+  let n = samples.len();
+  let median_ms = if n % 2 == 1 {
+    samples[n / 2]
+  } else {
+    (samples[n / 2 - 1] + samples[n / 2]) / 2.0
+  };
+  StageTimings {
+    median_ms,
+    mean_ms: samples.iter().sum::<f64>() / n as f64,
+    p95_ms: samples[(n * 95).div_ceil(100) - 1],
+  }
+}
+
+pub fn benchmark_internal(options: &BenchmarkOptions) -> anyhow::Result<BenchmarkResult> {
+  let (width, height) = options.background_size.unwrap_or(DEFAULT_BACKGROUND_SIZE);
+  anyhow::ensure!(
+    width as u64 * height as u64 <= DEFAULT_MAX_IMAGE_PIXELS,
+    "背景图像素数 {} 超过上限 {DEFAULT_MAX_IMAGE_PIXELS}",
+    width as u64 * height as u64
+  );
+  let target_size = options
+    .target_size
+    .unwrap_or((height / 3).max(MIN_SLIDER_SIZE));
+  anyhow::ensure!(
+    target_size >= MIN_SLIDER_SIZE && target_size <= width.min(height),
+    "滑块边长必须在 {MIN_SLIDER_SIZE}-{} 范围内（背景图 {width}x{height}）",
+    width.min(height)
+  );
+  let iterations = options.iterations.unwrap_or(DEFAULT_ITERATIONS);
+  anyhow::ensure!(
+    (1..=MAX_ITERATIONS).contains(&iterations),
+    "iterations 必须在 1-{MAX_ITERATIONS} 范围内"
+  );
+
+  // 缺口位于背景图宽度的 2/3 处，放不下时贴近右边缘
+  let slider_x = (width * 2 / 3).min(width - target_size);
+  let (target, background) = generate_slider_pair_sized(width, height, slider_x, target_size);
+  let config = SlideMatchConfig::default();
+
+  // 完整的 `slideMatch` 同时作为预热
+  let matched = slide_match_internal(&target, &background, None, &config)?.x1 == slider_x;
+
+  let mut durations = Vec::with_capacity(iterations as usize);
+  for _ in 0..iterations {
+    durations.push(run_stages(&target, &background, &config)?.0);
+  }
+
+  let millis = |stage: fn(&StageDurations) -> Duration| {
+    summarize(
+      durations
+        .iter()
+        .map(|d| stage(d).as_secs_f64() * 1000.0)
+        .collect(),
+    )
+  };
+  Ok(BenchmarkResult {
+    decode: millis(|d| d.decode),
+    canny: millis(|d| d.canny),
+    matching: millis(|d| d.matching),
+    total: millis(|d| d.decode + d.canny + d.matching),
+    iterations,
+    background_size: (width, height),
+    target_size,
+    simd: cfg!(feature = "simd"),
+    threads: rayon::current_num_threads() as u32,
+    matched,
+  })
+}
+
+/// 内置基准测试：合成一张带缺口的背景图和对应的滑块，按 `slideMatch` 的默认流程重复匹配，
+/// 返回解码、Canny 边缘检测和模板匹配各阶段的耗时统计
+///
+/// 用于评估当前机器（或容器镜像）上的匹配速度，同时确认原生模块能够正常工作（见 `matched`）。
+/// 计时前先完整执行一次 `slideMatch` 作为预热。
+///
+/// # 参数
+/// - options: 可选配置，见 `BenchmarkOptions`
+#[napi]
+pub fn benchmark(options: Option<BenchmarkOptions>) -> Result<BenchmarkResult, ErrorStatus> {
+  benchmark_internal(&options.unwrap_or_default())
+    .map_err(|e| napi_error(&e, format!("基准测试失败: {e}")))
+}
@@ -0,0 +1,152 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, OnceLock, RwLock};
+
+use image::GrayImage;
+use napi::bindgen_prelude::Either;
+use napi_derive::napi;
+
+use crate::preprocess::{detect_background_edges, MatchImage};
+use crate::{InvertMode, SlideAxis, SlideMatchConfig};
+
+// 缓存的最大条目数，超过时清空重新开始
+const MAX_CACHE_ENTRIES: usize = 64;
+
+/// 背景图各通道的边缘图（已完成 Canny 边缘检测和形态学后处理，覆盖整张背景图）
+#[derive(Debug)]
+pub struct BackgroundEdges {
+  channels: Vec<GrayImage>,
+}
+
+impl BackgroundEdges {
+  // 对背景图的所有通道执行边缘检测
+  pub(crate) fn detect(
+    background: &MatchImage,
+    thresholds: (f32, f32),
+    config: &SlideMatchConfig,
+  ) -> Self {
+    Self {
+      channels: background
+        .channels()
+        .iter()
+        .map(|channel| detect_background_edges(channel.clone(), thresholds, config))
+        .collect(),
+    }
+  }
+
+  /// 各通道的边缘图，顺序与参与匹配的通道一致
+  pub fn channels(&self) -> &[GrayImage] {
+    &self.channels
+  }
+}
+
+/// 背景边缘图缓存
+///
+/// 多个验证码请求共用同一张背景图时，只需做一次边缘检测。以背景图片数据、
+/// 预处理配置和 Canny 阈值的哈希为键，可以在多个线程间共享。
+#[derive(Debug, Clone, Default)]
+pub struct BackgroundCache {
+  entries: Arc<RwLock<HashMap<u64, Arc<BackgroundEdges>>>>,
+}
+
+impl BackgroundCache {
+  /// 进程内共享的全局缓存
+  pub fn global() -> &'static BackgroundCache {
+    static GLOBAL: OnceLock<BackgroundCache> = OnceLock::new();
+    GLOBAL.get_or_init(BackgroundCache::default)
+  }
+
+  /// 读取缓存，不存在时调用 `detect` 计算并写入
+  pub fn get_or_insert_with(
+    &self,
+    key: u64,
+    detect: impl FnOnce() -> BackgroundEdges,
+  ) -> Arc<BackgroundEdges> {
+    // 锁只在读写 HashMap 时持有，其他线程 panic 导致的中毒不影响数据一致性
+    if let Some(edges) = self
+      .entries
+      .read()
+      .unwrap_or_else(|e| e.into_inner())
+      .get(&key)
+    {
+      return edges.clone();
+    }
+
+    // 边缘检测耗时较长，不持有锁，并发计算同一张背景图时后写入的覆盖先写入的
+    let edges = Arc::new(detect());
+    let mut entries = self.entries.write().unwrap_or_else(|e| e.into_inner());
+    if entries.len() >= MAX_CACHE_ENTRIES {
+      entries.clear();
+    }
+    entries.insert(key, edges.clone());
+    edges
+  }
+
+  /// 缓存条目数
+  pub fn len(&self) -> usize {
+    self.entries.read().unwrap_or_else(|e| e.into_inner()).len()
+  }
+
+  /// 缓存是否为空
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// 清空缓存
+  pub fn clear(&self) {
+    self
+      .entries
+      .write()
+      .unwrap_or_else(|e| e.into_inner())
+      .clear();
+  }
+}
+
+// 计算背景图的缓存键，未启用 `cacheBackground` 时返回 None
+// 键包含图片数据和所有影响边缘图的预处理配置，Canny 阈值在匹配时再加入
+pub(crate) fn background_key(bytes: &[u8], config: &SlideMatchConfig) -> Option<u64> {
+  if !config.cache_background.unwrap_or(false) {
+    return None;
+  }
+
+  let mut hasher = DefaultHasher::new();
+  bytes.hash(&mut hasher);
+  config.clahe_tile_size.hash(&mut hasher);
+  config.clahe_clip_limit.map(f64::to_bits).hash(&mut hasher);
+  config.median_filter_radius.hash(&mut hasher);
+  config
+    .invert
+    .as_ref()
+    .map(|invert| match invert {
+      Either::A(invert) => *invert as u8,
+      Either::B(InvertMode::Auto) => 2,
+    })
+    .hash(&mut hasher);
+  config.invert_cutoff.map(f64::to_bits).hash(&mut hasher);
+  config.color_mode.map(|mode| mode as u8).hash(&mut hasher);
+  config.linear_light.hash(&mut hasher);
+  config.background_target_width.hash(&mut hasher);
+  config.background_fill.hash(&mut hasher);
+  config.edge_morphology.hash(&mut hasher);
+  config.dilate_edges.hash(&mut hasher);
+  config.canny_sigma.map(f64::to_bits).hash(&mut hasher);
+  config.auto_sigma.hash(&mut hasher);
+  config.mask_text_regions.hash(&mut hasher);
+  // 竖直滑动时缓存的是转置后的边缘图
+  (config.axis.unwrap_or_default() == SlideAxis::Y).hash(&mut hasher);
+  Some(hasher.finish())
+}
+
+// 在背景图缓存键中加入 Canny 阈值
+pub(crate) fn edges_key(background_key: u64, (low, high): (f32, f32)) -> u64 {
+  let mut hasher = DefaultHasher::new();
+  (background_key, low.to_bits(), high.to_bits()).hash(&mut hasher);
+  hasher.finish()
+}
+
+/// 清空背景边缘图缓存（见 `cacheBackground` 选项）
+#[napi]
+pub fn clear_background_cache() {
+  BackgroundCache::global().clear();
+}
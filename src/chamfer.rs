@@ -0,0 +1,61 @@
+use image::{GrayImage, Luma};
+use imageproc::definitions::Image;
+use imageproc::distance_transform::euclidean_squared_distance_transform;
+
+// 距离截断上限（像素）：缺失的轮廓段只按该距离计入，避免少量缺失边缘主导平均距离
+const MAX_CHAMFER_DISTANCE: f32 = 10.0;
+
+/// 在背景边缘图上滑动模板计算倒角匹配得分图
+///
+/// 先求背景边缘图的欧氏距离变换（每个像素到最近边缘像素的距离，截断到 10 像素），
+/// 每个位置的平均距离为模板所有边缘像素落点处距离的平均值，得分为 `1 / (1 + 平均距离)`，
+/// 范围 (0, 1]，越大越好，轮廓整体偏移 1-3 像素时得分仍然明显高于其他位置。
+/// 模板没有边缘像素时得分全为 0。
+///
+/// # Panics
+/// 模板的宽度或高度大于图片时 panic，调用方需事先检查尺寸。
+pub(crate) fn match_chamfer(image: &GrayImage, template: &GrayImage) -> Image<Luma<f32>> {
+  assert!(
+    image.width() >= template.width() && image.height() >= template.height(),
+    "模板尺寸 {:?} 大于图片尺寸 {:?}",
+    template.dimensions(),
+    image.dimensions()
+  );
+
+  let (width, height) = image.dimensions();
+  let (template_width, template_height) = template.dimensions();
+  let (out_width, out_height) = (width - template_width + 1, height - template_height + 1);
+
+  // 背景全无边缘时距离为无穷大，同样按上限截断
+  let distances: Vec<f32> = euclidean_squared_distance_transform(image)
+    .iter()
+    .map(|&squared| (squared.sqrt() as f32).min(MAX_CHAMFER_DISTANCE))
+    .collect();
+
+  let template_pixels: Vec<(u32, u32)> = template
+    .enumerate_pixels()
+    .filter(|(_, _, value)| value[0] != 0)
+    .map(|(x, y, _)| (x, y))
+    .collect();
+  if template_pixels.is_empty() {
+    return Image::new(out_width, out_height);
+  }
+
+  // 逐个模板边缘像素，把对应位置的距离按行累加到距离和上
+  let mut sums = vec![0f32; (out_width * out_height) as usize];
+  for &(tx, ty) in &template_pixels {
+    for y in 0..out_height {
+      let row = &distances[((y + ty) * width + tx) as usize..][..out_width as usize];
+      let totals = &mut sums[(y * out_width) as usize..][..out_width as usize];
+      for (total, &distance) in totals.iter_mut().zip(row) {
+        *total += distance;
+      }
+    }
+  }
+
+  let count = template_pixels.len() as f32;
+  Image::from_fn(out_width, out_height, |x, y| {
+    let mean = sums[(y * out_width + x) as usize] / count;
+    Luma([1.0 / (1.0 + mean)])
+  })
+}
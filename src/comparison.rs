@@ -0,0 +1,161 @@
+use image::{GrayImage, Luma};
+use imageproc::region_labelling::{connected_components, Connectivity};
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::error::{napi_error, ErrorStatus};
+use crate::{decode, SlideBBox, SlideMatchConfig};
+
+// 默认差异阈值（灰度差）
+const DEFAULT_DIFF_THRESHOLD: u32 = 30;
+// 默认最小连通区域面积（像素）
+const DEFAULT_MIN_BLOB_AREA: u32 = 50;
+
+/// 双背景图对比选项
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct SlideComparisonOptions {
+  /// 差异阈值，灰度差大于该值的像素视为不同，范围 0-255，默认 30；用于抑制 JPEG 压缩噪声
+  pub diff_threshold: Option<u32>,
+  /// 最小连通区域面积（像素），更小的差异区域视为噪声，默认 50
+  pub min_blob_area: Option<u32>,
+}
+
+// 连通区域的面积和外接矩形
+#[derive(Debug, Clone, Copy)]
+struct Blob {
+  area: u32,
+  x1: u32,
+  y1: u32,
+  x2: u32,
+  y2: u32,
+}
+
+impl Blob {
+  fn new(x: u32, y: u32) -> Self {
+    Self {
+      area: 0,
+      x1: x,
+      y1: y,
+      x2: x + 1,
+      y2: y + 1,
+    }
+  }
+
+  fn add(&mut self, x: u32, y: u32) {
+    self.area += 1;
+    self.x1 = self.x1.min(x);
+    self.y1 = self.y1.min(y);
+    self.x2 = self.x2.max(x + 1);
+    self.y2 = self.y2.max(y + 1);
+  }
+
+  // 紧凑度：面积占外接矩形的比例
+  fn compactness(&self) -> f64 {
+    self.area as f64 / ((self.x2 - self.x1) as f64 * (self.y2 - self.y1) as f64)
+  }
+}
+
+// 灰度差大于阈值的像素为 255，其余为 0
+fn diff_mask(full: &GrayImage, gapped: &GrayImage, threshold: u8) -> GrayImage {
+  GrayImage::from_fn(full.width(), full.height(), |x, y| {
+    let diff = full.get_pixel(x, y)[0].abs_diff(gapped.get_pixel(x, y)[0]);
+    Luma([if diff > threshold { u8::MAX } else { 0 }])
+  })
+}
+
+// 差异图中面积最大的连通区域
+fn largest_blob(mask: &GrayImage) -> Option<Blob> {
+  let labels = connected_components(mask, Connectivity::Eight, Luma([0]));
+
+  // 标签从 1 开始连续编号，0 为背景
+  let mut blobs: Vec<Option<Blob>> = Vec::new();
+  for (x, y, label) in labels.enumerate_pixels() {
+    let label = label[0] as usize;
+    if label == 0 {
+      continue;
+    }
+    if blobs.len() <= label {
+      blobs.resize(label + 1, None);
+    }
+    blobs[label]
+      .get_or_insert_with(|| Blob::new(x, y))
+      .add(x, y);
+  }
+
+  blobs.into_iter().flatten().max_by_key(|blob| blob.area)
+}
+
+fn slide_comparison_internal(
+  full_background: &[u8],
+  gapped_background: &[u8],
+  options: &SlideComparisonOptions,
+) -> anyhow::Result<SlideBBox> {
+  let diff_threshold = options.diff_threshold.unwrap_or(DEFAULT_DIFF_THRESHOLD);
+  anyhow::ensure!(diff_threshold <= 255, "差异阈值必须在 0-255 范围内");
+  let min_blob_area = options.min_blob_area.unwrap_or(DEFAULT_MIN_BLOB_AREA);
+
+  let config = SlideMatchConfig::default();
+  let full = decode::load_image(full_background, "完整背景图片", &config)?.to_luma8();
+  let gapped = decode::load_image(gapped_background, "缺口背景图片", &config)?.to_luma8();
+
+  anyhow::ensure!(
+    full.dimensions() == gapped.dimensions(),
+    "两张背景图片的尺寸必须一致（{}x{} 与 {}x{}）",
+    full.width(),
+    full.height(),
+    gapped.width(),
+    gapped.height()
+  );
+
+  let mask = diff_mask(&full, &gapped, diff_threshold as u8);
+  let blob = largest_blob(&mask)
+    .filter(|blob| blob.area >= min_blob_area)
+    .ok_or_else(|| anyhow::anyhow!("两张背景图片没有面积不小于 {min_blob_area} 像素的差异区域"))?;
+
+  Ok(SlideBBox {
+    target_x: 0,
+    target_y: 0,
+    x1: blob.x1,
+    y1: blob.y1,
+    x2: blob.x2,
+    y2: blob.y2,
+    confidence: Some(blob.compactness()),
+    metadata: None,
+    score_profile: None,
+    heatmap: None,
+    coordinate_mode: None,
+    ratio: None,
+    target_scale: None,
+    is_ambiguous: None,
+    second_best_score: None,
+    second_best_x: None,
+    psr: None,
+    slide_distance: None,
+    degenerate_target: None,
+    elapsed_ms: None,
+  })
+}
+
+/// 双背景图对比：同时提供完整背景图和带缺口的背景图时，通过逐像素差异定位缺口
+///
+/// 对两张图片的灰度差做阈值化，返回面积最大的差异区域的外接矩形。`targetX/targetY` 固定为 0，
+/// `confidence` 为该区域面积占外接矩形的比例，形状越紧凑越接近 1。
+///
+/// # 参数
+/// - full_background: 完整背景图片 Buffer
+/// - gapped_background: 带缺口的背景图片 Buffer，尺寸必须与完整背景图片一致
+/// - options: 可选，见 `SlideComparisonOptions`
+#[napi]
+pub fn slide_comparison(
+  full_background: Buffer,
+  gapped_background: Buffer,
+  options: Option<SlideComparisonOptions>,
+) -> Result<SlideBBox, ErrorStatus> {
+  slide_comparison_internal(
+    &full_background,
+    &gapped_background,
+    &options.unwrap_or_default(),
+  )
+  .map_err(|e| napi_error(&e, format!("双背景图对比失败: {e}")))
+}
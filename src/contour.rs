@@ -0,0 +1,172 @@
+use image::{GrayImage, Luma, RgbaImage};
+use imageproc::contours::{find_contours, Contour};
+use imageproc::drawing::draw_polygon_mut;
+use imageproc::point::Point;
+
+use crate::preprocess::ALPHA_MASK_THRESHOLD;
+
+// 候选轮廓外接框的宽高与滑块相差不超过该比例，缺口与滑块等大，过滤掉纹理中的细碎轮廓
+const SIZE_TOLERANCE: f64 = 0.25;
+// Hu 矩绝对值低于该值时视为 0，不参与比较（取对数后数值不稳定）
+const HU_EPSILON: f64 = 1e-5;
+
+/// 轮廓匹配的结果，位置为背景图坐标
+pub(crate) struct ContourMatch {
+  /// 与滑块等大的匹配区域左上角
+  pub(crate) x: u32,
+  pub(crate) y: u32,
+  /// 形状相似度，范围 (0, 1]，1 表示 Hu 矩完全相同
+  pub(crate) score: f64,
+}
+
+/// 在背景边缘图的闭合轮廓中寻找与滑块轮廓形状最接近的一个
+///
+/// 滑块轮廓取自（裁剪透明边缘后的）目标图片的透明度；背景中每个外接框尺寸与滑块接近的
+/// 轮廓（边缘环的外边界和内边界）都填充为区域后计算 Hu 矩，与滑块的 Hu 矩按
+/// `Σ |1/m_a - 1/m_b|`（`m = sign(h)·log10|h|`）比较，相似度为 `1 / (1 + 距离)`。
+/// 匹配区域以候选轮廓外接框的中心对齐滑块。
+pub(crate) fn match_contour(
+  target: &RgbaImage,
+  background_edges: &GrayImage,
+) -> anyhow::Result<ContourMatch> {
+  // 四周留出 1 像素空白：`find_contours` 不追踪贴着图片边界的像素，完全不透明的目标图片也要有闭合轮廓
+  let (target_width, target_height) = target.dimensions();
+  let silhouette = GrayImage::from_fn(target_width + 2, target_height + 2, |x, y| {
+    let opaque = (1..=target_width).contains(&x)
+      && (1..=target_height).contains(&y)
+      && target.get_pixel(x - 1, y - 1)[3] >= ALPHA_MASK_THRESHOLD;
+    Luma([if opaque { u8::MAX } else { 0 }])
+  });
+  let target_hu = find_contours::<i32>(&silhouette)
+    .iter()
+    .filter_map(|contour| filled(contour).map(|region| (region.len(), region)))
+    .max_by_key(|(area, _)| *area)
+    .and_then(|(_, region)| hu_moments(&region))
+    .ok_or_else(|| anyhow::anyhow!("目标图片没有不透明区域，无法提取滑块轮廓"))?;
+
+  let (background_width, background_height) = background_edges.dimensions();
+  let size_matches = |size: u32, expected: u32| {
+    (size as f64 - expected as f64).abs() <= expected as f64 * SIZE_TOLERANCE
+  };
+
+  let best = find_contours::<i32>(background_edges)
+    .iter()
+    .filter_map(|contour| {
+      let (min_x, min_y, max_x, max_y) = bounds(contour)?;
+      let (width, height) = ((max_x - min_x + 1) as u32, (max_y - min_y + 1) as u32);
+      if !size_matches(width, target_width) || !size_matches(height, target_height) {
+        return None;
+      }
+      let hu = hu_moments(&filled(contour)?)?;
+      let center = ((min_x + max_x) as f64 / 2.0, (min_y + max_y) as f64 / 2.0);
+      Some((hu_distance(&target_hu, &hu), center))
+    })
+    .min_by(|(a, _), (b, _)| a.total_cmp(b));
+
+  let Some((distance, (center_x, center_y))) = best else {
+    anyhow::bail!("背景图中没有与滑块尺寸接近的闭合轮廓");
+  };
+
+  // 外接框中心对齐滑块中心，并限制在背景图内
+  let place = |center: f64, size: u32, limit: u32| {
+    (center - (size as f64 - 1.0) / 2.0)
+      .round()
+      .clamp(0.0, limit.saturating_sub(size) as f64) as u32
+  };
+  Ok(ContourMatch {
+    x: place(center_x, target_width, background_width),
+    y: place(center_y, target_height, background_height),
+    score: 1.0 / (1.0 + distance),
+  })
+}
+
+// 轮廓点的外接框 (min_x, min_y, max_x, max_y)
+fn bounds(contour: &Contour<i32>) -> Option<(i32, i32, i32, i32)> {
+  let first = contour.points.first()?;
+  Some(contour.points.iter().fold(
+    (first.x, first.y, first.x, first.y),
+    |(min_x, min_y, max_x, max_y), p| {
+      (
+        min_x.min(p.x),
+        min_y.min(p.y),
+        max_x.max(p.x),
+        max_y.max(p.y),
+      )
+    },
+  ))
+}
+
+// 把轮廓围成的多边形填充为区域，返回区域内的像素坐标（相对外接框左上角）
+// 少于 3 个不同点的轮廓围不成区域，返回 None
+fn filled(contour: &Contour<i32>) -> Option<Vec<(f64, f64)>> {
+  let (min_x, min_y, max_x, max_y) = bounds(contour)?;
+  let mut points: Vec<Point<i32>> = contour
+    .points
+    .iter()
+    .map(|p| Point::new(p.x - min_x, p.y - min_y))
+    .collect();
+  points.dedup();
+  // `draw_polygon_mut` 要求首尾两点不同
+  while points.len() > 1 && points.first() == points.last() {
+    points.pop();
+  }
+  if points.len() < 3 {
+    return None;
+  }
+
+  let mut region = GrayImage::new((max_x - min_x + 1) as u32, (max_y - min_y + 1) as u32);
+  draw_polygon_mut(&mut region, &points, Luma([u8::MAX]));
+  Some(
+    region
+      .enumerate_pixels()
+      .filter(|(_, _, value)| value[0] != 0)
+      .map(|(x, y, _)| (x as f64, y as f64))
+      .collect(),
+  )
+}
+
+// 区域的 7 个 Hu 不变矩，空区域返回 None
+fn hu_moments(region: &[(f64, f64)]) -> Option<[f64; 7]> {
+  if region.is_empty() {
+    return None;
+  }
+  let area = region.len() as f64;
+  let (cx, cy) = region
+    .iter()
+    .fold((0.0, 0.0), |(sx, sy), &(x, y)| (sx + x, sy + y));
+  let (cx, cy) = (cx / area, cy / area);
+
+  // 归一化中心矩 η_pq = μ_pq / μ00^(1 + (p + q) / 2)
+  let eta = |p: i32, q: i32| {
+    let mu: f64 = region
+      .iter()
+      .map(|&(x, y)| (x - cx).powi(p) * (y - cy).powi(q))
+      .sum();
+    mu / area.powf(1.0 + (p + q) as f64 / 2.0)
+  };
+  let (n20, n02, n11) = (eta(2, 0), eta(0, 2), eta(1, 1));
+  let (n30, n03, n21, n12) = (eta(3, 0), eta(0, 3), eta(2, 1), eta(1, 2));
+
+  let (a, b) = (n30 + n12, n21 + n03);
+  Some([
+    n20 + n02,
+    (n20 - n02).powi(2) + 4.0 * n11.powi(2),
+    (n30 - 3.0 * n12).powi(2) + (3.0 * n21 - n03).powi(2),
+    a.powi(2) + b.powi(2),
+    (n30 - 3.0 * n12) * a * (a.powi(2) - 3.0 * b.powi(2))
+      + (3.0 * n21 - n03) * b * (3.0 * a.powi(2) - b.powi(2)),
+    (n20 - n02) * (a.powi(2) - b.powi(2)) + 4.0 * n11 * a * b,
+    (3.0 * n21 - n03) * a * (a.powi(2) - 3.0 * b.powi(2))
+      - (n30 - 3.0 * n12) * b * (3.0 * a.powi(2) - b.powi(2)),
+  ])
+}
+
+// 两组 Hu 矩的距离，任一方接近 0 的分量不参与比较
+fn hu_distance(a: &[f64; 7], b: &[f64; 7]) -> f64 {
+  let log = |h: f64| h.signum() * h.abs().log10();
+  a.iter()
+    .zip(b)
+    .filter(|(a, b)| a.abs() > HU_EPSILON && b.abs() > HU_EPSILON)
+    .map(|(&a, &b)| (1.0 / log(a) - 1.0 / log(b)).abs())
+    .sum()
+}
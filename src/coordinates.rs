@@ -0,0 +1,163 @@
+use crate::{
+  CoordinateMode, CoordinateSpace, CoordinateUnit, RatioCoordinates, SlideAxis, SlideBBox,
+  SlideMatchConfig,
+};
+
+/// 按比例缩放单个坐标值，统一使用四舍五入
+fn scale_coordinate(value: u32, ratio: f64) -> u32 {
+  (value as f64 * ratio).round() as u32
+}
+
+/// 校验显示尺寸相关配置
+pub(crate) fn validate_display_config(config: &SlideMatchConfig) -> anyhow::Result<()> {
+  if let Some(width) = config.background_display_width {
+    anyhow::ensure!(
+      width.is_finite() && width > 0.0,
+      "背景图片显示宽度必须是大于 0 的有限数值"
+    );
+  }
+
+  if let Some(width) = config.target_display_width {
+    anyhow::ensure!(
+      width.is_finite() && width > 0.0,
+      "目标图片显示宽度必须是大于 0 的有限数值"
+    );
+  }
+
+  if let Some(ratio) = config.device_pixel_ratio {
+    anyhow::ensure!(
+      ratio.is_finite() && ratio > 0.0,
+      "设备像素比必须是大于 0 的有限数值"
+    );
+  }
+
+  Ok(())
+}
+
+/// 将原始尺寸（natural size）下的匹配结果换算到显示尺寸（rendered size），并按
+/// `coordinateMode` 确定 `x1/y1` 的含义
+///
+/// - `x1/y1/x2/y2` 按 `backgroundDisplayWidth / 背景原始宽度` 缩放
+/// - `target_x/target_y` 按 `targetDisplayWidth / 目标原始宽度` 缩放，
+///   未指定 `targetDisplayWidth` 时沿用背景的缩放比例
+/// - 设置 `devicePixelRatio` 时，上述坐标再除以 DPR，得到 CSS 像素坐标
+/// - `coordinateMode` 为 `center` 时，缩放后的 `x1/y1` 移到匹配区域的中心点
+/// - `coordinateSpace` 为 `backgroundRelativeToTarget` 时，匹配区域减去同一尺度下的 `target_x/target_y`
+/// - `coordinateUnit` 为 `ratio` 时，由原始尺寸下的坐标计算 `ratio`，不受显示尺寸影响
+/// - 设置 `axis` 时，由缩放后的左上角和 `target_x/target_y` 计算 `slideDistance`
+pub(crate) fn to_display_coordinates(
+  bbox: SlideBBox,
+  background_size: (u32, u32),
+  target_size: (u32, u32),
+  config: &SlideMatchConfig,
+) -> SlideBBox {
+  let mode = config.coordinate_mode.unwrap_or_default();
+  let space = config.coordinate_space.unwrap_or_default();
+  // 原始尺寸下的坐标原点
+  let origin = match space {
+    CoordinateSpace::BackgroundAbsolute => (0, 0),
+    CoordinateSpace::BackgroundRelativeToTarget => (bbox.target_x, bbox.target_y),
+  };
+  let ratio = match config.coordinate_unit.unwrap_or_default() {
+    CoordinateUnit::Pixel => None,
+    CoordinateUnit::Ratio => Some(to_ratio(&bbox, mode, origin, background_size, target_size)),
+  };
+
+  let bbox = scale_to_display(bbox, background_size.0, target_size.0, config);
+  let slide_distance = config.axis.map(|axis| match axis {
+    SlideAxis::X => bbox.x1 as i32 - bbox.target_x as i32,
+    SlideAxis::Y => bbox.y1 as i32 - bbox.target_y as i32,
+  });
+  let bbox = match space {
+    CoordinateSpace::BackgroundAbsolute => bbox,
+    CoordinateSpace::BackgroundRelativeToTarget => relative_to_target(bbox),
+  };
+  let (x1, y1) = anchor(&bbox, mode);
+
+  SlideBBox {
+    x1,
+    y1,
+    coordinate_mode: Some(mode),
+    ratio,
+    slide_distance,
+    ..bbox
+  }
+}
+
+// 匹配区域整体平移到以目标图片裁剪起点为原点的坐标系，小于 0 时取 0
+fn relative_to_target(bbox: SlideBBox) -> SlideBBox {
+  SlideBBox {
+    x1: bbox.x1.saturating_sub(bbox.target_x),
+    y1: bbox.y1.saturating_sub(bbox.target_y),
+    x2: bbox.x2.saturating_sub(bbox.target_x),
+    y2: bbox.y2.saturating_sub(bbox.target_y),
+    ..bbox
+  }
+}
+
+// 按 `coordinateMode` 确定的 `x1/y1`
+fn anchor(bbox: &SlideBBox, mode: CoordinateMode) -> (u32, u32) {
+  match mode {
+    CoordinateMode::TopLeft => (bbox.x1, bbox.y1),
+    CoordinateMode::Center => (
+      bbox.x1 + (bbox.x2 - bbox.x1) / 2,
+      bbox.y1 + (bbox.y2 - bbox.y1) / 2,
+    ),
+  }
+}
+
+// 原始尺寸下的坐标减去原点 `origin` 后除以对应图片的宽高，`target_x/target_y` 不受原点影响
+fn to_ratio(
+  bbox: &SlideBBox,
+  mode: CoordinateMode,
+  (origin_x, origin_y): (u32, u32),
+  (background_width, background_height): (u32, u32),
+  (target_width, target_height): (u32, u32),
+) -> RatioCoordinates {
+  let (x1, y1) = anchor(bbox, mode);
+  let ratio = |value: u32, size: u32| value as f64 / size as f64;
+
+  RatioCoordinates {
+    target_x: ratio(bbox.target_x, target_width),
+    target_y: ratio(bbox.target_y, target_height),
+    x1: ratio(x1.saturating_sub(origin_x), background_width),
+    y1: ratio(y1.saturating_sub(origin_y), background_height),
+    x2: ratio(bbox.x2.saturating_sub(origin_x), background_width),
+    y2: ratio(bbox.y2.saturating_sub(origin_y), background_height),
+  }
+}
+
+// 按显示宽度缩放坐标后再除以 DPR，两个显示宽度和 DPR 都未指定时原样返回
+fn scale_to_display(
+  bbox: SlideBBox,
+  background_width: u32,
+  target_width: u32,
+  config: &SlideMatchConfig,
+) -> SlideBBox {
+  let background_ratio = config
+    .background_display_width
+    .map(|w| w / background_width as f64);
+  let target_ratio = config
+    .target_display_width
+    .map(|w| w / target_width as f64)
+    .or(background_ratio);
+  let device_pixel_ratio = config.device_pixel_ratio;
+
+  let (bbox_ratio, target_ratio) = match (background_ratio, target_ratio, device_pixel_ratio) {
+    (None, None, None) => return bbox,
+    (b, t, dpr) => {
+      let dpr = dpr.unwrap_or(1.0);
+      (b.unwrap_or(1.0) / dpr, t.unwrap_or(1.0) / dpr)
+    }
+  };
+
+  SlideBBox {
+    target_x: scale_coordinate(bbox.target_x, target_ratio),
+    target_y: scale_coordinate(bbox.target_y, target_ratio),
+    x1: scale_coordinate(bbox.x1, bbox_ratio),
+    y1: scale_coordinate(bbox.y1, bbox_ratio),
+    x2: scale_coordinate(bbox.x2, bbox_ratio),
+    y2: scale_coordinate(bbox.y2, bbox_ratio),
+    ..bbox
+  }
+}
@@ -0,0 +1,105 @@
+use image::{DynamicImage, RgbaImage};
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::error::{napi_error, ErrorStatus};
+use crate::pipeline::{ImagePipeline, OutputFormat};
+use crate::{decode, SlideMatchConfig};
+
+// 匹配函数裁剪目标图片时使用的透明度阈值：透明度大于该值的像素视为不透明
+pub(crate) const DEFAULT_ALPHA_THRESHOLD: u8 = 0;
+
+/// 裁剪透明边框的结果
+#[napi(object)]
+pub struct CroppedImage {
+  /// 裁剪后的 PNG 图片
+  pub data: Buffer,
+  /// 裁剪起点，即匹配结果中的 `targetX/targetY`
+  pub x: u32,
+  pub y: u32,
+  pub width: u32,
+  pub height: u32,
+  /// 图片中没有不透明的像素，此时返回原图，`x/y` 为 0
+  pub fully_transparent: bool,
+}
+
+impl std::fmt::Debug for CroppedImage {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("CroppedImage")
+      .field("x", &self.x)
+      .field("y", &self.y)
+      .field("width", &self.width)
+      .field("height", &self.height)
+      .field("fully_transparent", &self.fully_transparent)
+      .finish_non_exhaustive()
+  }
+}
+
+// 透明度大于 `alpha_threshold` 的像素的外接矩形 (x, y, 宽度, 高度)，没有这样的像素时返回 None
+pub(crate) fn opaque_bounds(img: &RgbaImage, alpha_threshold: u8) -> Option<(u32, u32, u32, u32)> {
+  let mut bounds: Option<(u32, u32, u32, u32)> = None;
+
+  for (x, y, p) in img.enumerate_pixels() {
+    if p[3] <= alpha_threshold {
+      continue;
+    }
+
+    bounds = Some(match bounds {
+      Some((start_x, start_y, end_x, end_y)) => {
+        (start_x.min(x), start_y.min(y), end_x.max(x), end_y.max(y))
+      }
+      None => (x, y, x, y),
+    });
+  }
+
+  bounds.map(|(start_x, start_y, end_x, end_y)| {
+    (start_x, start_y, end_x - start_x + 1, end_y - start_y + 1)
+  })
+}
+
+fn crop_transparent_internal(image: &[u8], alpha_threshold: u32) -> anyhow::Result<CroppedImage> {
+  anyhow::ensure!(alpha_threshold <= 255, "透明度阈值必须在 0-255 范围内");
+
+  let image = decode::load_image(image, "图片", &SlideMatchConfig::default())?.to_rgba8();
+  let (cropped, x, y, fully_transparent) = match opaque_bounds(&image, alpha_threshold as u8) {
+    Some((x, y, width, height)) => (
+      image::imageops::crop_imm(&image, x, y, width, height).to_image(),
+      x,
+      y,
+      false,
+    ),
+    None => (image, 0, 0, true),
+  };
+
+  let (width, height) = cropped.dimensions();
+  let data =
+    ImagePipeline::from_image(DynamicImage::ImageRgba8(cropped)).to_bytes(OutputFormat::Png)?;
+
+  Ok(CroppedImage {
+    data: data.into(),
+    x,
+    y,
+    width,
+    height,
+    fully_transparent,
+  })
+}
+
+/// 裁剪图片四周的透明边框，与匹配函数裁剪目标图片的逻辑相同
+///
+/// 可用于预先裁剪滑块图片，或在不执行匹配的情况下得到 `targetX/targetY`。
+///
+/// # 参数
+/// - image: 图片 Buffer
+/// - alpha_threshold: 可选，透明度不大于该值的像素视为透明，范围 0-255，默认 0（与匹配函数一致）
+#[napi]
+pub fn crop_transparent(
+  image: Buffer,
+  alpha_threshold: Option<u32>,
+) -> Result<CroppedImage, ErrorStatus> {
+  crop_transparent_internal(
+    &image,
+    alpha_threshold.unwrap_or(DEFAULT_ALPHA_THRESHOLD as u32),
+  )
+  .map_err(|e| napi_error(&e, format!("裁剪透明边框失败: {e}")))
+}
@@ -0,0 +1,167 @@
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::error::{napi_error, ErrorStatus};
+use crate::multi::MatchCandidate;
+use crate::preprocess::{self, MatchImage};
+use crate::{
+  background_canny_thresholds, cache, coordinates, decode, improved_match_gray_images,
+  match_gray_images, original_canny_thresholds, oversize, prepare_improved_target, scores_to_bbox,
+  small_target_config, target_canny_thresholds, validate_config, MatchStrategy, SlideBBox,
+  SlideMatchConfig,
+};
+
+// 默认的一致性容差（像素）
+const DEFAULT_TOLERANCE: u32 = 2;
+// 改进版算法默认的置信度阈值，与 `improvedSlideMatch` 相同
+const DEFAULT_CONFIDENCE_THRESHOLD: f64 = 0.3;
+
+/// `slideMatchCrossCheck` 的选项
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct CrossCheckOptions {
+  /// 两种算法的 `x1` 相差不超过该值（像素）时视为一致，默认 2
+  pub tolerance: Option<u32>,
+  /// 改进版算法的置信度阈值，范围 0.0-1.0，默认 0.3，与 `improvedSlideMatch` 相同
+  pub confidence_threshold: Option<f64>,
+}
+
+/// 同时运行改进版和原版算法的结果，见 `slideMatchCrossCheck`
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct CrossCheckResult {
+  /// 改进版算法（与 `improvedSlideMatch` 相同）的结果
+  pub improved: MatchCandidate,
+  /// 原版算法（与 `slideMatch` 相同，固定 Canny 阈值）的结果
+  pub original: MatchCandidate,
+  /// 两个结果的 `x1` 相差不超过 `tolerance`
+  pub agrees: bool,
+  /// `improved.bbox.x1 - original.bbox.x1`
+  pub delta_x: i32,
+}
+
+/// 同时运行改进版和原版算法并比较结果，`slideMatchCrossCheck` 的 Rust 实现
+///
+/// 两次匹配共用解码、缩放和裁剪透明背景后的灰度图，只分别执行边缘检测和模板匹配。原版算法不做置信度验证，
+/// 设置了 `confidenceThreshold` 时同样返回结果；`contour` 策略不支持。
+pub fn slide_match_cross_check_internal(
+  target_image: &[u8],
+  background_image: &[u8],
+  options: &CrossCheckOptions,
+  config: &SlideMatchConfig,
+) -> anyhow::Result<CrossCheckResult> {
+  validate_config(config)?;
+  let confidence_threshold = options
+    .confidence_threshold
+    .unwrap_or(DEFAULT_CONFIDENCE_THRESHOLD);
+  anyhow::ensure!(
+    (0.0..=1.0).contains(&confidence_threshold),
+    "置信度阈值必须在 0.0-1.0 范围内"
+  );
+  anyhow::ensure!(
+    config.strategy.unwrap_or_default() != MatchStrategy::Contour,
+    "contour 策略不支持交叉验证"
+  );
+
+  let (target_image, _) = decode::load_target(target_image, "目标图片", config)?;
+  let background_key = cache::background_key(background_image, config);
+  let background_image = decode::load_background(background_image, config)?;
+
+  // 原始尺寸，用于换算显示坐标
+  let target_size = (target_image.width(), target_image.height());
+
+  let (target_image, background_image, fit) =
+    oversize::fit_images(target_image, background_image, config)?;
+  let background_key = fit.cache_key(background_key);
+  let fitted_config = fit.config(config);
+  let config = &*fitted_config;
+
+  // 两次匹配共用的灰度图；原版算法额外用透明度掩码清除异形滑块透明区域内的边缘
+  let (cropped_image, target, (start_x, start_y)) = prepare_improved_target(target_image, config);
+  let target_mask = preprocess::alpha_mask(&cropped_image, preprocess::ALPHA_MASK_THRESHOLD);
+  let background =
+    MatchImage::from_dynamic(background_image, config).with_cache_key(background_key);
+
+  let convert = |bbox| {
+    coordinates::to_display_coordinates(
+      fit.restore(SlideBBox {
+        target_x: start_x,
+        target_y: start_y,
+        ..bbox
+      }),
+      fit.output_size(),
+      target_size,
+      config,
+    )
+  };
+  let candidate = |bbox: SlideBBox| MatchCandidate {
+    confidence: bbox.confidence.expect("交叉验证的结果必然带有得分"),
+    bbox,
+  };
+
+  let (result, metadata, _) =
+    improved_match_gray_images(&target, &background, confidence_threshold as f32, config)?;
+  let confidence = Some(result.extremes.max_value as f64);
+  let improved = candidate(scores_to_bbox(
+    result,
+    confidence,
+    Some(metadata),
+    (target.width(), target.height()),
+    config,
+    convert,
+  ));
+
+  let small_config = small_target_config(&target, config);
+  let config = &*small_config;
+  let result = match_gray_images(
+    &target,
+    &background,
+    target_canny_thresholds(original_canny_thresholds(&target, config), config)?,
+    background_canny_thresholds(original_canny_thresholds(&background, config), config)?,
+    Some(&target_mask),
+    None,
+    config,
+  )?;
+  let confidence = Some(result.extremes.max_value as f64);
+  let original = candidate(scores_to_bbox(
+    result,
+    confidence,
+    None,
+    (target.width(), target.height()),
+    config,
+    convert,
+  ));
+
+  let delta_x = improved.bbox.x1 as i64 - original.bbox.x1 as i64;
+  let tolerance = options.tolerance.unwrap_or(DEFAULT_TOLERANCE);
+  Ok(CrossCheckResult {
+    agrees: delta_x.unsigned_abs() <= tolerance as u64,
+    delta_x: delta_x as i32,
+    improved,
+    original,
+  })
+}
+
+/// 交叉验证：同时运行改进版（`improvedSlideMatch`）和原版（`slideMatch`）算法，返回两个结果及是否一致，
+/// 便于在切换算法前用线上流量做对比
+///
+/// # 参数
+/// - target: 目标图片 Buffer
+/// - background: 背景图片 Buffer
+/// - options: 交叉验证选项
+/// - config: 可选配置，见 `SlideMatchConfig`
+#[napi]
+pub fn slide_match_cross_check(
+  target: Buffer,
+  background: Buffer,
+  options: Option<CrossCheckOptions>,
+  config: Option<SlideMatchConfig>,
+) -> Result<CrossCheckResult, ErrorStatus> {
+  slide_match_cross_check_internal(
+    &target,
+    &background,
+    &options.unwrap_or_default(),
+    &config.unwrap_or_default(),
+  )
+  .map_err(|e| napi_error(&e, format!("交叉验证失败: {e}")))
+}
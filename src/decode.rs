@@ -0,0 +1,219 @@
+use std::io::Cursor;
+
+use anyhow::Context;
+use image::error::{ImageError, UnsupportedErrorKind};
+use image::{DynamicImage, ImageBuffer, ImageFormat, ImageReader, Luma, Rgb};
+
+use crate::error::SlideMatchError;
+use crate::{crop, SlideMatchConfig};
+
+// 默认图片像素上限（4 MP）
+pub(crate) const DEFAULT_MAX_IMAGE_PIXELS: u64 = 4_000_000;
+
+// 背景图片透明像素默认合成到的颜色（白色）
+const DEFAULT_BACKGROUND_FILL: [u8; 3] = [255, 255, 255];
+
+/// 读取目标图片和背景图片文件，返回 `(目标图片, 背景图片)` 的数据
+///
+/// 读取失败时错误信息包含文件路径。
+pub(crate) fn read_image_files(
+  target_path: &str,
+  background_path: &str,
+) -> anyhow::Result<(Vec<u8>, Vec<u8>)> {
+  let target =
+    std::fs::read(target_path).with_context(|| format!("无法读取目标图片 {target_path}"))?;
+  let background = std::fs::read(background_path)
+    .with_context(|| format!("无法读取背景图片 {background_path}"))?;
+
+  Ok((target, background))
+}
+
+/// 在已读取的图片文件上执行 `f`，返回的错误附加两张图片的路径，
+/// 使用 `{:#}` 格式化时可以看到完整的错误链
+pub(crate) fn match_image_files<T>(
+  target_path: &str,
+  background_path: &str,
+  (target, background): &(Vec<u8>, Vec<u8>),
+  f: impl FnOnce(&[u8], &[u8]) -> anyhow::Result<T>,
+) -> anyhow::Result<T> {
+  f(target, background)
+    .with_context(|| format!("目标图片 {target_path}，背景图片 {background_path}"))
+}
+
+/// 读取目标图片和背景图片文件后执行 `f`
+///
+/// 读取失败时错误信息包含文件路径；`f` 返回的错误也会附加两张图片的路径，
+/// 使用 `{:#}` 格式化时可以看到完整的错误链。
+pub(crate) fn with_image_files<T>(
+  target_path: &str,
+  background_path: &str,
+  f: impl FnOnce(&[u8], &[u8]) -> anyhow::Result<T>,
+) -> anyhow::Result<T> {
+  let files = read_image_files(target_path, background_path)?;
+  match_image_files(target_path, background_path, &files, f)
+}
+
+/// 解码图片
+///
+/// 先只读取图片头中的尺寸，像素数超过 `maxImagePixels` 时直接返回
+/// `SlideMatchError::ImageTooLarge`，避免为不可信的超大图片分配大量内存。
+///
+/// # 参数
+/// - bytes: 图片数据
+/// - name: 图片名称，用于错误信息（如 "目标图片"）
+/// - config: 匹配配置
+pub(crate) fn load_image(
+  bytes: &[u8],
+  name: &str,
+  config: &SlideMatchConfig,
+) -> anyhow::Result<DynamicImage> {
+  let limit = config
+    .max_image_pixels
+    .map_or(DEFAULT_MAX_IMAGE_PIXELS, u64::from);
+
+  let reader = ImageReader::new(Cursor::new(bytes))
+    .with_guessed_format()
+    .with_context(|| format!("无法加载{name}"))?;
+  let format = reader.format();
+  let (width, height) = match (reader.into_dimensions(), format) {
+    (Ok(dimensions), _) => dimensions,
+    // 识别出格式但没有对应的解码器时（如 AVIF）给出明确的错误，而不是笼统的加载失败
+    (Err(ImageError::Unsupported(e)), Some(format))
+      if matches!(e.kind(), UnsupportedErrorKind::Format(_)) =>
+    {
+      return Err(
+        SlideMatchError::UnsupportedFormat {
+          format: format_name(format),
+        }
+        .into(),
+      );
+    }
+    (Err(e), _) => return Err(anyhow::Error::new(e).context(format!("无法加载{name}"))),
+  };
+
+  let actual = width as u64 * height as u64;
+  if actual > limit {
+    return Err(SlideMatchError::ImageTooLarge { actual, limit }.into());
+  }
+
+  image::load_from_memory(bytes).with_context(|| format!("无法加载{name}"))
+}
+
+/// 解码目标图片，并检查不透明像素数是否达到 `minOpaquePixels`
+///
+/// 完全透明的目标图片裁剪时会退回到整张图片，转灰度后得到的模板没有意义，却仍会返回一个看似正常的位置。
+/// 不透明像素不足时返回 `SlideMatchError::DegenerateTarget`，设置了 `allowDegenerateTarget` 时
+/// 改为返回 `(图片, true)`，由调用方在结果中标记。
+pub(crate) fn load_target(
+  bytes: &[u8],
+  name: &str,
+  config: &SlideMatchConfig,
+) -> anyhow::Result<(DynamicImage, bool)> {
+  let image = load_image(bytes, name, config)?;
+  let min = config.min_opaque_pixels.unwrap_or(1);
+  if min == 0 {
+    return Ok((image, false));
+  }
+
+  let opaque_pixels = if image.color().has_alpha() {
+    image
+      .to_rgba8()
+      .pixels()
+      .filter(|p| p[3] > crop::DEFAULT_ALPHA_THRESHOLD)
+      .count() as u64
+  } else {
+    image.width() as u64 * image.height() as u64
+  };
+  if opaque_pixels >= min as u64 {
+    return Ok((image, false));
+  }
+  if config.allow_degenerate_target.unwrap_or(false) {
+    return Ok((image, true));
+  }
+  Err(SlideMatchError::DegenerateTarget { opaque_pixels, min }.into())
+}
+
+/// 解码背景图片，带透明度通道时合成到 `backgroundFill` 指定的纯色上
+///
+/// 透明像素的 RGB 值通常是任意的（多为黑色），直接转灰度会在圆角、透明边框处产生大片假边缘。
+/// 8/16 位灰度图合成后仍为同位深的灰度图（填充色按亮度换算），其余格式合成为 8 位 RGB 图片。
+pub(crate) fn load_background(
+  bytes: &[u8],
+  config: &SlideMatchConfig,
+) -> anyhow::Result<DynamicImage> {
+  let image = load_image(bytes, "背景图片", config)?;
+  Ok(flatten_alpha(image, background_fill(config)))
+}
+
+// `backgroundFill`，未指定时为白色；配置需已校验
+fn background_fill(config: &SlideMatchConfig) -> [u8; 3] {
+  config
+    .background_fill
+    .as_deref()
+    .map_or(DEFAULT_BACKGROUND_FILL, |fill| {
+      [fill[0] as u8, fill[1] as u8, fill[2] as u8]
+    })
+}
+
+// 按透明度把颜色值与填充色混合（四舍五入），`max` 为通道最大值
+fn blend(value: u32, fill: u32, alpha: u32, max: u32) -> u32 {
+  (value * alpha + fill * (max - alpha) + max / 2) / max
+}
+
+// 把带透明度通道的图片合成到纯色上，不带透明度通道时原样返回
+fn flatten_alpha(image: DynamicImage, [r, g, b]: [u8; 3]) -> DynamicImage {
+  // 与 `image` 转灰度时相同的 BT.709 亮度权重
+  let fill_luma = (2126 * r as u32 + 7152 * g as u32 + 722 * b as u32 + 5000) / 10000;
+
+  match image {
+    DynamicImage::ImageLumaA8(img) => {
+      DynamicImage::ImageLuma8(ImageBuffer::from_fn(img.width(), img.height(), |x, y| {
+        let [value, alpha] = img.get_pixel(x, y).0;
+        Luma([blend(value as u32, fill_luma, alpha as u32, 255) as u8])
+      }))
+    }
+    DynamicImage::ImageLumaA16(img) => {
+      DynamicImage::ImageLuma16(ImageBuffer::from_fn(img.width(), img.height(), |x, y| {
+        let [value, alpha] = img.get_pixel(x, y).0;
+        Luma([blend(value as u32, fill_luma * 257, alpha as u32, 65535) as u16])
+      }))
+    }
+    image if image.color().has_alpha() => {
+      let img = image.into_rgba8();
+      DynamicImage::ImageRgb8(ImageBuffer::from_fn(img.width(), img.height(), |x, y| {
+        let [red, green, blue, alpha] = img.get_pixel(x, y).0;
+        let alpha = alpha as u32;
+        Rgb([
+          blend(red as u32, r as u32, alpha, 255) as u8,
+          blend(green as u32, g as u32, alpha, 255) as u8,
+          blend(blue as u32, b as u32, alpha, 255) as u8,
+        ])
+      }))
+    }
+    image => image,
+  }
+}
+
+// 格式名称（大写的首选扩展名，如 `AVIF`）
+fn format_name(format: ImageFormat) -> String {
+  format
+    .extensions_str()
+    .first()
+    .map_or_else(|| format!("{format:?}"), |ext| ext.to_uppercase())
+}
+
+/// 校验图片尺寸上限配置
+pub(crate) fn validate_decode_config(config: &SlideMatchConfig) -> anyhow::Result<()> {
+  if let Some(limit) = config.max_image_pixels {
+    anyhow::ensure!(limit > 0, "图片像素上限必须大于 0");
+  }
+
+  if let Some(fill) = &config.background_fill {
+    anyhow::ensure!(
+      fill.len() == 3 && fill.iter().all(|&c| c <= 255),
+      "backgroundFill 必须是 3 个 0-255 范围内的整数 [r, g, b]"
+    );
+  }
+
+  Ok(())
+}
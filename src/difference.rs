@@ -0,0 +1,212 @@
+use image::{DynamicImage, GrayImage, Rgb, RgbImage, RgbaImage};
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::error::{napi_error, ErrorStatus};
+use crate::pipeline::{ImagePipeline, OutputFormat};
+use crate::preprocess::ALPHA_MASK_THRESHOLD;
+use crate::{crop, decode, CoordinateMode, SlideBBox, SlideMatchConfig};
+
+/// 目标图片与背景图片匹配区域的逐像素灰度差异图
+///
+/// 匹配区域为 `bbox` 的 `[x1, x2) × [y1, y2)`，目标图片取从 `(target_x, target_y)` 开始的同尺寸区域，
+/// 即 `slideMatch` 裁剪透明边框后参与匹配的部分。结果与匹配区域同尺寸，R 通道为灰度差的绝对值，
+/// G、B 通道为 0；目标图片中透明（alpha 低于 128）的像素不参与比较，差异记为 0。
+///
+/// `bbox` 必须是背景图片原始尺寸下以左上角表示的坐标（`coordinateMode` 为 `topLeft`，
+/// 未换算到显示尺寸），否则报错或得到错位的差异图。
+pub fn difference_map(
+  target: &RgbaImage,
+  background: &GrayImage,
+  bbox: &SlideBBox,
+) -> anyhow::Result<RgbImage> {
+  anyhow::ensure!(
+    bbox.coordinate_mode != Some(CoordinateMode::Center),
+    "bbox 的 coordinateMode 必须为 topLeft"
+  );
+  anyhow::ensure!(
+    bbox.x2 > bbox.x1 && bbox.y2 > bbox.y1,
+    "bbox 的匹配区域为空"
+  );
+
+  let (width, height) = (bbox.x2 - bbox.x1, bbox.y2 - bbox.y1);
+  anyhow::ensure!(
+    bbox.x2 <= background.width() && bbox.y2 <= background.height(),
+    "匹配区域 [{}, {}) × [{}, {}) 超出背景图片（{}x{}）",
+    bbox.x1,
+    bbox.x2,
+    bbox.y1,
+    bbox.y2,
+    background.width(),
+    background.height()
+  );
+  anyhow::ensure!(
+    bbox.target_x as u64 + width as u64 <= target.width() as u64
+      && bbox.target_y as u64 + height as u64 <= target.height() as u64,
+    "目标图片（{}x{}）中从 ({}, {}) 开始放不下 {width}x{height} 的匹配区域",
+    target.width(),
+    target.height(),
+    bbox.target_x,
+    bbox.target_y
+  );
+
+  let target_gray = image::imageops::grayscale(target);
+  Ok(RgbImage::from_fn(width, height, |x, y| {
+    let (tx, ty) = (bbox.target_x + x, bbox.target_y + y);
+    if target.get_pixel(tx, ty)[3] < ALPHA_MASK_THRESHOLD {
+      return Rgb([0, 0, 0]);
+    }
+    let difference =
+      target_gray.get_pixel(tx, ty)[0].abs_diff(background.get_pixel(bbox.x1 + x, bbox.y1 + y)[0]);
+    Rgb([difference, 0, 0])
+  }))
+}
+
+pub fn compute_difference_map_internal(
+  target: &[u8],
+  background: &[u8],
+  bbox: &SlideBBox,
+) -> anyhow::Result<Vec<u8>> {
+  let config = SlideMatchConfig::default();
+  let target = decode::load_image(target, "目标图片", &config)?.into_rgba8();
+  let background = decode::load_background(background, &config)?.into_luma8();
+
+  let difference = difference_map(&target, &background, bbox)?;
+  ImagePipeline::from_image(DynamicImage::ImageRgb8(difference)).to_bytes(OutputFormat::Png)
+}
+
+/// 计算目标图片与背景图片中匹配区域的逐像素灰度差异，用于排查错误的匹配结果
+///
+/// 返回与匹配区域同尺寸的 PNG 图片，R 通道为灰度差的绝对值（越红差异越大），G、B 通道为 0；
+/// 目标图片的透明像素差异记为 0。`bbox` 通常是 `slideMatch` 的返回值，必须是背景图片原始尺寸下
+/// 以左上角表示的坐标（未设置 `backgroundDisplayWidth`、`devicePixelRatio`、`coordinateMode: 'center'` 等选项）。
+///
+/// # 参数
+/// - target: 目标图片 Buffer
+/// - background: 背景图片 Buffer
+/// - bbox: 匹配结果
+#[napi]
+pub fn compute_difference_map(
+  target: Buffer,
+  background: Buffer,
+  bbox: SlideBBox,
+) -> Result<Buffer, ErrorStatus> {
+  compute_difference_map_internal(&target, &background, &bbox)
+    .map(Buffer::from)
+    .map_err(|e| napi_error(&e, format!("差异图计算失败: {e}")))
+}
+
+/// 把目标图片按 `alpha` 的不透明度叠加到背景图片上匹配到的位置，用于直观地检查匹配结果
+///
+/// 目标图片为未裁剪的原始图片，左上角放在 `(x1 - target_x, y1 - target_y)`，超出背景图片的部分被截掉；
+/// 目标图片自身的透明度与 `alpha` 相乘。`bbox` 的要求与 [`difference_map`] 相同。
+pub fn match_overlay(
+  target: &RgbaImage,
+  background: &RgbaImage,
+  bbox: &SlideBBox,
+  alpha: f32,
+) -> anyhow::Result<RgbaImage> {
+  anyhow::ensure!(
+    bbox.coordinate_mode != Some(CoordinateMode::Center),
+    "bbox 的 coordinateMode 必须为 topLeft"
+  );
+  anyhow::ensure!((0.0..=1.0).contains(&alpha), "alpha 必须在 0.0-1.0 范围内");
+
+  let mut target = target.clone();
+  for pixel in target.pixels_mut() {
+    pixel[3] = (pixel[3] as f32 * alpha).round() as u8;
+  }
+  let mut overlay = background.clone();
+  image::imageops::overlay(
+    &mut overlay,
+    &target,
+    bbox.x1 as i64 - bbox.target_x as i64,
+    bbox.y1 as i64 - bbox.target_y as i64,
+  );
+  Ok(overlay)
+}
+
+pub fn render_match_overlay_internal(
+  target: &[u8],
+  background: &[u8],
+  bbox: &SlideBBox,
+  alpha: f32,
+) -> anyhow::Result<Vec<u8>> {
+  let config = SlideMatchConfig::default();
+  let target = decode::load_image(target, "目标图片", &config)?.into_rgba8();
+  let background = decode::load_image(background, "背景图片", &config)?.into_rgba8();
+
+  let overlay = match_overlay(&target, &background, bbox, alpha)?;
+  ImagePipeline::from_image(DynamicImage::ImageRgba8(overlay)).to_bytes(OutputFormat::Png)
+}
+
+/// 把目标图片半透明地叠加到背景图片上匹配到的位置，返回与背景图片同尺寸的 PNG 图片，用于快速目视检查匹配结果
+///
+/// `bbox` 通常是 `slideMatch` 的返回值，要求与 `computeDifferenceMap` 相同。
+///
+/// # 参数
+/// - target: 目标图片 Buffer（未裁剪的原始图片）
+/// - background: 背景图片 Buffer
+/// - bbox: 匹配结果
+/// - alpha: 目标图片的不透明度，范围 0.0-1.0
+#[napi]
+pub fn render_match_overlay(
+  target: Buffer,
+  background: Buffer,
+  bbox: SlideBBox,
+  alpha: f64,
+) -> Result<Buffer, ErrorStatus> {
+  render_match_overlay_internal(&target, &background, &bbox, alpha as f32)
+    .map(Buffer::from)
+    .map_err(|e| napi_error(&e, format!("叠加图生成失败: {e}")))
+}
+
+pub fn preview_placement_internal(
+  target: &[u8],
+  background: &[u8],
+  x: u32,
+  y: u32,
+) -> anyhow::Result<Vec<u8>> {
+  let config = SlideMatchConfig::default();
+  let target = decode::load_image(target, "目标图片", &config)?.into_rgba8();
+  let background = decode::load_image(background, "背景图片", &config)?.into_rgba8();
+
+  // 与 `slideMatch` 相同的透明边框判断，(x, y) 对应裁剪后的目标图片左上角
+  let (target_x, target_y, width, height) =
+    crop::opaque_bounds(&target, crop::DEFAULT_ALPHA_THRESHOLD).unwrap_or((0, 0, 0, 0));
+  let bbox = SlideBBox {
+    target_x,
+    target_y,
+    x1: x,
+    y1: y,
+    x2: x.saturating_add(width),
+    y2: y.saturating_add(height),
+    ..SlideBBox::from_center(0, 0, 0, 0)
+  };
+
+  let overlay = match_overlay(&target, &background, &bbox, 1.0)?;
+  ImagePipeline::from_image(DynamicImage::ImageRgba8(overlay)).to_bytes(OutputFormat::Png)
+}
+
+/// 把目标图片放到背景图片的指定位置，返回与背景图片同尺寸的 PNG 图片，用于在不执行匹配的情况下
+/// 预览某个拖动位置的效果
+///
+/// `(x, y)` 为裁剪透明边框后的目标图片左上角（与 `slideMatch` 返回的 `x1/y1` 含义相同，背景图片原始尺寸像素），
+/// 透明边框按与 `slideMatch` 相同的规则判断；超出背景图片的部分被截掉。
+///
+/// # 参数
+/// - target: 目标图片 Buffer
+/// - background: 背景图片 Buffer
+/// - x: 目标图片左上角的 x 坐标
+/// - y: 目标图片左上角的 y 坐标
+#[napi]
+pub fn preview_placement(
+  target: Buffer,
+  background: Buffer,
+  x: u32,
+  y: u32,
+) -> Result<Buffer, ErrorStatus> {
+  preview_placement_internal(&target, &background, x, y)
+    .map(Buffer::from)
+    .map_err(|e| napi_error(&e, format!("预览图生成失败: {e}")))
+}
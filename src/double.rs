@@ -0,0 +1,239 @@
+use image::{DynamicImage, GrayImage};
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::error::{napi_error, ErrorStatus};
+use crate::preprocess::{self, MatchImage};
+use crate::{
+  background_canny_thresholds, cache, coordinates, crop_transparent, decode, is_better_peak,
+  original_canny_thresholds, oversize, score_map, target_canny_thresholds, validate_config,
+  ScoreMap, SlideBBox, SlideMatchConfig,
+};
+
+// 第一个缺口区域向外扩展的像素数，Canny 检测到的轮廓可能落在区域外侧
+const FIRST_MATCH_MASK_MARGIN: u32 = 2;
+
+/// 双滑块匹配结果
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct DoubleSlideResult {
+  /// 第一个滑块（`target1`）的缺口位置
+  pub first: SlideBBox,
+  /// 第二个滑块（`target2`）的缺口位置，与第一个缺口不重叠
+  pub second: SlideBBox,
+}
+
+// 背景图中的矩形区域 [x1, x2) × [y1, y2)（原始尺寸像素）
+#[derive(Debug, Clone, Copy)]
+struct Region {
+  x1: u32,
+  y1: u32,
+  x2: u32,
+  y2: u32,
+}
+
+impl Region {
+  // 左上角位于 (x, y)、尺寸为 (width, height) 的区域是否与该区域重叠
+  fn overlaps(&self, x: u32, y: u32, (width, height): (u32, u32)) -> bool {
+    x < self.x2 && x + width > self.x1 && y < self.y2 && y + height > self.y1
+  }
+}
+
+// 预处理后的滑块
+struct Piece {
+  image: MatchImage,
+  // 透明度掩码
+  mask: GrayImage,
+  // 裁剪起点
+  offset: (u32, u32),
+  // 原始尺寸，用于换算显示坐标
+  original_size: (u32, u32),
+}
+
+impl Piece {
+  fn new(image: &DynamicImage, config: &SlideMatchConfig) -> Self {
+    let (cropped_image, offset) = crop_transparent(image.to_rgba8());
+    Self {
+      mask: preprocess::alpha_mask(&cropped_image, preprocess::ALPHA_MASK_THRESHOLD),
+      image: MatchImage::from_rgba(
+        &cropped_image,
+        &preprocess::target_preprocess_config(config),
+      ),
+      offset,
+      original_size: (image.width(), image.height()),
+    }
+  }
+
+  fn size(&self) -> (u32, u32) {
+    (self.image.width(), self.image.height())
+  }
+}
+
+// 得分最高的位置（背景图坐标），跳过与 `exclude` 重叠的位置；得分相同时取最左侧的位置
+fn best_position(
+  map: &ScoreMap,
+  size: (u32, u32),
+  exclude: Option<Region>,
+) -> Option<(u32, u32, f32)> {
+  map
+    .scores
+    .enumerate_pixels()
+    .map(|(x, y, score)| (x, y + map.row_offset, score[0]))
+    .filter(|&(x, y, _)| !exclude.is_some_and(|region| region.overlaps(x, y, size)))
+    .fold(None, |best, candidate| match best {
+      Some(best) if !is_better_peak(candidate, best) => Some(best),
+      _ => Some(candidate),
+    })
+}
+
+fn double_slide_match_internal(
+  target1: &[u8],
+  target2: &[u8],
+  background_image: &[u8],
+  config: &SlideMatchConfig,
+) -> anyhow::Result<DoubleSlideResult> {
+  validate_config(config)?;
+
+  let (target1_image, _) = decode::load_target(target1, "第一个目标图片", config)?;
+  // 两个滑块完全相同时只解码一次，直接借用第一个滑块的解码结果
+  let target2_decoded = if target1 == target2 {
+    None
+  } else {
+    Some(decode::load_target(target2, "第二个目标图片", config)?.0)
+  };
+  let target2_image = target2_decoded.as_ref().unwrap_or(&target1_image);
+  let background_key = cache::background_key(background_image, config);
+  let background_image = decode::load_background(background_image, config)?;
+
+  for target_image in [&target1_image, target2_image] {
+    oversize::ensure_fits(
+      (target_image.width(), target_image.height()),
+      (background_image.width(), background_image.height()),
+    )?;
+  }
+
+  let (width, height) = (background_image.width(), background_image.height());
+  let background =
+    MatchImage::from_dynamic(background_image, config).with_cache_key(background_key);
+  let background_thresholds =
+    background_canny_thresholds(original_canny_thresholds(&background, config), config)?;
+
+  let first_piece = Piece::new(&target1_image, config);
+  let first_map = score_map(
+    &first_piece.image,
+    &background,
+    target_canny_thresholds(
+      original_canny_thresholds(&first_piece.image, config),
+      config,
+    )?,
+    background_thresholds,
+    Some(&first_piece.mask),
+    None,
+    config,
+  )?;
+  let (x, y, first_score) = best_position(&first_map, first_piece.size(), None)
+    .ok_or_else(|| anyhow::anyhow!("未找到第一个滑块的缺口"))?;
+  let (first_width, first_height) = first_piece.size();
+  let first_region = Region {
+    x1: x,
+    y1: y,
+    x2: x + first_width,
+    y2: y + first_height,
+  };
+
+  // 第一个缺口（含轮廓外侧的边缘）不参与第二次匹配，两个滑块相同时也不会再次匹配到同一位置
+  let mask = GrayImage::from_fn(width, height, |x, y| {
+    let inside = (first_region.x1.saturating_sub(FIRST_MATCH_MASK_MARGIN)
+      ..first_region.x2 + FIRST_MATCH_MASK_MARGIN)
+      .contains(&x)
+      && (first_region.y1.saturating_sub(FIRST_MATCH_MASK_MARGIN)
+        ..first_region.y2 + FIRST_MATCH_MASK_MARGIN)
+        .contains(&y);
+    image::Luma([if inside { 0 } else { u8::MAX }])
+  });
+
+  let second_piece = Piece::new(target2_image, config);
+  let second_map = score_map(
+    &second_piece.image,
+    &background,
+    target_canny_thresholds(
+      original_canny_thresholds(&second_piece.image, config),
+      config,
+    )?,
+    background_thresholds,
+    Some(&second_piece.mask),
+    Some(&mask),
+    config,
+  )?;
+  let (x, y, second_score) = best_position(&second_map, second_piece.size(), Some(first_region))
+    .ok_or_else(|| anyhow::anyhow!("背景图中没有与第一个缺口不重叠的位置"))?;
+  let (second_width, second_height) = second_piece.size();
+  let second_region = Region {
+    x1: x,
+    y1: y,
+    x2: x + second_width,
+    y2: y + second_height,
+  };
+
+  let to_bbox = |piece: &Piece, region: Region, score: f32| {
+    coordinates::to_display_coordinates(
+      SlideBBox {
+        target_x: piece.offset.0,
+        target_y: piece.offset.1,
+        x1: region.x1,
+        y1: region.y1,
+        x2: region.x2,
+        y2: region.y2,
+        confidence: Some(score as f64),
+        metadata: None,
+        score_profile: None,
+        heatmap: None,
+        coordinate_mode: None,
+        ratio: None,
+        target_scale: None,
+        is_ambiguous: None,
+        second_best_score: None,
+        second_best_x: None,
+        psr: None,
+        slide_distance: None,
+        degenerate_target: None,
+        elapsed_ms: None,
+      },
+      (width, height),
+      piece.original_size,
+      config,
+    )
+  };
+
+  Ok(DoubleSlideResult {
+    first: to_bbox(&first_piece, first_region, first_score),
+    second: to_bbox(&second_piece, second_region, second_score),
+  })
+}
+
+/// 双滑块匹配：背景图中有两个缺口，分别对应两个滑块
+///
+/// 先匹配第一个滑块，再在去掉第一个缺口区域的背景图中匹配第二个滑块，
+/// 保证两个结果互不重叠。两个滑块相同时返回得分最高和次高的两个不重叠位置。
+/// 预处理方式与 `slideMatch` 一致，`confidence` 为各自的匹配得分。
+///
+/// # 参数
+/// - target1: 第一个目标图片 Buffer
+/// - target2: 第二个目标图片 Buffer，可以与第一个相同
+/// - background: 背景图片 Buffer
+/// - options: 可选配置，见 `SlideMatchConfig`
+#[napi]
+pub fn double_slide_match(
+  target1: Buffer,
+  target2: Buffer,
+  background: Buffer,
+  options: Option<SlideMatchConfig>,
+) -> Result<DoubleSlideResult, ErrorStatus> {
+  double_slide_match_internal(
+    &target1,
+    &target2,
+    &background,
+    &options.unwrap_or_default(),
+  )
+  .map_err(|e| napi_error(&e, format!("双滑块匹配失败: {e}")))
+}
@@ -0,0 +1,270 @@
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+// 默认拖动总时长（毫秒）
+const DEFAULT_TOTAL_DURATION_MS: u32 = 1000;
+// 默认竖直抖动幅度（像素）
+const DEFAULT_JITTER_AMPLITUDE: f64 = 1.0;
+
+/// 拖动轨迹上的一个点
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct DragPoint {
+  pub x: u32,
+  pub y: u32,
+  /// 相对于拖动开始的时间（毫秒）
+  pub t_ms: u32,
+}
+
+/// 拖动轨迹配置
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct DragPathConfig {
+  /// 拖动总时长（毫秒），默认 1000
+  pub total_duration_ms: u32,
+  /// 竖直方向随机抖动的最大幅度（像素），默认 1.0，0 表示不抖动
+  pub jitter_amplitude: f64,
+}
+
+impl Default for DragPathConfig {
+  fn default() -> Self {
+    Self {
+      total_duration_ms: DEFAULT_TOTAL_DURATION_MS,
+      jitter_amplitude: DEFAULT_JITTER_AMPLITUDE,
+    }
+  }
+}
+
+// 简单的 xorshift 随机数生成器，仅用于轨迹抖动，不需要密码学强度
+pub(crate) struct Xorshift(u64);
+
+impl Xorshift {
+  // 以当前时间作为种子
+  pub(crate) fn from_time() -> Self {
+    let nanos = std::time::SystemTime::now()
+      .duration_since(std::time::UNIX_EPOCH)
+      .map_or(0, |d| d.as_nanos() as u64);
+    Self(nanos | 1)
+  }
+
+  // 返回 [0, 1) 范围内的随机数
+  pub(crate) fn next_f64(&mut self) -> f64 {
+    self.0 ^= self.0 << 13;
+    self.0 ^= self.0 >> 7;
+    self.0 ^= self.0 << 17;
+    (self.0 >> 11) as f64 / (1u64 << 53) as f64
+  }
+
+  // 返回 [-amplitude, amplitude] 范围内的随机数
+  pub(crate) fn jitter(&mut self, amplitude: f64) -> f64 {
+    (self.next_f64() * 2.0 - 1.0) * amplitude
+  }
+}
+
+// 缓入缓出（三次）：起步加速，接近终点时减速
+fn ease_in_out_cubic(t: f64) -> f64 {
+  if t < 0.5 {
+    4.0 * t * t * t
+  } else {
+    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+  }
+}
+
+fn validate_drag_path_config(steps: u32, config: &DragPathConfig) -> anyhow::Result<()> {
+  anyhow::ensure!(steps >= 2, "轨迹点数量必须大于等于 2");
+  anyhow::ensure!(
+    config.jitter_amplitude.is_finite() && config.jitter_amplitude >= 0.0,
+    "抖动幅度必须是大于等于 0 的有限数值"
+  );
+  Ok(())
+}
+
+// 生成拖动轨迹
+fn generate_drag_path_internal(
+  start: (u32, u32),
+  end: (u32, u32),
+  steps: u32,
+  config: &DragPathConfig,
+  rng: &mut Xorshift,
+) -> anyhow::Result<Vec<DragPoint>> {
+  validate_drag_path_config(steps, config)?;
+
+  let last = (steps - 1) as f64;
+  let dx = end.0 as f64 - start.0 as f64;
+  let dy = end.1 as f64 - start.1 as f64;
+
+  let path = (0..steps)
+    .map(|i| {
+      let t = i as f64 / last;
+      let progress = ease_in_out_cubic(t);
+
+      // 起点和终点不抖动，保证轨迹准确落在目标位置
+      let jitter = if i == 0 || i == steps - 1 {
+        0.0
+      } else {
+        rng.jitter(config.jitter_amplitude)
+      };
+
+      DragPoint {
+        x: (start.0 as f64 + dx * progress).round().max(0.0) as u32,
+        y: (start.1 as f64 + dy * progress + jitter).round().max(0.0) as u32,
+        t_ms: (config.total_duration_ms as f64 * t).round() as u32,
+      }
+    })
+    .collect();
+
+  Ok(path)
+}
+
+// 三次贝塞尔曲线在参数 t 处的取值
+fn cubic_bezier(
+  p0: (f64, f64),
+  p1: (f64, f64),
+  p2: (f64, f64),
+  p3: (f64, f64),
+  t: f64,
+) -> (f64, f64) {
+  let u = 1.0 - t;
+  let b0 = u * u * u;
+  let b1 = 3.0 * u * u * t;
+  let b2 = 3.0 * u * t * t;
+  let b3 = t * t * t;
+
+  (
+    b0 * p0.0 + b1 * p1.0 + b2 * p2.0 + b3 * p3.0,
+    b0 * p0.1 + b1 * p1.1 + b2 * p2.1 + b3 * p3.1,
+  )
+}
+
+// 自动生成两个控制点，使轨迹形成一段轻微的弧线
+// 弧高为起终点距离的 3%-8%，方向随机（向上或向下），至少 2 像素
+fn auto_control_points(
+  p0: (f64, f64),
+  p3: (f64, f64),
+  rng: &mut Xorshift,
+) -> ((f64, f64), (f64, f64)) {
+  let dx = p3.0 - p0.0;
+  let dy = p3.1 - p0.1;
+  let distance = (dx * dx + dy * dy).sqrt();
+
+  let ratio = 0.03 + rng.next_f64() * 0.05;
+  let sign = if rng.next_f64() < 0.5 { -1.0 } else { 1.0 };
+  let arc = (distance * ratio).max(2.0) * sign;
+
+  // 沿起终点连线的法线方向偏移
+  let (nx, ny) = if distance > 0.0 {
+    (-dy / distance, dx / distance)
+  } else {
+    (0.0, 1.0)
+  };
+
+  (
+    (p0.0 + dx / 3.0 + nx * arc, p0.1 + dy / 3.0 + ny * arc),
+    (
+      p0.0 + dx * 2.0 / 3.0 + nx * arc,
+      p0.1 + dy * 2.0 / 3.0 + ny * arc,
+    ),
+  )
+}
+
+// 生成贝塞尔曲线拖动轨迹
+fn generate_bezier_drag_path_internal(
+  start: &DragPoint,
+  end: &DragPoint,
+  control_points: &[(f64, f64)],
+  steps: u32,
+  total_ms: u32,
+  rng: &mut Xorshift,
+) -> anyhow::Result<Vec<DragPoint>> {
+  anyhow::ensure!(steps >= 2, "轨迹点数量必须大于等于 2");
+  anyhow::ensure!(
+    control_points.is_empty() || control_points.len() == 2,
+    "三次贝塞尔曲线需要 2 个控制点（或不传，由程序自动生成），当前为 {} 个",
+    control_points.len()
+  );
+  anyhow::ensure!(
+    control_points
+      .iter()
+      .all(|(x, y)| x.is_finite() && y.is_finite()),
+    "控制点坐标必须是有限数值"
+  );
+
+  let p0 = (start.x as f64, start.y as f64);
+  let p3 = (end.x as f64, end.y as f64);
+  let (p1, p2) = match control_points {
+    [p1, p2] => (*p1, *p2),
+    _ => auto_control_points(p0, p3, rng),
+  };
+
+  let last = (steps - 1) as f64;
+  let path = (0..steps)
+    .map(|i| {
+      let t = i as f64 / last;
+      let (x, y) = cubic_bezier(p0, p1, p2, p3, t);
+
+      DragPoint {
+        x: x.round().max(0.0) as u32,
+        y: y.round().max(0.0) as u32,
+        t_ms: start.t_ms + (total_ms as f64 * t).round() as u32,
+      }
+    })
+    .collect();
+
+  Ok(path)
+}
+
+/// 根据起点和终点生成拖动滑块的轨迹
+/// 轨迹在起步阶段加速、接近终点时减速，并在竖直方向加入少量随机抖动
+///
+/// # 参数
+/// - start_x / start_y: 起点坐标
+/// - end_x / end_y: 终点坐标
+/// - steps: 轨迹点数量（包含起点和终点），必须大于等于 2
+/// - config: 可选配置，见 `DragPathConfig`
+#[napi]
+pub fn generate_drag_path(
+  start_x: u32,
+  start_y: u32,
+  end_x: u32,
+  end_y: u32,
+  steps: u32,
+  config: Option<DragPathConfig>,
+) -> Result<Vec<DragPoint>> {
+  let config = config.unwrap_or_default();
+
+  generate_drag_path_internal(
+    (start_x, start_y),
+    (end_x, end_y),
+    steps,
+    &config,
+    &mut Xorshift::from_time(),
+  )
+  .map_err(|e| Error::from_reason(format!("生成拖动轨迹失败: {e}")))
+}
+
+/// 生成基于三次贝塞尔曲线的拖动轨迹
+/// 在参数 t 上等间隔取 `steps` 个点，映射为像素坐标和时间戳
+///
+/// # 参数
+/// - start / end: 起点和终点，时间戳以 `start.tMs` 为起始
+/// - control_points: 两个控制点 `[[x1, y1], [x2, y2]]`；传空数组时自动生成两个控制点，使轨迹形成轻微弧线
+/// - steps: 轨迹点数量（包含起点和终点），必须大于等于 2
+/// - total_ms: 拖动总时长（毫秒）
+#[napi]
+pub fn generate_bezier_drag_path(
+  start: DragPoint,
+  end: DragPoint,
+  control_points: Vec<(f64, f64)>,
+  steps: u32,
+  total_ms: u32,
+) -> Result<Vec<DragPoint>> {
+  generate_bezier_drag_path_internal(
+    &start,
+    &end,
+    &control_points,
+    steps,
+    total_ms,
+    &mut Xorshift::from_time(),
+  )
+  .map_err(|e| Error::from_reason(format!("生成拖动轨迹失败: {e}")))
+}
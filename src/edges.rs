@@ -0,0 +1,96 @@
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::error::{napi_error, ErrorStatus};
+use crate::pipeline::{ImagePipeline, OutputFormat};
+use crate::preprocess::{self, MatchImage};
+use crate::{
+  adaptive_canny_thresholds, decode, original_canny_thresholds, override_thresholds,
+  validate_config, SlideMatchConfig,
+};
+
+/// Canny 边缘检测选项
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct CannyEdgesOptions {
+  /// 低阈值，范围 0-255，优先级高于自适应阈值和 `SlideMatchConfig.cannyLow`
+  pub low: Option<f64>,
+  /// 高阈值，范围 0-255，优先级高于自适应阈值和 `SlideMatchConfig.cannyHigh`
+  pub high: Option<f64>,
+  /// 是否使用改进版函数的自适应阈值（按 `thresholdStrategy` 计算），默认 false，
+  /// 即使用原版函数的阈值（未指定 `thresholdStrategy` 时为固定阈值 (100, 200)）
+  pub adaptive: Option<bool>,
+}
+
+/// Canny 边缘检测结果
+#[napi(object)]
+pub struct CannyEdgesResult {
+  /// 边缘图（PNG 灰度图，边缘像素为 255）
+  pub data: Buffer,
+  /// 实际使用的 Canny 阈值 [low, high]
+  pub thresholds: Vec<f64>,
+}
+
+impl std::fmt::Debug for CannyEdgesResult {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("CannyEdgesResult")
+      .field("thresholds", &self.thresholds)
+      .finish_non_exhaustive()
+  }
+}
+
+fn canny_edges_internal(
+  image: &[u8],
+  options: &CannyEdgesOptions,
+  config: &SlideMatchConfig,
+) -> anyhow::Result<CannyEdgesResult> {
+  validate_config(config)?;
+
+  let image = decode::load_image(image, "图片", config)?;
+  let config = preprocess::resolve_canny_sigma(config, image.width(), image.height());
+  // 与匹配函数相同的灰度转换和预处理
+  let image = MatchImage::from_dynamic(image, &config);
+
+  let thresholds = if options.adaptive.unwrap_or(false) {
+    adaptive_canny_thresholds(&image, &config)
+  } else {
+    original_canny_thresholds(&image, &config)
+  };
+  let thresholds = override_thresholds(
+    "图片",
+    thresholds,
+    options.low.or(config.canny_low),
+    options.high.or(config.canny_high),
+  )?;
+
+  let edges = preprocess::detect_edges(image.gray, thresholds, &config);
+  let data = ImagePipeline::from_gray(edges).to_bytes(OutputFormat::Png)?;
+
+  Ok(CannyEdgesResult {
+    data: data.into(),
+    thresholds: vec![thresholds.0 as f64, thresholds.1 as f64],
+  })
+}
+
+/// 执行与匹配函数相同的 Canny 边缘检测，用于离线调参
+///
+/// 彩色图片自动转换为灰度图，并按 `config` 执行相同的预处理（CLAHE、高斯模糊）和边缘后处理
+/// （形态学操作、边缘膨胀）。`colorMode` 为 `rgb` 时也只输出灰度图的边缘。
+///
+/// # 参数
+/// - image: 图片 Buffer
+/// - options: 可选，阈值选项，见 `CannyEdgesOptions`
+/// - config: 可选配置，见 `SlideMatchConfig`
+#[napi]
+pub fn canny_edges(
+  image: Buffer,
+  options: Option<CannyEdgesOptions>,
+  config: Option<SlideMatchConfig>,
+) -> Result<CannyEdgesResult, ErrorStatus> {
+  canny_edges_internal(
+    &image,
+    &options.unwrap_or_default(),
+    &config.unwrap_or_default(),
+  )
+  .map_err(|e| napi_error(&e, format!("边缘检测失败: {e}")))
+}
@@ -0,0 +1,232 @@
+use image::GrayImage;
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::error::{napi_error, ErrorStatus};
+use crate::preprocess::{self, MatchImage};
+use crate::{
+  background_canny_thresholds, coordinates, decode, ncc, original_canny_thresholds, score_map,
+  target_canny_thresholds, validate_config, ScoreMap, SlideBBox, SlideMatchConfig,
+  FIXED_CANNY_THRESHOLDS,
+};
+
+// 自动检测时只在背景图左侧 1/N 宽度内寻找滑块
+const PIECE_SEARCH_FRACTION: u32 = 3;
+// 自动检测的滑块最小边长（像素）
+const MIN_PIECE_SIZE: u32 = 10;
+// 滑块左右两条边的纵向范围至少重合的比例
+const MIN_SIDE_OVERLAP: f64 = 0.8;
+// 滑块区域向外扩展的像素数，Canny 检测到的轮廓可能落在区域外侧
+const PIECE_MASK_MARGIN: u32 = 2;
+
+/// 图片中的矩形区域（原始尺寸像素）
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PieceRegion {
+  pub x: u32,
+  pub y: u32,
+  pub width: u32,
+  pub height: u32,
+}
+
+/// 内嵌滑块匹配结果
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct EmbeddedMatchResult {
+  /// 滑块在图片中的区域（原始尺寸像素）
+  pub piece: PieceRegion,
+  /// 缺口位置；`targetX/targetY` 为滑块在图片中的左上角
+  pub bbox: SlideBBox,
+}
+
+// 一列中最长的连续边缘像素 (起始行, 长度)
+fn longest_vertical_run(edges: &GrayImage, x: u32) -> (u32, u32) {
+  let mut best = (0, 0);
+  let mut start = 0;
+  let mut len = 0;
+
+  for y in 0..edges.height() {
+    if edges.get_pixel(x, y)[0] > 0 {
+      if len == 0 {
+        start = y;
+      }
+      len += 1;
+      if len > best.1 {
+        best = (start, len);
+      }
+    } else {
+      len = 0;
+    }
+  }
+
+  best
+}
+
+// 两段纵向范围重合部分占较短一段的比例
+fn vertical_overlap((start_a, len_a): (u32, u32), (start_b, len_b): (u32, u32)) -> f64 {
+  let top = start_a.max(start_b);
+  let bottom = (start_a + len_a).min(start_b + len_b);
+  bottom.saturating_sub(top) as f64 / len_a.min(len_b) as f64
+}
+
+// 在图片左侧寻找贴入的滑块：滑块边缘对比度高，左右两条边是纵向最长的两段连续边缘
+fn detect_piece_region(gray: &GrayImage) -> Option<PieceRegion> {
+  let strip_width = (gray.width() / PIECE_SEARCH_FRACTION).max(1);
+  let strip = image::imageops::crop_imm(gray, 0, 0, strip_width, gray.height()).to_image();
+  let (low, high) = FIXED_CANNY_THRESHOLDS;
+  let edges = imageproc::edges::canny(&strip, low, high);
+
+  let runs: Vec<(u32, (u32, u32))> = (0..strip_width)
+    .map(|x| (x, longest_vertical_run(&edges, x)))
+    .filter(|(_, (_, len))| *len >= MIN_PIECE_SIZE)
+    .collect();
+
+  let &(left_x, left_run) = runs
+    .iter()
+    .max_by_key(|(x, (_, len))| (*len, u32::MAX - x))?;
+  let &(right_x, right_run) = runs
+    .iter()
+    .filter(|(x, run)| {
+      x.abs_diff(left_x) + 1 >= MIN_PIECE_SIZE
+        && vertical_overlap(left_run, *run) >= MIN_SIDE_OVERLAP
+    })
+    .max_by_key(|(x, (_, len))| (*len, x.abs_diff(left_x)))?;
+
+  let x0 = left_x.min(right_x);
+  let x1 = left_x.max(right_x);
+  let y0 = left_run.0.min(right_run.0);
+  let y1 = (left_run.0 + left_run.1).max(right_run.0 + right_run.1);
+
+  Some(PieceRegion {
+    x: x0,
+    y: y0,
+    width: x1 - x0 + 1,
+    height: y1 - y0,
+  })
+}
+
+fn slide_match_embedded_internal(
+  image: &[u8],
+  piece_region_hint: Option<PieceRegion>,
+  config: &SlideMatchConfig,
+) -> anyhow::Result<EmbeddedMatchResult> {
+  validate_config(config)?;
+
+  let image = decode::load_background(image, config)?;
+  let (width, height) = (image.width(), image.height());
+
+  let piece = match piece_region_hint {
+    Some(hint) => {
+      anyhow::ensure!(
+        hint.width > 0 && hint.height > 0,
+        "滑块区域的宽度和高度必须大于 0"
+      );
+      anyhow::ensure!(
+        hint.x as u64 + hint.width as u64 <= width as u64
+          && hint.y as u64 + hint.height as u64 <= height as u64,
+        "滑块区域超出图片范围（图片 {width}x{height}）"
+      );
+      hint
+    }
+    None => detect_piece_region(&image.to_luma8())
+      .ok_or_else(|| anyhow::anyhow!("未能在图片左侧检测到滑块，请通过 pieceRegionHint 指定"))?,
+  };
+
+  // 以滑块区域为模板
+  let template = image::imageops::crop_imm(
+    &image.to_rgba8(),
+    piece.x,
+    piece.y,
+    piece.width,
+    piece.height,
+  )
+  .to_image();
+
+  // 滑块所在区域（含轮廓外侧的边缘）不参与匹配，否则会匹配到滑块本身
+  let left = piece.x.saturating_sub(PIECE_MASK_MARGIN);
+  let top = piece.y.saturating_sub(PIECE_MASK_MARGIN);
+  let right = piece.x + piece.width + PIECE_MASK_MARGIN;
+  let bottom = piece.y + piece.height + PIECE_MASK_MARGIN;
+  let mask = GrayImage::from_fn(width, height, |x, y| {
+    let inside = (left..right).contains(&x) && (top..bottom).contains(&y);
+    image::Luma([if inside { 0 } else { u8::MAX }])
+  });
+
+  let target = MatchImage::from_rgba(&template, &preprocess::target_preprocess_config(config));
+  let background = MatchImage::from_dynamic(image, config);
+
+  let ScoreMap {
+    mut scores,
+    row_offset,
+  } = score_map(
+    &target,
+    &background,
+    target_canny_thresholds(original_canny_thresholds(&target, config), config)?,
+    background_canny_thresholds(original_canny_thresholds(&background, config), config)?,
+    None,
+    Some(&mask),
+    config,
+  )?;
+  // 与滑块区域重叠的位置不可能是缺口
+  for (x, y, score) in scores.enumerate_pixels_mut() {
+    let y = y + row_offset;
+    if x < right && x + piece.width > left && y < bottom && y + piece.height > top {
+      score[0] = 0.0;
+    }
+  }
+  let extremes = ncc::find_extremes(&scores);
+  let (x1, y1) = (
+    extremes.max_value_location.0,
+    extremes.max_value_location.1 + row_offset,
+  );
+
+  // 滑块和缺口都在同一张图片中，坐标按背景图的比例换算
+  let bbox = coordinates::to_display_coordinates(
+    SlideBBox {
+      target_x: piece.x,
+      target_y: piece.y,
+      x1,
+      y1,
+      x2: x1 + piece.width,
+      y2: y1 + piece.height,
+      confidence: Some(extremes.max_value as f64),
+      metadata: None,
+      score_profile: None,
+      heatmap: None,
+      coordinate_mode: None,
+      ratio: None,
+      target_scale: None,
+      is_ambiguous: None,
+      second_best_score: None,
+      second_best_x: None,
+      psr: None,
+      slide_distance: None,
+      degenerate_target: None,
+      elapsed_ms: None,
+    },
+    (width, height),
+    (width, height),
+    config,
+  );
+
+  Ok(EmbeddedMatchResult { piece, bbox })
+}
+
+/// 内嵌滑块匹配：滑块直接画在背景图中（通常位于左侧起始位置），没有单独的目标图片
+///
+/// 先根据 `pieceRegionHint` 或自动检测（图片左侧 1/3 内边缘对比度最高的矩形）确定滑块区域，
+/// 以该区域为模板，在排除滑块区域的背景图中寻找缺口。
+///
+/// # 参数
+/// - background: 包含滑块的背景图片 Buffer
+/// - piece_region_hint: 可选，滑块区域（原始尺寸像素）
+/// - options: 可选配置，见 `SlideMatchConfig`
+#[napi]
+pub fn slide_match_embedded(
+  background: Buffer,
+  piece_region_hint: Option<PieceRegion>,
+  options: Option<SlideMatchConfig>,
+) -> Result<EmbeddedMatchResult, ErrorStatus> {
+  slide_match_embedded_internal(&background, piece_region_hint, &options.unwrap_or_default())
+    .map_err(|e| napi_error(&e, format!("内嵌滑块匹配失败: {e}")))
+}
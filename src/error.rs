@@ -0,0 +1,200 @@
+use std::fmt;
+
+use image::ImageError;
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+/// 滑块匹配过程中可识别的错误类型
+#[derive(Debug, Clone, PartialEq)]
+pub enum SlideMatchError {
+  /// 图片像素数超过 `maxImagePixels` 上限
+  ImageTooLarge { actual: u64, limit: u64 },
+  /// 背景边缘图中边缘像素占比低于 `edgeDensityThreshold`（如纯色占位图）
+  InsufficientEdges { density: f32 },
+  /// 能从文件头识别出格式，但当前构建未包含该格式的解码器（如 AVIF）
+  UnsupportedFormat { format: String },
+  /// 匹配得分峰值不高于置信度阈值：原版函数设置了 `confidenceThreshold`，或改进版函数的
+  /// `onLowConfidence` 为 `error`
+  LowConfidence { confidence: f64, threshold: f64 },
+  /// 目标图片的不透明像素数少于 `minOpaquePixels`（如完全透明的图片），在这样的模板上匹配没有意义
+  DegenerateTarget { opaque_pixels: u64, min: u32 },
+  /// 目标图片的宽或高大于背景图片（`oversizePolicy` 为 `error` 时），尺寸为 (宽, 高)
+  TargetLargerThanBackground {
+    target: (u32, u32),
+    background: (u32, u32),
+  },
+}
+
+/// 可预期的匹配失败原因，由 `trySlideMatch` 等不抛出异常的函数在结果的 `code` 中返回，
+/// 抛出异常的函数以同样的值作为异常的 `code`
+///
+/// 与抛出异常的函数共用同一套错误类型（[`SlideMatchError`]），同样的输入在两类函数中对应同样的失败原因。
+#[napi(string_enum = "camelCase")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+  /// 图片无法解码（数据损坏、不是图片等）
+  DecodeFailed,
+  /// 无法读取图片文件（路径不存在、没有权限等）
+  ReadFailed,
+  /// 当前构建未包含该格式的解码器（如 AVIF）
+  UnsupportedFormat,
+  /// 图片像素数超过 `maxImagePixels` 上限
+  ImageTooLarge,
+  /// 目标图片的宽或高大于背景图片
+  TargetLargerThanBackground,
+  /// 背景图片边缘过少（如纯色占位图）
+  InsufficientEdges,
+  /// 匹配置信度不高于阈值（`confidenceThreshold` 或 `onLowConfidence` 为 `error`）
+  LowConfidence,
+  /// 目标图片的不透明像素数少于 `minOpaquePixels`
+  DegenerateTarget,
+}
+
+impl ErrorCode {
+  /// JS 中的取值，与 `ErrorCode` 枚举的值相同
+  pub fn as_str(self) -> &'static str {
+    match self {
+      Self::DecodeFailed => "decodeFailed",
+      Self::ReadFailed => "readFailed",
+      Self::UnsupportedFormat => "unsupportedFormat",
+      Self::ImageTooLarge => "imageTooLarge",
+      Self::TargetLargerThanBackground => "targetLargerThanBackground",
+      Self::InsufficientEdges => "insufficientEdges",
+      Self::LowConfidence => "lowConfidence",
+      Self::DegenerateTarget => "degenerateTarget",
+    }
+  }
+}
+
+/// 抛给 JS 的异常的 `code`：可预期的失败为对应的 [`ErrorCode`]，与 `trySlideMatch` 结果中的 `code`
+/// 相同；配置无效等其余错误为 napi 的 [`Status`]（如 `'GenericFailure'`）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorStatus {
+  Code(ErrorCode),
+  Napi(Status),
+}
+
+impl AsRef<str> for ErrorStatus {
+  fn as_ref(&self) -> &str {
+    match self {
+      Self::Code(code) => code.as_str(),
+      Self::Napi(status) => status.as_ref(),
+    }
+  }
+}
+
+impl From<Status> for ErrorStatus {
+  fn from(status: Status) -> Self {
+    Self::Napi(status)
+  }
+}
+
+/// 把匹配失败转换为抛给 JS 的异常，可预期的失败以错误码作为异常的 `code`，其余为 `GenericFailure`
+pub(crate) fn napi_error(error: &anyhow::Error, message: String) -> Error<ErrorStatus> {
+  let status =
+    error_code(error).map_or(ErrorStatus::Napi(Status::GenericFailure), ErrorStatus::Code);
+  Error::new(status, message)
+}
+
+/// 不带错误码的异常（如配置无效），与 `Error::from_reason` 相同，`code` 为 `GenericFailure`
+pub(crate) fn generic_error(reason: impl ToString) -> Error<ErrorStatus> {
+  Error::new(ErrorStatus::Napi(Status::GenericFailure), reason)
+}
+
+/// 在 JS 线程上转换后台任务的异常
+///
+/// 后台任务只能以 napi 的 [`Error`] 拒绝，其 `code` 只能是 [`Status`]。带错误码时先创建 JS 异常对象并设置
+/// `code`，再以该对象拒绝。
+pub(crate) fn to_js_error(env: &Env, error: Error<ErrorStatus>) -> Error {
+  let code = match error.status {
+    ErrorStatus::Code(code) => code,
+    ErrorStatus::Napi(status) => return Error::new(status, error.reason),
+  };
+  let reject = || -> Result<Error> {
+    let mut object = env.create_error(Error::from_reason(error.reason.clone()))?;
+    object.set_named_property("code", code.as_str())?;
+    Ok(Error::from(object.to_unknown()))
+  };
+  reject().unwrap_or_else(|e| e)
+}
+
+impl SlideMatchError {
+  /// 对应的错误码
+  pub fn code(&self) -> ErrorCode {
+    match self {
+      Self::ImageTooLarge { .. } => ErrorCode::ImageTooLarge,
+      Self::InsufficientEdges { .. } => ErrorCode::InsufficientEdges,
+      Self::UnsupportedFormat { .. } => ErrorCode::UnsupportedFormat,
+      Self::LowConfidence { .. } => ErrorCode::LowConfidence,
+      Self::DegenerateTarget { .. } => ErrorCode::DegenerateTarget,
+      Self::TargetLargerThanBackground { .. } => ErrorCode::TargetLargerThanBackground,
+    }
+  }
+}
+
+/// 可预期的失败（图片无法读取或解码、尺寸不匹配、置信度过低等）返回对应的错误码，
+/// 配置无效等调用方的错误返回 `None`
+///
+/// 解码时数据截断等 I/O 错误由 `image` 包装为 [`ImageError`]，仍是 `DecodeFailed`；
+/// 只有读取文件时的 [`std::io::Error`] 是 `ReadFailed`。
+pub(crate) fn error_code(error: &anyhow::Error) -> Option<ErrorCode> {
+  if let Some(error) = error.downcast_ref::<SlideMatchError>() {
+    return Some(error.code());
+  }
+  if error.downcast_ref::<ImageError>().is_some() {
+    return Some(ErrorCode::DecodeFailed);
+  }
+  if error.downcast_ref::<std::io::Error>().is_some() {
+    return Some(ErrorCode::ReadFailed);
+  }
+  None
+}
+
+impl fmt::Display for SlideMatchError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::ImageTooLarge { actual, limit } => {
+        write!(f, "图片像素数 {actual} 超过上限 {limit}")
+      }
+      Self::InsufficientEdges { density } => {
+        write!(
+          f,
+          "背景图片边缘过少（边缘像素占比 {:.2}%），可能是纯色或加载失败的占位图片",
+          density * 100.0
+        )
+      }
+      Self::UnsupportedFormat { format } => {
+        write!(
+          f,
+          "不支持 {format} 格式的图片（当前构建未包含该格式的解码器）"
+        )
+      }
+      Self::LowConfidence {
+        confidence,
+        threshold,
+      } => {
+        write!(f, "匹配置信度 {confidence:.3} 不高于阈值 {threshold:.3}")
+      }
+      Self::DegenerateTarget {
+        opaque_pixels: 0, ..
+      } => {
+        write!(f, "目标图片没有不透明像素")
+      }
+      Self::DegenerateTarget { opaque_pixels, min } => {
+        write!(
+          f,
+          "目标图片只有 {opaque_pixels} 个不透明像素，少于 minOpaquePixels（{min}）"
+        )
+      }
+      Self::TargetLargerThanBackground { target, background } => {
+        write!(
+          f,
+          "背景图片的尺寸必须大于等于目标图片的尺寸（目标图片 {}x{}，背景图片 {}x{}）",
+          target.0, target.1, background.0, background.1
+        )
+      }
+    }
+  }
+}
+
+impl std::error::Error for SlideMatchError {}
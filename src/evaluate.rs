@@ -0,0 +1,236 @@
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use anyhow::Context;
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use rayon::prelude::*;
+
+use crate::error::{napi_error, ErrorStatus};
+use crate::{
+  improved_simple_slide_match_internal, improved_slide_match_internal, simple_slide_match_internal,
+  slide_match_internal, SlideBBox, SlideMatchConfig,
+};
+
+// 默认的容差（像素）
+const DEFAULT_TOLERANCE_PX: u32 = 5;
+// 改进版函数的置信度阈值，与 `improvedSlideMatch` 的默认值相同
+const IMPROVED_CONFIDENCE_THRESHOLD: f32 = 0.3;
+
+// 数据集中每个样本的文件名后缀
+const TARGET_SUFFIX: &str = "_target.png";
+const BACKGROUND_SUFFIX: &str = "_bg.png";
+const LABEL_SUFFIX: &str = ".json";
+
+/// `evaluateDataset` 使用的匹配函数
+#[napi(string_enum = "camelCase")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DatasetAlgorithm {
+  /// `slideMatch`（默认）
+  #[default]
+  SlideMatch,
+  /// `improvedSlideMatch`，置信度阈值为默认的 0.3
+  ImprovedSlideMatch,
+  /// `simpleSlideMatch`
+  SimpleSlideMatch,
+  /// `improvedSimpleSlideMatch`，置信度阈值为默认的 0.3
+  ImprovedSimpleSlideMatch,
+}
+
+/// `evaluateDataset` 的选项
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct EvaluateDatasetOptions {
+  /// 结果的 `x1` 与标注的 x 相差不超过该值（像素）时视为正确，默认 5
+  pub tolerance_px: Option<u32>,
+  /// 使用的匹配函数，默认 `slideMatch`
+  pub algorithm: Option<DatasetAlgorithm>,
+}
+
+/// 数据集中未通过的样本
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct DatasetFailure {
+  /// 样本名称，即文件名中的 `NNN`
+  pub name: String,
+  /// 标注的 x，标注文件缺失或无法解析时为空
+  pub expected_x: Option<u32>,
+  /// 匹配结果的 `x1`，匹配失败时为空
+  pub got_x: Option<u32>,
+  /// 匹配得分，匹配失败时为空
+  pub confidence: Option<f64>,
+  /// 文件缺失、无法读取或匹配失败时的错误信息，匹配结果超出容差时为空
+  pub error: Option<String>,
+}
+
+/// `evaluateDataset` 的返回值
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct DatasetEvaluation {
+  /// 样本总数
+  pub total: u32,
+  /// 匹配结果在容差内的样本数
+  pub correct: u32,
+  /// `correct / total`，没有样本时为 0
+  pub accuracy: f64,
+  /// 未通过的样本，按名称排序
+  pub failures: Vec<DatasetFailure>,
+}
+
+// 读取标注文件中的 x：JSON 对象的 `x` 字段，必须是非负整数
+fn read_label(path: &Path) -> anyhow::Result<u32> {
+  let text = std::fs::read_to_string(path)
+    .with_context(|| format!("无法读取标注文件 {}", path.display()))?;
+  let label: serde_json::Value =
+    serde_json::from_str(&text).with_context(|| format!("无法解析标注文件 {}", path.display()))?;
+  label
+    .get("x")
+    .and_then(serde_json::Value::as_u64)
+    .and_then(|x| u32::try_from(x).ok())
+    .with_context(|| format!("标注文件 {} 中没有非负整数字段 x", path.display()))
+}
+
+// 对一个样本执行匹配
+fn match_sample(
+  dir: &Path,
+  name: &str,
+  algorithm: DatasetAlgorithm,
+  config: &SlideMatchConfig,
+) -> anyhow::Result<SlideBBox> {
+  let target_path = dir.join(format!("{name}{TARGET_SUFFIX}"));
+  let background_path = dir.join(format!("{name}{BACKGROUND_SUFFIX}"));
+  let target = std::fs::read(&target_path)
+    .with_context(|| format!("无法读取目标图片 {}", target_path.display()))?;
+  let background = std::fs::read(&background_path)
+    .with_context(|| format!("无法读取背景图片 {}", background_path.display()))?;
+
+  match algorithm {
+    DatasetAlgorithm::SlideMatch => slide_match_internal(&target, &background, None, config),
+    DatasetAlgorithm::ImprovedSlideMatch => {
+      improved_slide_match_internal(&target, &background, IMPROVED_CONFIDENCE_THRESHOLD, config)
+    }
+    DatasetAlgorithm::SimpleSlideMatch => simple_slide_match_internal(&target, &background, config),
+    DatasetAlgorithm::ImprovedSimpleSlideMatch => improved_simple_slide_match_internal(
+      &target,
+      &background,
+      IMPROVED_CONFIDENCE_THRESHOLD,
+      config,
+    ),
+  }
+}
+
+// 评估一个样本，通过时返回 None
+fn evaluate_sample(
+  dir: &Path,
+  name: &str,
+  tolerance_px: u32,
+  algorithm: DatasetAlgorithm,
+  config: &SlideMatchConfig,
+) -> Option<DatasetFailure> {
+  let failure = |expected_x, error: anyhow::Error| DatasetFailure {
+    name: name.to_string(),
+    expected_x,
+    got_x: None,
+    confidence: None,
+    error: Some(format!("{error:#}")),
+  };
+
+  let expected_x = match read_label(&dir.join(format!("{name}{LABEL_SUFFIX}"))) {
+    Ok(x) => x,
+    Err(e) => return Some(failure(None, e)),
+  };
+  let bbox = match match_sample(dir, name, algorithm, config) {
+    Ok(bbox) => bbox,
+    Err(e) => return Some(failure(Some(expected_x), e)),
+  };
+
+  (bbox.x1.abs_diff(expected_x) > tolerance_px).then(|| DatasetFailure {
+    name: name.to_string(),
+    expected_x: Some(expected_x),
+    got_x: Some(bbox.x1),
+    confidence: bbox.confidence,
+    error: None,
+  })
+}
+
+/// 在带标注的验证码目录上评估匹配准确率，`evaluateDataset` 的 Rust 实现
+///
+/// 目录中每个样本由 `NNN_target.png`、`NNN_bg.png` 和 `NNN.json` 三个文件组成，`NNN.json` 为
+/// `{ "x": 123 }` 形式的标注。只要出现其中任意一个文件就算作一个样本，文件缺失、无法读取或匹配失败的样本
+/// 计入 `failures` 而不中断评估。`slideMatch`、`simpleSlideMatch` 未设置 `confidenceThreshold` 时以 0 为阈值，
+/// 保证结果带有得分。
+pub fn evaluate_dataset_internal(
+  dir: &str,
+  options: &EvaluateDatasetOptions,
+  config: &SlideMatchConfig,
+) -> anyhow::Result<DatasetEvaluation> {
+  let dir = Path::new(dir);
+  let entries =
+    std::fs::read_dir(dir).with_context(|| format!("无法读取目录 {}", dir.display()))?;
+
+  let mut names = BTreeSet::new();
+  for entry in entries {
+    let file_name = entry
+      .with_context(|| format!("无法读取目录 {}", dir.display()))?
+      .file_name();
+    let Some(file_name) = file_name.to_str() else {
+      continue;
+    };
+    let name = [TARGET_SUFFIX, BACKGROUND_SUFFIX, LABEL_SUFFIX]
+      .iter()
+      .find_map(|suffix| file_name.strip_suffix(suffix))
+      .filter(|name| !name.is_empty());
+    if let Some(name) = name {
+      names.insert(name.to_string());
+    }
+  }
+
+  let tolerance_px = options.tolerance_px.unwrap_or(DEFAULT_TOLERANCE_PX);
+  let algorithm = options.algorithm.unwrap_or_default();
+  let config = &SlideMatchConfig {
+    confidence_threshold: config.confidence_threshold.or(Some(0.0)),
+    ..config.clone()
+  };
+
+  let names: Vec<_> = names.into_iter().collect();
+  let failures: Vec<_> = names
+    .par_iter()
+    .filter_map(|name| evaluate_sample(dir, name, tolerance_px, algorithm, config))
+    .collect();
+
+  let total = names.len() as u32;
+  let correct = total - failures.len() as u32;
+  Ok(DatasetEvaluation {
+    total,
+    correct,
+    accuracy: if total == 0 {
+      0.0
+    } else {
+      correct as f64 / total as f64
+    },
+    failures,
+  })
+}
+
+/// 在带标注的验证码目录上评估匹配准确率，用于在调整算法或参数后做回归测试
+///
+/// 目录中每个样本由 `NNN_target.png`、`NNN_bg.png` 和 `NNN.json`（`{ "x": 123 }`）组成，
+/// 匹配结果的 `x1` 与标注的 x 相差不超过 `tolerancePx` 时视为正确。单个样本出错时记入 `failures`，不中断评估。
+///
+/// # 参数
+/// - dir: 数据集目录
+/// - options: 可选，评估选项
+/// - config: 可选配置，见 `SlideMatchConfig`
+#[napi]
+pub fn evaluate_dataset(
+  dir: String,
+  options: Option<EvaluateDatasetOptions>,
+  config: Option<SlideMatchConfig>,
+) -> Result<DatasetEvaluation, ErrorStatus> {
+  evaluate_dataset_internal(
+    &dir,
+    &options.unwrap_or_default(),
+    &config.unwrap_or_default(),
+  )
+  .map_err(|e| napi_error(&e, format!("数据集评估失败: {e:#}")))
+}
@@ -0,0 +1,363 @@
+//! SIMD 加速的灰度转换（`simd` 特性）
+//!
+//! x86-64（运行时检测到 AVX2）和 aarch64（NEON）上每次处理 8 个像素，其他平台或 CPU 直接调用 image 库。
+//! 两个函数分别替代 image 库的 `imageops::grayscale` 和 `DynamicImage::to_luma8`，结果与被替代的函数
+//! 逐字节一致，启用该特性不会改变匹配结果。两者都使用 sRGB（Rec.709）的亮度权重，但舍入方式不同：
+//! 前者整数加权后向下取整，后者按浮点计算后四舍五入。
+
+use image::metadata::CicpColorPrimaries;
+use image::{DynamicImage, GrayImage, RgbaImage};
+
+// 与 `imageops::grayscale` 相同的整数权重：L = (2126 R + 7152 G + 722 B) / 10000（向下取整）
+const R_WEIGHT: u32 = 2126;
+const G_WEIGHT: u32 = 7152;
+const B_WEIGHT: u32 = 722;
+const WEIGHT_DIV: u32 = 10000;
+// `to_luma8` 对 sRGB 图片使用的浮点权重：image 库由 BT.709 原色和 D65 白点推导出的 XYZ 矩阵 Y 行，
+// 与四位小数的 0.2126 / 0.7152 / 0.0722 略有差异
+const SRGB_LUMA: [f32; 3] = [0.212_647_84, 0.715_179_2, 0.072_173_04];
+// 与 image 库相同，先乘以 1/255 归一化
+const INV_255: f32 = 1.0 / 255.0;
+// SIMD 每次处理的像素数
+const LANES: usize = 8;
+
+// 单个像素的灰度值（整数权重，向下取整），用于 SIMD 处理不完的尾部像素
+#[inline]
+fn luma(r: u8, g: u8, b: u8) -> u8 {
+  ((R_WEIGHT * r as u32 + G_WEIGHT * g as u32 + B_WEIGHT * b as u32) / WEIGHT_DIV) as u8
+}
+
+// 与 image 库相同：编译目标启用了 FMA 时用融合乘加，否则分别乘、加
+#[cfg(any(
+  all(
+    any(target_arch = "x86", target_arch = "x86_64"),
+    target_feature = "fma"
+  ),
+  all(target_arch = "aarch64", target_feature = "neon")
+))]
+#[inline]
+fn multiply_accumulate(acc: f32, a: f32, b: f32) -> f32 {
+  a.mul_add(b, acc)
+}
+
+#[cfg(not(any(
+  all(
+    any(target_arch = "x86", target_arch = "x86_64"),
+    target_feature = "fma"
+  ),
+  all(target_arch = "aarch64", target_feature = "neon")
+)))]
+#[inline]
+fn multiply_accumulate(acc: f32, a: f32, b: f32) -> f32 {
+  acc + a * b
+}
+
+// 单个像素的灰度值（浮点权重，四舍五入），运算顺序与 `to_luma8` 相同，用于 SIMD 处理不完的尾部像素
+#[inline]
+fn luma_rounded(r: u8, g: u8, b: u8) -> u8 {
+  let mut luma = 0.0;
+  for (value, weight) in [r, g, b].into_iter().zip(SRGB_LUMA) {
+    luma = multiply_accumulate(luma, value as f32 * INV_255, weight);
+  }
+  // 与 image 库相同，`as` 转换超出 255 时饱和
+  (luma * 255.0).round() as u8
+}
+
+/// RGBA 图片转换为灰度图，结果与 `image::imageops::grayscale` 逐字节一致（忽略透明度）
+pub fn rgba_to_luma(img: &RgbaImage) -> GrayImage {
+  to_luma::<4>(img.as_raw(), img.width(), img.height())
+    .unwrap_or_else(|| image::imageops::grayscale(img))
+}
+
+/// 解码后的图片转换为灰度图，结果与 `DynamicImage::to_luma8` 逐字节一致
+///
+/// 只加速 sRGB 色彩空间（解码得到的图片默认如此）的 8 位 RGB/RGBA 图片，其他图片直接调用 `to_luma8`。
+pub fn to_luma8(img: &DynamicImage) -> GrayImage {
+  let srgb = img.color_space().primaries == CicpColorPrimaries::SRgb;
+  let converted = match img {
+    DynamicImage::ImageRgb8(rgb) if srgb => {
+      to_luma_rounded::<3>(rgb.as_raw(), rgb.width(), rgb.height())
+    }
+    DynamicImage::ImageRgba8(rgba) if srgb => {
+      to_luma_rounded::<4>(rgba.as_raw(), rgba.width(), rgba.height())
+    }
+    _ => None,
+  };
+  converted.unwrap_or_else(|| img.to_luma8())
+}
+
+// `CHANNELS` 为每个像素的字节数（3 或 4），前三个字节为 R、G、B；不支持 SIMD 时返回 None
+fn to_luma<const CHANNELS: usize>(pixels: &[u8], width: u32, height: u32) -> Option<GrayImage> {
+  if !simd::available() {
+    return None;
+  }
+
+  let mut output = vec![0u8; pixels.len() / CHANNELS];
+  // SAFETY: 已确认当前 CPU 支持对应的指令集
+  let done = unsafe { simd::convert::<CHANNELS>(pixels, &mut output) };
+
+  for (out, pixel) in output[done..]
+    .iter_mut()
+    .zip(pixels[done * CHANNELS..].chunks_exact(CHANNELS))
+  {
+    *out = luma(pixel[0], pixel[1], pixel[2]);
+  }
+
+  GrayImage::from_raw(width, height, output)
+}
+
+// 同 `to_luma`，按 `to_luma8` 的浮点权重计算并四舍五入
+fn to_luma_rounded<const CHANNELS: usize>(
+  pixels: &[u8],
+  width: u32,
+  height: u32,
+) -> Option<GrayImage> {
+  if !simd::available() {
+    return None;
+  }
+
+  let mut output = vec![0u8; pixels.len() / CHANNELS];
+  // SAFETY: 已确认当前 CPU 支持对应的指令集
+  let done = unsafe { simd::convert_rounded::<CHANNELS>(pixels, &mut output) };
+
+  for (out, pixel) in output[done..]
+    .iter_mut()
+    .zip(pixels[done * CHANNELS..].chunks_exact(CHANNELS))
+  {
+    *out = luma_rounded(pixel[0], pixel[1], pixel[2]);
+  }
+
+  GrayImage::from_raw(width, height, output)
+}
+
+#[cfg(target_arch = "x86_64")]
+mod simd {
+  use std::arch::x86_64::*;
+
+  use super::{B_WEIGHT, G_WEIGHT, INV_255, LANES, R_WEIGHT, SRGB_LUMA, WEIGHT_DIV};
+
+  pub(super) fn available() -> bool {
+    is_x86_feature_detected!("avx2")
+  }
+
+  // 收集 8 个像素，每个像素的 4 个字节放到一个 32 位整数中，返回 R、G、B 三个通道
+  #[target_feature(enable = "avx2")]
+  unsafe fn gather<const CHANNELS: usize>(pixels: *const u8) -> [__m256i; 3] {
+    let step = CHANNELS as i32;
+    let offsets = _mm256_setr_epi32(
+      0,
+      step,
+      2 * step,
+      3 * step,
+      4 * step,
+      5 * step,
+      6 * step,
+      7 * step,
+    );
+    let byte_mask = _mm256_set1_epi32(0xFF);
+    let values = _mm256_i32gather_epi32::<1>(pixels as *const i32, offsets);
+
+    [
+      _mm256_and_si256(values, byte_mask),
+      _mm256_and_si256(_mm256_srli_epi32::<8>(values), byte_mask),
+      _mm256_and_si256(_mm256_srli_epi32::<16>(values), byte_mask),
+    ]
+  }
+
+  // 8 个 32 位整数写入输出（调用方保证取值在 0-255 内）
+  #[target_feature(enable = "avx2")]
+  unsafe fn store(values: __m256i, output: &mut [u8]) {
+    let mut lanes = [0i32; LANES];
+    _mm256_storeu_si256(lanes.as_mut_ptr() as *mut __m256i, values);
+    for (out, &lane) in output.iter_mut().zip(&lanes) {
+      *out = lane as u8;
+    }
+  }
+
+  // 每个像素读取 4 个字节，RGB 图片的最后一个像素会多读 1 个字节；返回能安全处理的像素数上限
+  fn safe_pixels<const CHANNELS: usize>(pixels: &[u8]) -> usize {
+    pixels.len().saturating_sub(4 - CHANNELS) / CHANNELS
+  }
+
+  // 每次处理 8 个像素：整数加权求和，再用单精度浮点除以 10000 并截断
+  // （和不超过 2^22，除法结果不会越过整数边界），返回已转换的像素数
+  #[target_feature(enable = "avx2")]
+  pub(super) unsafe fn convert<const CHANNELS: usize>(pixels: &[u8], output: &mut [u8]) -> usize {
+    let r_weight = _mm256_set1_epi32(R_WEIGHT as i32);
+    let g_weight = _mm256_set1_epi32(G_WEIGHT as i32);
+    let b_weight = _mm256_set1_epi32(B_WEIGHT as i32);
+    let divisor = _mm256_set1_ps(WEIGHT_DIV as f32);
+    let limit = safe_pixels::<CHANNELS>(pixels);
+
+    let mut done = 0;
+    while done + LANES <= limit {
+      let [r, g, b] = gather::<CHANNELS>(pixels.as_ptr().add(done * CHANNELS));
+      let sum = _mm256_add_epi32(
+        _mm256_add_epi32(
+          _mm256_mullo_epi32(r, r_weight),
+          _mm256_mullo_epi32(g, g_weight),
+        ),
+        _mm256_mullo_epi32(b, b_weight),
+      );
+      let quotient = _mm256_cvttps_epi32(_mm256_div_ps(_mm256_cvtepi32_ps(sum), divisor));
+
+      store(quotient, &mut output[done..done + LANES]);
+      done += LANES;
+    }
+
+    done
+  }
+
+  // `acc + a × b`，与 image 库一样只在编译目标启用 FMA 时使用融合乘加
+  #[target_feature(enable = "avx2")]
+  unsafe fn multiply_accumulate(acc: __m256, a: __m256, b: __m256) -> __m256 {
+    #[cfg(target_feature = "fma")]
+    return _mm256_fmadd_ps(a, b, acc);
+    #[cfg(not(target_feature = "fma"))]
+    return _mm256_add_ps(acc, _mm256_mul_ps(a, b));
+  }
+
+  // 每次处理 8 个像素：归一化到 0-1 后按 `luma_rounded` 的顺序加权求和，乘以 255 后四舍五入
+  // （`f32::round` 的 0.5 远离 0 舍入：截断后小数部分不小于 0.5 时加 1），返回已转换的像素数
+  #[target_feature(enable = "avx2")]
+  pub(super) unsafe fn convert_rounded<const CHANNELS: usize>(
+    pixels: &[u8],
+    output: &mut [u8],
+  ) -> usize {
+    let scale = _mm256_set1_ps(INV_255);
+    let weights = [
+      _mm256_set1_ps(SRGB_LUMA[0]),
+      _mm256_set1_ps(SRGB_LUMA[1]),
+      _mm256_set1_ps(SRGB_LUMA[2]),
+    ];
+    let max = _mm256_set1_ps(255.0);
+    let half = _mm256_set1_ps(0.5);
+    let one = _mm256_set1_ps(1.0);
+    let limit = safe_pixels::<CHANNELS>(pixels);
+
+    let mut done = 0;
+    while done + LANES <= limit {
+      let channels = gather::<CHANNELS>(pixels.as_ptr().add(done * CHANNELS));
+      let mut luma = _mm256_setzero_ps();
+      for (channel, weight) in channels.into_iter().zip(weights) {
+        let value = _mm256_mul_ps(_mm256_cvtepi32_ps(channel), scale);
+        luma = multiply_accumulate(luma, value, weight);
+      }
+
+      let scaled = _mm256_mul_ps(luma, max);
+      let truncated = _mm256_round_ps::<{ _MM_FROUND_TO_ZERO | _MM_FROUND_NO_EXC }>(scaled);
+      let round_up = _mm256_cmp_ps::<_CMP_GE_OQ>(_mm256_sub_ps(scaled, truncated), half);
+      let rounded = _mm256_add_ps(truncated, _mm256_and_ps(round_up, one));
+      let clamped = _mm256_min_epi32(_mm256_cvttps_epi32(rounded), _mm256_set1_epi32(255));
+
+      store(clamped, &mut output[done..done + LANES]);
+      done += LANES;
+    }
+
+    done
+  }
+}
+
+#[cfg(target_arch = "aarch64")]
+mod simd {
+  use std::arch::aarch64::*;
+
+  use super::{B_WEIGHT, G_WEIGHT, INV_255, LANES, R_WEIGHT, SRGB_LUMA, WEIGHT_DIV};
+
+  // aarch64 目标默认启用 NEON
+  pub(super) fn available() -> bool {
+    true
+  }
+
+  // 读取 8 个像素，按通道拆分并扩展到 16 位，返回 R、G、B 三个通道
+  unsafe fn load<const CHANNELS: usize>(pixels: *const u8) -> [uint16x8_t; 3] {
+    let (r, g, b) = if CHANNELS == 4 {
+      let v = vld4_u8(pixels);
+      (v.0, v.1, v.2)
+    } else {
+      let v = vld3_u8(pixels);
+      (v.0, v.1, v.2)
+    };
+    [vmovl_u8(r), vmovl_u8(g), vmovl_u8(b)]
+  }
+
+  // 每次处理 8 个像素：vld3/vld4 按通道拆分后扩展到 32 位整数加权求和，
+  // 再用单精度浮点除以 10000 并截断，返回已转换的像素数
+  pub(super) unsafe fn convert<const CHANNELS: usize>(pixels: &[u8], output: &mut [u8]) -> usize {
+    let divisor = vdupq_n_f32(WEIGHT_DIV as f32);
+    let r_weight = vdup_n_u16(R_WEIGHT as u16);
+    let g_weight = vdup_n_u16(G_WEIGHT as u16);
+    let b_weight = vdup_n_u16(B_WEIGHT as u16);
+
+    let weighted_luma = |r: uint16x4_t, g: uint16x4_t, b: uint16x4_t| {
+      let sum = vmlal_u16(vmlal_u16(vmull_u16(r, r_weight), g, g_weight), b, b_weight);
+      vmovn_u32(vcvtq_u32_f32(vdivq_f32(vcvtq_f32_u32(sum), divisor)))
+    };
+
+    let mut done = 0;
+    while done + LANES <= output.len() {
+      let [r, g, b] = load::<CHANNELS>(pixels.as_ptr().add(done * CHANNELS));
+      let low = weighted_luma(vget_low_u16(r), vget_low_u16(g), vget_low_u16(b));
+      let high = weighted_luma(vget_high_u16(r), vget_high_u16(g), vget_high_u16(b));
+      vst1_u8(
+        output.as_mut_ptr().add(done),
+        vmovn_u16(vcombine_u16(low, high)),
+      );
+      done += LANES;
+    }
+
+    done
+  }
+
+  // 每次处理 8 个像素：归一化到 0-1 后按 `luma_rounded` 的顺序融合乘加（aarch64 上 image 库同样使用 FMA），
+  // 乘以 255 后用 vrndaq（0.5 远离 0 舍入，与 `f32::round` 相同）取整，返回已转换的像素数
+  pub(super) unsafe fn convert_rounded<const CHANNELS: usize>(
+    pixels: &[u8],
+    output: &mut [u8],
+  ) -> usize {
+    let [r_weight, g_weight, b_weight] = SRGB_LUMA.map(|weight| vdupq_n_f32(weight));
+    let max = vdupq_n_u32(255);
+
+    let weighted_luma = |r: uint16x4_t, g: uint16x4_t, b: uint16x4_t| {
+      let normalize = |value: uint16x4_t| vmulq_n_f32(vcvtq_f32_u32(vmovl_u16(value)), INV_255);
+      let luma = vmulq_f32(normalize(r), r_weight);
+      let luma = vfmaq_f32(luma, normalize(g), g_weight);
+      let luma = vfmaq_f32(luma, normalize(b), b_weight);
+      let rounded = vcvtq_u32_f32(vrndaq_f32(vmulq_n_f32(luma, 255.0)));
+      vmovn_u32(vminq_u32(rounded, max))
+    };
+
+    let mut done = 0;
+    while done + LANES <= output.len() {
+      let [r, g, b] = load::<CHANNELS>(pixels.as_ptr().add(done * CHANNELS));
+      let low = weighted_luma(vget_low_u16(r), vget_low_u16(g), vget_low_u16(b));
+      let high = weighted_luma(vget_high_u16(r), vget_high_u16(g), vget_high_u16(b));
+      vst1_u8(
+        output.as_mut_ptr().add(done),
+        vmovn_u16(vcombine_u16(low, high)),
+      );
+      done += LANES;
+    }
+
+    done
+  }
+}
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+mod simd {
+  // 其他平台不做 SIMD 加速
+  pub(super) fn available() -> bool {
+    false
+  }
+
+  pub(super) unsafe fn convert<const CHANNELS: usize>(_pixels: &[u8], _output: &mut [u8]) -> usize {
+    0
+  }
+
+  pub(super) unsafe fn convert_rounded<const CHANNELS: usize>(
+    _pixels: &[u8],
+    _output: &mut [u8],
+  ) -> usize {
+    0
+  }
+}
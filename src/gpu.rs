@@ -0,0 +1,210 @@
+use std::sync::mpsc;
+use std::sync::OnceLock;
+
+use image::{GrayImage, Luma};
+use imageproc::definitions::Image;
+use wgpu::util::DeviceExt;
+
+// 计算着色器每个工作组的边长，与 ncc.wgsl 中的 `workgroup_size` 一致
+const WORKGROUP_SIZE: u32 = 16;
+// 着色器用 u32 累加互相关和窗口平方和，模板像素数不能超过该值
+const MAX_TEMPLATE_PIXELS: u64 = u32::MAX as u64 / (255 * 255);
+
+// 缓存的 GPU 设备和计算管线，进程内只初始化一次
+struct GpuContext {
+  device: wgpu::Device,
+  queue: wgpu::Queue,
+  pipeline: wgpu::ComputePipeline,
+}
+
+impl GpuContext {
+  fn new() -> anyhow::Result<Self> {
+    let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+    let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+      power_preference: wgpu::PowerPreference::HighPerformance,
+      ..Default::default()
+    }))?;
+    let (device, queue) = pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor {
+      label: Some("slide_match"),
+      required_limits: adapter.limits(),
+      ..Default::default()
+    }))?;
+
+    let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+      label: Some("ncc"),
+      source: wgpu::ShaderSource::Wgsl(include_str!("ncc.wgsl").into()),
+    });
+    let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+      label: Some("ncc"),
+      layout: None,
+      module: &module,
+      entry_point: Some("main"),
+      compilation_options: Default::default(),
+      cache: None,
+    });
+
+    Ok(Self {
+      device,
+      queue,
+      pipeline,
+    })
+  }
+
+  // 图片和得分图是否都能放进单个存储缓冲区
+  fn fits(&self, image: &GrayImage) -> bool {
+    let limit = self.device.limits().max_storage_buffer_binding_size as u64;
+    image.width() as u64 * image.height() as u64 * 4 <= limit
+  }
+
+  fn match_template(&self, image: &GrayImage, template: &GrayImage) -> anyhow::Result<Vec<f32>> {
+    let out_width = image.width() - template.width() + 1;
+    let out_height = image.height() - template.height() + 1;
+    let template_squared_sum: u64 = template.iter().map(|&t| t as u64 * t as u64).sum();
+
+    let mut params = Vec::with_capacity(32);
+    for value in [
+      image.width(),
+      out_width,
+      out_height,
+      template.width(),
+      template.height(),
+      (template_squared_sum as f32).to_bits(),
+      0,
+      0,
+    ] {
+      params.extend_from_slice(&value.to_le_bytes());
+    }
+
+    let device = &self.device;
+    let storage = |label, pixels: &GrayImage| {
+      let contents: Vec<u8> = pixels
+        .iter()
+        .flat_map(|&value| (value as u32).to_le_bytes())
+        .collect();
+      device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some(label),
+        contents: &contents,
+        usage: wgpu::BufferUsages::STORAGE,
+      })
+    };
+    let params = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+      label: Some("params"),
+      contents: &params,
+      usage: wgpu::BufferUsages::UNIFORM,
+    });
+    let image_buffer = storage("image", image);
+    let template_buffer = storage("template", template);
+
+    let size = out_width as u64 * out_height as u64 * 4;
+    let scores = device.create_buffer(&wgpu::BufferDescriptor {
+      label: Some("scores"),
+      size,
+      usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+      mapped_at_creation: false,
+    });
+    let readback = device.create_buffer(&wgpu::BufferDescriptor {
+      label: Some("readback"),
+      size,
+      usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+      mapped_at_creation: false,
+    });
+
+    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+      label: Some("ncc"),
+      layout: &self.pipeline.get_bind_group_layout(0),
+      entries: &[
+        wgpu::BindGroupEntry {
+          binding: 0,
+          resource: params.as_entire_binding(),
+        },
+        wgpu::BindGroupEntry {
+          binding: 1,
+          resource: image_buffer.as_entire_binding(),
+        },
+        wgpu::BindGroupEntry {
+          binding: 2,
+          resource: template_buffer.as_entire_binding(),
+        },
+        wgpu::BindGroupEntry {
+          binding: 3,
+          resource: scores.as_entire_binding(),
+        },
+      ],
+    });
+
+    let mut encoder = device.create_command_encoder(&Default::default());
+    {
+      let mut pass = encoder.begin_compute_pass(&Default::default());
+      pass.set_pipeline(&self.pipeline);
+      pass.set_bind_group(0, &bind_group, &[]);
+      pass.dispatch_workgroups(
+        out_width.div_ceil(WORKGROUP_SIZE),
+        out_height.div_ceil(WORKGROUP_SIZE),
+        1,
+      );
+    }
+    encoder.copy_buffer_to_buffer(&scores, 0, &readback, 0, size);
+    self.queue.submit([encoder.finish()]);
+
+    let (sender, receiver) = mpsc::channel();
+    readback
+      .slice(..)
+      .map_async(wgpu::MapMode::Read, move |result| {
+        let _ = sender.send(result);
+      });
+    device.poll(wgpu::PollType::Wait)?;
+    receiver.recv()??;
+
+    let data = readback
+      .slice(..)
+      .get_mapped_range()
+      .chunks_exact(4)
+      .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+      .collect();
+    readback.unmap();
+    Ok(data)
+  }
+}
+
+// 首次调用时初始化 GPU，失败时输出一次警告，之后始终返回 None
+fn context() -> Option<&'static GpuContext> {
+  static CONTEXT: OnceLock<Option<GpuContext>> = OnceLock::new();
+  CONTEXT
+    .get_or_init(|| match GpuContext::new() {
+      Ok(context) => Some(context),
+      Err(e) => {
+        tracing::warn!("GPU 初始化失败，回退到 CPU 计算: {e:#}");
+        None
+      }
+    })
+    .as_ref()
+}
+
+/// 在 GPU 上计算归一化互相关得分图，与 [`crate::ncc::match_template_normalized`] 的结果
+/// 相差在 1e-4 以内
+///
+/// GPU 不可用、模板过大（像素数超过 u32 累加的上限）、图片超出缓冲区大小限制或计算出错时返回 None，
+/// 由调用方回退到 CPU。调用方需保证模板不大于图片。
+pub(crate) fn match_template_normalized(
+  image: &GrayImage,
+  template: &GrayImage,
+) -> Option<Image<Luma<f32>>> {
+  let context = context()?;
+  if template.width() as u64 * template.height() as u64 > MAX_TEMPLATE_PIXELS
+    || !context.fits(image)
+  {
+    return None;
+  }
+
+  // 计算出错时只输出一次警告，避免每次匹配都刷屏
+  static WARNING: std::sync::Once = std::sync::Once::new();
+  let data = context
+    .match_template(image, template)
+    .map_err(|e| WARNING.call_once(|| tracing::warn!("GPU 模板匹配失败，回退到 CPU 计算: {e:#}")))
+    .ok()?;
+  Image::from_raw(
+    image.width() - template.width() + 1,
+    image.height() - template.height() + 1,
+    data,
+  )
+}
@@ -1,10 +1,83 @@
+//! 基于 Canny 边缘检测和归一化互相关的滑块验证码缺口匹配
+//!
+//! # 图片格式
+//!
+//! 图片由 `image` 库解码，格式由文件头自动识别：
+//!
+//! | 格式 | 默认构建 | 说明 |
+//! |------|----------|------|
+//! | PNG  | ✅ | 支持透明通道 |
+//! | JPEG | ✅ | |
+//! | WebP | ✅ | |
+//! | BMP  | ✅ | |
+//! | TIFF | ✅ | |
+//! | AVIF | ❌ | `image` 默认只包含 AVIF 编码器，解码需要其 `avif-native` 特性和系统的 libdav1d |
+//!
+//! GIF、ICO、HDR、EXR、QOI、PNM、DDS 同样默认支持，TGA 无法从文件头识别，不支持。
+//! 传入 AVIF 图片时返回 [`error::SlideMatchError::UnsupportedFormat`]。
+//! 本库没有提供转发到 `image/avif-native` 的 cargo 特性：该特性会让所有构建都解析 `dav1d` 等依赖，
+//! 启用后每个预编译目标平台（含 Windows、musl 和交叉编译目标）还需要系统安装 libdav1d。
 #![deny(clippy::all)]
+// 丢弃 `Result` 等必须使用的返回值视为错误，避免忽略匹配或解码失败
+#![deny(unused_must_use)]
+
+use std::borrow::Cow;
+use std::time::Instant;
 
-use anyhow::Context;
 use image::GrayImage;
 use napi::bindgen_prelude::*;
 use napi_derive::napi;
 
+use crate::embedded::PieceRegion;
+use crate::error::{generic_error, napi_error, ErrorStatus, SlideMatchError};
+use crate::preprocess::MatchImage;
+
+pub mod backend;
+pub mod benchmark;
+pub mod cache;
+mod chamfer;
+pub mod comparison;
+mod contour;
+mod coordinates;
+pub mod crop;
+pub mod cross_check;
+mod decode;
+pub mod difference;
+pub mod double;
+pub mod drag;
+pub mod edges;
+pub mod embedded;
+pub mod error;
+pub mod evaluate;
+#[cfg(feature = "simd")]
+pub mod fast_grayscale;
+#[cfg(feature = "gpu")]
+mod gpu;
+#[cfg(feature = "mmap")]
+pub mod mmap;
+pub mod multi;
+pub mod multi_target;
+pub mod ncc;
+mod orientation;
+mod oversize;
+pub mod pipeline;
+pub mod preprocess;
+pub mod quality;
+pub mod raw;
+pub mod rotation;
+pub mod score_matrix;
+pub mod shape;
+pub mod shuffle;
+pub mod stats;
+pub mod task;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
+// `benchmark` 使用其中的合成图片，未启用 `test-utils` 特性时不公开
+#[cfg(not(any(test, feature = "test-utils")))]
+#[allow(dead_code)]
+mod test_utils;
+pub mod try_match;
+
 // 定义 SlideBBox 结构体
 #[napi(object)]
 #[derive(Debug, Clone)]
@@ -15,387 +88,2605 @@ pub struct SlideBBox {
   pub y1: u32,
   pub x2: u32,
   pub y2: u32,
+  /// 匹配得分（归一化互相关的最大值），改进版函数及设置了 `confidenceThreshold` 的原版函数返回
+  pub confidence: Option<f64>,
+  /// 匹配过程的诊断信息，仅改进版函数返回
+  pub metadata: Option<MatchMetadata>,
+  /// 每个候选 x（原始尺寸像素，下标即 `x1`）上所有 y 的最大匹配得分，
+  /// 长度为 `背景宽度 - 目标宽度 + 1`；`axis` 为 `y` 时为每个候选 y 上所有 x 的最大匹配得分，
+  /// 长度为 `背景高度 - 目标高度 + 1`。仅在 `returnScoreProfile` 为 true 时返回
+  pub score_profile: Option<Vec<f64>>,
+  /// 完整的匹配得分图，仅在 `returnHeatmap` 为 true 时返回
+  pub heatmap: Option<ScoreHeatmap>,
+  /// `x1/y1` 的含义（见 `SlideMatchConfig.coordinateMode`），接受 `SlideMatchConfig` 的匹配函数返回
+  pub coordinate_mode: Option<CoordinateMode>,
+  /// 以图片尺寸比例表示的坐标，仅在 `coordinateUnit` 为 `ratio` 时返回
+  pub ratio: Option<RatioCoordinates>,
+  /// 目标图片与返回坐标所在的背景图尺度不一致时（按 `oversizePolicy` 缩放，或 `backgroundTargetWidth`
+  /// 配合 `natural` 坐标系），目标图片相对背景图片的缩放比例（目标图片像素 × `targetScale` = 背景图片像素），
+  /// 未缩放时不返回
+  pub target_scale: Option<f64>,
+  /// 次优峰值的得分是否与最优峰值相差不到 `ambiguityMargin`（匹配结果接近二选一），
+  /// 仅在设置了 `ambiguityMargin` 时返回
+  pub is_ambiguous: Option<bool>,
+  /// 次优峰值（与最优匹配区域不重叠的位置中的最高得分）的得分，仅在设置了 `ambiguityMargin` 时返回
+  pub second_best_score: Option<f64>,
+  /// 次优峰值的 `x1`（与 `x1` 同一坐标系），仅在设置了 `ambiguityMargin` 时返回
+  pub second_best_x: Option<u32>,
+  /// 峰值旁瓣比（PSR）：(峰值 - 旁瓣均值) / 旁瓣标准差，旁瓣为得分图中以峰值为中心的 11x11 窗口之外的位置；
+  /// 比原始得分更适合跨背景比较匹配质量，仅在 `returnPsr` 为 true 时返回，没有旁瓣或旁瓣得分全部相同时不返回
+  pub psr: Option<f64>,
+  /// 沿 `axis` 方向的滑动距离：`x1 - targetX`，`axis` 为 `y` 时为 `y1 - targetY`；与 `x1` 同一尺度，
+  /// 按左上角计算、不受 `coordinateMode` 和 `coordinateSpace` 影响，可能为负。仅在设置了 `axis` 时返回
+  pub slide_distance: Option<i32>,
+  /// 目标图片的不透明像素少于 `minOpaquePixels`、因 `allowDegenerateTarget` 继续匹配时为 true，
+  /// 此时结果没有意义；其他情况不返回
+  pub degenerate_target: Option<bool>,
+  /// 匹配耗时（毫秒，从开始解码图片到得到结果），仅在 `measureTime` 为 true 时返回
+  pub elapsed_ms: Option<f64>,
+}
+
+impl SlideBBox {
+  /// 由中心点和尺寸构造，`x1 = cx - w / 2`、`x2 = cx + w / 2`（y 方向同理，整数除法，
+  /// 不小于 0，也不超过 `u32::MAX`）
+  ///
+  /// ```
+  /// use slide_match::SlideBBox;
+  ///
+  /// let bbox = SlideBBox::from_center(u32::MAX, 10, 40, 30);
+  /// assert_eq!((bbox.x1, bbox.y1, bbox.x2, bbox.y2), (u32::MAX - 20, 0, u32::MAX, 25));
+  /// ```
+  #[must_use]
+  pub fn from_center(cx: u32, cy: u32, w: u32, h: u32) -> Self {
+    Self {
+      target_x: 0,
+      target_y: 0,
+      x1: cx.saturating_sub(w / 2),
+      y1: cy.saturating_sub(h / 2),
+      x2: cx.saturating_add(w / 2),
+      y2: cy.saturating_add(h / 2),
+      confidence: None,
+      metadata: None,
+      score_profile: None,
+      heatmap: None,
+      coordinate_mode: None,
+      ratio: None,
+      target_scale: None,
+      is_ambiguous: None,
+      second_best_score: None,
+      second_best_x: None,
+      psr: None,
+      slide_distance: None,
+      degenerate_target: None,
+      elapsed_ms: None,
+    }
+  }
+
+  /// 中心点 x 坐标
+  pub fn center_x(&self) -> u32 {
+    match self.coordinate_mode {
+      Some(CoordinateMode::Center) => self.x1,
+      _ => (self.x1 + self.x2) / 2,
+    }
+  }
+
+  /// 中心点 y 坐标
+  pub fn center_y(&self) -> u32 {
+    match self.coordinate_mode {
+      Some(CoordinateMode::Center) => self.y1,
+      _ => (self.y1 + self.y2) / 2,
+    }
+  }
+
+  /// 转换为有符号坐标，便于调用方平移到原点不在左上角的坐标系（结果可能为负）
+  #[must_use]
+  pub fn to_signed(&self) -> SlideBBoxSigned {
+    // 图片尺寸受 `maxImagePixels` 限制，坐标远小于 i32::MAX
+    SlideBBoxSigned {
+      target_x: self.target_x as i32,
+      target_y: self.target_y as i32,
+      x1: self.x1 as i32,
+      y1: self.y1 as i32,
+      x2: self.x2 as i32,
+      y2: self.y2 as i32,
+      confidence: self.confidence,
+    }
+  }
+
+  /// 同时包含两个匹配区域的最小矩形
+  ///
+  /// 按 `x1/y1/x2/y2` 围成的矩形计算，`coordinateMode` 为 `center` 的结果需先换算回左上角。
+  /// `target_x/target_y` 和 `confidence` 取自得分较高的一个（没有得分视为最低，相同时取 `a`），
+  /// 其余诊断字段不保留。
+  ///
+  /// ```
+  /// use slide_match::SlideBBox;
+  ///
+  /// let a = SlideBBox::from_center(50, 50, 20, 20);
+  /// let b = SlideBBox::from_center(65, 45, 20, 20);
+  /// let union = SlideBBox::union(&a, &b);
+  /// assert_eq!((union.x1, union.y1, union.x2, union.y2), (40, 35, 75, 60));
+  /// ```
+  #[must_use]
+  pub fn union(a: &SlideBBox, b: &SlideBBox) -> SlideBBox {
+    Self::combine(
+      a,
+      b,
+      (
+        a.x1.min(b.x1),
+        a.y1.min(b.y1),
+        a.x2.max(b.x2),
+        a.y2.max(b.y2),
+      ),
+    )
+  }
+
+  /// 两个匹配区域的重叠部分，不重叠（包括只有边相接）时返回 None
+  ///
+  /// 其余字段的取法与 [`SlideBBox::union`] 相同。
+  #[must_use]
+  pub fn intersection(a: &SlideBBox, b: &SlideBBox) -> Option<SlideBBox> {
+    let (x1, y1) = (a.x1.max(b.x1), a.y1.max(b.y1));
+    let (x2, y2) = (a.x2.min(b.x2), a.y2.min(b.y2));
+    (x1 < x2 && y1 < y2).then(|| Self::combine(a, b, (x1, y1, x2, y2)))
+  }
+
+  // 以得分较高的一个的 `target_x/target_y` 和 `confidence` 构造新的匹配区域
+  fn combine(a: &SlideBBox, b: &SlideBBox, (x1, y1, x2, y2): (u32, u32, u32, u32)) -> SlideBBox {
+    let best = if b.confidence > a.confidence { b } else { a };
+    SlideBBox {
+      target_x: best.target_x,
+      target_y: best.target_y,
+      x1,
+      y1,
+      x2,
+      y2,
+      confidence: best.confidence,
+      ..SlideBBox::from_center(0, 0, 0, 0)
+    }
+  }
+}
+
+/// 只比较匹配区域 `(x1, y1, x2, y2)`，得分、诊断信息等其余字段不参与比较
+impl PartialEq for SlideBBox {
+  fn eq(&self, other: &Self) -> bool {
+    (self.x1, self.y1, self.x2, self.y2) == (other.x1, other.y1, other.x2, other.y2)
+  }
+}
+
+impl Eq for SlideBBox {}
+
+impl PartialOrd for SlideBBox {
+  fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+/// 按左上角 `(x1, y1)` 排序，左上角相同时再比较 `(x2, y2)`，与 `PartialEq` 保持一致
+///
+/// ```
+/// use slide_match::SlideBBox;
+///
+/// let mut boxes = vec![
+///   SlideBBox::from_center(60, 40, 20, 20),
+///   SlideBBox::from_center(30, 50, 20, 20),
+///   SlideBBox::from_center(60, 40, 20, 20),
+/// ];
+/// boxes.sort();
+/// boxes.dedup();
+/// assert_eq!(boxes.iter().map(|b| (b.x1, b.y1)).collect::<Vec<_>>(), [(20, 40), (50, 30)]);
+/// ```
+impl Ord for SlideBBox {
+  fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+    (self.x1, self.y1, self.x2, self.y2).cmp(&(other.x1, other.y1, other.x2, other.y2))
+  }
+}
+
+/// 便于日志阅读的单行格式，没有 `confidence` 时省略 `conf`
+///
+/// ```
+/// use slide_match::SlideBBox;
+///
+/// let bbox = SlideBBox { confidence: Some(0.85321), ..SlideBBox::from_center(60, 40, 20, 20) };
+/// assert_eq!(bbox.to_string(), "x:[50,70] y:[30,50] target@(0,0) conf=0.853");
+/// ```
+impl std::fmt::Display for SlideBBox {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "x:[{},{}] y:[{},{}] target@({},{})",
+      self.x1, self.x2, self.y1, self.y2, self.target_x, self.target_y
+    )?;
+    if let Some(confidence) = self.confidence {
+      write!(f, " conf={confidence:.3}")?;
+    }
+    Ok(())
+  }
+}
+
+/// 有符号坐标的匹配结果，字段含义与 `SlideBBox` 相同
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlideBBoxSigned {
+  pub target_x: i32,
+  pub target_y: i32,
+  pub x1: i32,
+  pub y1: i32,
+  pub x2: i32,
+  pub y2: i32,
+  /// 匹配得分，设置了 `confidenceThreshold` 时返回
+  pub confidence: Option<f64>,
+}
+
+/// 以背景图片原始尺寸比例（0.0-1.0）表示的匹配结果，见 `slideMatchNormalized`
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlideBBoxNormalized {
+  /// `x1 / 背景图片宽度`，`x1` 的含义由 `coordinateMode` 决定
+  pub x1: f64,
+  /// `y1 / 背景图片高度`
+  pub y1: f64,
+  /// `x2 / 背景图片宽度`
+  pub x2: f64,
+  /// `y2 / 背景图片高度`
+  pub y2: f64,
+  /// 匹配得分
+  pub confidence: f64,
+}
+
+/// 指定位置的匹配得分，见 `matchAtPosition`
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct PositionScore {
+  /// 目标图片放在该位置时的匹配得分，与 `slideMatch` 在同一位置的 `confidence` 一致
+  pub score: f64,
+  /// 预处理的诊断信息，`primaryConfidence` 与 `score` 相同，`usedFallback` 恒为 false
+  pub metadata: MatchMetadata,
+}
+
+/// 匹配结果中 `x1/y1` 的含义
+#[napi(string_enum = "camelCase")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CoordinateMode {
+  /// 匹配区域的左上角（默认）
+  #[default]
+  TopLeft,
+  /// 匹配区域的中心点：`x1 = x1 + 宽度 / 2`、`y1 = y1 + 高度 / 2`（整数除法），`x2/y2` 仍为右下角，
+  /// 适合以元素中心为坐标的拖动工具（如 Playwright 的 `page.mouse.move`）
+  Center,
+}
+
+/// 匹配结果中位置坐标的参照点
+#[napi(string_enum = "camelCase")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CoordinateSpace {
+  /// 背景图片左上角（默认）
+  #[default]
+  BackgroundAbsolute,
+  /// 目标图片的裁剪起点：匹配区域整体平移 `(-target_x, -target_y)`，`x1` 即滑块需要拖动的距离，
+  /// 结果小于 0 时取 0
+  BackgroundRelativeToTarget,
+}
+
+/// 匹配结果中坐标的单位
+#[napi(string_enum = "camelCase")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CoordinateUnit {
+  /// 像素（默认）
+  #[default]
+  Pixel,
+  /// 图片尺寸的比例，额外在 `SlideBBox.ratio` 中返回
+  Ratio,
+}
+
+/// 以图片原始尺寸比例表示的坐标，乘以图片原始尺寸即得到（未换算到显示尺寸的）像素坐标
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct RatioCoordinates {
+  /// `target_x / 目标图片宽度`
+  pub target_x: f64,
+  /// `target_y / 目标图片高度`
+  pub target_y: f64,
+  /// `x1 / 背景图片宽度`，`x1` 的含义同样由 `coordinateMode` 决定
+  pub x1: f64,
+  /// `y1 / 背景图片高度`
+  pub y1: f64,
+  /// `x2 / 背景图片宽度`
+  pub x2: f64,
+  /// `y2 / 背景图片高度`
+  pub y2: f64,
+}
+
+/// 目标图片大于背景图片时的处理方式
+#[napi(string_enum = "camelCase")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OversizePolicy {
+  /// 报错（默认），错误信息包含两张图片的尺寸
+  #[default]
+  Error,
+  /// 按比例缩小目标图片直到能放进背景图片，适合滑块按 2 倍图、背景按 1 倍图下发的情况
+  ScaleTarget,
+  /// 按比例放大背景图片直到能放下目标图片
+  ScaleBackground,
+}
+
+/// 指定 `backgroundTargetWidth` 时返回坐标所在的背景图坐标系
+#[napi(string_enum = "camelCase")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BackgroundCoordinateSpace {
+  /// 重采样后的背景图（默认），与目标图片匹配时所用的尺寸一致
+  #[default]
+  Resized,
+  /// 原始背景图
+  Natural,
+}
+
+/// 改进版函数首次匹配置信度不高于阈值时的处理方式
+#[napi(string_enum = "camelCase")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LowConfidenceAction {
+  /// 回退到固定阈值 (100, 200) 重新匹配（默认）
+  #[default]
+  Fallback,
+  /// 不回退，直接返回自适应阈值的匹配结果
+  ReturnBest,
+  /// 报错，由调用方换用其他策略
+  Error,
+}
+
+/// `ignorePieceRegion` 的预设区域
+#[napi(string_enum = "camelCase")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PieceRegionPreset {
+  /// 背景图左侧从 x = 0 开始、与（裁剪透明边缘后的）目标图片等宽的整列区域
+  AutoLeft,
+}
+
+/// 由中心点和尺寸构造 `SlideBBox`，见 `SlideBBox::from_center`
+///
+/// # 参数
+/// - cx / cy: 中心点坐标
+/// - w / h: 宽度和高度
+#[napi]
+pub fn bbox_from_center(cx: u32, cy: u32, w: u32, h: u32) -> SlideBBox {
+  SlideBBox::from_center(cx, cy, w, h)
 }
 
+/// 同时包含两个匹配区域的最小矩形，见 `SlideBBox::union`
+///
+/// 用于合并多种算法的结果，`targetX/targetY` 和 `confidence` 取自得分较高的一个
+#[napi]
+pub fn bbox_union(a: SlideBBox, b: SlideBBox) -> SlideBBox {
+  SlideBBox::union(&a, &b)
+}
+
+/// 两个匹配区域的重叠部分，不重叠时返回 null，见 `SlideBBox::intersection`
+///
+/// `targetX/targetY` 和 `confidence` 取自得分较高的一个
+#[napi]
+pub fn bbox_intersection(a: SlideBBox, b: SlideBBox) -> Option<SlideBBox> {
+  SlideBBox::intersection(&a, &b)
+}
+
+/// 模板匹配的得分图（归一化互相关，按行存储）
+///
+/// `data[y * width + x]` 为目标图片左上角位于背景图 (x, y) 时的得分（原始尺寸像素）。
+/// 指定 `fixedY` 时只包含搜索的行，第 0 行对应 `y = fixedY - fixedYTolerance`（不小于 0）。
+/// 指定 `fixedX` 时同理只包含搜索的列，第 0 列对应 `x = fixedX - fixedXTolerance`（不小于 0）。
+#[napi(object)]
+pub struct ScoreHeatmap {
+  pub data: Float32Array,
+  pub width: u32,
+  pub height: u32,
+}
+
+// `Float32Array` 不实现 `Clone`，复制时拷贝一份数据
+impl Clone for ScoreHeatmap {
+  fn clone(&self) -> Self {
+    Self {
+      data: Float32Array::new(self.data.to_vec()),
+      width: self.width,
+      height: self.height,
+    }
+  }
+}
+
+impl std::fmt::Debug for ScoreHeatmap {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("ScoreHeatmap")
+      .field("width", &self.width)
+      .field("height", &self.height)
+      .finish_non_exhaustive()
+  }
+}
+
+/// 匹配过程的诊断信息
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct MatchMetadata {
+  /// 是否因置信度过低回退到了固定阈值（100, 200）
+  pub used_fallback: bool,
+  /// 最终结果所用的 Canny 阈值策略，回退时为 `fixed`
+  pub threshold_strategy: ThresholdStrategy,
+  /// 最终结果所用的目标图 Canny 阈值 [low, high]，`edgeMode` 为 `none` 时为空
+  pub target_thresholds: Vec<f64>,
+  /// 最终结果所用的背景图 Canny 阈值 [low, high]，`edgeMode` 为 `none` 时为空
+  pub background_thresholds: Vec<f64>,
+  /// 自适应阈值（首次尝试）的匹配得分，发生回退时也会保留
+  pub primary_confidence: f64,
+  /// 首次尝试的得分是否不高于置信度阈值
+  pub low_confidence: bool,
+  /// `low_confidence` 为 true 时按 `onLowConfidence` 采取的处理方式（`fallback` 或 `returnBest`），否则不返回
+  pub low_confidence_action: Option<LowConfidenceAction>,
+  /// 目标图片的灰度图是否被反相（见 `invert`）
+  pub target_inverted: bool,
+  /// 背景图片的灰度图是否被反相（见 `invert`）
+  pub background_inverted: bool,
+}
+
+/// 改进版匹配中一次匹配尝试的结果
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct MatchAttempt {
+  /// 匹配策略：`adaptive` 为自适应 Canny 阈值，`fixed` 为回退使用的固定阈值 (100, 200)
+  pub strategy: String,
+  /// 该次尝试的匹配得分（归一化互相关的最大值）
+  pub score: f64,
+  /// 该次尝试的最佳位置 `[x1, y1]`，与结果的 `x1/y1` 同一坐标系
+  pub location: (u32, u32),
+}
+
+/// `improvedSlideMatchVerbose` 的返回值
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct SlideMatchVerbose {
+  /// 与 `improvedSlideMatch` 相同的匹配结果
+  pub bbox: SlideBBox,
+  /// 按执行顺序排列的每次匹配尝试，未回退时只有 `adaptive` 一项
+  pub attempts: Vec<MatchAttempt>,
+}
+
+/// 边缘图形态学操作，参数为结构元素半径（像素），范围 0-255
+///
+/// JavaScript 中写作 `{ op: 'dilate', radius: 1 }`。imageproc 的结构元素半径为 `u8`，超过 255 时报错。
+#[napi(discriminant = "op", discriminant_case = "camelCase")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MorphOp {
+  /// 膨胀：加粗边缘，使细线更容易重合
+  Dilate(#[napi(js_name = "radius")] u32),
+  /// 腐蚀：细化过粗的边缘
+  Erode(#[napi(js_name = "radius")] u32),
+  /// 开运算（先腐蚀后膨胀）：去除孤立噪点
+  Open(#[napi(js_name = "radius")] u32),
+  /// 闭运算（先膨胀后腐蚀）：连接断开的边缘
+  Close(#[napi(js_name = "radius")] u32),
+}
+
+impl MorphOp {
+  /// 结构元素半径
+  pub fn radius(self) -> u32 {
+    match self {
+      Self::Dilate(radius) | Self::Erode(radius) | Self::Open(radius) | Self::Close(radius) => {
+        radius
+      }
+    }
+  }
+}
+
+/// 模板匹配前的边缘处理方式
+#[napi(string_enum = "camelCase")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EdgeMode {
+  /// Canny 边缘检测后在边缘图上匹配（默认）
+  #[default]
+  Canny,
+  /// 跳过边缘检测，直接在灰度图上做归一化互相关匹配，
+  /// 适合缺口为半透明暗色遮罩（而非描边）的背景
+  None,
+}
+
+/// 参与匹配的颜色通道
+#[napi(string_enum = "camelCase")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorMode {
+  /// 转换为灰度图后匹配（默认）
+  #[default]
+  Gray,
+  /// 分别在 R、G、B 三个通道上匹配，适合只靠颜色区分真假缺口的验证码
+  Rgb,
+}
+
+/// Canny 阈值的计算策略，除 `Fixed` 外都按每张图片的灰度分布计算
+#[napi(string_enum = "camelCase")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ThresholdStrategy {
+  /// 固定阈值 low = 100，high = 200（原版函数的默认值），可用 `cannyLow/cannyHigh` 等选项指定其他值
+  Fixed,
+  /// 均值和标准差：low = 均值 - 标准差，high = 均值 + 2 * 标准差（改进版函数的默认值）
+  #[default]
+  MeanStdDev,
+  /// Otsu 法：high = 使前景和背景类间方差最大的灰度值，low = 0.5 * high，适合双峰灰度分布的图片
+  Otsu,
+  /// 双峰直方图：找出灰度直方图中被谷底分开的两个主峰，low = 谷底 - 暗峰，high = 亮峰 - 暗峰
+  /// （即两块区域分界处的灰度差），适合两块区域分明的图片；找不到第二个峰时与 `MeanStdDev` 相同
+  Bimodal,
+  /// 中位数：low = 0.66 * 中位数，high = 1.33 * 中位数，
+  /// 对大片明亮天空等灰度分布偏斜的图片更稳定
+  Median,
+}
+
+/// 缺口定位方式
+#[napi(string_enum = "camelCase")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MatchStrategy {
+  /// 边缘图模板匹配（归一化互相关，默认）
+  #[default]
+  Correlation,
+  /// 轮廓形状匹配：在背景边缘图的闭合轮廓中寻找 Hu 不变矩与滑块轮廓最接近的一个，
+  /// 适合背景纹理过于杂乱、边缘互相关失效但缺口轮廓仍然清晰的情况
+  Contour,
+  /// 梯度方向匹配：按 Sobel 梯度方向（忽略明暗方向）把边缘像素分箱，只统计方向相同的重合边缘，
+  /// 对明暗对比变化更不敏感
+  Orientation,
+  /// 倒角匹配：按模板边缘像素到最近背景边缘的平均距离评分（距离变换），
+  /// 滑块轮廓与缺口轮廓错开 1-3 像素（如服务端缩放）时仍能定位
+  Chamfer,
+}
+
+/// `invert` 的自动模式
+#[napi(string_enum = "camelCase")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvertMode {
+  /// 灰度均值低于 `invertCutoff` 时反相
+  Auto,
+}
+
+/// 滑块的滑动方向
+#[napi(string_enum = "camelCase")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SlideAxis {
+  /// 水平滑动，缺口与滑块在同一行（默认）
+  #[default]
+  X,
+  /// 竖直滑动，缺口在滑块正上方或正下方
+  Y,
+}
+
+/// 滑块匹配可选配置
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct SlideMatchConfig {
+  /// CLAHE 分块边长（像素），设置后在阈值计算和边缘检测前启用 CLAHE 预处理
+  pub clahe_tile_size: Option<u32>,
+  /// CLAHE 对比度限制，默认 2.0
+  pub clahe_clip_limit: Option<f64>,
+  /// 背景图片在页面上的显示宽度（CSS 像素），设置后返回的坐标换算到显示尺寸
+  pub background_display_width: Option<f64>,
+  /// 目标图片在页面上的显示宽度（CSS 像素），未设置时沿用背景的缩放比例
+  pub target_display_width: Option<f64>,
+  /// 对目标和背景的边缘图执行形态学后处理（在模板匹配之前）
+  pub edge_morphology: Option<MorphOp>,
+  /// 边缘膨胀半径（像素），0 表示不膨胀；在 `edge_morphology` 之后对两张边缘图执行膨胀，
+  /// 使抗锯齿差异导致错开 1 像素的轮廓仍能重合
+  pub dilate_edges: Option<u32>,
+  /// Canny 低阈值，同时覆盖目标图和背景图的固定阈值与自适应阈值，范围 0-255
+  pub canny_low: Option<f64>,
+  /// Canny 高阈值，同时覆盖目标图和背景图的固定阈值与自适应阈值，范围 0-255
+  pub canny_high: Option<f64>,
+  /// 目标图 Canny 低阈值，优先级高于 `canny_low`
+  pub target_canny_low: Option<f64>,
+  /// 目标图 Canny 高阈值，优先级高于 `canny_high`
+  pub target_canny_high: Option<f64>,
+  /// 背景图 Canny 低阈值，优先级高于 `canny_low`
+  pub background_canny_low: Option<f64>,
+  /// 背景图 Canny 高阈值，优先级高于 `canny_high`
+  pub background_canny_high: Option<f64>,
+  /// Canny 阈值策略，原版函数默认 `fixed`，改进版函数默认 `meanStdDev`；显式指定的 `cannyLow/cannyHigh`
+  /// 等阈值仍会覆盖策略计算的结果
+  pub threshold_strategy: Option<ThresholdStrategy>,
+  /// 边缘处理方式，默认 `canny`；为 `none` 时 Canny 阈值和形态学配置不生效
+  pub edge_mode: Option<EdgeMode>,
+  /// 颜色模式，默认 `gray`；为 `rgb` 时分别在 R、G、B 三个通道上匹配并取平均得分
+  pub color_mode: Option<ColorMode>,
+  /// 是否在线性光强下计算灰度（先把 sRGB 解码为线性值再按亮度权重求和，再编码回 8 位），默认 false；
+  /// 暗背景上饱和色的缺口轮廓在默认的灰度转换中容易与背景混在一起，开启后自适应阈值统计和
+  /// Canny 都使用该灰度图。`colorMode` 为 `rgb` 时只影响用于计算阈值的灰度图
+  pub linear_light: Option<bool>,
+  /// 缺口（匹配区域左上角）的 y 坐标，即结果中的 `y1`（原始尺寸像素）；
+  /// 指定后只在该行附近沿 x 方向搜索，背景图较宽时速度明显快于完整的二维匹配
+  pub fixed_y: Option<u32>,
+  /// `fixed_y` 上下允许的偏差（像素），默认 0
+  pub fixed_y_tolerance: Option<u32>,
+  /// 单张图片允许的最大像素数（宽 × 高），默认 4000000，超过时在解码像素数据前报错
+  pub max_image_pixels: Option<u32>,
+  /// 背景边缘图中边缘像素占比的下限，范围 0.0-1.0，默认 0.001；
+  /// 低于该值（如纯色占位图）时报错而不是返回不可靠的匹配结果，设为 0 可关闭检查。
+  /// 真实验证码背景的边缘像素占比通常只有 0.3%-1%，默认值不能取 1%，否则会拒绝正常图片。
+  /// `edgeMode` 为 `none` 时不检查
+  pub edge_density_threshold: Option<f64>,
+  /// 是否在结果中返回每个 x（`axis` 为 `y` 时为每个 y）上的最大匹配得分（`scoreProfile`），默认 false
+  pub return_score_profile: Option<bool>,
+  /// 是否在结果中返回完整的匹配得分图（`heatmap`），默认 false
+  pub return_heatmap: Option<bool>,
+  /// 是否在进程内缓存背景图的边缘图，默认 false；
+  /// 同一张背景图（图片数据、预处理配置和阈值都相同）重复匹配时跳过背景的边缘检测，
+  /// 可调用 `clearBackgroundCache` 清空
+  pub cache_background: Option<bool>,
+  /// Canny 边缘检测前额外执行的高斯模糊 σ，默认不模糊；优先级高于 `auto_sigma`
+  pub canny_sigma: Option<f64>,
+  /// 是否按背景图分辨率自动选择 Canny 前的高斯模糊 σ，默认 false；
+  /// σ = max(1.0, sqrt(宽 × 高) / 500)，目标图和背景图使用相同的 σ
+  pub auto_sigma: Option<bool>,
+  /// 是否清除背景边缘图中疑似文字（站点名称、标签等）的水平条带，默认 false；
+  /// 边缘密度明显高于整张图、高度不超过 24 像素的连续行视为文字区域
+  pub mask_text_regions: Option<bool>,
+  /// 返回结果中 `x1/y1` 的含义，默认 `topLeft`（左上角）；为 `center` 时为匹配区域的中心点，
+  /// 换算到显示尺寸后再取中心
+  pub coordinate_mode: Option<CoordinateMode>,
+  /// 返回结果的坐标单位，默认 `pixel`；为 `ratio` 时在 `SlideBBox.ratio` 中额外返回
+  /// 按图片原始尺寸归一化的坐标（0-1），像素坐标字段保持不变
+  pub coordinate_unit: Option<CoordinateUnit>,
+  /// 目标图片大于背景图片时的处理方式，默认 `error`；缩放后返回的坐标仍在原始背景图的坐标系中，
+  /// 结果的 `targetScale` 记录实际的缩放比例。只对 `slideMatch`、`simpleSlideMatch` 和改进版函数生效
+  pub oversize_policy: Option<OversizePolicy>,
+  /// 匹配前把背景图片重采样（Lanczos3）到该宽度，高度按比例缩放，用于目标图片对应的是按该宽度
+  /// 渲染的背景图、而不是背景图原始尺寸的情况。只对 `slideMatch`、`simpleSlideMatch` 和改进版函数生效
+  pub background_target_width: Option<u32>,
+  /// 指定 `backgroundTargetWidth` 时返回坐标所在的坐标系，默认 `resized`（重采样后的背景图）；
+  /// `fixedY` 和 `fixedYTolerance` 同样按该坐标系解释
+  pub background_coordinate_space: Option<BackgroundCoordinateSpace>,
+  /// 背景图中画有滑块本身（起始位置）的区域，该区域的背景边缘在匹配前清零，避免匹配到滑块本身
+  /// 而返回接近 0 的滑动距离；可以是原始尺寸像素的矩形，或 `autoLeft`（左侧与目标图片等宽的整列）
+  pub ignore_piece_region: Option<Either<PieceRegion, PieceRegionPreset>>,
+  /// 原版函数（`slideMatch`、`simpleSlideMatch`）的置信度阈值，范围 0.0-1.0，默认不检查；
+  /// 设置后结果包含 `confidence`（匹配得分峰值），峰值不高于阈值时报错而不是返回不可靠的位置。
+  /// 改进版函数使用各自的 `confidenceThreshold` 参数
+  pub confidence_threshold: Option<f64>,
+  /// `slideMatch` 的小目标快速路径，默认 false；开启后裁剪后的目标图片像素数小于 2500（约 50×50）时
+  /// 跳过 Canny 边缘检测，直接在灰度图上做归一化互相关（相当于 `edgeMode: 'none'`）。
+  /// 互相关只遍历模板中的非零像素，灰度图比边缘图稠密得多，因此总耗时不一定更短；
+  /// 匹配效果与 `edgeMode: 'none'` 相同，只适合缺口与滑块外观接近的背景
+  pub fast_small: Option<bool>,
+  /// 判断匹配是否模棱两可的相对差距，范围 0.0-1.0，默认不检查；设置后结果包含 `isAmbiguous`、
+  /// `secondBestScore` 和 `secondBestX`，次优峰值得分不低于 最优得分 × (1 - `ambiguityMargin`) 时
+  /// `isAmbiguous` 为 true。只对 `slideMatch`、`simpleSlideMatch` 和改进版函数生效
+  pub ambiguity_margin: Option<f64>,
+  /// 改进版函数首次匹配（自适应阈值）的置信度不高于 `confidenceThreshold` 时的处理方式，默认 `fallback`
+  pub on_low_confidence: Option<LowConfidenceAction>,
+  /// 是否在结果中返回峰值旁瓣比（`psr`），默认 false。只对 `slideMatch`、`simpleSlideMatch` 和改进版函数生效
+  pub return_psr: Option<bool>,
+  /// 缺口定位方式，默认 `correlation`。`contour` 只对 `slideMatch` 及其路径、掩码版本生效，
+  /// `confidence` 为形状相似度，得分图相关的选项（`returnScoreProfile`、`returnHeatmap`、
+  /// `ambiguityMargin`、`returnPsr`、`fixedY`）不生效；`orientation` 和 `chamfer` 替换所有边缘图模板匹配的
+  /// 得分计算，`edgeMode` 为 `none` 时不生效
+  pub strategy: Option<MatchStrategy>,
+  /// `x1/y1/x2/y2` 的参照点，默认 `backgroundAbsolute`；为 `backgroundRelativeToTarget` 时
+  /// 减去（换算到显示尺寸的）`target_x/target_y`，`coordinateUnit` 为 `ratio` 时同样减去原始尺寸下的裁剪起点
+  pub coordinate_space: Option<CoordinateSpace>,
+  /// `strategy` 为 `orientation` 时梯度方向（0°-180°）的分箱数，范围 1-64，默认 8
+  pub orientation_bins: Option<u32>,
+  /// 截图的设备像素比（如高分屏上的 2），设置后返回的 `x1/y1/x2/y2/target_x/target_y` 在显示尺寸换算后
+  /// 再除以 DPR，得到可直接用于 `page.mouse.move()` 的 CSS 像素坐标；必须是大于 0 的有限数值
+  pub device_pixel_ratio: Option<f64>,
+  /// 背景灰度图的中值滤波半径（像素），0 表示不滤波，范围 0-255；在 CLAHE、自适应阈值计算和边缘检测之前执行，
+  /// 用于去除低质量 JPEG 的 8×8 块噪声
+  pub median_filter_radius: Option<u32>,
+  /// 是否同时对目标图片做中值滤波，默认 false
+  pub median_filter_target: Option<bool>,
+  /// 是否在中值滤波、CLAHE、阈值计算和边缘检测之前把灰度图反相（255 - 灰度值），默认 false；
+  /// 近乎全黑的背景上画浅色轮廓的深色主题验证码，灰度直方图挤在 0 附近，自适应阈值过低。
+  /// 为 `auto` 时目标图片和背景图片分别判断，灰度均值低于 `invertCutoff` 的才反相
+  pub invert: Option<Either<bool, InvertMode>>,
+  /// `invert` 为 `auto` 时的灰度均值阈值，范围 0-255，默认 64
+  pub invert_cutoff: Option<f64>,
+  /// 滑块的滑动方向，默认 `x`；为 `y` 时在转置后的图片上沿 y 方向匹配，`fixedX`、`scoreProfile` 和
+  /// `ignorePieceRegion: 'autoLeft'`（此时为顶部与目标图片等高的整行）都按 y 方向处理，`maskTextRegions`
+  /// 清除的是竖直条带。只对 `slideMatch`、`simpleSlideMatch` 和改进版函数生效，其他函数设置为 `y` 时报错
+  pub axis: Option<SlideAxis>,
+  /// 缺口（匹配区域左上角）的 x 坐标，即结果中的 `x1`（原始尺寸像素），只在 `axis` 为 `y` 时可用；
+  /// 指定后只在该列附近沿 y 方向搜索，与水平滑动时的 `fixedY` 对应
+  pub fixed_x: Option<u32>,
+  /// `fixed_x` 左右允许的偏差（像素），默认 0
+  pub fixed_x_tolerance: Option<u32>,
+  /// 背景图片带透明度通道（圆角、透明边框等）时，灰度转换前把它合成到的纯色 `[r, g, b]`，默认白色
+  /// `[255, 255, 255]`；透明像素直接转灰度时通常为黑色，会在透明区域边界产生大片假边缘
+  pub background_fill: Option<Vec<u32>>,
+  /// 模板匹配的滑动步长（像素），范围 1-16，默认 1（逐像素）；大于 1 时先按步长粗搜索，
+  /// 再在最优位置周围逐像素细化，速度更快但可能错过不在网格上的缺口，见 `ncc::match_template_strided`。
+  /// 只对默认的 `correlation` 策略生效
+  pub stride: Option<u32>,
+  /// 模板匹配前在背景边缘图四周补 0 的宽度（像素），默认 0；缺口靠近背景右侧或底部边缘、
+  /// 目标图片（含透明边距）放到正确位置时会超出背景的情况下使用。结果的 `x2/y2` 可能大于背景尺寸，
+  /// 超出左侧和上方的位置无法用无符号坐标表示，不参与比较；只搜索固定行（`fixedY`）时不在上下补
+  pub background_padding: Option<u32>,
+  /// 目标图片至少需要的不透明像素数（透明度大于 0），默认 1，即只拒绝完全透明的目标图片；0 表示不检查。
+  /// 不足时报错“目标图片没有不透明像素”或“目标图片只有 N 个不透明像素”
+  pub min_opaque_pixels: Option<u32>,
+  /// 目标图片的不透明像素不足 `minOpaquePixels` 时不报错，继续匹配并在结果中设置 `degenerateTarget: true`，
+  /// 默认 false。只有返回 `SlideBBox` 的 `slideMatch`、`simpleSlideMatch` 和改进版函数会设置该标记
+  pub allow_degenerate_target: Option<bool>,
+  /// 在结果的 `elapsedMs` 中返回匹配耗时（毫秒），默认 false；不含 JavaScript 与原生模块之间传递参数的开销。
+  /// 只对 `slideMatch`、`simpleSlideMatch` 和改进版函数（含按路径读取、异步和 `try` 前缀的变体）生效
+  pub measure_time: Option<bool>,
+}
+
+// 自适应阈值中高低阈值之间的最小间隔
+const MIN_CANNY_THRESHOLD_GAP: f32 = 20.0;
+
 // 改进算法1: 自适应Canny阈值计算
-fn calculate_adaptive_canny_thresholds(img: &GrayImage) -> (f32, f32) {
-  let total_pixels = (img.width() * img.height()) as f32;
+// `scale` 为灰度值换算到 0-255 的除数（8 位为 1，16 位为 257）
+fn calculate_adaptive_canny_thresholds<T: Copy + Into<f32>>(
+  pixels: &[T],
+  scale: f32,
+) -> (f32, f32) {
+  let total_pixels = pixels.len() as f32;
+
+  // 计算图像均值
+  let sum: f32 = pixels.iter().map(|&p| p.into() / scale).sum();
+  let mean = sum / total_pixels;
+
+  // 计算标准差
+  let variance: f32 = pixels
+    .iter()
+    .map(|&p| {
+      let diff = p.into() / scale - mean;
+      diff * diff
+    })
+    .sum();
+  let std_dev = (variance / total_pixels).sqrt();
+
+  // 自适应设置阈值
+  // low_threshold: 均值 - 标准差
+  // high_threshold: 均值 + 2*标准差
+  let low_threshold = (mean - std_dev).max(0.0);
+  let high_threshold = (mean + std_dev * 2.0).min(255.0);
+
+  // 确保阈值在合理范围内
+  // 近乎纯色的图片标准差很小，钳制后低阈值可能不小于高阈值
+  ensure_threshold_gap(low_threshold.max(50.0), high_threshold.min(250.0))
+}
+
+// 中位数自适应Canny阈值计算（经典 auto-canny）
+// `scale` 含义同 `calculate_adaptive_canny_thresholds`
+fn calculate_median_canny_thresholds<T: Copy + Into<u32>>(pixels: &[T], scale: f32) -> (f32, f32) {
+  // 由灰度直方图求中位数，无需对所有像素排序
+  let hist = gray_histogram(pixels, scale);
+
+  let half = (pixels.len() as u64).div_ceil(2);
+  let mut cumulative = 0u64;
+  let median = hist
+    .iter()
+    .position(|&count| {
+      cumulative += count;
+      cumulative >= half
+    })
+    .unwrap_or(0) as f32
+    / scale;
+
+  ensure_threshold_gap(median * 0.66, (median * 1.33).min(255.0))
+}
+
+// Otsu 法Canny阈值计算：类间方差最大的灰度值作为高阈值，低阈值取其一半
+// `scale` 含义同 `calculate_adaptive_canny_thresholds`
+fn calculate_otsu_canny_thresholds<T: Copy + Into<u32>>(pixels: &[T], scale: f32) -> (f32, f32) {
+  let hist = gray_histogram(pixels, scale);
+  let total = pixels.len() as f64;
+  let total_sum: f64 = hist
+    .iter()
+    .enumerate()
+    .map(|(value, &count)| value as f64 * count as f64)
+    .sum();
+
+  // 逐个灰度值累加背景类的像素数和灰度和，取类间方差最大的分割点（相同时取第一个）
+  let mut background_count = 0.0;
+  let mut background_sum = 0.0;
+  let mut best = (0usize, 0.0f64);
+  for (value, &count) in hist.iter().enumerate() {
+    background_count += count as f64;
+    background_sum += value as f64 * count as f64;
+    let foreground_count = total - background_count;
+    if background_count == 0.0 || foreground_count == 0.0 {
+      continue;
+    }
+
+    let background_mean = background_sum / background_count;
+    let foreground_mean = (total_sum - background_sum) / foreground_count;
+    let variance =
+      background_count * foreground_count * (background_mean - foreground_mean).powi(2);
+    if variance > best.1 {
+      best = (value, variance);
+    }
+  }
+
+  let otsu = best.0 as f32 / scale;
+  ensure_threshold_gap(otsu * 0.5, otsu)
+}
+
+// 双峰直方图中两个峰之间的最小距离（灰度级）
+const MIN_PEAK_DISTANCE: usize = 16;
+// 两个峰之间的谷底不高于较低峰的该比例时才认为是双峰
+const MAX_VALLEY_RATIO: f64 = 0.5;
+// 寻峰前平滑直方图的窗口半径（灰度级）
+const HISTOGRAM_SMOOTHING_RADIUS: usize = 3;
+
+// 双峰直方图Canny阈值计算（如明亮天空 + 暗色地面）：找出直方图中被谷底分开的两个主峰，
+// 低阈值为暗峰到谷底的灰度差，高阈值为两峰之间的灰度差；找不到第二个峰时按均值和标准差计算
+// `scale` 含义同 `calculate_adaptive_canny_thresholds`
+fn calculate_bimodal_canny_thresholds<T: Copy + Into<u32> + Into<f32>>(
+  pixels: &[T],
+  scale: f32,
+) -> (f32, f32) {
+  // 16 位灰度值按对应的 8 位灰度级合并
+  let mut hist = [0u64; 256];
+  for (value, &count) in gray_histogram(pixels, scale).iter().enumerate() {
+    hist[((value as f32 / scale).round() as usize).min(255)] += count;
+  }
+
+  // 滑动平均平滑直方图，避免噪声造成的小尖峰被当作峰
+  let smoothed: Vec<f64> = (0..hist.len())
+    .map(|i| {
+      let window = i.saturating_sub(HISTOGRAM_SMOOTHING_RADIUS)
+        ..(i + HISTOGRAM_SMOOTHING_RADIUS + 1).min(hist.len());
+      let len = window.len() as f64;
+      hist[window].iter().sum::<u64>() as f64 / len
+    })
+    .collect();
+
+  // 最高的峰（相同时取灰度值较小的一个）
+  let first = (0..smoothed.len()).fold(0, |best, i| {
+    if smoothed[i] > smoothed[best] {
+      i
+    } else {
+      best
+    }
+  });
+
+  // 谷底：两个灰度级之间（含两端）平滑后计数最小的位置
+  let valley = |a: usize, b: usize| {
+    let (low, high) = (a.min(b), a.max(b));
+    (low..=high).fold(low, |best, i| {
+      if smoothed[i] < smoothed[best] {
+        i
+      } else {
+        best
+      }
+    })
+  };
+
+  // 第二个峰：与最高峰相距足够远、中间有足够深的谷底的位置中计数最高的一个
+  let second = (0..smoothed.len())
+    .filter(|&i| i.abs_diff(first) >= MIN_PEAK_DISTANCE && smoothed[i] > 0.0)
+    .filter(|&i| smoothed[valley(first, i)] <= smoothed[i].min(smoothed[first]) * MAX_VALLEY_RATIO)
+    .fold(None, |best: Option<usize>, i| match best {
+      Some(best) if smoothed[best] >= smoothed[i] => Some(best),
+      _ => Some(i),
+    });
+
+  let Some(second) = second else {
+    return calculate_adaptive_canny_thresholds(pixels, scale);
+  };
+
+  // Canny 阈值是梯度幅值，按灰度差换算：暗峰到谷底的灰度差为低阈值，两峰之间的灰度差为高阈值
+  let (lower, upper) = (first.min(second), first.max(second));
+  ensure_threshold_gap(
+    (valley(first, second) - lower) as f32,
+    (upper - lower) as f32,
+  )
+}
+
+// 灰度直方图，16 位灰度图按 16 位灰度值分桶
+fn gray_histogram<T: Copy + Into<u32>>(pixels: &[T], scale: f32) -> Vec<u64> {
+  let mut hist = vec![0u64; (255.0 * scale) as usize + 1];
+  for &p in pixels {
+    hist[p.into() as usize] += 1;
+  }
+  hist
+}
+
+// 保证低阈值小于高阈值：低阈值最多取到 255 - 最小间隔，高阈值至少比低阈值大最小间隔
+fn ensure_threshold_gap(low: f32, high: f32) -> (f32, f32) {
+  let low = low.min(255.0 - MIN_CANNY_THRESHOLD_GAP);
+  (low, high.max(low + MIN_CANNY_THRESHOLD_GAP))
+}
+
+// 原版算法使用的固定 Canny 阈值
+pub(crate) const FIXED_CANNY_THRESHOLDS: (f32, f32) = (100.0, 200.0);
+
+// 按策略计算 Canny 阈值，所有匹配流程共用；16 位灰度图直接在 16 位灰度值上统计
+pub(crate) fn strategy_canny_thresholds(
+  image: &MatchImage,
+  strategy: ThresholdStrategy,
+) -> (f32, f32) {
+  // 16 位灰度值 / 257 即对应的 8 位灰度值（65535 / 257 = 255）
+  const GRAY16_SCALE: f32 = 257.0;
+
+  match (strategy, &image.gray16) {
+    (ThresholdStrategy::Fixed, _) => FIXED_CANNY_THRESHOLDS,
+    (ThresholdStrategy::MeanStdDev, Some(gray16)) => {
+      calculate_adaptive_canny_thresholds(gray16.as_raw(), GRAY16_SCALE)
+    }
+    (ThresholdStrategy::MeanStdDev, None) => {
+      calculate_adaptive_canny_thresholds(image.gray.as_raw(), 1.0)
+    }
+    (ThresholdStrategy::Otsu, Some(gray16)) => {
+      calculate_otsu_canny_thresholds(gray16.as_raw(), GRAY16_SCALE)
+    }
+    (ThresholdStrategy::Otsu, None) => calculate_otsu_canny_thresholds(image.gray.as_raw(), 1.0),
+    (ThresholdStrategy::Bimodal, Some(gray16)) => {
+      calculate_bimodal_canny_thresholds(gray16.as_raw(), GRAY16_SCALE)
+    }
+    (ThresholdStrategy::Bimodal, None) => {
+      calculate_bimodal_canny_thresholds(image.gray.as_raw(), 1.0)
+    }
+    (ThresholdStrategy::Median, Some(gray16)) => {
+      calculate_median_canny_thresholds(gray16.as_raw(), GRAY16_SCALE)
+    }
+    (ThresholdStrategy::Median, None) => {
+      calculate_median_canny_thresholds(image.gray.as_raw(), 1.0)
+    }
+  }
+}
+
+// 原版算法的 Canny 阈值：默认固定阈值，配置了 `threshold_strategy` 时按该策略计算
+pub(crate) fn original_canny_thresholds(
+  image: &MatchImage,
+  config: &SlideMatchConfig,
+) -> (f32, f32) {
+  strategy_canny_thresholds(
+    image,
+    config
+      .threshold_strategy
+      .unwrap_or(ThresholdStrategy::Fixed),
+  )
+}
+
+// 改进版算法首次尝试的 Canny 阈值：默认 `MeanStdDev`
+pub(crate) fn adaptive_canny_thresholds(
+  image: &MatchImage,
+  config: &SlideMatchConfig,
+) -> (f32, f32) {
+  strategy_canny_thresholds(image, config.threshold_strategy.unwrap_or_default())
+}
+
+// 用配置中显式指定的阈值覆盖计算得到的阈值（未指定的一侧保持不变）
+// 发生覆盖时校验最终阈值，避免只指定一侧导致 low >= high
+pub(crate) fn override_thresholds(
+  name: &str,
+  thresholds: (f32, f32),
+  low: Option<f64>,
+  high: Option<f64>,
+) -> anyhow::Result<(f32, f32)> {
+  if low.is_none() && high.is_none() {
+    return Ok(thresholds);
+  }
+
+  let thresholds = (
+    low.map_or(thresholds.0, |v| v as f32),
+    high.map_or(thresholds.1, |v| v as f32),
+  );
+  validate_canny_thresholds(name, thresholds)?;
+  Ok(thresholds)
+}
+
+// 目标图最终使用的 Canny 阈值
+pub(crate) fn target_canny_thresholds(
+  thresholds: (f32, f32),
+  config: &SlideMatchConfig,
+) -> anyhow::Result<(f32, f32)> {
+  override_thresholds(
+    "目标图",
+    thresholds,
+    config.target_canny_low.or(config.canny_low),
+    config.target_canny_high.or(config.canny_high),
+  )
+}
+
+// 背景图最终使用的 Canny 阈值
+pub(crate) fn background_canny_thresholds(
+  thresholds: (f32, f32),
+  config: &SlideMatchConfig,
+) -> anyhow::Result<(f32, f32)> {
+  override_thresholds(
+    "背景图",
+    thresholds,
+    config.background_canny_low.or(config.canny_low),
+    config.background_canny_high.or(config.canny_high),
+  )
+}
+
+// 校验 Canny 阈值：范围 0-255，且 low < high
+fn validate_canny_thresholds(name: &str, thresholds: (f32, f32)) -> anyhow::Result<()> {
+  let (low, high) = thresholds;
+  anyhow::ensure!(
+    (0.0..=255.0).contains(&low) && (0.0..=255.0).contains(&high),
+    "{name} Canny 阈值必须在 0-255 范围内（low={low}, high={high}）"
+  );
+  anyhow::ensure!(
+    low < high,
+    "{name} Canny 低阈值必须小于高阈值（low={low}, high={high}）"
+  );
+  Ok(())
+}
+
+// 校验配置中显式指定的 Canny 阈值
+fn validate_canny_config(config: &SlideMatchConfig) -> anyhow::Result<()> {
+  for (name, value) in [
+    ("cannyLow", config.canny_low),
+    ("cannyHigh", config.canny_high),
+    ("targetCannyLow", config.target_canny_low),
+    ("targetCannyHigh", config.target_canny_high),
+    ("backgroundCannyLow", config.background_canny_low),
+    ("backgroundCannyHigh", config.background_canny_high),
+  ] {
+    if let Some(value) = value {
+      anyhow::ensure!(
+        value.is_finite() && (0.0..=255.0).contains(&value),
+        "{name} 必须在 0-255 范围内，当前为 {value}"
+      );
+    }
+  }
+
+  // 同一张图的低/高阈值都显式指定时，要求 low < high
+  let target = (
+    config.target_canny_low.or(config.canny_low),
+    config.target_canny_high.or(config.canny_high),
+  );
+  if let (Some(low), Some(high)) = target {
+    validate_canny_thresholds("目标图", (low as f32, high as f32))?;
+  }
+
+  let background = (
+    config.background_canny_low.or(config.canny_low),
+    config.background_canny_high.or(config.canny_high),
+  );
+  if let (Some(low), Some(high)) = background {
+    validate_canny_thresholds("背景图", (low as f32, high as f32))?;
+  }
+
+  Ok(())
+}
+
+// 校验全部可选配置
+pub(crate) fn validate_config(config: &SlideMatchConfig) -> anyhow::Result<()> {
+  decode::validate_decode_config(config)?;
+  preprocess::validate_preprocess_config(config)?;
+  coordinates::validate_display_config(config)?;
+  oversize::validate_fit_config(config)?;
+  if let Some(threshold) = config.confidence_threshold {
+    anyhow::ensure!(
+      (0.0..=1.0).contains(&threshold),
+      "置信度阈值必须在 0.0-1.0 范围内"
+    );
+  }
+  if let Some(margin) = config.ambiguity_margin {
+    anyhow::ensure!(
+      (0.0..=1.0).contains(&margin),
+      "ambiguityMargin 必须在 0.0-1.0 范围内"
+    );
+  }
+  if let Some(stride) = config.stride {
+    anyhow::ensure!((1..=16).contains(&stride), "stride 必须在 1-16 范围内");
+  }
+  if let Some(bins) = config.orientation_bins {
+    anyhow::ensure!(
+      (1..=64).contains(&bins),
+      "orientationBins 必须在 1-64 范围内"
+    );
+  }
+  if let Some(Either::A(region)) = &config.ignore_piece_region {
+    anyhow::ensure!(
+      region.width > 0 && region.height > 0,
+      "ignorePieceRegion 的宽度和高度必须大于 0"
+    );
+  }
+  match config.axis.unwrap_or_default() {
+    SlideAxis::X => anyhow::ensure!(
+      config.fixed_x.is_none(),
+      "fixedX 只在 axis 为 y 时可用，水平滑动请使用 fixedY"
+    ),
+    SlideAxis::Y => anyhow::ensure!(
+      config.fixed_y.is_none(),
+      "axis 为 y 时不能使用 fixedY，请使用 fixedX"
+    ),
+  }
+  validate_canny_config(config)?;
+  Ok(())
+}
+
+// 背景边缘像素占比的默认下限
+// Canny 边缘只有 1 像素宽，正常验证码背景的边缘占比通常只有 0.3%-1%，纯色图片则接近 0
+const DEFAULT_EDGE_DENSITY_THRESHOLD: f64 = 0.001;
+
+// 只搜索固定行时，背景图在搜索行之外额外保留的行数，避免 Canny 的高斯模糊在裁剪边界处产生偏差
+const FIXED_Y_EDGE_MARGIN: u32 = 8;
+
+// 根据 `fixed_y` 计算背景图中参与匹配的行范围 (起始行, 行数)，未指定时返回 None
+fn search_rows(
+  target_height: u32,
+  background_height: u32,
+  config: &SlideMatchConfig,
+) -> anyhow::Result<Option<(u32, u32)>> {
+  let Some(fixed_y) = config.fixed_y else {
+    return Ok(None);
+  };
+
+  anyhow::ensure!(
+    fixed_y as u64 + target_height as u64 <= background_height as u64,
+    "fixedY 超出范围：fixedY ({fixed_y}) + 目标图片高度 ({target_height}) 大于背景图片高度 ({background_height})"
+  );
+
+  let tolerance = config.fixed_y_tolerance.unwrap_or(0);
+  let first = fixed_y.saturating_sub(tolerance);
+  let last = fixed_y
+    .saturating_add(tolerance)
+    .min(background_height - target_height);
+
+  Ok(Some((first, last - first + target_height)))
+}
+
+// `axis` 为 `y` 时把配置换算到转置后的图片：`fixedX` 变为 `fixedY`，`ignorePieceRegion` 的矩形交换 x、y
+fn transposed_config(
+  config: &SlideMatchConfig,
+  target_width: u32,
+  background_width: u32,
+) -> anyhow::Result<SlideMatchConfig> {
+  if let Some(fixed_x) = config.fixed_x {
+    anyhow::ensure!(
+      fixed_x as u64 + target_width as u64 <= background_width as u64,
+      "fixedX 超出范围：fixedX ({fixed_x}) + 目标图片宽度 ({target_width}) 大于背景图片宽度 ({background_width})"
+    );
+  }
+
+  Ok(SlideMatchConfig {
+    axis: None,
+    fixed_x: None,
+    fixed_x_tolerance: None,
+    fixed_y: config.fixed_x,
+    fixed_y_tolerance: config.fixed_x_tolerance,
+    ignore_piece_region: match &config.ignore_piece_region {
+      Some(Either::A(region)) => Some(Either::A(PieceRegion {
+        x: region.y,
+        y: region.x,
+        width: region.height,
+        height: region.width,
+      })),
+      region => region.clone(),
+    },
+    ..config.clone()
+  })
+}
+
+// 截取图片中的若干行
+fn crop_rows(img: &GrayImage, start: u32, height: u32) -> GrayImage {
+  image::imageops::crop_imm(img, 0, start, img.width(), height).to_image()
+}
+
+// 背景图中参与匹配的区域
+struct SearchRegion<'a> {
+  // 掩码，值为 0 的像素不参与匹配
+  mask: Option<&'a GrayImage>,
+  // 只在这些行内搜索 (起始行, 行数)
+  rows: Option<(u32, u32)>,
+  // 画有滑块本身、不参与匹配的区域（`ignorePieceRegion`）
+  ignore: Option<PieceRegion>,
+}
+
+// 按 `ignorePieceRegion` 确定背景图中不参与匹配的区域
+fn ignored_region(
+  config: &SlideMatchConfig,
+  target_width: u32,
+  background_height: u32,
+) -> Option<PieceRegion> {
+  match config.ignore_piece_region.as_ref()? {
+    Either::A(region) => Some(region.clone()),
+    Either::B(PieceRegionPreset::AutoLeft) => Some(PieceRegion {
+      x: 0,
+      y: 0,
+      width: target_width,
+      height: background_height,
+    }),
+  }
+}
+
+// 将边缘图中落在区域内的像素清零，`start` 为边缘图第 0 行对应的背景图行
+fn clear_region(mut edges: GrayImage, region: &PieceRegion, start: u32) -> GrayImage {
+  let right = region.x.saturating_add(region.width).min(edges.width());
+  let top = region.y.max(start) - start;
+  let bottom = region
+    .y
+    .saturating_add(region.height)
+    .min(start + edges.height())
+    .saturating_sub(start);
+  for y in top..bottom {
+    for x in region.x..right {
+      edges.put_pixel(x, y, image::Luma([0]));
+    }
+  }
+  edges
+}
+
+// 参与匹配的背景通道
+enum BackgroundChannel<'a> {
+  // 预处理后的灰度图，匹配时再做边缘检测
+  Gray(&'a GrayImage),
+  // 缓存的整张背景图的边缘图
+  Edges(&'a GrayImage),
+}
+
+// 对单个通道执行边缘检测 + 模板匹配，返回匹配得分图
+// 指定搜索行时，得分图的第 0 行对应背景图的起始行
+fn match_channel(
+  target_gray: &GrayImage,
+  background: BackgroundChannel,
+  target_thresholds: (f32, f32),
+  background_thresholds: (f32, f32),
+  target_mask: Option<&GrayImage>,
+  region: &SearchRegion,
+  config: &SlideMatchConfig,
+) -> anyhow::Result<imageproc::definitions::Image<image::Luma<f32>>> {
+  let edge_detection = matches!(config.edge_mode.unwrap_or_default(), EdgeMode::Canny);
+  // 按梯度方向匹配时的分箱数
+  let orientation_bins = (edge_detection
+    && config.strategy.unwrap_or_default() == MatchStrategy::Orientation)
+    .then(|| {
+      config
+        .orientation_bins
+        .unwrap_or(orientation::DEFAULT_ORIENTATION_BINS)
+    });
+
+  // 按方向匹配时同时返回背景每个像素的方向分箱（与边缘图对齐）
+  let (background_edges, background_orientations) = match background {
+    BackgroundChannel::Edges(edges) => {
+      // 按方向匹配需要灰度图，不使用缓存的边缘图
+      debug_assert!(orientation_bins.is_none());
+      let edges = match region.rows {
+        Some((start, height)) => Cow::Owned(crop_rows(edges, start, height)),
+        None => Cow::Borrowed(edges),
+      };
+      (edges, None)
+    }
+    BackgroundChannel::Gray(background_gray) => {
+      // 先截取搜索行（带边距）再做边缘检测，边缘图再裁掉边距
+      let (background_gray, margin) = match region.rows {
+        Some((start, height)) => {
+          let top = start.saturating_sub(FIXED_Y_EDGE_MARGIN);
+          let bottom = (start + height + FIXED_Y_EDGE_MARGIN).min(background_gray.height());
+          (
+            Cow::Owned(crop_rows(background_gray, top, bottom - top)),
+            start - top,
+          )
+        }
+        None => (Cow::Borrowed(background_gray), 0),
+      };
+      let orientations = orientation_bins.map(|bins| {
+        let orientations = orientation::orientation_bins(&background_gray, bins);
+        match region.rows {
+          Some((_, height)) => crop_rows(&orientations, margin, height),
+          None => orientations,
+        }
+      });
+      let background_edges = if edge_detection {
+        Cow::Owned(preprocess::detect_background_edges(
+          background_gray.into_owned(),
+          background_thresholds,
+          config,
+        ))
+      } else {
+        // 直接使用灰度图匹配
+        background_gray
+      };
+      let background_edges = match region.rows {
+        Some((_, height)) => Cow::Owned(crop_rows(&background_edges, margin, height)),
+        None => background_edges,
+      };
+      (background_edges, orientations)
+    }
+  };
+
+  let target_edges = if edge_detection {
+    Cow::Owned(preprocess::detect_edges(
+      target_gray.clone(),
+      target_thresholds,
+      config,
+    ))
+  } else {
+    Cow::Borrowed(target_gray)
+  };
+  // 异形滑块：清除透明区域内的像素
+  let target_edges = match target_mask {
+    Some(mask) => Cow::Owned(preprocess::mask_edges(target_edges.into_owned(), mask)),
+    None => target_edges,
+  };
+
+  // 背景边缘过少（如纯色占位图）时匹配结果没有意义
+  if edge_detection {
+    let density = preprocess::edge_density(&background_edges);
+    let threshold = config
+      .edge_density_threshold
+      .unwrap_or(DEFAULT_EDGE_DENSITY_THRESHOLD);
+    if (density as f64) < threshold {
+      return Err(SlideMatchError::InsufficientEdges { density }.into());
+    }
+  }
+  // 只保留掩码内的背景像素参与匹配
+  let background_edges = match region.mask {
+    Some(mask) => {
+      let mask = match region.rows {
+        Some((start, height)) => Cow::Owned(crop_rows(mask, start, height)),
+        None => Cow::Borrowed(mask),
+      };
+      Cow::Owned(preprocess::mask_edges(background_edges.into_owned(), &mask))
+    }
+    None => background_edges,
+  };
+  // 清除背景图中滑块本身所在的区域
+  let background_edges = match &region.ignore {
+    Some(ignore) => Cow::Owned(clear_region(
+      background_edges.into_owned(),
+      ignore,
+      region.rows.map_or(0, |(start, _)| start),
+    )),
+    None => background_edges,
+  };
+
+  // `background_padding`：在背景四周补 0，使目标图片可以部分超出背景；只搜索固定行时不在上下补
+  // 超出目标图片尺寸的部分不会与模板重合，最多补到目标图片尺寸 - 1
+  let padding = config.background_padding.unwrap_or(0);
+  let pad_x = padding.min(target_edges.width() - 1);
+  let pad_y = match region.rows {
+    Some(_) => 0,
+    None => padding.min(target_edges.height() - 1),
+  };
+
+  let scores = if let (Some(bins), Some(background_orientations)) =
+    (orientation_bins, background_orientations)
+  {
+    let target_orientations = orientation::orientation_bins(target_gray, bins);
+    orientation::match_orientation(
+      &pad_image(
+        &orientation::edge_orientations(&background_orientations, &background_edges),
+        pad_x,
+        pad_y,
+      ),
+      &orientation::edge_orientations(&target_orientations, &target_edges),
+    )
+  } else {
+    let background_edges = pad_image(&background_edges, pad_x, pad_y);
+    if edge_detection && config.strategy.unwrap_or_default() == MatchStrategy::Chamfer {
+      chamfer::match_chamfer(&background_edges, &target_edges)
+    } else {
+      match config.stride.filter(|&stride| stride > 1) {
+        Some(stride) => ncc::match_template_strided(&background_edges, &target_edges, stride),
+        None => ncc::match_template_normalized(&background_edges, &target_edges),
+      }
+    }
+  };
+
+  // 左侧和上方超出背景的位置无法用无符号坐标表示，去掉后得分图原点与背景图原点重合
+  if pad_x == 0 && pad_y == 0 {
+    return Ok(scores);
+  }
+  let (width, height) = scores.dimensions();
+  Ok(image::imageops::crop_imm(&scores, pad_x, pad_y, width - pad_x, height - pad_y).to_image())
+}
+
+// 在图片四周补 0：左右各 `pad_x` 列，上下各 `pad_y` 行
+fn pad_image(img: &GrayImage, pad_x: u32, pad_y: u32) -> Cow<'_, GrayImage> {
+  if pad_x == 0 && pad_y == 0 {
+    return Cow::Borrowed(img);
+  }
+  let mut padded = GrayImage::new(img.width() + 2 * pad_x, img.height() + 2 * pad_y);
+  image::imageops::replace(&mut padded, img, pad_x as i64, pad_y as i64);
+  Cow::Owned(padded)
+}
+
+// 模板匹配的结果
+struct MatchScores {
+  // 得分极值，位置为背景图坐标
+  extremes: imageproc::template_matching::Extremes<f32>,
+  // 每个 x 上所有 y 的最大得分，仅在 `return_score_profile` 时计算
+  profile: Option<Vec<f64>>,
+  // 完整得分图，仅在 `return_heatmap` 时返回
+  heatmap: Option<ScoreHeatmap>,
+  // 次优峰值 (x, y, 得分)，位置为背景图坐标，仅在设置了 `ambiguity_margin` 时计算
+  runner_up: Option<(u32, u32, f32)>,
+  // 峰值旁瓣比，仅在 `return_psr` 时计算
+  psr: Option<f64>,
+}
+
+// 次优峰值：抑制与最优位置重叠（相距不到模板尺寸）的所有位置后，得分图中的最高得分
+// 得分相同时与 `ncc::find_extremes` 一样取最左侧的位置（x 最小，x 相同时 y 最小）
+fn runner_up(
+  scores: &imageproc::definitions::Image<image::Luma<f32>>,
+  (best_x, best_y): (u32, u32),
+  (target_width, target_height): (u32, u32),
+) -> Option<(u32, u32, f32)> {
+  scores
+    .enumerate_pixels()
+    .filter(|&(x, y, _)| x.abs_diff(best_x) >= target_width || y.abs_diff(best_y) >= target_height)
+    .map(|(x, y, score)| (x, y, score[0]))
+    .fold(None, |best, candidate| match best {
+      Some(best) if !is_better_peak(candidate, best) => Some(best),
+      _ => Some(candidate),
+    })
+}
+
+// `candidate` 是否优于 `best`：得分更高，或得分相同且位置更靠左（x 相同时更靠上）
+pub(crate) fn is_better_peak(candidate: (u32, u32, f32), best: (u32, u32, f32)) -> bool {
+  candidate.2 > best.2 || candidate.2 == best.2 && (candidate.0, candidate.1) < (best.0, best.1)
+}
+
+// 计算峰值旁瓣比时排除的峰值邻域半径，即以峰值为中心的 11x11 窗口
+// 边缘图的得分图在远离缺口处几乎全为 0，排除整个模板尺寸会使旁瓣标准差趋近于 0
+const PSR_EXCLUSION_RADIUS: u32 = 5;
+
+// 峰值旁瓣比：(峰值 - 旁瓣均值) / 旁瓣标准差，旁瓣为峰值邻域之外的所有位置
+// 没有旁瓣或旁瓣标准差为 0（比值无意义）时返回 None
+fn peak_to_sidelobe_ratio(
+  scores: &imageproc::definitions::Image<image::Luma<f32>>,
+  (best_x, best_y): (u32, u32),
+  peak: f32,
+) -> Option<f64> {
+  let sidelobe = || {
+    scores
+      .enumerate_pixels()
+      .filter(|&(x, y, _)| {
+        x.abs_diff(best_x) > PSR_EXCLUSION_RADIUS || y.abs_diff(best_y) > PSR_EXCLUSION_RADIUS
+      })
+      .map(|(_, _, score)| score[0] as f64)
+  };
 
-  // 计算图像均值
-  let sum: f32 = img.iter().map(|&p| p as f32).sum();
-  let mean = sum / total_pixels;
+  let (count, sum) = sidelobe().fold((0usize, 0.0), |(count, sum), score| {
+    (count + 1, sum + score)
+  });
+  if count == 0 {
+    return None;
+  }
+  let mean = sum / count as f64;
+  // 先求均值再求方差，旁瓣得分全部相同时方差恰好为 0
+  let variance = sidelobe().map(|score| (score - mean).powi(2)).sum::<f64>() / count as f64;
+  let std_dev = variance.sqrt();
+  (std_dev > 0.0).then(|| (peak as f64 - mean) / std_dev)
+}
 
-  // 计算标准差
-  let variance: f32 = img
-    .iter()
-    .map(|&p| {
-      let diff = p as f32 - mean;
-      diff * diff
-    })
-    .sum();
-  let std_dev = (variance / total_pixels).sqrt();
+// 按 `ambiguityMargin` 比较最优与次优峰值并记录到结果中；`convert` 为把得分图上的位置换算到
+// 返回坐标系的函数（与最优结果相同），用于换算次优峰值的 x 坐标
+fn with_ambiguity(
+  bbox: SlideBBox,
+  best_score: f32,
+  runner_up: Option<(u32, u32, f32)>,
+  (target_width, target_height): (u32, u32),
+  config: &SlideMatchConfig,
+  convert: impl Fn(SlideBBox) -> SlideBBox,
+) -> SlideBBox {
+  let Some(margin) = config.ambiguity_margin else {
+    return bbox;
+  };
+  let Some((x, y, score)) = runner_up else {
+    // 得分图中没有与最优位置不重叠的位置
+    return SlideBBox {
+      is_ambiguous: Some(false),
+      ..bbox
+    };
+  };
 
-  // 自适应设置阈值
-  // low_threshold: 均值 - 标准差
-  // high_threshold: 均值 + 2*标准差
-  let low_threshold = (mean - std_dev).max(0.0);
-  let high_threshold = (mean + std_dev * 2.0).min(255.0);
+  let second = convert(SlideBBox {
+    x1: x,
+    y1: y,
+    x2: x + target_width,
+    y2: y + target_height,
+    ..SlideBBox::from_center(0, 0, 0, 0)
+  });
+  SlideBBox {
+    is_ambiguous: Some(score as f64 >= best_score as f64 * (1.0 - margin)),
+    second_best_score: Some(score as f64),
+    second_best_x: Some(second.x1),
+    ..bbox
+  }
+}
 
-  // 确保阈值在合理范围内
-  (low_threshold.max(50.0), high_threshold.min(250.0))
+// 由模板匹配结果构造 SlideBBox，位置为匹配用的背景图坐标，经 `convert` 换算到返回的坐标系
+// （`convert` 同时负责填写目标图片的裁剪起点），并按 `ambiguityMargin` 标记次优峰值
+pub(crate) fn scores_to_bbox(
+  result: MatchScores,
+  confidence: Option<f64>,
+  metadata: Option<MatchMetadata>,
+  (target_width, target_height): (u32, u32),
+  config: &SlideMatchConfig,
+  convert: impl Fn(SlideBBox) -> SlideBBox,
+) -> SlideBBox {
+  let (x, y) = result.extremes.max_value_location;
+  let bbox = convert(SlideBBox {
+    target_x: 0,
+    target_y: 0,
+    x1: x,
+    y1: y,
+    x2: x + target_width,
+    y2: y + target_height,
+    confidence,
+    metadata,
+    score_profile: result.profile,
+    heatmap: result.heatmap,
+    coordinate_mode: None,
+    ratio: None,
+    target_scale: None,
+    is_ambiguous: None,
+    second_best_score: None,
+    second_best_x: None,
+    psr: result.psr,
+    slide_distance: None,
+    degenerate_target: None,
+    elapsed_ms: None,
+  });
+  with_ambiguity(
+    bbox,
+    result.extremes.max_value,
+    result.runner_up,
+    (target_width, target_height),
+    config,
+    convert,
+  )
 }
 
-// 改进算法2: 置信度验证
-fn validate_match_result(max_value: f32, confidence_threshold: f32) -> bool {
-  max_value > confidence_threshold
+// 得分图按列取最大值
+fn column_max(scores: &imageproc::definitions::Image<image::Luma<f32>>) -> Vec<f64> {
+  let mut profile = vec![f64::NEG_INFINITY; scores.width() as usize];
+  for (x, _, score) in scores.enumerate_pixels() {
+    let max = &mut profile[x as usize];
+    *max = max.max(score[0] as f64);
+  }
+  profile
 }
 
-// 滑块匹配函数（带透明背景裁剪）
-fn slide_match_internal(target_image: &[u8], background_image: &[u8]) -> anyhow::Result<SlideBBox> {
-  let target_image = image::load_from_memory(target_image).context("无法加载目标图片")?;
-  let background_image = image::load_from_memory(background_image).context("无法加载背景图片")?;
+// 模板匹配得分图
+pub(crate) struct ScoreMap {
+  // 归一化互相关得分
+  pub(crate) scores: imageproc::definitions::Image<image::Luma<f32>>,
+  // 得分图第 0 行对应的背景图行（只搜索固定行时不为 0）
+  pub(crate) row_offset: u32,
+}
 
+// 对两张图片的所有通道执行边缘检测 + 模板匹配，返回匹配得分图
+// RGB 模式下取三个通道得分的平均值，得分范围与灰度模式一致
+pub(crate) fn score_map(
+  target: &MatchImage,
+  background: &MatchImage,
+  target_thresholds: (f32, f32),
+  background_thresholds: (f32, f32),
+  target_mask: Option<&GrayImage>,
+  background_mask: Option<&GrayImage>,
+  config: &SlideMatchConfig,
+) -> anyhow::Result<ScoreMap> {
+  // 自适应阈值保证低阈值小于高阈值，显式指定的阈值在覆盖时已校验
+  debug_assert!(target_thresholds.0 < target_thresholds.1);
+  debug_assert!(background_thresholds.0 < background_thresholds.1);
   anyhow::ensure!(
-    background_image.width() >= target_image.width(),
-    "背景图片的宽度必须大于等于目标图片的宽度"
+    config.axis.unwrap_or_default() == SlideAxis::X,
+    "该函数不支持 axis 为 y"
   );
 
-  anyhow::ensure!(
-    background_image.height() >= target_image.height(),
-    "背景图片的高度必须大于等于目标图片的高度"
+  let config = &*preprocess::resolve_canny_sigma(config, background.width(), background.height());
+  let region = SearchRegion {
+    mask: background_mask,
+    rows: search_rows(target.height(), background.height(), config)?,
+    ignore: ignored_region(config, target.width(), background.height()),
+  };
+
+  // 启用缓存时直接使用缓存的背景边缘图
+  // 按梯度方向匹配时需要背景灰度图，不使用缓存
+  let edge_detection = matches!(config.edge_mode.unwrap_or_default(), EdgeMode::Canny)
+    && config.strategy.unwrap_or_default() != MatchStrategy::Orientation;
+  let cached_edges = background.cache_key.filter(|_| edge_detection).map(|key| {
+    cache::BackgroundCache::global()
+      .get_or_insert_with(cache::edges_key(key, background_thresholds), || {
+        cache::BackgroundEdges::detect(background, background_thresholds, config)
+      })
+  });
+  let background_channels: Vec<_> = match &cached_edges {
+    Some(edges) => edges
+      .channels()
+      .iter()
+      .map(BackgroundChannel::Edges)
+      .collect(),
+    None => background
+      .channels()
+      .iter()
+      .map(BackgroundChannel::Gray)
+      .collect(),
+  };
+
+  let mut scores = target.channels().iter().zip(background_channels).map(
+    |(target_channel, background_channel)| {
+      match_channel(
+        target_channel,
+        background_channel,
+        target_thresholds,
+        background_thresholds,
+        target_mask,
+        &region,
+        config,
+      )
+    },
   );
 
-  let target_image = target_image.to_rgba8();
+  let mut combined = scores.next().expect("至少有一个通道参与匹配")?;
+  let mut count = 1.0;
+  for score in scores {
+    let score = score?;
+    for (total, value) in combined.iter_mut().zip(score.iter()) {
+      *total += value;
+    }
+    count += 1.0;
+  }
+  if count > 1.0 {
+    combined.iter_mut().for_each(|total| *total /= count);
+  }
 
-  // 裁剪图片，只保留不透明部分
-  let width = target_image.width();
-  let height = target_image.height();
-  let mut start_x = width;
-  let mut start_y = height;
-  let mut end_x = 0;
-  let mut end_y = 0;
+  Ok(ScoreMap {
+    scores: combined,
+    row_offset: region.rows.map_or(0, |(start, _)| start),
+  })
+}
 
-  for x in 0..width {
-    for y in 0..height {
-      let p = target_image.get_pixel(x, y);
+// 对两张图片执行模板匹配，返回匹配得分的极值
+pub(crate) fn match_gray_images(
+  target: &MatchImage,
+  background: &MatchImage,
+  target_thresholds: (f32, f32),
+  background_thresholds: (f32, f32),
+  target_mask: Option<&GrayImage>,
+  background_mask: Option<&GrayImage>,
+  config: &SlideMatchConfig,
+) -> anyhow::Result<MatchScores> {
+  // 竖直滑动时在转置后的图片上沿 x 方向匹配，得到的位置再交换回来
+  let vertical = config.axis.unwrap_or_default() == SlideAxis::Y;
+  let ScoreMap {
+    scores: combined,
+    row_offset,
+  } = if vertical {
+    let transposed = transposed_config(config, target.width(), background.width())?;
+    score_map(
+      &target.transposed(),
+      &background.transposed(),
+      target_thresholds,
+      background_thresholds,
+      target_mask.map(preprocess::transpose).as_ref(),
+      background_mask.map(preprocess::transpose).as_ref(),
+      &transposed,
+    )?
+  } else {
+    score_map(
+      target,
+      background,
+      target_thresholds,
+      background_thresholds,
+      target_mask,
+      background_mask,
+      config,
+    )?
+  };
 
-      if p[3] != 0 {
-        if x < start_x {
-          start_x = x;
-        }
+  // 得分图中目标图片的尺寸
+  let target_size = if vertical {
+    (target.height(), target.width())
+  } else {
+    (target.width(), target.height())
+  };
+  let mut extremes = ncc::find_extremes(&combined);
+  let runner_up = config
+    .ambiguity_margin
+    .and_then(|_| runner_up(&combined, extremes.max_value_location, target_size))
+    .map(|(x, y, score)| (x, y + row_offset, score));
+  let psr = config
+    .return_psr
+    .unwrap_or(false)
+    .then(|| peak_to_sidelobe_ratio(&combined, extremes.max_value_location, extremes.max_value))
+    .flatten();
+  // 换算回背景图坐标
+  extremes.max_value_location.1 += row_offset;
+  extremes.min_value_location.1 += row_offset;
+
+  let profile = config
+    .return_score_profile
+    .unwrap_or(false)
+    .then(|| column_max(&combined));
+
+  let (combined, runner_up) = if vertical {
+    let swap = |(x, y)| (y, x);
+    extremes.max_value_location = swap(extremes.max_value_location);
+    extremes.min_value_location = swap(extremes.min_value_location);
+    (
+      preprocess::transpose(&combined),
+      runner_up.map(|(x, y, score)| (y, x, score)),
+    )
+  } else {
+    (combined, runner_up)
+  };
 
-        if y < start_y {
-          start_y = y;
-        }
+  // 直接移交得分图的内存，不逐元素复制
+  let heatmap = config
+    .return_heatmap
+    .unwrap_or(false)
+    .then(|| ScoreHeatmap {
+      width: combined.width(),
+      height: combined.height(),
+      data: Float32Array::new(combined.into_raw()),
+    });
 
-        if x > end_x {
-          end_x = x;
-        }
+  Ok(MatchScores {
+    extremes,
+    profile,
+    heatmap,
+    runner_up,
+    psr,
+  })
+}
 
-        if y > end_y {
-          end_y = y;
-        }
-      }
+// 裁剪掉四周完全透明的像素，返回裁剪后的图片和裁剪起点 (x, y)
+// 没有任何不透明的像素时返回原图，裁剪起点为 (宽度, 高度)
+pub(crate) fn crop_transparent(img: image::RgbaImage) -> (image::RgbaImage, (u32, u32)) {
+  match crop::opaque_bounds(&img, crop::DEFAULT_ALPHA_THRESHOLD) {
+    Some((x, y, width, height)) => (
+      image::imageops::crop_imm(&img, x, y, width, height).to_image(),
+      (x, y),
+    ),
+    None => {
+      let (width, height) = img.dimensions();
+      (img, (width, height))
     }
   }
+}
+
+// 改进算法2: 置信度验证
+fn validate_match_result(max_value: f32, confidence_threshold: f32) -> bool {
+  max_value > confidence_threshold
+}
+
+// 原版算法的置信度验证：设置 `confidenceThreshold` 时返回得分峰值，峰值不高于阈值时报错
+fn check_confidence(max_value: f32, config: &SlideMatchConfig) -> anyhow::Result<Option<f64>> {
+  let Some(threshold) = config.confidence_threshold else {
+    return Ok(None);
+  };
+  if !validate_match_result(max_value, threshold as f32) {
+    return Err(
+      SlideMatchError::LowConfidence {
+        confidence: max_value as f64,
+        threshold,
+      }
+      .into(),
+    );
+  }
+  Ok(Some(max_value as f64))
+}
+
+/// 滑块匹配函数（带透明背景裁剪），`slideMatch` 的 Rust 实现，供基准测试直接调用
+pub fn slide_match_internal(
+  target_image: &[u8],
+  background_image: &[u8],
+  background_mask: Option<&[u8]>,
+  config: &SlideMatchConfig,
+) -> anyhow::Result<SlideBBox> {
+  let start = start_timer(config);
+  validate_config(config)?;
+
+  let (target_image, degenerate) = decode::load_target(target_image, "目标图片", config)?;
+  let background_key = cache::background_key(background_image, config);
+  let background_image = decode::load_background(background_image, config)?;
+
+  // 背景掩码：与背景图片同尺寸的单通道灰度图，值为 0 的像素不参与匹配
+  let background_mask = background_mask
+    .map(|mask| -> anyhow::Result<GrayImage> {
+      let mask = decode::load_image(mask, "掩码图片", config)?.to_luma8();
+      anyhow::ensure!(
+        mask.width() == background_image.width() && mask.height() == background_image.height(),
+        "掩码图片的尺寸必须与背景图片一致（掩码 {}x{}，背景 {}x{}）",
+        mask.width(),
+        mask.height(),
+        background_image.width(),
+        background_image.height()
+      );
+      Ok(mask)
+    })
+    .transpose()?;
+
+  slide_match_decoded(
+    target_image,
+    background_image,
+    background_key,
+    background_mask.as_ref(),
+    config,
+  )
+  .map(|bbox| mark_elapsed(mark_degenerate(bbox, degenerate), start))
+}
+
+// `fastSmall` 生效的目标图片像素数上限（不含）
+const FAST_SMALL_MAX_PIXELS: u32 = 2500;
+
+// `fastSmall`：目标图片很小时边缘数据很少，高斯模糊 + Canny 的开销不划算，改为直接匹配灰度图
+pub(crate) fn small_target_config<'a>(
+  target: &MatchImage,
+  config: &'a SlideMatchConfig,
+) -> Cow<'a, SlideMatchConfig> {
+  if config.fast_small.unwrap_or(false)
+    && (target.width() as u64 * target.height() as u64) < FAST_SMALL_MAX_PIXELS as u64
+  {
+    Cow::Owned(SlideMatchConfig {
+      edge_mode: Some(EdgeMode::None),
+      ..config.clone()
+    })
+  } else {
+    Cow::Borrowed(config)
+  }
+}
 
-  let cropped_image = if start_x > end_x || start_y > end_y {
-    // 没有任何不透明的像素
-    target_image
+// 裁剪目标图片的透明背景并转换为参与匹配的图片，返回图片、透明度掩码和裁剪起点 (x, y)
+pub(crate) fn prepare_target(
+  target_image: image::DynamicImage,
+  config: &SlideMatchConfig,
+) -> (MatchImage, Option<GrayImage>, (u32, u32)) {
+  let target_config = preprocess::target_preprocess_config(config);
+  if target_image.as_luma8().is_some() {
+    // 8 位灰度图没有透明通道，不需要裁剪和透明度掩码，直接用作灰度图，
+    // 省去转换为 RGBA 再转回灰度图的两次分配
+    (
+      MatchImage::from_dynamic(target_image, &target_config),
+      None,
+      (0, 0),
+    )
   } else {
-    image::imageops::crop_imm(
-      &target_image,
-      start_x,
-      start_y,
-      end_x - start_x + 1,
-      end_y - start_y + 1,
+    // 裁剪图片，只保留不透明部分
+    let (cropped_image, offset) = crop_transparent(target_image.into_rgba8());
+    // 透明度掩码，用于清除异形滑块透明区域内的边缘
+    let target_mask = preprocess::alpha_mask(&cropped_image, preprocess::ALPHA_MASK_THRESHOLD);
+    // 图片转换到灰度图
+    (
+      MatchImage::from_rgba(&cropped_image, &target_config),
+      Some(target_mask),
+      offset,
     )
-    .to_image()
-  };
+  }
+}
 
-  // 图片转换到灰度图
-  let target_image = image::imageops::grayscale(&cropped_image);
+// 按 `allowDegenerateTarget` 继续匹配时，在结果中标记目标图片的不透明像素不足
+fn mark_degenerate(bbox: SlideBBox, degenerate: bool) -> SlideBBox {
+  SlideBBox {
+    degenerate_target: degenerate.then_some(true),
+    ..bbox
+  }
+}
 
-  // 使用 canny 进行边缘检测
-  let target_image = imageproc::edges::canny(&target_image, 100.0, 200.0);
-  let background_image = imageproc::edges::canny(&background_image.to_luma8(), 100.0, 200.0);
+// `measureTime` 为 true 时开始计时
+fn start_timer(config: &SlideMatchConfig) -> Option<Instant> {
+  config.measure_time.unwrap_or(false).then(Instant::now)
+}
 
-  // 模板匹配
-  let result =
-    imageproc::template_matching::find_extremes(&imageproc::template_matching::match_template(
-      &background_image,
-      &target_image,
-      imageproc::template_matching::MatchTemplateMethod::CrossCorrelationNormalized,
-    ));
-
-  Ok(SlideBBox {
-    target_x: start_x,
-    target_y: start_y,
-    x1: result.max_value_location.0,
-    y1: result.max_value_location.1,
-    x2: result.max_value_location.0 + target_image.width(),
-    y2: result.max_value_location.1 + target_image.height(),
-  })
+// 在结果中记录从 `start_timer` 开始的耗时（毫秒）
+fn mark_elapsed(bbox: SlideBBox, start: Option<Instant>) -> SlideBBox {
+  SlideBBox {
+    elapsed_ms: start.map(|start| start.elapsed().as_secs_f64() * 1000.0),
+    ..bbox
+  }
 }
 
-// 简单滑块匹配函数（无透明背景裁剪）
-fn simple_slide_match_internal(
-  target_image: &[u8],
-  background_image: &[u8],
+// 在已解码的图片上执行滑块匹配（带透明背景裁剪），配置需已校验
+pub(crate) fn slide_match_decoded(
+  target_image: image::DynamicImage,
+  background_image: image::DynamicImage,
+  background_key: Option<u64>,
+  background_mask: Option<&GrayImage>,
+  config: &SlideMatchConfig,
 ) -> anyhow::Result<SlideBBox> {
-  let target_image = image::load_from_memory(target_image).context("无法加载目标图片")?;
-  let background_image = image::load_from_memory(background_image).context("无法加载背景图片")?;
+  slide_match_decoded_with_peak(
+    target_image,
+    background_image,
+    background_key,
+    background_mask,
+    config,
+  )
+  .map(|(bbox, _)| bbox)
+}
 
-  anyhow::ensure!(
-    background_image.width() >= target_image.width(),
-    "背景图片的宽度必须大于等于目标图片的宽度"
-  );
+// 同 `slide_match_decoded`，同时返回得分峰值：未设置 `confidenceThreshold` 时结果中没有 `confidence`
+fn slide_match_decoded_with_peak(
+  target_image: image::DynamicImage,
+  background_image: image::DynamicImage,
+  background_key: Option<u64>,
+  background_mask: Option<&GrayImage>,
+  config: &SlideMatchConfig,
+) -> anyhow::Result<(SlideBBox, f32)> {
+  // 原始尺寸，用于换算显示坐标
+  let target_size = (target_image.width(), target_image.height());
+
+  // 按 `backgroundTargetWidth` 重采样背景图片，目标图片大于背景图片时按 `oversizePolicy` 报错或缩放其中一张
+  let (target_image, background_image, fit) =
+    oversize::fit_images(target_image, background_image, config)?;
+  let background_key = fit.cache_key(background_key);
+  let fitted_config = fit.config(config);
+  let config = &*fitted_config;
+  let background_mask = background_mask.map(|mask| fit.background_mask(mask));
+
+  if config.strategy.unwrap_or_default() == MatchStrategy::Contour {
+    let (cropped_image, (start_x, start_y)) = crop_transparent(target_image.into_rgba8());
+    let background = MatchImage::from_dynamic(background_image, config);
+    let background_thresholds =
+      background_canny_thresholds(original_canny_thresholds(&background, config), config)?;
+    let background_edges =
+      preprocess::detect_background_edges(background.gray, background_thresholds, config);
+    let background_edges = match &background_mask {
+      Some(mask) => preprocess::mask_edges(background_edges, mask),
+      None => background_edges,
+    };
+
+    let found = contour::match_contour(&cropped_image, &background_edges)?;
+    let peak = found.score as f32;
+    let confidence = check_confidence(peak, config)?;
+    let bbox = SlideBBox {
+      target_x: start_x,
+      target_y: start_y,
+      x1: found.x,
+      y1: found.y,
+      x2: found.x + cropped_image.width(),
+      y2: found.y + cropped_image.height(),
+      confidence,
+      ..SlideBBox::from_center(0, 0, 0, 0)
+    };
+    let bbox = coordinates::to_display_coordinates(
+      fit.restore(bbox),
+      fit.output_size(),
+      target_size,
+      config,
+    );
+    return Ok((bbox, peak));
+  }
 
-  anyhow::ensure!(
-    background_image.height() >= target_image.height(),
-    "背景图片的高度必须大于等于目标图片的高度"
+  let (target, target_mask, (start_x, start_y)) = prepare_target(target_image, config);
+  let small_config = small_target_config(&target, config);
+  let config = &*small_config;
+  let background =
+    MatchImage::from_dynamic(background_image, config).with_cache_key(background_key);
+
+  // 使用固定阈值（可被配置覆盖）进行边缘检测 + 模板匹配
+  let result = match_gray_images(
+    &target,
+    &background,
+    target_canny_thresholds(original_canny_thresholds(&target, config), config)?,
+    background_canny_thresholds(original_canny_thresholds(&background, config), config)?,
+    target_mask.as_ref(),
+    background_mask.as_deref(),
+    config,
+  )?;
+  let peak = result.extremes.max_value;
+  let confidence = check_confidence(peak, config)?;
+
+  // 次优峰值等位置同样相对目标图片的裁剪起点换算
+  let convert = |bbox| {
+    coordinates::to_display_coordinates(
+      fit.restore(SlideBBox {
+        target_x: start_x,
+        target_y: start_y,
+        ..bbox
+      }),
+      fit.output_size(),
+      target_size,
+      config,
+    )
+  };
+  let bbox = scores_to_bbox(
+    result,
+    confidence,
+    None,
+    (target.width(), target.height()),
+    config,
+    convert,
   );
+  Ok((bbox, peak))
+}
 
-  // 使用 canny 进行边缘检测
-  let target_image = imageproc::edges::canny(&target_image.to_luma8(), 100.0, 200.0);
-  let background_image = imageproc::edges::canny(&background_image.to_luma8(), 100.0, 200.0);
+/// 简单滑块匹配函数（无透明背景裁剪），`simpleSlideMatch` 的 Rust 实现
+pub fn simple_slide_match_internal(
+  target_image: &[u8],
+  background_image: &[u8],
+  config: &SlideMatchConfig,
+) -> anyhow::Result<SlideBBox> {
+  let start = start_timer(config);
+  validate_config(config)?;
 
-  // 模板匹配
-  let result =
-    imageproc::template_matching::find_extremes(&imageproc::template_matching::match_template(
-      &background_image,
-      &target_image,
-      imageproc::template_matching::MatchTemplateMethod::CrossCorrelationNormalized,
-    ));
+  let (target_image, degenerate) = decode::load_target(target_image, "目标图片", config)?;
+  let background_key = cache::background_key(background_image, config);
+  let background_image = decode::load_background(background_image, config)?;
 
-  Ok(SlideBBox {
-    target_x: 0,
-    target_y: 0,
-    x1: result.max_value_location.0,
-    y1: result.max_value_location.1,
-    x2: result.max_value_location.0 + target_image.width(),
-    y2: result.max_value_location.1 + target_image.height(),
-  })
+  // 原始尺寸，用于换算显示坐标
+  let target_size = (target_image.width(), target_image.height());
+
+  // 按 `backgroundTargetWidth` 重采样背景图片，目标图片大于背景图片时按 `oversizePolicy` 报错或缩放其中一张
+  let (target_image, background_image, fit) =
+    oversize::fit_images(target_image, background_image, config)?;
+  let background_key = fit.cache_key(background_key);
+  let fitted_config = fit.config(config);
+  let config = &*fitted_config;
+
+  // 图片转换到灰度图
+  let target =
+    MatchImage::from_dynamic(target_image, &preprocess::target_preprocess_config(config));
+  let background =
+    MatchImage::from_dynamic(background_image, config).with_cache_key(background_key);
+
+  // 使用固定阈值（可被配置覆盖）进行边缘检测 + 模板匹配
+  let result = match_gray_images(
+    &target,
+    &background,
+    target_canny_thresholds(original_canny_thresholds(&target, config), config)?,
+    background_canny_thresholds(original_canny_thresholds(&background, config), config)?,
+    None,
+    None,
+    config,
+  )?;
+  let confidence = check_confidence(result.extremes.max_value, config)?;
+
+  let convert = |bbox| {
+    coordinates::to_display_coordinates(fit.restore(bbox), fit.output_size(), target_size, config)
+  };
+  let bbox = scores_to_bbox(
+    result,
+    confidence,
+    None,
+    (target.width(), target.height()),
+    config,
+    convert,
+  );
+  Ok(mark_elapsed(mark_degenerate(bbox, degenerate), start))
 }
 
 /// 滑块匹配（带透明背景裁剪）
 /// 接受 Buffer 参数（支持 base64 解码后的 u8 数组）
+/// 可选参数 options 见 `SlideMatchConfig`
 #[napi]
-pub fn slide_match(target_image: Buffer, background_image: Buffer) -> Result<SlideBBox> {
+pub fn slide_match(
+  target_image: Buffer,
+  background_image: Buffer,
+  options: Option<SlideMatchConfig>,
+) -> Result<SlideBBox, ErrorStatus> {
   let target_bytes = target_image.as_ref();
   let background_bytes = background_image.as_ref();
+  let config = options.unwrap_or_default();
 
-  let result = slide_match_internal(target_bytes, background_bytes)
-    .map_err(|e| Error::from_reason(format!("滑块匹配失败: {e}")))?;
+  let result = slide_match_internal(target_bytes, background_bytes, None, &config)
+    .map_err(|e| napi_error(&e, format!("滑块匹配失败: {e}")))?;
 
   Ok(result)
 }
 
-/// 简单滑块匹配（无透明背景裁剪）
-/// 接受 Buffer 参数（支持 base64 解码后的 u8 数组）
+/// 滑块匹配（带透明背景裁剪），返回有符号坐标，参数与 `slideMatch` 相同
 #[napi]
-pub fn simple_slide_match(target_image: Buffer, background_image: Buffer) -> Result<SlideBBox> {
-  let target_bytes = target_image.as_ref();
-  let background_bytes = background_image.as_ref();
+pub fn slide_match_signed(
+  target_image: Buffer,
+  background_image: Buffer,
+  options: Option<SlideMatchConfig>,
+) -> Result<SlideBBoxSigned, ErrorStatus> {
+  let config = options.unwrap_or_default();
 
-  let result = simple_slide_match_internal(target_bytes, background_bytes)
-    .map_err(|e| Error::from_reason(format!("滑块匹配失败: {e}")))?;
+  slide_match_internal(&target_image, &background_image, None, &config)
+    .map(|bbox| bbox.to_signed())
+    .map_err(|e| napi_error(&e, format!("滑块匹配失败: {e}")))
+}
 
-  Ok(result)
+/// 滑块匹配（带透明背景裁剪），返回以背景图片原始尺寸比例表示的坐标，`slideMatchNormalized` 的 Rust 实现
+///
+/// 坐标与 `coordinateUnit: 'ratio'` 时的 `ratio` 相同，不受显示尺寸和 DPR 影响；`confidence` 始终为得分峰值
+/// （包括 0），只有设置了 `confidenceThreshold` 时才检查阈值。背景图片的宽度或高度为 0 时报错。
+pub fn slide_match_normalized_internal(
+  target_image: &[u8],
+  background_image: &[u8],
+  config: &SlideMatchConfig,
+) -> anyhow::Result<SlideBBoxNormalized> {
+  validate_config(config)?;
+  let config = &SlideMatchConfig {
+    coordinate_unit: Some(CoordinateUnit::Ratio),
+    ..config.clone()
+  };
+
+  let (target_image, _) = decode::load_target(target_image, "目标图片", config)?;
+  let background_key = cache::background_key(background_image, config);
+  let background_image = decode::load_background(background_image, config)?;
+  anyhow::ensure!(
+    background_image.width() > 0 && background_image.height() > 0,
+    "背景图片的宽度和高度必须大于 0（背景 {}x{}）",
+    background_image.width(),
+    background_image.height()
+  );
+
+  let (bbox, peak) =
+    slide_match_decoded_with_peak(target_image, background_image, background_key, None, config)?;
+  let ratio = bbox
+    .ratio
+    .ok_or_else(|| anyhow::anyhow!("coordinateUnit 为 ratio 时没有返回 ratio 坐标"))?;
+  Ok(SlideBBoxNormalized {
+    x1: ratio.x1,
+    y1: ratio.y1,
+    x2: ratio.x2,
+    y2: ratio.y2,
+    confidence: peak as f64,
+  })
 }
 
-// ========== 改进算法实现 ==========
+/// 滑块匹配（带透明背景裁剪），返回 0.0-1.0 范围内、相对于背景图片原始尺寸的坐标，
+/// 便于在不同视口尺寸下换算；参数与 `slideMatch` 相同
+#[napi]
+pub fn slide_match_normalized(
+  target_image: Buffer,
+  background_image: Buffer,
+  options: Option<SlideMatchConfig>,
+) -> Result<SlideBBoxNormalized, ErrorStatus> {
+  slide_match_normalized_internal(
+    &target_image,
+    &background_image,
+    &options.unwrap_or_default(),
+  )
+  .map_err(|e| napi_error(&e, format!("滑块匹配失败: {e}")))
+}
+
+// 只取水平滑动距离时的配置：`x1` 固定为左上角、相对背景图片左上角，保证 `x1 - target_x` 是滑动距离
+fn x_offset_config(config: &SlideMatchConfig) -> SlideMatchConfig {
+  SlideMatchConfig {
+    coordinate_mode: Some(CoordinateMode::TopLeft),
+    coordinate_space: Some(CoordinateSpace::BackgroundAbsolute),
+    ..config.clone()
+  }
+}
+
+// 水平滑动距离 `x1 - target_x`，小于 0 时取 0
+fn x_offset(bbox: &SlideBBox) -> u32 {
+  bbox.x1.saturating_sub(bbox.target_x)
+}
+
+/// 只返回滑块需要水平拖动的距离（`x1 - targetX`，小于 0 时为 0），参数与 `slideMatch` 相同
+///
+/// 按 `slideMatch` 匹配，`coordinateMode` 和 `coordinateSpace` 不生效；设置了显示尺寸时返回显示尺寸下的距离
+#[napi]
+pub fn slide_x_offset(
+  target: Buffer,
+  background: Buffer,
+  options: Option<SlideMatchConfig>,
+) -> Result<u32, ErrorStatus> {
+  let config = x_offset_config(&options.unwrap_or_default());
+
+  slide_match_internal(&target, &background, None, &config)
+    .map(|bbox| x_offset(&bbox))
+    .map_err(|e| napi_error(&e, format!("滑块匹配失败: {e}")))
+}
 
-// 改进版滑块匹配函数（带透明背景裁剪 + 自适应阈值 + 置信度验证）
-// 如果改进版置信度过低，自动回退到原版算法
-fn improved_slide_match_internal(
+/// 计算目标图片放在背景图指定位置时的匹配得分，`matchAtPosition` 的 Rust 实现
+///
+/// 图片的预处理与 `slideMatch` 相同，`(x, y)` 为裁剪透明背景后的目标图片左上角在背景图中的位置，
+/// 与 `slideMatch` 返回的 `x1/y1` 同一坐标系（见 `backgroundCoordinateSpace`）。背景图只处理
+/// 目标图片所在的行（与 `fixedY` 相同），目标图片超出背景图时报错。`contour` 策略没有得分图，不支持。
+pub fn match_at_position_internal(
   target_image: &[u8],
   background_image: &[u8],
-  confidence_threshold: f32,
-) -> anyhow::Result<SlideBBox> {
-  let target_image = image::load_from_memory(target_image).context("无法加载目标图片")?;
-  let background_image = image::load_from_memory(background_image).context("无法加载背景图片")?;
-
+  x: u32,
+  y: u32,
+  config: &SlideMatchConfig,
+) -> anyhow::Result<PositionScore> {
+  validate_config(config)?;
   anyhow::ensure!(
-    background_image.width() >= target_image.width(),
-    "背景图片的宽度必须大于等于目标图片的宽度"
+    config.strategy.unwrap_or_default() != MatchStrategy::Contour,
+    "contour 策略不支持计算指定位置的得分"
   );
 
+  let (target_image, _) = decode::load_target(target_image, "目标图片", config)?;
+  let background_key = cache::background_key(background_image, config);
+  let background_image = decode::load_background(background_image, config)?;
+
+  let (target_image, background_image, fit) =
+    oversize::fit_images(target_image, background_image, config)?;
+  let background_key = fit.cache_key(background_key);
+  let fitted_config = fit.config(config);
+  let (target, target_mask, _) = prepare_target(target_image, &fitted_config);
+  let small_config = small_target_config(&target, &fitted_config);
+
+  let (x, y) = (fit.background_position(x), fit.background_position(y));
+  let (background_width, background_height) = (background_image.width(), background_image.height());
   anyhow::ensure!(
-    background_image.height() >= target_image.height(),
-    "背景图片的高度必须大于等于目标图片的高度"
+    x as u64 + target.width() as u64 <= background_width as u64
+      && y as u64 + target.height() as u64 <= background_height as u64,
+    "位置超出范围：目标图片 {}x{} 放在 ({x}, {y}) 时超出背景图片 {background_width}x{background_height}",
+    target.width(),
+    target.height()
   );
+  // 只处理目标图片所在的行
+  let config = &SlideMatchConfig {
+    fixed_y: Some(y),
+    fixed_y_tolerance: Some(0),
+    ..small_config.into_owned()
+  };
+  let background =
+    MatchImage::from_dynamic(background_image, config).with_cache_key(background_key);
+
+  let target_thresholds =
+    target_canny_thresholds(original_canny_thresholds(&target, config), config)?;
+  let background_thresholds =
+    background_canny_thresholds(original_canny_thresholds(&background, config), config)?;
+  let ScoreMap { scores, .. } = score_map(
+    &target,
+    &background,
+    target_thresholds,
+    background_thresholds,
+    target_mask.as_ref(),
+    None,
+    config,
+  )?;
+  let score = scores.get_pixel(x, 0)[0] as f64;
+
+  // 不做边缘检测时没有使用任何阈值
+  let thresholds_used = |(low, high): (f32, f32)| {
+    if matches!(config.edge_mode.unwrap_or_default(), EdgeMode::Canny) {
+      vec![low as f64, high as f64]
+    } else {
+      Vec::new()
+    }
+  };
+  Ok(PositionScore {
+    score,
+    metadata: MatchMetadata {
+      used_fallback: false,
+      threshold_strategy: config
+        .threshold_strategy
+        .unwrap_or(ThresholdStrategy::Fixed),
+      target_thresholds: thresholds_used(target_thresholds),
+      background_thresholds: thresholds_used(background_thresholds),
+      primary_confidence: score,
+      low_confidence: false,
+      low_confidence_action: None,
+      target_inverted: target.inverted,
+      background_inverted: background.inverted,
+    },
+  })
+}
+
+/// 计算目标图片放在背景图指定位置时的匹配得分，不搜索其他位置，
+/// 适合低成本地验证外部预测或缓存的坐标；参数与 `slideMatch` 相同
+#[napi]
+pub fn match_at_position(
+  target_image: Buffer,
+  background_image: Buffer,
+  x: u32,
+  y: u32,
+  options: Option<SlideMatchConfig>,
+) -> Result<PositionScore, ErrorStatus> {
+  match_at_position_internal(
+    &target_image,
+    &background_image,
+    x,
+    y,
+    &options.unwrap_or_default(),
+  )
+  .map_err(|e| napi_error(&e, format!("指定位置匹配失败: {e}")))
+}
+
+/// 带背景掩码的滑块匹配（带透明背景裁剪）
+/// 匹配前将背景边缘图中掩码值为 0 的像素清零，只在掩码内的区域搜索缺口
+///
+/// # 参数
+/// - target_image: 目标图片 Buffer
+/// - background_image: 背景图片 Buffer
+/// - mask: 掩码图片 Buffer，单通道灰度图，尺寸必须与背景图片一致
+/// - options: 可选配置，见 `SlideMatchConfig`
+#[napi]
+pub fn slide_match_with_mask(
+  target_image: Buffer,
+  background_image: Buffer,
+  mask: Buffer,
+  options: Option<SlideMatchConfig>,
+) -> Result<SlideBBox, ErrorStatus> {
+  let config = options.unwrap_or_default();
+
+  slide_match_internal(
+    target_image.as_ref(),
+    background_image.as_ref(),
+    Some(mask.as_ref()),
+    &config,
+  )
+  .map_err(|e| napi_error(&e, format!("滑块匹配失败: {e}")))
+}
 
-  let target_image = target_image.to_rgba8();
+/// 简单滑块匹配（无透明背景裁剪）
+/// 接受 Buffer 参数（支持 base64 解码后的 u8 数组）
+/// 可选参数 options 见 `SlideMatchConfig`
+#[napi]
+pub fn simple_slide_match(
+  target_image: Buffer,
+  background_image: Buffer,
+  options: Option<SlideMatchConfig>,
+) -> Result<SlideBBox, ErrorStatus> {
+  let target_bytes = target_image.as_ref();
+  let background_bytes = background_image.as_ref();
+  let config = options.unwrap_or_default();
 
-  // 裁剪图片，只保留不透明部分
-  let width = target_image.width();
-  let height = target_image.height();
-  let mut start_x = width;
-  let mut start_y = height;
-  let mut end_x = 0;
-  let mut end_y = 0;
+  let result = simple_slide_match_internal(target_bytes, background_bytes, &config)
+    .map_err(|e| napi_error(&e, format!("滑块匹配失败: {e}")))?;
 
-  for x in 0..width {
-    for y in 0..height {
-      let p = target_image.get_pixel(x, y);
+  Ok(result)
+}
 
-      if p[3] != 0 {
-        if x < start_x {
-          start_x = x;
-        }
+// ========== 改进算法实现 ==========
 
-        if y < start_y {
-          start_y = y;
-        }
+// 改进版匹配中的一次尝试，位置为背景图坐标
+struct Attempt {
+  strategy: &'static str,
+  score: f32,
+  location: (u32, u32),
+}
 
-        if x > end_x {
-          end_x = x;
-        }
+impl Attempt {
+  fn new(strategy: &'static str, scores: &MatchScores) -> Self {
+    Self {
+      strategy,
+      score: scores.extremes.max_value,
+      location: scores.extremes.max_value_location,
+    }
+  }
+}
 
-        if y > end_y {
-          end_y = y;
-        }
+// 自适应阈值匹配 + 置信度验证，置信度过低时按 `on_low_confidence` 回退到原版算法（固定阈值100, 200）、直接返回或报错
+// 灰度图在两次尝试间复用，回退时只重新执行边缘检测和模板匹配
+pub(crate) fn improved_match_gray_images(
+  target: &MatchImage,
+  background: &MatchImage,
+  confidence_threshold: f32,
+  config: &SlideMatchConfig,
+) -> anyhow::Result<(MatchScores, MatchMetadata, Vec<Attempt>)> {
+  let target_thresholds =
+    target_canny_thresholds(adaptive_canny_thresholds(target, config), config)?;
+  let background_thresholds =
+    background_canny_thresholds(adaptive_canny_thresholds(background, config), config)?;
+  let primary = match_gray_images(
+    target,
+    background,
+    target_thresholds,
+    background_thresholds,
+    None,
+    None,
+    config,
+  )?;
+  let primary_confidence = primary.extremes.max_value as f64;
+  let mut attempts = vec![Attempt::new("adaptive", &primary)];
+
+  let fallback_target_thresholds = target_canny_thresholds(FIXED_CANNY_THRESHOLDS, config)?;
+  let fallback_background_thresholds = background_canny_thresholds(FIXED_CANNY_THRESHOLDS, config)?;
+
+  // 阈值被配置完全覆盖时，回退使用的阈值与首次尝试相同，无需重复匹配；
+  // 不做边缘检测时阈值不影响结果，同样无需回退
+  let edge_detection = matches!(config.edge_mode.unwrap_or_default(), EdgeMode::Canny);
+  let fallback_is_identical = !edge_detection
+    || (fallback_target_thresholds == target_thresholds
+      && fallback_background_thresholds == background_thresholds);
+
+  let confident = validate_match_result(primary.extremes.max_value, confidence_threshold);
+  let action = config.on_low_confidence.unwrap_or_default();
+  if !confident && action == LowConfidenceAction::Error {
+    return Err(
+      SlideMatchError::LowConfidence {
+        confidence: primary_confidence,
+        threshold: confidence_threshold as f64,
       }
-    }
+      .into(),
+    );
   }
 
-  let cropped_image = if start_x > end_x || start_y > end_y {
-    // 没有任何不透明的像素
-    target_image
-  } else {
-    image::imageops::crop_imm(
-      &target_image,
-      start_x,
-      start_y,
-      end_x - start_x + 1,
-      end_y - start_y + 1,
-    )
-    .to_image()
+  let (result, used_fallback, target_thresholds, background_thresholds) =
+    if confident || fallback_is_identical || action == LowConfidenceAction::ReturnBest {
+      (primary, false, target_thresholds, background_thresholds)
+    } else {
+      let fallback = match_gray_images(
+        target,
+        background,
+        fallback_target_thresholds,
+        fallback_background_thresholds,
+        None,
+        None,
+        config,
+      )?;
+      attempts.push(Attempt::new("fixed", &fallback));
+      (
+        fallback,
+        true,
+        fallback_target_thresholds,
+        fallback_background_thresholds,
+      )
+    };
+
+  // 不做边缘检测时没有使用任何阈值
+  let thresholds_used = |(low, high): (f32, f32)| {
+    if edge_detection {
+      vec![low as f64, high as f64]
+    } else {
+      Vec::new()
+    }
+  };
+  let metadata = MatchMetadata {
+    used_fallback,
+    threshold_strategy: if used_fallback {
+      ThresholdStrategy::Fixed
+    } else {
+      config.threshold_strategy.unwrap_or_default()
+    },
+    target_thresholds: thresholds_used(target_thresholds),
+    background_thresholds: thresholds_used(background_thresholds),
+    primary_confidence,
+    low_confidence: !confident,
+    low_confidence_action: (!confident).then_some(action),
+    target_inverted: target.inverted,
+    background_inverted: background.inverted,
   };
 
-  // 图片转换到灰度图
-  let target_gray = image::imageops::grayscale(&cropped_image);
-  let background_gray = background_image.to_luma8();
+  Ok((result, metadata, attempts))
+}
 
-  // 使用自适应阈值进行边缘检测
-  let (target_low, target_high) = calculate_adaptive_canny_thresholds(&target_gray);
-  let (bg_low, bg_high) = calculate_adaptive_canny_thresholds(&background_gray);
+// 改进版算法的目标图片：裁剪透明背景并转换为参与匹配的图片，返回裁剪后的图片、参与匹配的图片和裁剪起点 (x, y)
+// 16 位灰度图按相同区域裁剪 16 位灰度值，用于计算自适应阈值
+pub(crate) fn prepare_improved_target(
+  target_image: image::DynamicImage,
+  config: &SlideMatchConfig,
+) -> (image::RgbaImage, MatchImage, (u32, u32)) {
+  let target_config = preprocess::target_preprocess_config(config);
+  let target_gray16 = preprocess::gray16(&target_image, &target_config);
+  let (cropped_image, (start_x, start_y)) = crop_transparent(target_image.into_rgba8());
+  let target_gray16 = target_gray16.map(|gray| {
+    let (width, height) = cropped_image.dimensions();
+    image::imageops::crop_imm(&gray, start_x, start_y, width, height).to_image()
+  });
+
+  let target = MatchImage::from_rgba(&cropped_image, &target_config).with_gray16(target_gray16);
+  (cropped_image, target, (start_x, start_y))
+}
 
-  let target_edges = imageproc::edges::canny(&target_gray, target_low, target_high);
-  let background_edges = imageproc::edges::canny(&background_gray, bg_low, bg_high);
+/// 改进版滑块匹配函数（带透明背景裁剪 + 自适应阈值 + 置信度验证），`improvedSlideMatch` 的 Rust 实现
+///
+/// 如果改进版置信度过低，自动回退到原版算法
+pub fn improved_slide_match_internal(
+  target_image: &[u8],
+  background_image: &[u8],
+  confidence_threshold: f32,
+  config: &SlideMatchConfig,
+) -> anyhow::Result<SlideBBox> {
+  improved_slide_match_verbose_internal(
+    target_image,
+    background_image,
+    confidence_threshold,
+    config,
+  )
+  .map(|verbose| verbose.bbox)
+}
 
-  // 模板匹配
-  let result =
-    imageproc::template_matching::find_extremes(&imageproc::template_matching::match_template(
-      &background_edges,
-      &target_edges,
-      imageproc::template_matching::MatchTemplateMethod::CrossCorrelationNormalized,
-    ));
-
-  // 置信度验证 - 如果置信度过低，回退到原版算法
-  if !validate_match_result(result.max_value, confidence_threshold) {
-    // 回退到原版算法（固定阈值100, 200）
-    let target_gray = image::imageops::grayscale(&cropped_image);
-    let background_gray = background_image.to_luma8();
-    let target_edges = imageproc::edges::canny(&target_gray, 100.0, 200.0);
-    let background_edges = imageproc::edges::canny(&background_gray, 100.0, 200.0);
-    let fallback_result =
-      imageproc::template_matching::find_extremes(&imageproc::template_matching::match_template(
-        &background_edges,
-        &target_edges,
-        imageproc::template_matching::MatchTemplateMethod::CrossCorrelationNormalized,
-      ));
-
-    return Ok(SlideBBox {
-      target_x: start_x,
-      target_y: start_y,
-      x1: fallback_result.max_value_location.0,
-      y1: fallback_result.max_value_location.1,
-      x2: fallback_result.max_value_location.0 + target_edges.width(),
-      y2: fallback_result.max_value_location.1 + target_edges.height(),
-    });
-  }
+/// 与 `improved_slide_match_internal` 相同，同时返回每次匹配尝试的得分和位置，
+/// `improvedSlideMatchVerbose` 的 Rust 实现
+pub fn improved_slide_match_verbose_internal(
+  target_image: &[u8],
+  background_image: &[u8],
+  confidence_threshold: f32,
+  config: &SlideMatchConfig,
+) -> anyhow::Result<SlideMatchVerbose> {
+  let start = start_timer(config);
+  validate_config(config)?;
+
+  let (target_image, degenerate) = decode::load_target(target_image, "目标图片", config)?;
+  let background_key = cache::background_key(background_image, config);
+  let background_image = decode::load_background(background_image, config)?;
+
+  // 原始尺寸，用于换算显示坐标
+  let target_size = (target_image.width(), target_image.height());
+
+  // 按 `backgroundTargetWidth` 重采样背景图片，目标图片大于背景图片时按 `oversizePolicy` 报错或缩放其中一张
+  let (target_image, background_image, fit) =
+    oversize::fit_images(target_image, background_image, config)?;
+  let background_key = fit.cache_key(background_key);
+  let fitted_config = fit.config(config);
+  let config = &*fitted_config;
+
+  // 图片转换到灰度图（只计算一次）
+  let (_, target, (start_x, start_y)) = prepare_improved_target(target_image, config);
+  let background =
+    MatchImage::from_dynamic(background_image, config).with_cache_key(background_key);
+
+  let (result, metadata, attempts) =
+    improved_match_gray_images(&target, &background, confidence_threshold, config)?;
+
+  // 次优峰值和每次尝试的位置同样相对目标图片的裁剪起点换算
+  let convert = |bbox| {
+    coordinates::to_display_coordinates(
+      fit.restore(SlideBBox {
+        target_x: start_x,
+        target_y: start_y,
+        ..bbox
+      }),
+      fit.output_size(),
+      target_size,
+      config,
+    )
+  };
+  let confidence = Some(result.extremes.max_value as f64);
+  let bbox = scores_to_bbox(
+    result,
+    confidence,
+    Some(metadata),
+    (target.width(), target.height()),
+    config,
+    convert,
+  );
+  let bbox = mark_degenerate(bbox, degenerate);
+
+  // 尝试的位置与结果一样换算到返回的坐标系
+  let attempts = attempts
+    .into_iter()
+    .map(|attempt| {
+      let (x, y) = attempt.location;
+      let location = convert(SlideBBox {
+        x1: x,
+        y1: y,
+        x2: x + target.width(),
+        y2: y + target.height(),
+        ..SlideBBox::from_center(0, 0, 0, 0)
+      });
+      MatchAttempt {
+        strategy: attempt.strategy.to_string(),
+        score: attempt.score as f64,
+        location: (location.x1, location.y1),
+      }
+    })
+    .collect();
 
-  Ok(SlideBBox {
-    target_x: start_x,
-    target_y: start_y,
-    x1: result.max_value_location.0,
-    y1: result.max_value_location.1,
-    x2: result.max_value_location.0 + target_edges.width(),
-    y2: result.max_value_location.1 + target_edges.height(),
+  Ok(SlideMatchVerbose {
+    bbox: mark_elapsed(bbox, start),
+    attempts,
   })
 }
 
 // 改进版简单滑块匹配函数（无透明背景裁剪 + 自适应阈值 + 置信度验证）
-fn improved_simple_slide_match_internal(
+pub(crate) fn improved_simple_slide_match_internal(
   target_image: &[u8],
   background_image: &[u8],
   confidence_threshold: f32,
+  config: &SlideMatchConfig,
 ) -> anyhow::Result<SlideBBox> {
-  let target_image = image::load_from_memory(target_image).context("无法加载目标图片")?;
-  let background_image = image::load_from_memory(background_image).context("无法加载背景图片")?;
-
-  anyhow::ensure!(
-    background_image.width() >= target_image.width(),
-    "背景图片的宽度必须大于等于目标图片的宽度"
-  );
-
-  anyhow::ensure!(
-    background_image.height() >= target_image.height(),
-    "背景图片的高度必须大于等于目标图片的高度"
+  let start = start_timer(config);
+  validate_config(config)?;
+
+  let (target_image, degenerate) = decode::load_target(target_image, "目标图片", config)?;
+  let background_key = cache::background_key(background_image, config);
+  let background_image = decode::load_background(background_image, config)?;
+
+  // 原始尺寸，用于换算显示坐标
+  let target_size = (target_image.width(), target_image.height());
+
+  // 按 `backgroundTargetWidth` 重采样背景图片，目标图片大于背景图片时按 `oversizePolicy` 报错或缩放其中一张
+  let (target_image, background_image, fit) =
+    oversize::fit_images(target_image, background_image, config)?;
+  let background_key = fit.cache_key(background_key);
+  let fitted_config = fit.config(config);
+  let config = &*fitted_config;
+
+  // 图片转换到灰度图（只计算一次）
+  let target =
+    MatchImage::from_dynamic(target_image, &preprocess::target_preprocess_config(config));
+  let background =
+    MatchImage::from_dynamic(background_image, config).with_cache_key(background_key);
+
+  let (result, metadata, _) =
+    improved_match_gray_images(&target, &background, confidence_threshold, config)?;
+
+  let convert = |bbox| {
+    coordinates::to_display_coordinates(fit.restore(bbox), fit.output_size(), target_size, config)
+  };
+  let confidence = Some(result.extremes.max_value as f64);
+  let bbox = scores_to_bbox(
+    result,
+    confidence,
+    Some(metadata),
+    (target.width(), target.height()),
+    config,
+    convert,
   );
-
-  let target_gray = target_image.to_luma8();
-  let background_gray = background_image.to_luma8();
-
-  // 使用自适应阈值进行边缘检测
-  let (target_low, target_high) = calculate_adaptive_canny_thresholds(&target_gray);
-  let (bg_low, bg_high) = calculate_adaptive_canny_thresholds(&background_gray);
-
-  let target_edges = imageproc::edges::canny(&target_gray, target_low, target_high);
-  let background_edges = imageproc::edges::canny(&background_gray, bg_low, bg_high);
-
-  // 模板匹配
-  let result =
-    imageproc::template_matching::find_extremes(&imageproc::template_matching::match_template(
-      &background_edges,
-      &target_edges,
-      imageproc::template_matching::MatchTemplateMethod::CrossCorrelationNormalized,
-    ));
-
-  // 置信度验证 - 如果置信度过低，回退到原版算法
-  if !validate_match_result(result.max_value, confidence_threshold) {
-    // 回退到原版算法（固定阈值100, 200）
-    let target_gray = target_image.to_luma8();
-    let background_gray = background_image.to_luma8();
-    let target_edges = imageproc::edges::canny(&target_gray, 100.0, 200.0);
-    let background_edges = imageproc::edges::canny(&background_gray, 100.0, 200.0);
-    let fallback_result =
-      imageproc::template_matching::find_extremes(&imageproc::template_matching::match_template(
-        &background_edges,
-        &target_edges,
-        imageproc::template_matching::MatchTemplateMethod::CrossCorrelationNormalized,
-      ));
-
-    return Ok(SlideBBox {
-      target_x: 0,
-      target_y: 0,
-      x1: fallback_result.max_value_location.0,
-      y1: fallback_result.max_value_location.1,
-      x2: fallback_result.max_value_location.0 + target_edges.width(),
-      y2: fallback_result.max_value_location.1 + target_edges.height(),
-    });
-  }
-
-  Ok(SlideBBox {
-    target_x: 0,
-    target_y: 0,
-    x1: result.max_value_location.0,
-    y1: result.max_value_location.1,
-    x2: result.max_value_location.0 + target_edges.width(),
-    y2: result.max_value_location.1 + target_edges.height(),
-  })
+  Ok(mark_elapsed(mark_degenerate(bbox, degenerate), start))
 }
 
 /// 改进版滑块匹配（带透明背景裁剪 + 自适应阈值 + 置信度验证）
@@ -405,28 +2696,72 @@ fn improved_simple_slide_match_internal(
 /// - target_image: 目标图片 Buffer
 /// - background_image: 背景图片 Buffer
 /// - confidence_threshold: 置信度阈值，范围 0.0-1.0，默认 0.3
+/// - options: 可选配置，见 `SlideMatchConfig`
 ///
 #[napi]
 pub fn improved_slide_match(
   target_image: Buffer,
   background_image: Buffer,
   confidence_threshold: Option<f64>,
-) -> Result<SlideBBox> {
+  options: Option<SlideMatchConfig>,
+) -> Result<SlideBBox, ErrorStatus> {
   let target_bytes = target_image.as_ref();
   let background_bytes = background_image.as_ref();
   let threshold = confidence_threshold.unwrap_or(0.3) as f32;
+  let config = options.unwrap_or_default();
 
   // 验证置信度阈值范围
   if !(0.0..=1.0).contains(&threshold) {
-    return Err(Error::from_reason("置信度阈值必须在 0.0-1.0 范围内"));
+    return Err(generic_error("置信度阈值必须在 0.0-1.0 范围内"));
   }
 
-  let result = improved_slide_match_internal(target_bytes, background_bytes, threshold)
-    .map_err(|e| Error::from_reason(format!("改进版滑块匹配失败: {e}")))?;
+  let result = improved_slide_match_internal(target_bytes, background_bytes, threshold, &config)
+    .map_err(|e| napi_error(&e, format!("改进版滑块匹配失败: {e}")))?;
 
   Ok(result)
 }
 
+/// 改进版滑块匹配，只返回滑块需要水平拖动的距离（`x1 - targetX`，小于 0 时为 0），
+/// 参数与 `improvedSlideMatch` 相同；`coordinateMode` 和 `coordinateSpace` 不生效
+#[napi]
+pub fn improved_slide_x_offset(
+  target: Buffer,
+  background: Buffer,
+  confidence_threshold: Option<f64>,
+  options: Option<SlideMatchConfig>,
+) -> Result<u32, ErrorStatus> {
+  let threshold = confidence_threshold.unwrap_or(0.3) as f32;
+  if !(0.0..=1.0).contains(&threshold) {
+    return Err(generic_error("置信度阈值必须在 0.0-1.0 范围内"));
+  }
+  let config = x_offset_config(&options.unwrap_or_default());
+
+  improved_slide_match_internal(&target, &background, threshold, &config)
+    .map(|bbox| x_offset(&bbox))
+    .map_err(|e| napi_error(&e, format!("改进版滑块匹配失败: {e}")))
+}
+
+/// 改进版滑块匹配，同时返回每次匹配尝试（自适应阈值、回退的固定阈值）的得分和位置，
+/// 参数与 `improvedSlideMatch` 相同
+#[napi]
+pub fn improved_slide_match_verbose(
+  target_image: Buffer,
+  background_image: Buffer,
+  confidence_threshold: Option<f64>,
+  options: Option<SlideMatchConfig>,
+) -> Result<SlideMatchVerbose, ErrorStatus> {
+  let threshold = confidence_threshold.unwrap_or(0.3) as f32;
+  let config = options.unwrap_or_default();
+
+  // 验证置信度阈值范围
+  if !(0.0..=1.0).contains(&threshold) {
+    return Err(generic_error("置信度阈值必须在 0.0-1.0 范围内"));
+  }
+
+  improved_slide_match_verbose_internal(&target_image, &background_image, threshold, &config)
+    .map_err(|e| napi_error(&e, format!("改进版滑块匹配失败: {e}")))
+}
+
 /// 改进版简单滑块匹配（无透明背景裁剪 + 自适应阈值 + 置信度验证）
 /// 接受 Buffer 参数（支持 base64 解码后的 u8 数组）
 ///
@@ -434,24 +2769,28 @@ pub fn improved_slide_match(
 /// - target_image: 目标图片 Buffer
 /// - background_image: 背景图片 Buffer
 /// - confidence_threshold: 置信度阈值，范围 0.0-1.0，默认 0.3
+/// - options: 可选配置，见 `SlideMatchConfig`
 ///
 #[napi]
 pub fn improved_simple_slide_match(
   target_image: Buffer,
   background_image: Buffer,
   confidence_threshold: Option<f64>,
-) -> Result<SlideBBox> {
+  options: Option<SlideMatchConfig>,
+) -> Result<SlideBBox, ErrorStatus> {
   let target_bytes = target_image.as_ref();
   let background_bytes = background_image.as_ref();
   let threshold = confidence_threshold.unwrap_or(0.3) as f32;
+  let config = options.unwrap_or_default();
 
   // 验证置信度阈值范围
   if !(0.0..=1.0).contains(&threshold) {
-    return Err(Error::from_reason("置信度阈值必须在 0.0-1.0 范围内"));
+    return Err(generic_error("置信度阈值必须在 0.0-1.0 范围内"));
   }
 
-  let result = improved_simple_slide_match_internal(target_bytes, background_bytes, threshold)
-    .map_err(|e| Error::from_reason(format!("改进版滑块匹配失败: {e}")))?;
+  let result =
+    improved_simple_slide_match_internal(target_bytes, background_bytes, threshold, &config)
+      .map_err(|e| napi_error(&e, format!("改进版滑块匹配失败: {e}")))?;
 
   Ok(result)
 }
@@ -462,20 +2801,21 @@ pub fn improved_slide_match_with_path(
   target_image_path: String,
   background_image_path: String,
   confidence_threshold: Option<f64>,
-) -> Result<SlideBBox> {
-  let target_bytes = std::fs::read(&target_image_path)
-    .map_err(|e| Error::from_reason(format!("无法读取目标图片: {e}")))?;
-  let background_bytes = std::fs::read(&background_image_path)
-    .map_err(|e| Error::from_reason(format!("无法读取背景图片: {e}")))?;
-
+  options: Option<SlideMatchConfig>,
+) -> Result<SlideBBox, ErrorStatus> {
   let threshold = confidence_threshold.unwrap_or(0.3) as f32;
+  let config = options.unwrap_or_default();
 
   if !(0.0..=1.0).contains(&threshold) {
-    return Err(Error::from_reason("置信度阈值必须在 0.0-1.0 范围内"));
+    return Err(generic_error("置信度阈值必须在 0.0-1.0 范围内"));
   }
 
-  let result = improved_slide_match_internal(&target_bytes, &background_bytes, threshold)
-    .map_err(|e| Error::from_reason(format!("改进版滑块匹配失败: {e}")))?;
+  let result = decode::with_image_files(
+    &target_image_path,
+    &background_image_path,
+    |target, background| improved_slide_match_internal(target, background, threshold, &config),
+  )
+  .map_err(|e| napi_error(&e, format!("改进版滑块匹配失败: {e:#}")))?;
 
   Ok(result)
 }
@@ -486,20 +2826,23 @@ pub fn improved_simple_slide_match_with_path(
   target_image_path: String,
   background_image_path: String,
   confidence_threshold: Option<f64>,
-) -> Result<SlideBBox> {
-  let target_bytes = std::fs::read(&target_image_path)
-    .map_err(|e| Error::from_reason(format!("无法读取目标图片: {e}")))?;
-  let background_bytes = std::fs::read(&background_image_path)
-    .map_err(|e| Error::from_reason(format!("无法读取背景图片: {e}")))?;
-
+  options: Option<SlideMatchConfig>,
+) -> Result<SlideBBox, ErrorStatus> {
   let threshold = confidence_threshold.unwrap_or(0.3) as f32;
+  let config = options.unwrap_or_default();
 
   if !(0.0..=1.0).contains(&threshold) {
-    return Err(Error::from_reason("置信度阈值必须在 0.0-1.0 范围内"));
+    return Err(generic_error("置信度阈值必须在 0.0-1.0 范围内"));
   }
 
-  let result = improved_simple_slide_match_internal(&target_bytes, &background_bytes, threshold)
-    .map_err(|e| Error::from_reason(format!("改进版滑块匹配失败: {e}")))?;
+  let result = decode::with_image_files(
+    &target_image_path,
+    &background_image_path,
+    |target, background| {
+      improved_simple_slide_match_internal(target, background, threshold, &config)
+    },
+  )
+  .map_err(|e| napi_error(&e, format!("改进版滑块匹配失败: {e:#}")))?;
 
   Ok(result)
 }
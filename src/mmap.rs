@@ -0,0 +1,114 @@
+use std::fs::File;
+
+use anyhow::Context;
+use memmap2::Mmap;
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::error::{napi_error, ErrorStatus};
+use crate::{slide_match_internal, SlideBBox, SlideMatchConfig};
+
+// 以只读方式内存映射文件
+fn map_file(path: &str) -> std::io::Result<Mmap> {
+  let file = File::open(path)?;
+  // SAFETY: 映射只在本次匹配期间使用，调用方需保证期间文件不被截断或修改
+  unsafe { Mmap::map(&file) }
+}
+
+// 错误信息中包含文件路径
+fn slide_match_mmap_internal(
+  target_path: &str,
+  background_path: &str,
+  config: &SlideMatchConfig,
+) -> anyhow::Result<SlideBBox> {
+  let target = map_file(target_path).with_context(|| format!("无法读取目标图片 {target_path}"))?;
+  let background =
+    map_file(background_path).with_context(|| format!("无法读取背景图片 {background_path}"))?;
+
+  slide_match_internal(&target, &background, None, config)
+    .with_context(|| format!("目标图片 {target_path}，背景图片 {background_path}"))
+}
+
+/// 滑块匹配（带透明背景裁剪）- 从文件路径，使用内存映射读取
+/// 不会把整个文件读入内存，适合磁盘上较大的背景图片
+///
+/// 需要启用 `mmap` 特性编译。匹配期间图片文件不能被修改或截断。
+///
+/// # 参数
+/// - target_path: 目标图片路径
+/// - background_path: 背景图片路径
+/// - options: 可选配置，见 `SlideMatchConfig`
+#[napi]
+pub fn slide_match_mmap(
+  target_path: String,
+  background_path: String,
+  options: Option<SlideMatchConfig>,
+) -> Result<SlideBBox, ErrorStatus> {
+  let config = options.unwrap_or_default();
+
+  slide_match_mmap_internal(&target_path, &background_path, &config)
+    .map_err(|e| napi_error(&e, format!("滑块匹配失败: {e:#}")))
+}
+
+/// 内存映射读取的滑块匹配后台任务，执行方式与 [`crate::task::ImprovedSlideMatchTask`] 相同
+pub struct SlideMatchMmapTask {
+  target_path: String,
+  background_path: String,
+  config: SlideMatchConfig,
+}
+
+impl SlideMatchMmapTask {
+  fn run(&self) -> Result<SlideBBox, ErrorStatus> {
+    slide_match_mmap_internal(&self.target_path, &self.background_path, &self.config)
+      .map_err(|e| napi_error(&e, format!("滑块匹配失败: {e:#}")))
+  }
+}
+
+#[cfg(not(feature = "tokio"))]
+impl Task for SlideMatchMmapTask {
+  type Output = Result<SlideBBox, ErrorStatus>;
+  type JsValue = SlideBBox;
+
+  fn compute(&mut self) -> Result<Self::Output> {
+    Ok(self.run())
+  }
+
+  fn resolve(&mut self, env: Env, output: Self::Output) -> Result<Self::JsValue> {
+    output.map_err(|e| crate::error::to_js_error(&env, e))
+  }
+}
+
+/// 滑块匹配 - 从文件路径，使用内存映射读取（异步），参数与 `slideMatchMmap` 相同
+/// 映射文件和匹配都在后台线程中执行，不阻塞 JS 主线程；Promise 完成前图片文件不能被修改或截断
+#[cfg(not(feature = "tokio"))]
+#[napi]
+pub fn slide_match_mmap_async(
+  target_path: String,
+  background_path: String,
+  options: Option<SlideMatchConfig>,
+) -> AsyncTask<SlideMatchMmapTask> {
+  AsyncTask::new(SlideMatchMmapTask {
+    target_path,
+    background_path,
+    config: options.unwrap_or_default(),
+  })
+}
+
+/// 滑块匹配 - 从文件路径，使用内存映射读取（异步），参数与 `slideMatchMmap` 相同
+/// 映射文件和匹配都在 tokio 的阻塞线程池中执行，不阻塞 JS 主线程；Promise 完成前图片文件不能被修改或截断
+#[cfg(feature = "tokio")]
+#[napi(ts_return_type = "Promise<SlideBBox>")]
+pub fn slide_match_mmap_async<'env>(
+  env: &'env Env,
+  target_path: String,
+  background_path: String,
+  options: Option<SlideMatchConfig>,
+) -> Result<PromiseRaw<'env, SlideBBox>> {
+  let task = SlideMatchMmapTask {
+    target_path,
+    background_path,
+    config: options.unwrap_or_default(),
+  };
+
+  crate::task::spawn_blocking(env, "滑块匹配失败", move || task.run())
+}
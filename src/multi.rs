@@ -0,0 +1,298 @@
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::error::{napi_error, ErrorStatus};
+use crate::preprocess::{self, MatchImage};
+use crate::{
+  background_canny_thresholds, cache, coordinates, crop_transparent, decode,
+  original_canny_thresholds, oversize, score_map, target_canny_thresholds, validate_config,
+  ScoreMap, SlideBBox, SlideMatchConfig,
+};
+
+// 默认的最低得分
+const DEFAULT_SCORE_THRESHOLD: f64 = 0.5;
+// 默认最多返回的结果数
+const DEFAULT_MAX_RESULTS: u32 = 10;
+
+/// 多目标匹配选项
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct FindAllMatchesOptions {
+  /// 最低匹配得分，范围 0.0-1.0，默认 0.5
+  pub score_threshold: Option<f64>,
+  /// 最多返回的结果数，默认 10
+  pub max_results: Option<u32>,
+  /// 非极大值抑制半径（像素）：与得分更高的结果在 x、y 方向的距离都不超过该值时被抑制；
+  /// 默认按目标图片尺寸抑制所有互相重叠的结果
+  pub nms_radius: Option<u32>,
+}
+
+/// 多目标匹配的单个结果
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct MatchCandidate {
+  pub bbox: SlideBBox,
+  /// 匹配得分（归一化互相关）
+  pub confidence: f64,
+}
+
+// 得分不低于阈值的位置，按得分从高到低做非极大值抑制
+// 返回得分图坐标和得分
+fn non_maximum_suppression(
+  map: &ScoreMap,
+  score_threshold: f32,
+  max_results: usize,
+  (radius_x, radius_y): (u32, u32),
+) -> Vec<(u32, u32, f32)> {
+  let mut peaks: Vec<_> = map
+    .scores
+    .enumerate_pixels()
+    .filter(|(_, _, score)| score[0] >= score_threshold)
+    .map(|(x, y, score)| (x, y, score[0]))
+    .collect();
+  // 得分相同时按 y、x 排序，保证结果稳定
+  peaks.sort_by(|a, b| b.2.total_cmp(&a.2).then((a.1, a.0).cmp(&(b.1, b.0))));
+
+  let mut selected: Vec<(u32, u32, f32)> = Vec::new();
+  for peak in peaks {
+    if selected.len() >= max_results {
+      break;
+    }
+
+    let suppressed = selected
+      .iter()
+      .any(|kept| kept.0.abs_diff(peak.0) <= radius_x && kept.1.abs_diff(peak.1) <= radius_y);
+    if !suppressed {
+      selected.push(peak);
+    }
+  }
+
+  selected
+}
+
+// 多目标匹配的得分图，以及把得分图坐标换算为结果所需的尺寸信息
+pub(crate) struct CandidateScores {
+  pub(crate) map: ScoreMap,
+  // 裁剪后的目标图片尺寸
+  target_size: (u32, u32),
+  // 目标图片裁剪起点
+  target_offset: (u32, u32),
+  // 原始尺寸，用于换算显示坐标
+  background_size: (u32, u32),
+  original_target_size: (u32, u32),
+}
+
+impl CandidateScores {
+  // 解码、预处理并计算得分图，预处理方式与 `slideMatch` 一致
+  pub(crate) fn new(
+    target_image: &[u8],
+    background_image: &[u8],
+    config: &SlideMatchConfig,
+  ) -> anyhow::Result<Self> {
+    let (target_image, _) = decode::load_target(target_image, "目标图片", config)?;
+    let background_key = cache::background_key(background_image, config);
+    let background_image = decode::load_background(background_image, config)?;
+
+    oversize::ensure_fits(
+      (target_image.width(), target_image.height()),
+      (background_image.width(), background_image.height()),
+    )?;
+
+    // 原始尺寸，用于换算显示坐标
+    let background_size = (background_image.width(), background_image.height());
+    let original_target_size = (target_image.width(), target_image.height());
+
+    // 裁剪图片，只保留不透明部分
+    let (cropped_image, target_offset) = crop_transparent(target_image.into_rgba8());
+    let target_mask = preprocess::alpha_mask(&cropped_image, preprocess::ALPHA_MASK_THRESHOLD);
+
+    let target = MatchImage::from_rgba(
+      &cropped_image,
+      &preprocess::target_preprocess_config(config),
+    );
+    let background =
+      MatchImage::from_dynamic(background_image, config).with_cache_key(background_key);
+
+    let map = score_map(
+      &target,
+      &background,
+      target_canny_thresholds(original_canny_thresholds(&target, config), config)?,
+      background_canny_thresholds(original_canny_thresholds(&background, config), config)?,
+      Some(&target_mask),
+      None,
+      config,
+    )?;
+
+    Ok(Self {
+      map,
+      target_size: (target.width(), target.height()),
+      target_offset,
+      background_size,
+      original_target_size,
+    })
+  }
+
+  // 得分图坐标 (x, y) 处的结果
+  fn candidate(&self, x: u32, y: u32, score: f32, config: &SlideMatchConfig) -> MatchCandidate {
+    let y = y + self.map.row_offset;
+    MatchCandidate {
+      bbox: coordinates::to_display_coordinates(
+        SlideBBox {
+          target_x: self.target_offset.0,
+          target_y: self.target_offset.1,
+          x1: x,
+          y1: y,
+          x2: x + self.target_size.0,
+          y2: y + self.target_size.1,
+          confidence: Some(score as f64),
+          metadata: None,
+          score_profile: None,
+          heatmap: None,
+          coordinate_mode: None,
+          ratio: None,
+          target_scale: None,
+          is_ambiguous: None,
+          second_best_score: None,
+          second_best_x: None,
+          psr: None,
+          slide_distance: None,
+          degenerate_target: None,
+          elapsed_ms: None,
+        },
+        self.background_size,
+        self.original_target_size,
+        config,
+      ),
+      confidence: score as f64,
+    }
+  }
+}
+
+// 校验最低匹配得分
+fn validate_score_threshold(score_threshold: f64) -> anyhow::Result<()> {
+  anyhow::ensure!(
+    (0.0..=1.0).contains(&score_threshold),
+    "最低匹配得分必须在 0.0-1.0 范围内"
+  );
+  Ok(())
+}
+
+fn find_all_matches_internal(
+  target_image: &[u8],
+  background_image: &[u8],
+  options: &FindAllMatchesOptions,
+  config: &SlideMatchConfig,
+) -> anyhow::Result<Vec<MatchCandidate>> {
+  validate_config(config)?;
+
+  let score_threshold = options.score_threshold.unwrap_or(DEFAULT_SCORE_THRESHOLD);
+  validate_score_threshold(score_threshold)?;
+  let max_results = options.max_results.unwrap_or(DEFAULT_MAX_RESULTS);
+  anyhow::ensure!(max_results > 0, "最多返回的结果数必须大于 0");
+
+  let scores = CandidateScores::new(target_image, background_image, config)?;
+
+  // 未指定半径时，抑制与已选结果重叠的位置
+  let (target_width, target_height) = scores.target_size;
+  let radius = options.nms_radius.map_or(
+    (
+      target_width.saturating_sub(1),
+      target_height.saturating_sub(1),
+    ),
+    |radius| (radius, radius),
+  );
+
+  let peaks = non_maximum_suppression(
+    &scores.map,
+    score_threshold as f32,
+    max_results as usize,
+    radius,
+  );
+
+  Ok(
+    peaks
+      .into_iter()
+      .map(|(x, y, score)| scores.candidate(x, y, score, config))
+      .collect(),
+  )
+}
+
+// 是否为局部最大值：不低于 8 邻域内的所有得分；得分相同的平台只保留扫描顺序中的第一个位置
+fn is_local_maximum(map: &ScoreMap, x: u32, y: u32) -> bool {
+  let scores = &map.scores;
+  let score = scores.get_pixel(x, y)[0];
+
+  for ny in y.saturating_sub(1)..=(y + 1).min(scores.height() - 1) {
+    for nx in x.saturating_sub(1)..=(x + 1).min(scores.width() - 1) {
+      if (nx, ny) == (x, y) {
+        continue;
+      }
+      let neighbor = scores.get_pixel(nx, ny)[0];
+      let before = (ny, nx) < (y, x);
+      if neighbor > score || (before && neighbor == score) {
+        return false;
+      }
+    }
+  }
+
+  true
+}
+
+// 逐行扫描得分图，每发现一个不低于阈值的局部最大值就调用一次 `emit`，返回结果总数
+// `emit` 返回 false 时停止扫描
+pub(crate) fn stream_matches_internal(
+  target_image: &[u8],
+  background_image: &[u8],
+  score_threshold: f64,
+  config: &SlideMatchConfig,
+  mut emit: impl FnMut(MatchCandidate) -> bool,
+) -> anyhow::Result<u32> {
+  validate_config(config)?;
+  validate_score_threshold(score_threshold)?;
+
+  let scores = CandidateScores::new(target_image, background_image, config)?;
+  let threshold = score_threshold as f32;
+  let mut count = 0;
+
+  for y in 0..scores.map.scores.height() {
+    for x in 0..scores.map.scores.width() {
+      let score = scores.map.scores.get_pixel(x, y)[0];
+      if score < threshold || !is_local_maximum(&scores.map, x, y) {
+        continue;
+      }
+
+      count += 1;
+      if !emit(scores.candidate(x, y, score, config)) {
+        return Ok(count);
+      }
+    }
+  }
+
+  Ok(count)
+}
+
+/// 多目标匹配：返回背景图中所有得分不低于阈值的位置
+///
+/// 适合同一形状在背景图中出现多次的验证码。对得分图做非极大值抑制后按得分从高到低返回，
+/// 没有位置达到阈值时返回空数组。预处理方式与 `slideMatch` 一致（裁剪透明区域、固定 Canny 阈值）。
+///
+/// # 参数
+/// - target: 目标图片 Buffer
+/// - background: 背景图片 Buffer
+/// - options: 多目标匹配选项
+/// - config: 可选配置，见 `SlideMatchConfig`
+#[napi]
+pub fn find_all_matches(
+  target: Buffer,
+  background: Buffer,
+  options: Option<FindAllMatchesOptions>,
+  config: Option<SlideMatchConfig>,
+) -> Result<Vec<MatchCandidate>, ErrorStatus> {
+  find_all_matches_internal(
+    &target,
+    &background,
+    &options.unwrap_or_default(),
+    &config.unwrap_or_default(),
+  )
+  .map_err(|e| napi_error(&e, format!("多目标匹配失败: {e}")))
+}
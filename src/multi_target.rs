@@ -0,0 +1,86 @@
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use rayon::prelude::*;
+
+use crate::error::{napi_error, ErrorStatus};
+use crate::{slide_match_internal, validate_config, SlideBBox, SlideMatchConfig};
+
+/// 多目标图片匹配结果
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct MultiTargetResult {
+  /// 置信度最高的匹配结果
+  pub best_bbox: SlideBBox,
+  /// `best_bbox` 对应的目标图片在 `targets` 中的下标
+  pub target_index: u32,
+  /// 每个目标图片的匹配结果，与 `targets` 一一对应，匹配失败的为 null
+  pub all_results: Vec<Option<SlideBBox>>,
+}
+
+pub fn slide_match_multi_target_internal(
+  targets: &[&[u8]],
+  background: &[u8],
+  config: &SlideMatchConfig,
+) -> anyhow::Result<MultiTargetResult> {
+  validate_config(config)?;
+  anyhow::ensure!(!targets.is_empty(), "目标图片列表不能为空");
+
+  // 未设置置信度阈值时以 0 为阈值，保证每个结果都带有 `confidence`，得分为 0 的目标图片视为匹配失败
+  let config = &SlideMatchConfig {
+    confidence_threshold: config.confidence_threshold.or(Some(0.0)),
+    ..config.clone()
+  };
+  let results: Vec<anyhow::Result<SlideBBox>> = targets
+    .par_iter()
+    .map(|target| slide_match_internal(target, background, None, config))
+    .collect();
+
+  // 置信度相同时取下标最小的目标图片
+  let best = results
+    .iter()
+    .enumerate()
+    .filter_map(|(index, result)| Some((index, result.as_ref().ok()?.confidence?)))
+    .fold(None, |best, candidate| match best {
+      Some((_, confidence)) if confidence >= candidate.1 => best,
+      _ => Some(candidate),
+    });
+  let Some((target_index, _)) = best else {
+    let reasons: Vec<String> = results
+      .iter()
+      .enumerate()
+      .filter_map(|(index, result)| Some(format!("targets[{index}]: {}", result.as_ref().err()?)))
+      .collect();
+    anyhow::bail!("所有目标图片均匹配失败（{}）", reasons.join("；"));
+  };
+
+  let all_results: Vec<Option<SlideBBox>> = results.into_iter().map(|result| result.ok()).collect();
+  Ok(MultiTargetResult {
+    best_bbox: all_results[target_index]
+      .clone()
+      .expect("最优结果必然匹配成功"),
+    target_index: target_index as u32,
+    all_results,
+  })
+}
+
+/// 多目标图片匹配：同一个滑块有多个版本（不同分辨率、略有差异的裁剪）时，并行尝试每一个，
+/// 返回置信度最高的结果
+///
+/// 每个目标图片的匹配方式与 `slideMatch` 相同。未设置 `confidenceThreshold` 时以 0 为阈值，
+/// 保证每个结果都带有 `confidence`；匹配失败（包括置信度不高于阈值）的目标图片在 `allResults`
+/// 中为 null，全部失败时报错。
+///
+/// # 参数
+/// - targets: 目标图片 Buffer 列表，不能为空
+/// - background: 背景图片 Buffer
+/// - options: 可选配置，见 `SlideMatchConfig`
+#[napi]
+pub fn slide_match_multi_target(
+  targets: Vec<Buffer>,
+  background: Buffer,
+  options: Option<SlideMatchConfig>,
+) -> Result<MultiTargetResult, ErrorStatus> {
+  let targets: Vec<&[u8]> = targets.iter().map(|target| target.as_ref()).collect();
+  slide_match_multi_target_internal(&targets, &background, &options.unwrap_or_default())
+    .map_err(|e| napi_error(&e, format!("多目标图片匹配失败: {e}")))
+}
@@ -0,0 +1,467 @@
+use std::ops::AddAssign;
+
+use image::{GrayImage, Luma};
+use imageproc::definitions::Image;
+use imageproc::template_matching::{match_template, Extremes, MatchTemplateMethod};
+
+use crate::backend::{self, MatchBackend};
+
+// 背景图像素数低于该值时直接使用 imageproc 的实现，积分图的准备开销占比过高
+const MIN_INTEGRAL_IMAGE_PIXELS: u64 = 64 * 64;
+// `auto` 后端下背景图像素数不低于该值时才使用 GPU，小图的上传和回读开销超过计算本身
+#[cfg(feature = "gpu")]
+const MIN_AUTO_GPU_PIXELS: u64 = 512 * 256;
+
+// 平方积分图：`sums[y * (width + 1) + x]` 为 [0, x) × [0, y) 内像素值平方的和
+struct SquaredIntegral {
+  sums: Vec<u64>,
+  stride: usize,
+}
+
+impl SquaredIntegral {
+  fn new(image: &GrayImage) -> Self {
+    let (width, height) = (image.width() as usize, image.height() as usize);
+    let stride = width + 1;
+    let mut sums = vec![0u64; stride * (height + 1)];
+
+    for (y, row) in image.as_raw().chunks_exact(width).enumerate() {
+      let mut row_sum = 0u64;
+      for (x, &value) in row.iter().enumerate() {
+        row_sum += value as u64 * value as u64;
+        sums[(y + 1) * stride + x + 1] = sums[y * stride + x + 1] + row_sum;
+      }
+    }
+
+    Self { sums, stride }
+  }
+
+  // 左上角为 (x, y)、尺寸为 width × height 的窗口内的平方和
+  fn window(&self, x: usize, y: usize, width: usize, height: usize) -> u64 {
+    let (top, bottom) = (y * self.stride, (y + height) * self.stride);
+    self.sums[bottom + x + width] + self.sums[top + x]
+      - self.sums[top + x + width]
+      - self.sums[bottom + x]
+  }
+}
+
+/// 在 `image` 上滑动 `template` 计算归一化互相关得分图，与 imageproc 的
+/// `CrossCorrelationNormalized` 结果一致
+///
+/// 得分为 Σ(I·T) / sqrt(ΣI² · ΣT²)，分母为 0 时返回 Σ(I·T)（即 0）。imageproc 对每个位置都重新
+/// 遍历整个窗口；这里用平方积分图在 O(1) 时间内得到窗口平方和，互相关只遍历模板中的非零像素
+/// （边缘图通常只有不到 10% 的像素非零），并逐行累加到得分图上。整数累加没有舍入误差，
+/// 与 imageproc 的 f32 累加结果相差在 1e-4 以内。
+///
+/// # Panics
+/// 模板的宽度或高度大于图片时 panic（与 imageproc 一致），调用方需事先检查尺寸。
+#[must_use]
+pub fn match_template_normalized(image: &GrayImage, template: &GrayImage) -> Image<Luma<f32>> {
+  if (image.width() as u64 * image.height() as u64) < MIN_INTEGRAL_IMAGE_PIXELS {
+    return match_template(
+      image,
+      template,
+      MatchTemplateMethod::CrossCorrelationNormalized,
+    );
+  }
+
+  assert!(
+    image.width() >= template.width() && image.height() >= template.height(),
+    "模板尺寸 {:?} 大于图片尺寸 {:?}",
+    template.dimensions(),
+    image.dimensions()
+  );
+
+  if let Some(scores) = match_template_gpu(image, template) {
+    return scores;
+  }
+
+  let (template_pixels, template_squared_sum) = nonzero_pixels(template);
+
+  // 互相关不超过 非零像素数 × 255²，能用 u32 累加时得分图的中间结果占用减半
+  if template_pixels.len() as u64 * 255 * 255 <= u32::MAX as u64 {
+    let correlation = correlate::<u32>(image, template, &template_pixels, 1);
+    normalize(
+      image,
+      template,
+      &correlation,
+      template_squared_sum,
+      f64::from,
+    )
+  } else {
+    let correlation = correlate::<u64>(image, template, &template_pixels, 1);
+    normalize(image, template, &correlation, template_squared_sum, |v| {
+      v as f64
+    })
+  }
+}
+
+/// 按步长 `stride` 计算 [`match_template_normalized`] 的近似得分图，尺寸与完整得分图相同
+///
+/// 先只计算 x、y 都是 `stride` 整数倍的位置，再在其中得分最高的位置周围 `stride - 1` 像素内逐像素计算，
+/// 其余位置的得分为 0。粗搜索仍按整行累加互相关（利用 SIMD），只跳过不在网格上的行，互相关部分的耗时
+/// 约为完整匹配的 1 / stride。边缘图的线条只有 1-2 像素宽，缺口不在网格上时粗搜索的得分会明显下降，
+/// 可能被背景中其他恰好落在网格上的位置超过。不使用 GPU 后端，`stride` 不大于 1 时等同于
+/// [`match_template_normalized`]。
+///
+/// # Panics
+/// 模板的宽度或高度大于图片时 panic，调用方需事先检查尺寸。
+#[must_use]
+pub fn match_template_strided(
+  image: &GrayImage,
+  template: &GrayImage,
+  stride: u32,
+) -> Image<Luma<f32>> {
+  if stride <= 1 {
+    return match_template_normalized(image, template);
+  }
+
+  assert!(
+    image.width() >= template.width() && image.height() >= template.height(),
+    "模板尺寸 {:?} 大于图片尺寸 {:?}",
+    template.dimensions(),
+    image.dimensions()
+  );
+
+  let (template_pixels, template_squared_sum) = nonzero_pixels(template);
+  let (template_width, template_height) = (template.width() as usize, template.height() as usize);
+  let squares = SquaredIntegral::new(image);
+  let normalized = |correlation: f64, x: u32, y: u32| {
+    let window = squares.window(x as usize, y as usize, template_width, template_height) as f64;
+    let norm = (window * template_squared_sum as f64).sqrt();
+    (if norm > 0.0 {
+      correlation / norm
+    } else {
+      correlation
+    }) as f32
+  };
+
+  let out_width = image.width() - template.width() + 1;
+  let out_height = image.height() - template.height() + 1;
+  let mut scores = Image::new(out_width, out_height);
+
+  // 粗搜索：只累加网格所在的行（仍按整行累加以利用 SIMD），再取其中网格列上的得分
+  let step = stride as usize;
+  let coarse: Vec<f64> = if template_pixels.len() as u64 * 255 * 255 <= u32::MAX as u64 {
+    correlate::<u32>(image, template, &template_pixels, step)
+      .into_iter()
+      .map(f64::from)
+      .collect()
+  } else {
+    correlate::<u64>(image, template, &template_pixels, step)
+      .into_iter()
+      .map(|v| v as f64)
+      .collect()
+  };
+  let mut best = (0, 0, f32::MIN);
+  for (row, correlation) in coarse.chunks_exact(out_width as usize).enumerate() {
+    let y = (row * step) as u32;
+    for x in (0..out_width).step_by(step) {
+      let score = normalized(correlation[x as usize], x, y);
+      scores.put_pixel(x, y, Luma([score]));
+      if score > best.2 {
+        best = (x, y, score);
+      }
+    }
+  }
+
+  // 细化：在粗搜索的最优位置周围逐像素计算
+  let (best_x, best_y, _) = best;
+  let image_width = image.width() as usize;
+  let pixels = image.as_raw();
+  let reach = stride - 1;
+  for y in best_y.saturating_sub(reach)..=(best_y + reach).min(out_height - 1) {
+    for x in best_x.saturating_sub(reach)..=(best_x + reach).min(out_width - 1) {
+      if x % stride != 0 || y % stride != 0 {
+        let (left, top) = (x as usize, y as usize);
+        let correlation: u64 = template_pixels
+          .iter()
+          .map(|&(dx, dy, t)| pixels[(top + dy) * image_width + left + dx] as u64 * t as u64)
+          .sum();
+        scores.put_pixel(x, y, Luma([normalized(correlation as f64, x, y)]));
+      }
+    }
+  }
+
+  scores
+}
+
+/// 得分图的最大值、最小值及其位置，与 imageproc 的 `find_extremes` 返回相同的结构
+///
+/// 二值边缘图上相邻的几个位置经常得到完全相同的得分，imageproc 按行扫描并保留遇到的第一个，
+/// 取哪一个依赖于库的遍历顺序。这里按列显式扫描，得分相同时取最左侧的位置（x 最小，x 相同时 y 最小），
+/// 最小值同理。
+///
+/// ```
+/// use image::Luma;
+/// use imageproc::definitions::Image;
+/// use slide_match::ncc::find_extremes;
+///
+/// // 得分为 1.0 的平台覆盖 (3, 1)、(4, 1)、(2, 2)、(3, 2)，按行扫描会先遇到 (3, 1)
+/// let mut scores = Image::from_pixel(6, 4, Luma([0.5f32]));
+/// for (x, y) in [(3, 1), (4, 1), (2, 2), (3, 2)] {
+///   scores.put_pixel(x, y, Luma([1.0]));
+/// }
+/// scores.put_pixel(5, 0, Luma([0.0]));
+/// scores.put_pixel(1, 3, Luma([0.0]));
+///
+/// let extremes = find_extremes(&scores);
+/// assert_eq!(extremes.max_value, 1.0);
+/// assert_eq!(extremes.max_value_location, (2, 2));
+/// assert_eq!(extremes.min_value_location, (1, 3));
+/// ```
+///
+/// # Panics
+/// 得分图为空时 panic（与 imageproc 一致）。
+pub fn find_extremes(scores: &Image<Luma<f32>>) -> Extremes<f32> {
+  assert!(scores.width() > 0 && scores.height() > 0, "得分图不能为空");
+
+  let first = scores.get_pixel(0, 0)[0];
+  let mut extremes = Extremes {
+    max_value: first,
+    min_value: first,
+    max_value_location: (0, 0),
+    min_value_location: (0, 0),
+  };
+
+  for x in 0..scores.width() {
+    for y in 0..scores.height() {
+      let value = scores.get_pixel(x, y)[0];
+      if value > extremes.max_value {
+        extremes.max_value = value;
+        extremes.max_value_location = (x, y);
+      }
+      if value < extremes.min_value {
+        extremes.min_value = value;
+        extremes.min_value_location = (x, y);
+      }
+    }
+  }
+
+  extremes
+}
+
+// 模板中的非零像素 (x, y, 值) 及其平方和
+fn nonzero_pixels(template: &GrayImage) -> (Vec<(usize, usize, u16)>, u64) {
+  let pixels: Vec<(usize, usize, u16)> = template
+    .enumerate_pixels()
+    .filter(|(_, _, value)| value[0] != 0)
+    .map(|(x, y, value)| (x as usize, y as usize, value[0] as u16))
+    .collect();
+  let squared_sum = pixels.iter().map(|&(_, _, t)| t as u64 * t as u64).sum();
+  (pixels, squared_sum)
+}
+
+// 按选择的后端尝试在 GPU 上计算，不使用 GPU 或 GPU 不可用时返回 None
+#[cfg(feature = "gpu")]
+fn match_template_gpu(image: &GrayImage, template: &GrayImage) -> Option<Image<Luma<f32>>> {
+  let use_gpu = match backend::current() {
+    MatchBackend::Cpu => false,
+    MatchBackend::Gpu => true,
+    MatchBackend::Auto => image.width() as u64 * image.height() as u64 >= MIN_AUTO_GPU_PIXELS,
+  };
+  use_gpu
+    .then(|| crate::gpu::match_template_normalized(image, template))
+    .flatten()
+}
+
+// 未启用 `gpu` 特性时始终使用 CPU，选择了其他后端则输出一次警告
+#[cfg(not(feature = "gpu"))]
+fn match_template_gpu(_image: &GrayImage, _template: &GrayImage) -> Option<Image<Luma<f32>>> {
+  static WARNING: std::sync::Once = std::sync::Once::new();
+  if backend::current() != MatchBackend::Cpu {
+    WARNING.call_once(|| tracing::warn!("未启用 gpu 特性，回退到 CPU 计算"));
+  }
+  None
+}
+
+// 得分图的整数累加类型
+trait Accumulator: Copy + Default + AddAssign + From<u16> {
+  // `totals[k] += pixels[k] * t`
+  fn add_scaled_row(totals: &mut [Self], pixels: &[u8], t: u16) {
+    add_scaled_row_scalar(totals, pixels, t);
+  }
+}
+
+fn add_scaled_row_scalar<T: Accumulator>(totals: &mut [T], pixels: &[u8], t: u16) {
+  for (total, &i) in totals.iter_mut().zip(pixels) {
+    // 255 × 255 = 65025，乘积不会超出 u16
+    *total += T::from(i as u16 * t);
+  }
+}
+
+// 启用 `simd` 特性时，u32 累加在 x86-64（运行时检测到 AVX2）和 aarch64（NEON）上每次处理 16 个像素，
+// SIMD 处理不完的尾部像素走标量实现；整数运算没有舍入，结果与标量实现逐位一致
+impl Accumulator for u32 {
+  #[cfg(feature = "simd")]
+  fn add_scaled_row(totals: &mut [Self], pixels: &[u8], t: u16) {
+    let done = if simd::available() {
+      // SAFETY: 已确认当前 CPU 支持对应的指令集
+      unsafe { simd::add_scaled_row(totals, pixels, t) }
+    } else {
+      0
+    };
+    add_scaled_row_scalar(&mut totals[done..], &pixels[done..], t);
+  }
+}
+
+impl Accumulator for u64 {}
+
+/// 互相关逐行累加（`totals[k] += pixels[k] × t`）的标量实现和 SIMD 实现，供测试和基准测试在同一份
+/// 构建中直接比较
+#[cfg(feature = "simd")]
+pub mod row_kernels {
+  use super::Accumulator;
+
+  /// 当前 CPU 是否支持 SIMD 实现（x86-64 上运行时检测 AVX2，aarch64 上始终支持）
+  pub fn simd_available() -> bool {
+    super::simd::available()
+  }
+
+  /// 标量实现
+  pub fn add_scaled_row_scalar(totals: &mut [u32], pixels: &[u8], t: u16) {
+    super::add_scaled_row_scalar(totals, pixels, t);
+  }
+
+  /// 匹配时使用的实现：SIMD 每次处理 16 个像素，尾部像素和不支持 SIMD 的 CPU 走标量实现
+  pub fn add_scaled_row(totals: &mut [u32], pixels: &[u8], t: u16) {
+    u32::add_scaled_row(totals, pixels, t);
+  }
+}
+
+// 互相关得分图（未归一化）：每个非零模板像素乘以对应偏移处的背景行，逐行累加；
+// 只计算 y 为 `row_step` 整数倍的行，第 i 行对应 y = i × row_step
+fn correlate<T: Accumulator>(
+  image: &GrayImage,
+  template: &GrayImage,
+  template_pixels: &[(usize, usize, u16)],
+  row_step: usize,
+) -> Vec<T> {
+  let image_width = image.width() as usize;
+  let out_width = image_width - template.width() as usize + 1;
+  let out_height = image.height() as usize - template.height() as usize + 1;
+  let pixels = image.as_raw();
+
+  let mut correlation = vec![T::default(); out_width * out_height.div_ceil(row_step)];
+  for &(dx, dy, t) in template_pixels {
+    for (row_index, row) in correlation.chunks_exact_mut(out_width).enumerate() {
+      let start = (row_index * row_step + dy) * image_width + dx;
+      T::add_scaled_row(row, &pixels[start..start + out_width], t);
+    }
+  }
+
+  correlation
+}
+
+// 用窗口平方和归一化互相关得分
+fn normalize<T: Copy>(
+  image: &GrayImage,
+  template: &GrayImage,
+  correlation: &[T],
+  template_squared_sum: u64,
+  to_f64: impl Fn(T) -> f64,
+) -> Image<Luma<f32>> {
+  let (template_width, template_height) = (template.width() as usize, template.height() as usize);
+  let out_width = image.width() - template.width() + 1;
+  let out_height = image.height() - template.height() + 1;
+  let squares = SquaredIntegral::new(image);
+
+  Image::from_fn(out_width, out_height, |x, y| {
+    let (x, y) = (x as usize, y as usize);
+    let score = to_f64(correlation[y * out_width as usize + x]);
+    let window = squares.window(x, y, template_width, template_height) as f64;
+    let norm = (window * template_squared_sum as f64).sqrt();
+    let score = if norm > 0.0 { score / norm } else { score };
+    Luma([score as f32])
+  })
+}
+
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+mod simd {
+  use std::arch::x86_64::*;
+
+  // SIMD 每次处理的像素数
+  const LANES: usize = 16;
+
+  pub(super) fn available() -> bool {
+    is_x86_feature_detected!("avx2")
+  }
+
+  // 每次读取 16 个像素扩展到 16 位与 t 相乘（乘积不超过 65025，不会溢出 u16），
+  // 再拆成两组 8 个 32 位整数累加到得分上，返回已处理的像素数
+  #[target_feature(enable = "avx2")]
+  pub(super) unsafe fn add_scaled_row(totals: &mut [u32], pixels: &[u8], t: u16) -> usize {
+    let len = totals.len().min(pixels.len());
+    let weight = _mm256_set1_epi16(t as i16);
+
+    let mut done = 0;
+    while done + LANES <= len {
+      let values = _mm_loadu_si128(pixels.as_ptr().add(done) as *const __m128i);
+      let products = _mm256_mullo_epi16(_mm256_cvtepu8_epi16(values), weight);
+      let low = _mm256_cvtepu16_epi32(_mm256_castsi256_si128(products));
+      let high = _mm256_cvtepu16_epi32(_mm256_extracti128_si256::<1>(products));
+
+      let out = totals.as_mut_ptr().add(done) as *mut __m256i;
+      _mm256_storeu_si256(out, _mm256_add_epi32(_mm256_loadu_si256(out), low));
+      _mm256_storeu_si256(
+        out.add(1),
+        _mm256_add_epi32(_mm256_loadu_si256(out.add(1)), high),
+      );
+      done += LANES;
+    }
+
+    done
+  }
+}
+
+#[cfg(all(feature = "simd", target_arch = "aarch64"))]
+mod simd {
+  use std::arch::aarch64::*;
+
+  // SIMD 每次处理的像素数
+  const LANES: usize = 16;
+
+  // aarch64 目标默认启用 NEON
+  pub(super) fn available() -> bool {
+    true
+  }
+
+  // 每次读取 16 个像素扩展到 16 位与 t 相乘，再分四组加宽累加到 32 位得分上，返回已处理的像素数
+  pub(super) unsafe fn add_scaled_row(totals: &mut [u32], pixels: &[u8], t: u16) -> usize {
+    let len = totals.len().min(pixels.len());
+    let weight = vdupq_n_u16(t);
+
+    let mut done = 0;
+    while done + LANES <= len {
+      let values = vld1q_u8(pixels.as_ptr().add(done));
+      let low = vmulq_u16(vmovl_u8(vget_low_u8(values)), weight);
+      let high = vmulq_u16(vmovl_high_u8(values), weight);
+
+      let out = totals.as_mut_ptr().add(done);
+      vst1q_u32(out, vaddw_u16(vld1q_u32(out), vget_low_u16(low)));
+      vst1q_u32(out.add(4), vaddw_high_u16(vld1q_u32(out.add(4)), low));
+      vst1q_u32(
+        out.add(8),
+        vaddw_u16(vld1q_u32(out.add(8)), vget_low_u16(high)),
+      );
+      vst1q_u32(out.add(12), vaddw_high_u16(vld1q_u32(out.add(12)), high));
+      done += LANES;
+    }
+
+    done
+  }
+}
+
+#[cfg(all(
+  feature = "simd",
+  not(any(target_arch = "x86_64", target_arch = "aarch64"))
+))]
+mod simd {
+  // 其他平台不做 SIMD 加速
+  pub(super) fn available() -> bool {
+    false
+  }
+
+  pub(super) unsafe fn add_scaled_row(_totals: &mut [u32], _pixels: &[u8], _t: u16) -> usize {
+    0
+  }
+}
@@ -0,0 +1,45 @@
+// 归一化互相关得分图，每个线程计算一个位置
+//
+// 像素按 u32 存储，互相关和窗口平方和用 u32 累加（调用方保证模板像素数不超过 u32::MAX / 255²），
+// 与 CPU 实现一样没有累加误差，只在最后的归一化中使用 f32。
+
+struct Params {
+  image_width: u32,
+  out_width: u32,
+  out_height: u32,
+  template_width: u32,
+  template_height: u32,
+  template_squared_sum: f32,
+  _padding: vec2<u32>,
+}
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read> image: array<u32>;
+@group(0) @binding(2) var<storage, read> tpl: array<u32>;
+@group(0) @binding(3) var<storage, read_write> scores: array<f32>;
+
+@compute @workgroup_size(16, 16)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+  if (id.x >= params.out_width || id.y >= params.out_height) {
+    return;
+  }
+
+  var correlation = 0u;
+  var window = 0u;
+  for (var ty = 0u; ty < params.template_height; ty++) {
+    let row = (id.y + ty) * params.image_width + id.x;
+    let template_row = ty * params.template_width;
+    for (var tx = 0u; tx < params.template_width; tx++) {
+      let i = image[row + tx];
+      correlation += i * tpl[template_row + tx];
+      window += i * i;
+    }
+  }
+
+  let norm = sqrt(f32(window)) * sqrt(params.template_squared_sum);
+  var score = f32(correlation);
+  if (norm > 0.0) {
+    score = score / norm;
+  }
+  scores[id.y * params.out_width + id.x] = score;
+}
@@ -0,0 +1,98 @@
+use image::{GrayImage, Luma};
+use imageproc::definitions::Image;
+use imageproc::gradients::{horizontal_sobel, vertical_sobel};
+
+/// 默认的梯度方向分箱数，每箱 22.5°
+pub(crate) const DEFAULT_ORIENTATION_BINS: u32 = 8;
+
+/// 每个像素的梯度方向分箱 `1..=bins`
+///
+/// 方向由灰度图的 Sobel 梯度计算，按 180° 取模（忽略明暗方向），缺口与滑块的明暗对比相反时
+/// 同一段轮廓仍落在同一箱中。
+pub(crate) fn orientation_bins(gray: &GrayImage, bins: u32) -> GrayImage {
+  let gx = horizontal_sobel(gray);
+  let gy = vertical_sobel(gray);
+  let bin_width = std::f32::consts::PI / bins as f32;
+
+  GrayImage::from_fn(gray.width(), gray.height(), |x, y| {
+    let angle = (gy.get_pixel(x, y)[0] as f32)
+      .atan2(gx.get_pixel(x, y)[0] as f32)
+      .rem_euclid(std::f32::consts::PI);
+    Luma([((angle / bin_width) as u32 % bins + 1) as u8])
+  })
+}
+
+/// 只保留边缘像素的方向分箱，非边缘像素为 0
+pub(crate) fn edge_orientations(orientations: &GrayImage, edges: &GrayImage) -> GrayImage {
+  debug_assert_eq!(orientations.dimensions(), edges.dimensions());
+  let mut labels = orientations.clone();
+  for (label, edge) in labels.iter_mut().zip(edges.iter()) {
+    if *edge == 0 {
+      *label = 0;
+    }
+  }
+  labels
+}
+
+/// 在方向分箱图上滑动模板计算得分图：得分为 方向相同的重合边缘像素数 / sqrt(窗口内边缘像素数 × 模板边缘像素数)，
+/// 即把边缘图按方向拆成多个通道后的归一化互相关，窗口内没有边缘像素时为 0
+///
+/// # Panics
+/// 模板的宽度或高度大于图片时 panic，调用方需事先检查尺寸。
+pub(crate) fn match_orientation(image: &GrayImage, template: &GrayImage) -> Image<Luma<f32>> {
+  assert!(
+    image.width() >= template.width() && image.height() >= template.height(),
+    "模板尺寸 {:?} 大于图片尺寸 {:?}",
+    template.dimensions(),
+    image.dimensions()
+  );
+
+  let (width, height) = image.dimensions();
+  let (template_width, template_height) = template.dimensions();
+  let (out_width, out_height) = (width - template_width + 1, height - template_height + 1);
+
+  let template_pixels: Vec<(u32, u32, u8)> = template
+    .enumerate_pixels()
+    .filter(|(_, _, label)| label[0] != 0)
+    .map(|(x, y, label)| (x, y, label[0]))
+    .collect();
+
+  // 逐个模板边缘像素，把方向相同的背景像素按行累加到得分图上
+  let mut matches = vec![0u32; (out_width * out_height) as usize];
+  for &(tx, ty, label) in &template_pixels {
+    for y in 0..out_height {
+      let row = &image.as_raw()[((y + ty) * width + tx) as usize..][..out_width as usize];
+      let totals = &mut matches[(y * out_width) as usize..][..out_width as usize];
+      for (total, &value) in totals.iter_mut().zip(row) {
+        *total += (value == label) as u32;
+      }
+    }
+  }
+
+  // 边缘像素计数的积分图，用于 O(1) 得到每个窗口内的边缘像素数
+  let stride = (width + 1) as usize;
+  let mut counts = vec![0u32; stride * (height + 1) as usize];
+  for (y, row) in image.as_raw().chunks_exact(width as usize).enumerate() {
+    let mut row_count = 0;
+    for (x, &value) in row.iter().enumerate() {
+      row_count += (value != 0) as u32;
+      counts[(y + 1) * stride + x + 1] = counts[y * stride + x + 1] + row_count;
+    }
+  }
+  let window = |x: u32, y: u32| {
+    let (left, right) = (x as usize, (x + template_width) as usize);
+    let (top, bottom) = (y as usize * stride, (y + template_height) as usize * stride);
+    counts[bottom + right] + counts[top + left] - counts[top + right] - counts[bottom + left]
+  };
+
+  let template_count = template_pixels.len() as f64;
+  Image::from_fn(out_width, out_height, |x, y| {
+    let matched = matches[(y * out_width + x) as usize];
+    let denominator = (window(x, y) as f64 * template_count).sqrt();
+    Luma([if matched == 0 || denominator == 0.0 {
+      0.0
+    } else {
+      (matched as f64 / denominator) as f32
+    }])
+  })
+}
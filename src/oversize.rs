@@ -0,0 +1,231 @@
+use std::borrow::Cow;
+
+use image::imageops::FilterType;
+use image::{DynamicImage, GrayImage};
+
+use napi::bindgen_prelude::Either;
+
+use crate::embedded::PieceRegion;
+use crate::error::SlideMatchError;
+use crate::{BackgroundCoordinateSpace, OversizePolicy, SlideBBox, SlideMatchConfig};
+
+/// 检查目标图片是否能放进背景图片，错误信息包含两张图片的实际尺寸
+pub(crate) fn ensure_fits(target: (u32, u32), background: (u32, u32)) -> anyhow::Result<()> {
+  if target.0 > background.0 || target.1 > background.1 {
+    return Err(SlideMatchError::TargetLargerThanBackground { target, background }.into());
+  }
+  Ok(())
+}
+
+/// 校验 `backgroundTargetWidth`
+pub(crate) fn validate_fit_config(config: &SlideMatchConfig) -> anyhow::Result<()> {
+  anyhow::ensure!(
+    config.background_target_width != Some(0),
+    "backgroundTargetWidth 必须大于 0"
+  );
+  Ok(())
+}
+
+/// `fit_images` 实际应用的缩放比例，用于把匹配结果换算回原始图片的坐标系
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct FitScale {
+  // 目标图片的缩放比例（`scaleTarget` 时小于 1）
+  target_scale: f64,
+  // 按 `backgroundTargetWidth` 重采样背景图片的缩放比例
+  resize_scale: f64,
+  // 重采样后背景图片的缩放比例（`scaleBackground` 时大于 1）
+  background_scale: f64,
+  // 缩放后的背景图尺寸
+  background_size: (u32, u32),
+  // 返回坐标所在坐标系相对原始背景图的缩放比例（`backgroundCoordinateSpace`）
+  output_scale: f64,
+  // 返回坐标所在坐标系的背景图尺寸
+  output_size: (u32, u32),
+}
+
+// 按 `backgroundTargetWidth` 重采样背景图片（高度按比例缩放），返回重采样后的图片和缩放比例
+fn resize_background(background: DynamicImage, config: &SlideMatchConfig) -> (DynamicImage, f64) {
+  let Some(width) = config
+    .background_target_width
+    .filter(|&width| width != background.width())
+  else {
+    return (background, 1.0);
+  };
+
+  let scale = width as f64 / background.width() as f64;
+  let height = ((background.height() as f64 * scale).round() as u32).max(1);
+  (
+    background.resize_exact(width, height, FilterType::Lanczos3),
+    scale,
+  )
+}
+
+/// 先按 `backgroundTargetWidth` 重采样背景图片，之后目标图片仍大于背景图片时按 `oversizePolicy`
+/// 缩放其中一张（保持宽高比），否则原样返回
+///
+/// 匹配在返回的图片上进行，再用 [`FitScale::restore`] 把结果换算回 `backgroundCoordinateSpace`
+/// 指定的背景图坐标系（和原始目标图的坐标系）。
+pub(crate) fn fit_images(
+  target: DynamicImage,
+  background: DynamicImage,
+  config: &SlideMatchConfig,
+) -> anyhow::Result<(DynamicImage, DynamicImage, FitScale)> {
+  let natural_size = (background.width(), background.height());
+  let (background, resize_scale) = resize_background(background, config);
+
+  let (target_width, target_height) = (target.width(), target.height());
+  let (background_width, background_height) = (background.width(), background.height());
+  let (output_scale, output_size) = match config.background_coordinate_space.unwrap_or_default() {
+    BackgroundCoordinateSpace::Resized => (resize_scale, (background_width, background_height)),
+    BackgroundCoordinateSpace::Natural => (1.0, natural_size),
+  };
+  let unscaled = FitScale {
+    target_scale: 1.0,
+    resize_scale,
+    background_scale: 1.0,
+    background_size: (background_width, background_height),
+    output_scale,
+    output_size,
+  };
+
+  if target_width <= background_width && target_height <= background_height {
+    return Ok((target, background, unscaled));
+  }
+
+  match config.oversize_policy.unwrap_or_default() {
+    OversizePolicy::Error => {
+      ensure_fits(
+        (target_width, target_height),
+        (background_width, background_height),
+      )?;
+      Ok((target, background, unscaled))
+    }
+    OversizePolicy::ScaleTarget => {
+      let scale = f64::min(
+        background_width as f64 / target_width as f64,
+        background_height as f64 / target_height as f64,
+      );
+      // 向下取整，保证缩放后不超出背景图
+      let width = ((target_width as f64 * scale) as u32).clamp(1, background_width);
+      let height = ((target_height as f64 * scale) as u32).clamp(1, background_height);
+      let target = target.resize_exact(width, height, FilterType::Triangle);
+      Ok((
+        target,
+        background,
+        FitScale {
+          target_scale: scale,
+          ..unscaled
+        },
+      ))
+    }
+    OversizePolicy::ScaleBackground => {
+      let scale = f64::max(
+        target_width as f64 / background_width as f64,
+        target_height as f64 / background_height as f64,
+      );
+      // 向上取整，保证缩放后能放下目标图
+      let width = ((background_width as f64 * scale).ceil() as u32).max(target_width);
+      let height = ((background_height as f64 * scale).ceil() as u32).max(target_height);
+      let background = background.resize_exact(width, height, FilterType::Triangle);
+      Ok((
+        target,
+        background,
+        FitScale {
+          background_scale: scale,
+          background_size: (width, height),
+          ..unscaled
+        },
+      ))
+    }
+  }
+}
+
+impl FitScale {
+  // 背景图片是否按 `oversizePolicy` 被缩放
+  fn background_scaled(&self) -> bool {
+    self.background_scale != 1.0
+  }
+
+  // 匹配用的背景图相对返回坐标所在坐标系的缩放比例
+  fn output_to_background(&self) -> f64 {
+    self.resize_scale * self.background_scale / self.output_scale
+  }
+
+  /// 返回坐标所在坐标系的背景图尺寸，用于计算比例坐标和换算显示尺寸
+  pub(crate) fn output_size(&self) -> (u32, u32) {
+    self.output_size
+  }
+
+  /// 把返回坐标所在坐标系中的坐标换算到匹配用的背景图，取整方式与 [`FitScale::config`] 一致
+  pub(crate) fn background_position(&self, value: u32) -> u32 {
+    (value as f64 * self.output_to_background()).round() as u32
+  }
+
+  /// 背景图按 `oversizePolicy` 缩放后（缩放比例取决于目标图片），背景边缘图缓存键不再对应缓存中的图片，
+  /// 不使用缓存；`backgroundTargetWidth` 已计入缓存键
+  pub(crate) fn cache_key(&self, key: Option<u64>) -> Option<u64> {
+    key.filter(|_| !self.background_scaled())
+  }
+
+  /// 把背景掩码缩放到与匹配用的背景图一致（最近邻插值，保持二值）
+  pub(crate) fn background_mask<'a>(&self, mask: &'a GrayImage) -> Cow<'a, GrayImage> {
+    if mask.dimensions() == self.background_size {
+      return Cow::Borrowed(mask);
+    }
+    Cow::Owned(image::imageops::resize(
+      mask,
+      self.background_size.0,
+      self.background_size.1,
+      FilterType::Nearest,
+    ))
+  }
+
+  /// 把 `fixedY`、`fixedX`（及其容差）和 `ignorePieceRegion` 的矩形从返回坐标所在的坐标系换算到
+  /// 匹配用的背景图
+  pub(crate) fn config<'a>(&self, config: &'a SlideMatchConfig) -> Cow<'a, SlideMatchConfig> {
+    let factor = self.output_to_background();
+    let piece_region = matches!(config.ignore_piece_region, Some(Either::A(_)));
+    if factor == 1.0 || config.fixed_y.is_none() && config.fixed_x.is_none() && !piece_region {
+      return Cow::Borrowed(config);
+    }
+    let scale = |value: u32| (value as f64 * factor).round() as u32;
+    Cow::Owned(SlideMatchConfig {
+      fixed_y: config.fixed_y.map(scale),
+      fixed_y_tolerance: config.fixed_y_tolerance.map(scale),
+      fixed_x: config.fixed_x.map(scale),
+      fixed_x_tolerance: config.fixed_x_tolerance.map(scale),
+      ignore_piece_region: match &config.ignore_piece_region {
+        Some(Either::A(region)) => Some(Either::A(PieceRegion {
+          x: scale(region.x),
+          y: scale(region.y),
+          width: scale(region.width).max(1),
+          height: scale(region.height).max(1),
+        })),
+        region => region.clone(),
+      },
+      ..config.clone()
+    })
+  }
+
+  /// 把在调整后的图片上得到的匹配结果换算到返回坐标所在的坐标系（目标图片坐标换算回原始目标图），
+  /// 并记录 `targetScale`
+  pub(crate) fn restore(&self, bbox: SlideBBox) -> SlideBBox {
+    let factor = self.output_to_background();
+    if self.target_scale == 1.0 && factor == 1.0 {
+      return bbox;
+    }
+
+    let background = |value: u32| (value as f64 / factor).round() as u32;
+    let target = |value: u32| (value as f64 / self.target_scale).round() as u32;
+    SlideBBox {
+      target_x: target(bbox.target_x),
+      target_y: target(bbox.target_y),
+      x1: background(bbox.x1),
+      y1: background(bbox.y1),
+      x2: background(bbox.x2),
+      y2: background(bbox.y2),
+      target_scale: Some(self.target_scale / factor),
+      ..bbox
+    }
+  }
+}
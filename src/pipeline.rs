@@ -0,0 +1,149 @@
+use std::io::Cursor;
+
+use anyhow::Context;
+use image::{DynamicImage, GrayImage, ImageFormat};
+
+use crate::preprocess::{apply_morphology, clahe};
+use crate::MorphOp;
+
+/// 图片编码格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+  Png,
+  Jpeg,
+}
+
+impl From<OutputFormat> for ImageFormat {
+  fn from(format: OutputFormat) -> Self {
+    match format {
+      OutputFormat::Png => ImageFormat::Png,
+      OutputFormat::Jpeg => ImageFormat::Jpeg,
+    }
+  }
+}
+
+/// 内存中的图片处理流水线
+///
+/// 各个处理步骤直接在解码后的图片上链式执行，只在最后需要输出 Buffer 时编码一次，
+/// 避免每一步都做 PNG 编码/解码。灰度类操作（均衡化、模糊、边缘检测等）会先把图片转换为灰度图。
+///
+/// ```ignore
+/// let edges = ImagePipeline::from_bytes(&data)?
+///   .equalize()
+///   .gaussian_blur(1.0)
+///   .canny(100.0, 200.0)
+///   .to_bytes(OutputFormat::Png)?;
+/// ```
+#[derive(Debug, Clone)]
+#[must_use = "流水线的每一步都返回新的流水线，需要继续使用返回值"]
+pub struct ImagePipeline {
+  image: DynamicImage,
+}
+
+impl ImagePipeline {
+  /// 从图片数据解码
+  pub fn from_bytes(data: &[u8]) -> anyhow::Result<Self> {
+    let image = image::load_from_memory(data).context("无法加载图片")?;
+    Ok(Self { image })
+  }
+
+  /// 从已解码的图片创建
+  pub fn from_image(image: DynamicImage) -> Self {
+    Self { image }
+  }
+
+  /// 从灰度图创建
+  pub fn from_gray(gray: GrayImage) -> Self {
+    Self {
+      image: DynamicImage::ImageLuma8(gray),
+    }
+  }
+
+  // 取出灰度图，非灰度图会先转换
+  fn gray(self) -> GrayImage {
+    match self.image {
+      DynamicImage::ImageLuma8(gray) => gray,
+      image => image.to_luma8(),
+    }
+  }
+
+  // 对灰度图执行一步操作
+  fn map_gray(self, f: impl FnOnce(GrayImage) -> GrayImage) -> Self {
+    Self::from_gray(f(self.gray()))
+  }
+
+  /// 转换为灰度图
+  pub fn grayscale(self) -> Self {
+    self.map_gray(|gray| gray)
+  }
+
+  /// 反相：灰度值 v 变为 255 - v
+  pub fn invert(self) -> Self {
+    self.map_gray(|mut gray| {
+      image::imageops::invert(&mut gray);
+      gray
+    })
+  }
+
+  /// 全局直方图均衡化
+  pub fn equalize(self) -> Self {
+    self.map_gray(|gray| imageproc::contrast::equalize_histogram(&gray))
+  }
+
+  /// CLAHE（限制对比度自适应直方图均衡化），见 [`clahe`]
+  pub fn clahe(self, tile_size: u32, clip_limit: f32) -> Self {
+    self.map_gray(|gray| clahe(&gray, tile_size, clip_limit))
+  }
+
+  /// 中值滤波，窗口为边长 `2 * radius + 1` 的正方形
+  pub fn median_filter(self, radius: u32) -> Self {
+    self.map_gray(|gray| imageproc::filter::median_filter(&gray, radius, radius))
+  }
+
+  /// 高斯模糊
+  pub fn gaussian_blur(self, sigma: f32) -> Self {
+    self.map_gray(|gray| imageproc::filter::gaussian_blur_f32(&gray, sigma))
+  }
+
+  /// Canny 边缘检测，结果为二值边缘图（边缘为 255）
+  pub fn canny(self, low: f32, high: f32) -> Self {
+    self.map_gray(|gray| imageproc::edges::canny(&gray, low, high))
+  }
+
+  /// 形态学操作，见 [`apply_morphology`]
+  pub fn morphology(self, morphology: MorphOp) -> Self {
+    self.map_gray(|gray| apply_morphology(&gray, morphology))
+  }
+
+  /// 当前图片
+  pub fn image(&self) -> &DynamicImage {
+    &self.image
+  }
+
+  /// 结束流水线，返回图片
+  pub fn into_image(self) -> DynamicImage {
+    self.image
+  }
+
+  /// 结束流水线，返回灰度图
+  pub fn into_gray(self) -> GrayImage {
+    self.gray()
+  }
+
+  /// 编码为指定格式
+  pub fn to_bytes(&self, format: OutputFormat) -> anyhow::Result<Vec<u8>> {
+    let mut bytes = Cursor::new(Vec::new());
+
+    // JPEG 不支持透明通道
+    match (format, &self.image) {
+      (OutputFormat::Jpeg, DynamicImage::ImageLuma8(_) | DynamicImage::ImageRgb8(_))
+      | (OutputFormat::Png, _) => self.image.write_to(&mut bytes, format.into()),
+      (OutputFormat::Jpeg, image) => {
+        DynamicImage::ImageRgb8(image.to_rgb8()).write_to(&mut bytes, format.into())
+      }
+    }
+    .context("图片编码失败")?;
+
+    Ok(bytes.into_inner())
+  }
+}
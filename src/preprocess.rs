@@ -0,0 +1,654 @@
+use std::borrow::Cow;
+
+use image::{DynamicImage, GrayImage, ImageBuffer, Luma, Pixel, RgbaImage};
+use imageproc::distance_transform::Norm;
+use napi::bindgen_prelude::Either;
+
+use crate::pipeline::ImagePipeline;
+use crate::stats::ImageStats;
+use crate::{ColorMode, InvertMode, MorphOp, SlideMatchConfig};
+
+// CLAHE 默认对比度限制
+pub(crate) const DEFAULT_CLAHE_CLIP_LIMIT: f32 = 2.0;
+// `invert` 为 `auto` 时默认的灰度均值阈值
+const DEFAULT_INVERT_CUTOFF: f64 = 64.0;
+// 透明度低于该值的像素视为透明，不参与匹配
+pub(crate) const ALPHA_MASK_THRESHOLD: u8 = 128;
+// 自动选择高斯模糊 σ 时的最小值
+const MIN_AUTO_SIGMA: f64 = 1.0;
+// 自动选择高斯模糊 σ 时，sqrt(宽 × 高) 除以该值
+const AUTO_SIGMA_SCALE: f64 = 500.0;
+// 掩码向外扩展的像素数，Canny 检测到的滑块轮廓可能落在透明一侧
+const ALPHA_MASK_TOLERANCE: u8 = 1;
+// 一行的边缘像素占比达到整张边缘图平均占比的该倍数时视为高密度行
+const TEXT_ROW_DENSITY_RATIO: f32 = 3.0;
+// 高密度行的边缘像素占比下限，避免边缘稀少的图片中零散的行被误判
+const MIN_TEXT_ROW_DENSITY: f32 = 0.05;
+// 连续高密度行的高度在该范围内时视为文字所在的条带（像素），
+// 更矮的通常是缺口的水平轮廓，更高的通常是纹理丰富的区域
+const MIN_TEXT_BAND_HEIGHT: u32 = 4;
+const MAX_TEXT_BAND_HEIGHT: u32 = 24;
+// 条带内允许连续出现的低密度行数
+const MAX_TEXT_ROW_GAP: u32 = 2;
+
+/// 计算单个分块的映射表（限制对比度的直方图均衡化）
+fn clahe_tile_lut(
+  img: &GrayImage,
+  x0: u32,
+  y0: u32,
+  tile_w: u32,
+  tile_h: u32,
+  clip_limit: f32,
+) -> [u8; 256] {
+  let mut hist = [0u32; 256];
+  for y in y0..y0 + tile_h {
+    for x in x0..x0 + tile_w {
+      hist[img.get_pixel(x, y)[0] as usize] += 1;
+    }
+  }
+
+  let total = tile_w * tile_h;
+
+  // 裁剪直方图：每个灰度级最多 clip_limit * 平均数量个像素
+  let limit = ((clip_limit * total as f32 / 256.0) as u32).max(1);
+  let mut excess = 0u32;
+  for count in hist.iter_mut() {
+    if *count > limit {
+      excess += *count - limit;
+      *count = limit;
+    }
+  }
+
+  // 将超出部分平均分配回所有灰度级，余数按步长分散分配
+  let increment = excess / 256;
+  let remainder = excess % 256;
+  for count in hist.iter_mut() {
+    *count += increment;
+  }
+  if let Some(step) = 256u32.checked_div(remainder) {
+    for count in hist
+      .iter_mut()
+      .step_by(step.max(1) as usize)
+      .take(remainder as usize)
+    {
+      *count += 1;
+    }
+  }
+
+  // 累积分布函数 -> 映射表
+  let mut lut = [0u8; 256];
+  let mut cdf = 0u32;
+  for (value, count) in hist.iter().enumerate() {
+    cdf += count;
+    lut[value] = ((cdf as f32 * 255.0 / total as f32).round()).min(255.0) as u8;
+  }
+
+  lut
+}
+
+/// 计算像素在分块中心网格上的插值位置
+/// 返回 (前一个分块索引, 后一个分块索引, 权重)
+fn clahe_grid_position(coord: u32, tile_size: u32, tiles: u32) -> (usize, usize, f32) {
+  let pos = (coord as f32 + 0.5) / tile_size as f32 - 0.5;
+  let max_index = (tiles - 1) as f32;
+
+  if pos <= 0.0 {
+    return (0, 0, 0.0);
+  }
+  if pos >= max_index {
+    let last = tiles as usize - 1;
+    return (last, last, 0.0);
+  }
+
+  let i0 = pos.floor();
+  (i0 as usize, i0 as usize + 1, pos - i0)
+}
+
+/// CLAHE（限制对比度自适应直方图均衡化）
+///
+/// 将图片切分为 `tile_size` × `tile_size` 的分块，分别对每个分块做限制对比度的直方图均衡化，
+/// 再在相邻分块的映射结果之间做双线性插值，避免出现分块边界。
+///
+/// # 参数
+/// - img: 灰度图
+/// - tile_size: 分块边长（像素），必须大于 0
+/// - clip_limit: 对比度限制，相对于分块平均直方图高度的倍数，越大对比度增强越明显
+#[must_use]
+pub fn clahe(img: &GrayImage, tile_size: u32, clip_limit: f32) -> GrayImage {
+  let width = img.width();
+  let height = img.height();
+
+  if width == 0 || height == 0 || tile_size == 0 {
+    return img.clone();
+  }
+
+  let tiles_x = width.div_ceil(tile_size);
+  let tiles_y = height.div_ceil(tile_size);
+
+  // 预先计算所有分块的映射表
+  let mut luts = Vec::with_capacity((tiles_x * tiles_y) as usize);
+  for ty in 0..tiles_y {
+    for tx in 0..tiles_x {
+      let x0 = tx * tile_size;
+      let y0 = ty * tile_size;
+      let tile_w = tile_size.min(width - x0);
+      let tile_h = tile_size.min(height - y0);
+      luts.push(clahe_tile_lut(img, x0, y0, tile_w, tile_h, clip_limit));
+    }
+  }
+
+  let lut_at = |tx: usize, ty: usize| &luts[ty * tiles_x as usize + tx];
+
+  // 双线性插值
+  let mut output = GrayImage::new(width, height);
+  for y in 0..height {
+    let (ty0, ty1, fy) = clahe_grid_position(y, tile_size, tiles_y);
+
+    for x in 0..width {
+      let (tx0, tx1, fx) = clahe_grid_position(x, tile_size, tiles_x);
+      let value = img.get_pixel(x, y)[0] as usize;
+
+      let top = lut_at(tx0, ty0)[value] as f32 * (1.0 - fx) + lut_at(tx1, ty0)[value] as f32 * fx;
+      let bottom =
+        lut_at(tx0, ty1)[value] as f32 * (1.0 - fx) + lut_at(tx1, ty1)[value] as f32 * fx;
+      let mapped = top * (1.0 - fy) + bottom * fy;
+
+      output.put_pixel(x, y, image::Luma([mapped.round().clamp(0.0, 255.0) as u8]));
+    }
+  }
+
+  output
+}
+
+/// 按配置对灰度图执行预处理（在阈值计算和边缘检测之前）
+///
+/// 先按 `medianFilterRadius` 做中值滤波去除 JPEG 块噪声，再执行 CLAHE，避免噪声被对比度增强放大。
+pub(crate) fn preprocess_gray(pipeline: ImagePipeline, config: &SlideMatchConfig) -> ImagePipeline {
+  let pipeline = match config.median_filter_radius {
+    Some(radius) if radius > 0 => pipeline.median_filter(radius),
+    _ => pipeline,
+  };
+
+  match config.clahe_tile_size {
+    Some(tile_size) => {
+      let clip_limit = config
+        .clahe_clip_limit
+        .map_or(DEFAULT_CLAHE_CLIP_LIMIT, |v| v as f32);
+      pipeline.clahe(tile_size, clip_limit)
+    }
+    None => pipeline,
+  }
+}
+
+/// 按 `invert` 配置判断是否反相灰度图，`auto` 时按（反相前的）灰度均值判断
+pub(crate) fn should_invert(gray: &GrayImage, config: &SlideMatchConfig) -> bool {
+  match &config.invert {
+    None => false,
+    Some(Either::A(invert)) => *invert,
+    Some(Either::B(InvertMode::Auto)) => {
+      ImageStats::from_gray(gray).mean < config.invert_cutoff.unwrap_or(DEFAULT_INVERT_CUTOFF)
+    }
+  }
+}
+
+/// 目标图片使用的预处理配置
+///
+/// `medianFilterRadius` 默认只作用于背景图片，未启用 `medianFilterTarget` 时返回不做中值滤波的配置。
+pub(crate) fn target_preprocess_config(config: &SlideMatchConfig) -> Cow<'_, SlideMatchConfig> {
+  if config.median_filter_radius.is_none() || config.median_filter_target.unwrap_or(false) {
+    return Cow::Borrowed(config);
+  }
+
+  Cow::Owned(SlideMatchConfig {
+    median_filter_radius: None,
+    ..config.clone()
+  })
+}
+
+/// 16 位灰度图
+pub(crate) type Gray16Image = ImageBuffer<Luma<u16>, Vec<u16>>;
+
+/// 参与匹配的图片
+///
+/// 灰度图始终保留，用于计算自适应阈值；RGB 模式下额外保存 R、G、B 三个通道，
+/// 边缘检测和模板匹配在这些通道上分别进行。
+pub(crate) struct MatchImage {
+  /// 预处理后的灰度图
+  pub(crate) gray: GrayImage,
+  /// 预处理后的 R、G、B 通道，灰度模式下为空
+  color_channels: Vec<GrayImage>,
+  /// 背景边缘图缓存键，启用 `cacheBackground` 时设置
+  pub(crate) cache_key: Option<u64>,
+  /// 灰度图（及各颜色通道）是否已反相，见 [`should_invert`]
+  pub(crate) inverted: bool,
+  /// 原图为 16 位灰度图（`L16`/`La16`）时保留的 16 位灰度值，用于更精确地计算自适应阈值；
+  /// `imageproc::edges::canny` 只接受 8 位灰度图，边缘检测仍使用 `gray`
+  pub(crate) gray16: Option<Gray16Image>,
+}
+
+/// 16 位灰度图（`L16`/`La16`）的灰度值，其他格式返回 None
+///
+/// 启用 CLAHE 或中值滤波时 8 位灰度图已被重新映射，16 位原图的统计量不再对应参与匹配的灰度图，同样返回 None。
+pub(crate) fn gray16(img: &DynamicImage, config: &SlideMatchConfig) -> Option<Gray16Image> {
+  if config.clahe_tile_size.is_some() || config.median_filter_radius.is_some_and(|r| r > 0) {
+    return None;
+  }
+
+  match img {
+    DynamicImage::ImageLuma16(gray) => Some(gray.clone()),
+    DynamicImage::ImageLumaA16(_) => Some(img.to_luma16()),
+    _ => None,
+  }
+}
+
+// RGBA 图片转灰度图，启用 `simd` 特性时使用 SIMD 加速的实现（结果逐字节一致）
+fn rgba_to_gray(img: &RgbaImage) -> GrayImage {
+  #[cfg(feature = "simd")]
+  return crate::fast_grayscale::rgba_to_luma(img);
+  #[cfg(not(feature = "simd"))]
+  return image::imageops::grayscale(img);
+}
+
+// 解码后的图片转灰度图，启用 `simd` 特性时使用 SIMD 加速的实现（结果逐字节一致）；
+// 启用 `linearLight` 时在线性光强下计算亮度
+fn to_gray(img: &DynamicImage, config: &SlideMatchConfig) -> GrayImage {
+  if config.linear_light.unwrap_or(false) {
+    return match img {
+      // 灰度值本身就是亮度，两种方式结果相同
+      DynamicImage::ImageLuma8(gray) => gray.clone(),
+      DynamicImage::ImageRgb8(rgb) => linear_light_gray(rgb),
+      DynamicImage::ImageRgba8(rgba) => linear_light_gray(rgba),
+      img => linear_light_gray(&img.to_rgb8()),
+    };
+  }
+
+  #[cfg(feature = "simd")]
+  return crate::fast_grayscale::to_luma8(img);
+  #[cfg(not(feature = "simd"))]
+  return img.to_luma8();
+}
+
+// 同 `to_gray`，已是 8 位灰度图时不复制
+fn into_gray(img: DynamicImage, config: &SlideMatchConfig) -> GrayImage {
+  match img {
+    DynamicImage::ImageLuma8(gray) => gray,
+    img => to_gray(&img, config),
+  }
+}
+
+// sRGB 编码值 -> 线性光强（0.0-1.0）
+fn srgb_to_linear(value: u8) -> f32 {
+  let v = value as f32 / 255.0;
+  if v <= 0.04045 {
+    v / 12.92
+  } else {
+    ((v + 0.055) / 1.055).powf(2.4)
+  }
+}
+
+// 线性光强 -> sRGB 编码值（四舍五入）
+fn linear_to_srgb(value: f32) -> u8 {
+  let v = if value <= 0.0031308 {
+    value * 12.92
+  } else {
+    1.055 * value.powf(1.0 / 2.4) - 0.055
+  };
+  (v * 255.0).round().clamp(0.0, 255.0) as u8
+}
+
+/// 在线性光强下计算亮度的灰度转换（`linearLight`）
+///
+/// 先把 R、G、B 从 sRGB 解码为线性光强，按 Rec.709 权重求亮度，再编码回 sRGB 并量化为 8 位，
+/// 供自适应阈值统计和 Canny 使用。直接对 sRGB 编码值加权会低估饱和色（如暗背景上的红色轮廓）
+/// 的亮度，使其与周围的暗色区域难以区分。
+#[must_use]
+pub fn linear_light_gray<P: Pixel<Subpixel = u8>>(img: &ImageBuffer<P, Vec<u8>>) -> GrayImage {
+  let linear: [f32; 256] = std::array::from_fn(|value| srgb_to_linear(value as u8));
+
+  GrayImage::from_fn(img.width(), img.height(), |x, y| {
+    let [r, g, b] = img.get_pixel(x, y).to_rgb().0;
+    let luminance =
+      0.2126 * linear[r as usize] + 0.7152 * linear[g as usize] + 0.0722 * linear[b as usize];
+    image::Luma([linear_to_srgb(luminance)])
+  })
+}
+
+impl MatchImage {
+  /// 由 RGBA 图片（如裁剪后的目标图片）构建
+  pub(crate) fn from_rgba(img: &RgbaImage, config: &SlideMatchConfig) -> Self {
+    let gray = if config.linear_light.unwrap_or(false) {
+      linear_light_gray(img)
+    } else {
+      rgba_to_gray(img)
+    };
+    Self::new(gray, || split_channels(img), config)
+  }
+
+  /// 由解码后的图片构建
+  ///
+  /// 灰度模式下直接转换为灰度图并释放解码得到的彩色图片（已是 8 位灰度图时不复制），
+  /// 大尺寸背景图的彩色缓冲区不会一直保留到匹配结束。
+  pub(crate) fn from_dynamic(img: DynamicImage, config: &SlideMatchConfig) -> Self {
+    let gray16 = gray16(&img, config);
+    let image = match config.color_mode.unwrap_or_default() {
+      ColorMode::Gray => Self::new(into_gray(img, config), Vec::new, config),
+      ColorMode::Rgb => Self::new(
+        to_gray(&img, config),
+        || split_channels(&img.into_rgb8()),
+        config,
+      ),
+    };
+    image.with_gray16(gray16)
+  }
+
+  fn new(
+    gray: GrayImage,
+    channels: impl FnOnce() -> Vec<GrayImage>,
+    config: &SlideMatchConfig,
+  ) -> Self {
+    crate::test_utils::record_grayscale();
+    // 各颜色通道按灰度图的判断结果一起反相
+    let inverted = should_invert(&gray, config);
+    let preprocess = |channel: GrayImage| {
+      let pipeline = ImagePipeline::from_gray(channel);
+      let pipeline = if inverted {
+        pipeline.invert()
+      } else {
+        pipeline
+      };
+      preprocess_gray(pipeline, config).into_gray()
+    };
+
+    let color_channels = match config.color_mode.unwrap_or_default() {
+      ColorMode::Gray => Vec::new(),
+      ColorMode::Rgb => channels().into_iter().map(preprocess).collect(),
+    };
+
+    Self {
+      gray: preprocess(gray),
+      color_channels,
+      cache_key: None,
+      inverted,
+      gray16: None,
+    }
+  }
+
+  /// 设置 16 位灰度值（见 [`gray16`]），尺寸必须与灰度图一致；灰度图已反相时同样反相
+  pub(crate) fn with_gray16(self, gray16: Option<Gray16Image>) -> Self {
+    let gray16 = gray16.map(|mut gray16| {
+      if self.inverted {
+        image::imageops::invert(&mut gray16);
+      }
+      gray16
+    });
+    Self { gray16, ..self }
+  }
+
+  /// 设置背景边缘图缓存键
+  pub(crate) fn with_cache_key(self, cache_key: Option<u64>) -> Self {
+    Self { cache_key, ..self }
+  }
+
+  /// 转置后的图片（交换 x、y），用于竖直滑动的匹配；缓存键不变，`axis` 已计入缓存键
+  pub(crate) fn transposed(&self) -> Self {
+    Self {
+      gray: transpose(&self.gray),
+      color_channels: self.color_channels.iter().map(transpose).collect(),
+      cache_key: self.cache_key,
+      inverted: self.inverted,
+      gray16: self.gray16.as_ref().map(transpose),
+    }
+  }
+
+  /// 参与边缘检测和模板匹配的通道
+  pub(crate) fn channels(&self) -> &[GrayImage] {
+    if self.color_channels.is_empty() {
+      std::slice::from_ref(&self.gray)
+    } else {
+      &self.color_channels
+    }
+  }
+
+  pub(crate) fn width(&self) -> u32 {
+    self.gray.width()
+  }
+
+  pub(crate) fn height(&self) -> u32 {
+    self.gray.height()
+  }
+}
+
+/// 转置图片（交换 x、y）
+pub(crate) fn transpose<P: Pixel>(
+  img: &ImageBuffer<P, Vec<P::Subpixel>>,
+) -> ImageBuffer<P, Vec<P::Subpixel>> {
+  ImageBuffer::from_fn(img.height(), img.width(), |x, y| *img.get_pixel(y, x))
+}
+
+/// 拆分出图片的 R、G、B 三个通道
+fn split_channels<P: Pixel<Subpixel = u8>>(img: &ImageBuffer<P, Vec<u8>>) -> Vec<GrayImage> {
+  (0..3)
+    .map(|c| {
+      GrayImage::from_fn(img.width(), img.height(), |x, y| {
+        image::Luma([img.get_pixel(x, y).channels()[c]])
+      })
+    })
+    .collect()
+}
+
+/// 对边缘图执行形态学操作
+///
+/// 结构元素为边长 `2 * radius + 1` 的正方形（L∞ 范数）。
+#[must_use]
+pub fn apply_morphology(edges: &GrayImage, morphology: MorphOp) -> GrayImage {
+  let k = morphology.radius().min(u8::MAX as u32) as u8;
+
+  match morphology {
+    MorphOp::Dilate(_) => imageproc::morphology::dilate(edges, Norm::LInf, k),
+    MorphOp::Erode(_) => imageproc::morphology::erode(edges, Norm::LInf, k),
+    MorphOp::Open(_) => imageproc::morphology::open(edges, Norm::LInf, k),
+    MorphOp::Close(_) => imageproc::morphology::close(edges, Norm::LInf, k),
+  }
+}
+
+/// 按配置对边缘图执行后处理（在模板匹配之前）
+///
+/// 先执行 `edgeMorphology` 指定的形态学操作，再按 `dilateEdges` 膨胀边缘。
+pub(crate) fn postprocess_edges(edges: ImagePipeline, config: &SlideMatchConfig) -> ImagePipeline {
+  let edges = match config.edge_morphology {
+    Some(morphology) => edges.morphology(morphology),
+    None => edges,
+  };
+
+  match config.dilate_edges {
+    Some(radius) if radius > 0 => edges.morphology(MorphOp::Dilate(radius)),
+    _ => edges,
+  }
+}
+
+/// 按背景图分辨率确定 Canny 前的高斯模糊 σ
+///
+/// 启用 `autoSigma` 且未指定 `cannySigma` 时返回设置了 `canny_sigma` 的配置，
+/// 目标图和背景图的边缘检测都使用该配置，保证两者的模糊程度一致。
+pub(crate) fn resolve_canny_sigma(
+  config: &SlideMatchConfig,
+  background_width: u32,
+  background_height: u32,
+) -> Cow<'_, SlideMatchConfig> {
+  if config.canny_sigma.is_some() || !config.auto_sigma.unwrap_or(false) {
+    return Cow::Borrowed(config);
+  }
+
+  let resolution = (background_width as f64 * background_height as f64).sqrt();
+  Cow::Owned(SlideMatchConfig {
+    canny_sigma: Some((resolution / AUTO_SIGMA_SCALE).max(MIN_AUTO_SIGMA)),
+    ..config.clone()
+  })
+}
+
+/// 高斯模糊（`cannySigma`）+ Canny 边缘检测 + 后处理
+pub(crate) fn detect_edges(
+  gray: GrayImage,
+  (low, high): (f32, f32),
+  config: &SlideMatchConfig,
+) -> GrayImage {
+  crate::test_utils::record_canny();
+  let pipeline = ImagePipeline::from_gray(gray);
+  let pipeline = match config.canny_sigma {
+    Some(sigma) => pipeline.gaussian_blur(sigma as f32),
+    None => pipeline,
+  };
+  postprocess_edges(pipeline.canny(low, high), config).into_gray()
+}
+
+/// 背景图的边缘检测：`detect_edges` 之后按 `maskTextRegions` 清除文字区域的边缘
+pub(crate) fn detect_background_edges(
+  gray: GrayImage,
+  thresholds: (f32, f32),
+  config: &SlideMatchConfig,
+) -> GrayImage {
+  let edges = detect_edges(gray, thresholds, config);
+  if config.mask_text_regions.unwrap_or(false) {
+    mask_text_regions(edges)
+  } else {
+    edges
+  }
+}
+
+/// 清除边缘图中疑似文字的水平条带
+///
+/// 逐行统计边缘像素数，边缘明显比整张图密集的连续行（中间最多夹杂 `MAX_TEXT_ROW_GAP` 个
+/// 低密度行）组成条带，高度在 `MIN_TEXT_BAND_HEIGHT..=MAX_TEXT_BAND_HEIGHT` 之间的条带整体清零。
+pub(crate) fn mask_text_regions(mut edges: GrayImage) -> GrayImage {
+  let (width, height) = edges.dimensions();
+  if width == 0 || height == 0 {
+    return edges;
+  }
+
+  let row_densities: Vec<f32> = edges
+    .rows()
+    .map(|row| row.filter(|p| p[0] != 0).count() as f32 / width as f32)
+    .collect();
+  let mean = row_densities.iter().sum::<f32>() / height as f32;
+  let threshold = (mean * TEXT_ROW_DENSITY_RATIO).max(MIN_TEXT_ROW_DENSITY);
+
+  let mut y = 0;
+  while y < height {
+    if row_densities[y as usize] < threshold {
+      y += 1;
+      continue;
+    }
+
+    // 条带内允许夹杂少量低密度行（如横向笔画内部）
+    let start = y;
+    let mut end = y + 1;
+    y += 1;
+    while y < height && y - end <= MAX_TEXT_ROW_GAP {
+      if row_densities[y as usize] >= threshold {
+        end = y + 1;
+      }
+      y += 1;
+    }
+    if (MIN_TEXT_BAND_HEIGHT..=MAX_TEXT_BAND_HEIGHT).contains(&(end - start)) {
+      for row in start..end {
+        for x in 0..width {
+          edges.put_pixel(x, row, image::Luma([0]));
+        }
+      }
+    }
+  }
+
+  edges
+}
+
+/// 边缘图中边缘（非零）像素的占比，空图片返回 0
+pub(crate) fn edge_density(edges: &GrayImage) -> f32 {
+  let total = edges.width() as u64 * edges.height() as u64;
+  if total == 0 {
+    return 0.0;
+  }
+
+  let count = edges.iter().filter(|&&v| v != 0).count();
+  (count as f64 / total as f64) as f32
+}
+
+/// 根据目标图片的透明度生成掩码：不透明像素为 255，透明像素为 0
+///
+/// 掩码会向外扩展 1 像素，保留紧贴滑块外侧的轮廓边缘。
+#[must_use]
+pub fn alpha_mask(img: &RgbaImage, threshold: u8) -> GrayImage {
+  let mask = GrayImage::from_fn(img.width(), img.height(), |x, y| {
+    image::Luma([if img.get_pixel(x, y)[3] >= threshold {
+      u8::MAX
+    } else {
+      0
+    }])
+  });
+
+  imageproc::morphology::dilate(&mask, Norm::LInf, ALPHA_MASK_TOLERANCE)
+}
+
+/// 将掩码外（透明区域）的边缘像素清零
+///
+/// 异形滑块（如拼图块）透明部分在灰度图中表现为黑色，会在边缘图中产生多余的边缘，
+/// 清零后模板只保留滑块本身的边缘。
+pub(crate) fn mask_edges(mut edges: GrayImage, mask: &GrayImage) -> GrayImage {
+  for (edge, alpha) in edges.iter_mut().zip(mask.iter()) {
+    if *alpha == 0 {
+      *edge = 0;
+    }
+  }
+  edges
+}
+
+/// 校验预处理相关配置
+pub(crate) fn validate_preprocess_config(config: &SlideMatchConfig) -> anyhow::Result<()> {
+  if let Some(tile_size) = config.clahe_tile_size {
+    anyhow::ensure!(tile_size > 0, "CLAHE 分块大小必须大于 0");
+  }
+
+  if let Some(clip_limit) = config.clahe_clip_limit {
+    anyhow::ensure!(
+      clip_limit.is_finite() && clip_limit > 0.0,
+      "CLAHE 对比度限制必须是大于 0 的有限数值"
+    );
+  }
+
+  if let Some(morphology) = config.edge_morphology {
+    anyhow::ensure!(
+      morphology.radius() <= u8::MAX as u32,
+      "形态学操作半径必须在 0-255 范围内"
+    );
+  }
+
+  if let Some(radius) = config.dilate_edges {
+    anyhow::ensure!(radius <= u8::MAX as u32, "边缘膨胀半径必须在 0-255 范围内");
+  }
+
+  if let Some(radius) = config.median_filter_radius {
+    anyhow::ensure!(radius <= u8::MAX as u32, "中值滤波半径必须在 0-255 范围内");
+  }
+
+  if let Some(cutoff) = config.invert_cutoff {
+    anyhow::ensure!(
+      (0.0..=255.0).contains(&cutoff),
+      "反相灰度均值阈值必须在 0-255 范围内"
+    );
+  }
+
+  if let Some(sigma) = config.canny_sigma {
+    anyhow::ensure!(
+      sigma.is_finite() && sigma > 0.0,
+      "高斯模糊 σ 必须是大于 0 的有限数值"
+    );
+  }
+
+  if let Some(threshold) = config.edge_density_threshold {
+    anyhow::ensure!(
+      (0.0..=1.0).contains(&threshold),
+      "边缘密度下限必须在 0.0-1.0 范围内"
+    );
+  }
+
+  Ok(())
+}
@@ -0,0 +1,105 @@
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::decode::load_image;
+use crate::error::{napi_error, ErrorStatus};
+use crate::preprocess;
+use crate::{prepare_target, SlideMatchConfig, FIXED_CANNY_THRESHOLDS};
+
+// 边缘像素占比达到该值时边缘得分为满分
+const GOOD_EDGE_DENSITY: f32 = 0.1;
+// 不透明像素数达到该值时不透明度得分为满分（约 32×32 的滑块）
+const GOOD_OPAQUE_PIXELS: f32 = 1024.0;
+// 宽高比在 [1 / 该值, 该值] 范围内时宽高比得分为满分
+const GOOD_ASPECT_RATIO: f32 = 2.0;
+// 综合评分（加权几何平均）中边缘、不透明像素数和宽高比的权重
+const EDGE_WEIGHT: f32 = 0.5;
+const OPAQUE_WEIGHT: f32 = 0.3;
+const ASPECT_WEIGHT: f32 = 0.2;
+// 综合评分低于该值时输出警告
+const LOW_QUALITY_SCORE: f32 = 0.3;
+
+/// 目标图片（滑块）的质量评估
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct TargetQuality {
+  /// 裁剪透明边框后，按 `slideMatch` 的预处理和固定 Canny 阈值 (100, 200) 得到的边缘像素占比（不含透明区域内的边缘）
+  pub edge_density: f64,
+  /// 不透明像素（透明度大于 0）占整张图片的比例
+  pub opaque_fraction: f64,
+  /// 裁剪透明边框后的宽高比（宽 / 高），没有不透明像素时为 0
+  pub aspect_ratio: f64,
+  /// 综合评分，范围 0.0-1.0：边缘占比（达到 10% 为满分）、不透明像素数（达到 1024 为满分）和
+  /// 宽高比（在 1:2 到 2:1 之间为满分）三项得分按 0.5、0.3、0.2 的权重取加权几何平均，
+  /// 任何一项为 0（如没有边缘）时综合评分为 0
+  pub overall_score: f64,
+}
+
+fn target_quality_internal(target: &[u8]) -> anyhow::Result<TargetQuality> {
+  let config = SlideMatchConfig::default();
+  let image = load_image(target, "目标图片", &config)?;
+  let total_pixels = image.width() as u64 * image.height() as u64;
+  let opaque_pixels = if image.color().has_alpha() {
+    image
+      .to_rgba8()
+      .pixels()
+      .filter(|p| p[3] > crate::crop::DEFAULT_ALPHA_THRESHOLD)
+      .count() as u64
+  } else {
+    total_pixels
+  };
+  if opaque_pixels == 0 {
+    return Ok(TargetQuality {
+      edge_density: 0.0,
+      opaque_fraction: 0.0,
+      aspect_ratio: 0.0,
+      overall_score: 0.0,
+    });
+  }
+
+  // 与匹配时相同：裁剪透明边框，清除透明区域内的边缘
+  let (target, target_mask, _) = prepare_target(image, &config);
+  let aspect_ratio = target.width() as f32 / target.height() as f32;
+  let edges = preprocess::detect_edges(target.gray, FIXED_CANNY_THRESHOLDS, &config);
+  let edges = match &target_mask {
+    Some(mask) => preprocess::mask_edges(edges, mask),
+    None => edges,
+  };
+  let edge_density = preprocess::edge_density(&edges);
+
+  let edge_score = (edge_density / GOOD_EDGE_DENSITY).min(1.0);
+  let opaque_score = (opaque_pixels as f32 / GOOD_OPAQUE_PIXELS).min(1.0);
+  let aspect_score = (aspect_ratio.min(1.0 / aspect_ratio) * GOOD_ASPECT_RATIO).min(1.0);
+  let overall_score = edge_score.powf(EDGE_WEIGHT)
+    * opaque_score.powf(OPAQUE_WEIGHT)
+    * aspect_score.powf(ASPECT_WEIGHT);
+
+  Ok(TargetQuality {
+    edge_density: edge_density as f64,
+    opaque_fraction: opaque_pixels as f64 / total_pixels as f64,
+    aspect_ratio: aspect_ratio as f64,
+    overall_score: overall_score as f64,
+  })
+}
+
+/// 评估目标图片（滑块）是否适合匹配
+///
+/// 在匹配之前检查滑块的边缘是否丰富、不透明像素是否足够、宽高比是否正常，返回各项指标和综合评分
+/// （`overallScore`，范围 0.0-1.0）。综合评分低于 0.3 时通过 `tracing` 输出警告，此时匹配结果很可能不可靠，
+/// 可以换一张验证码。
+///
+/// # 参数
+/// - target: 目标图片 Buffer
+#[napi]
+pub fn target_quality_score(target: Buffer) -> Result<TargetQuality, ErrorStatus> {
+  let quality = target_quality_internal(&target)
+    .map_err(|e| napi_error(&e, format!("目标图片质量评估失败: {e}")))?;
+  if (quality.overall_score as f32) < LOW_QUALITY_SCORE {
+    tracing::warn!(
+      overall_score = quality.overall_score,
+      "目标图片质量评分 {:.2} 低于 {LOW_QUALITY_SCORE}，匹配结果可能不可靠",
+      quality.overall_score
+    );
+  }
+  Ok(quality)
+}
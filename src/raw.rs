@@ -0,0 +1,188 @@
+use image::GrayImage;
+use imageproc::template_matching::{self, MatchTemplateMethod};
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::error::{napi_error, ErrorStatus};
+use crate::{ncc, ScoreHeatmap, SlideBBox};
+
+/// 单通道原始图片（每个像素 1 字节，按行存储）
+#[napi(object)]
+pub struct RawImage {
+  /// 像素数据，长度必须等于 `width * height`
+  pub data: Buffer,
+  pub width: u32,
+  pub height: u32,
+}
+
+impl std::fmt::Debug for RawImage {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("RawImage")
+      .field("width", &self.width)
+      .field("height", &self.height)
+      .finish_non_exhaustive()
+  }
+}
+
+/// 模板匹配的得分计算方式
+#[napi(string_enum = "camelCase")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TemplateMatchMethod {
+  /// 差值平方和，越小越匹配
+  SumOfSquaredErrors,
+  /// 归一化差值平方和，越小越匹配
+  SumOfSquaredErrorsNormalized,
+  /// 互相关，越大越匹配
+  CrossCorrelation,
+  /// 归一化互相关，越大越匹配（默认，与匹配函数一致）
+  #[default]
+  CrossCorrelationNormalized,
+}
+
+impl TemplateMatchMethod {
+  // 得分越小越匹配
+  fn lower_is_better(self) -> bool {
+    matches!(
+      self,
+      Self::SumOfSquaredErrors | Self::SumOfSquaredErrorsNormalized
+    )
+  }
+}
+
+impl From<TemplateMatchMethod> for MatchTemplateMethod {
+  fn from(method: TemplateMatchMethod) -> Self {
+    match method {
+      TemplateMatchMethod::SumOfSquaredErrors => MatchTemplateMethod::SumOfSquaredErrors,
+      TemplateMatchMethod::SumOfSquaredErrorsNormalized => {
+        MatchTemplateMethod::SumOfSquaredErrorsNormalized
+      }
+      TemplateMatchMethod::CrossCorrelation => MatchTemplateMethod::CrossCorrelation,
+      TemplateMatchMethod::CrossCorrelationNormalized => {
+        MatchTemplateMethod::CrossCorrelationNormalized
+      }
+    }
+  }
+}
+
+/// 原始图片模板匹配结果
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct RawMatchResult {
+  /// 最佳匹配位置，`targetX/targetY` 为 0，`confidence` 与 `score` 相同
+  pub bbox: SlideBBox,
+  /// 最佳匹配位置的得分（含义取决于 `method`）
+  pub score: f64,
+  /// 完整的匹配得分图，仅在 `returnHeatmap` 为 true 时返回
+  pub heatmap: Option<ScoreHeatmap>,
+}
+
+// 校验尺寸并转换为灰度图
+fn to_gray_image(image: &RawImage, name: &str) -> anyhow::Result<GrayImage> {
+  anyhow::ensure!(
+    image.width > 0 && image.height > 0,
+    "{name}的宽度和高度必须大于 0"
+  );
+
+  let expected = image.width as u64 * image.height as u64;
+  anyhow::ensure!(
+    image.data.len() as u64 == expected,
+    "{name}数据长度 {} 与尺寸 {}x{} 不一致（应为 {expected}）",
+    image.data.len(),
+    image.width,
+    image.height
+  );
+
+  GrayImage::from_raw(image.width, image.height, image.data.to_vec())
+    .ok_or_else(|| anyhow::anyhow!("无法创建{name}"))
+}
+
+fn match_template_raw_internal(
+  template: &RawImage,
+  background: &RawImage,
+  method: TemplateMatchMethod,
+  return_heatmap: bool,
+) -> anyhow::Result<RawMatchResult> {
+  let template = to_gray_image(template, "模板图片")?;
+  let background = to_gray_image(background, "背景图片")?;
+
+  anyhow::ensure!(
+    background.width() >= template.width(),
+    "背景图片的宽度必须大于等于模板图片的宽度"
+  );
+
+  anyhow::ensure!(
+    background.height() >= template.height(),
+    "背景图片的高度必须大于等于模板图片的高度"
+  );
+
+  let scores = match method {
+    TemplateMatchMethod::CrossCorrelationNormalized => {
+      ncc::match_template_normalized(&background, &template)
+    }
+    method => template_matching::match_template(&background, &template, method.into()),
+  };
+  let extremes = ncc::find_extremes(&scores);
+  let ((x, y), score) = if method.lower_is_better() {
+    (extremes.min_value_location, extremes.min_value)
+  } else {
+    (extremes.max_value_location, extremes.max_value)
+  };
+
+  let heatmap = return_heatmap.then(|| ScoreHeatmap {
+    width: scores.width(),
+    height: scores.height(),
+    data: Float32Array::new(scores.into_raw()),
+  });
+
+  Ok(RawMatchResult {
+    bbox: SlideBBox {
+      target_x: 0,
+      target_y: 0,
+      x1: x,
+      y1: y,
+      x2: x + template.width(),
+      y2: y + template.height(),
+      confidence: Some(score as f64),
+      metadata: None,
+      score_profile: None,
+      heatmap: None,
+      coordinate_mode: None,
+      ratio: None,
+      target_scale: None,
+      is_ambiguous: None,
+      second_best_score: None,
+      second_best_x: None,
+      psr: None,
+      slide_distance: None,
+      degenerate_target: None,
+      elapsed_ms: None,
+    },
+    score: score as f64,
+    heatmap,
+  })
+}
+
+/// 在自行处理好的单通道图片（如边缘图、灰度图）上直接执行模板匹配
+///
+/// 不做任何预处理，只计算得分图并取最佳位置，可以配合 `cannyEdges` 或自定义的边缘检测使用。
+///
+/// # 参数
+/// - template: 模板图片
+/// - background: 背景图片，宽度和高度都不能小于模板图片
+/// - method: 可选，得分计算方式，默认 `crossCorrelationNormalized`
+/// - return_heatmap: 可选，是否返回完整的匹配得分图，默认 false
+#[napi]
+pub fn match_template_raw(
+  template: RawImage,
+  background: RawImage,
+  method: Option<TemplateMatchMethod>,
+  return_heatmap: Option<bool>,
+) -> Result<RawMatchResult, ErrorStatus> {
+  match_template_raw_internal(
+    &template,
+    &background,
+    method.unwrap_or_default(),
+    return_heatmap.unwrap_or(false),
+  )
+  .map_err(|e| napi_error(&e, format!("模板匹配失败: {e}")))
+}
@@ -0,0 +1,136 @@
+use image::Rgba;
+use imageproc::distance_transform::Norm;
+use imageproc::geometric_transformations::{rotate_about_center, Interpolation};
+use imageproc::morphology;
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::error::{napi_error, ErrorStatus};
+use crate::ncc;
+use crate::preprocess::{self, MatchImage};
+use crate::{
+  background_canny_thresholds, decode, original_canny_thresholds, oversize,
+  target_canny_thresholds, validate_config, SlideMatchConfig,
+};
+
+// 默认旋转角度步长（度）
+const DEFAULT_ANGLE_STEP_DEG: f64 = 5.0;
+
+// 透明度掩码向内收缩的像素数，去掉滑块轮廓本身的边缘（轮廓不随角度变化，只会干扰匹配）
+const CONTOUR_EROSION: u8 = 3;
+
+/// 旋转匹配结果
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct RotationResult {
+  /// 目标图片顺时针旋转该角度（度，0 到 360 之间）后与背景图最匹配
+  pub angle_deg: f64,
+  /// 该角度下的匹配得分（归一化互相关）
+  pub confidence: f64,
+}
+
+fn rotation_match_internal(
+  target_image: &[u8],
+  background_image: &[u8],
+  angle_step_deg: f64,
+  config: &SlideMatchConfig,
+) -> anyhow::Result<RotationResult> {
+  validate_config(config)?;
+  anyhow::ensure!(
+    angle_step_deg.is_finite() && angle_step_deg > 0.0 && angle_step_deg <= 360.0,
+    "旋转角度步长必须大于 0 且不超过 360"
+  );
+
+  let target_image = decode::load_image(target_image, "目标图片", config)?.to_rgba8();
+  let background_image = decode::load_background(background_image, config)?;
+
+  oversize::ensure_fits(
+    target_image.dimensions(),
+    (background_image.width(), background_image.height()),
+  )?;
+
+  // 背景图的边缘只需检测一次
+  let config =
+    preprocess::resolve_canny_sigma(config, background_image.width(), background_image.height());
+  let background = MatchImage::from_dynamic(background_image, &config);
+  let background_thresholds =
+    background_canny_thresholds(original_canny_thresholds(&background, &config), &config)?;
+  let background_edges =
+    preprocess::detect_background_edges(background.gray, background_thresholds, &config);
+  // 目标图片的阈值按未旋转的图片计算一次
+  let target_config = preprocess::target_preprocess_config(&config);
+  let target_thresholds = target_canny_thresholds(
+    original_canny_thresholds(
+      &MatchImage::from_rgba(&target_image, &target_config),
+      &config,
+    ),
+    &config,
+  )?;
+
+  let steps = (360.0 / angle_step_deg).ceil() as u32;
+  let mut best = RotationResult {
+    angle_deg: 0.0,
+    confidence: f64::NEG_INFINITY,
+  };
+
+  for step in 0..steps {
+    let angle_deg = step as f64 * angle_step_deg;
+    // 旋转后超出原尺寸的部分被裁掉，空出的角落为透明
+    let rotated = rotate_about_center(
+      &target_image,
+      angle_deg.to_radians() as f32,
+      Interpolation::Bilinear,
+      Rgba([0, 0, 0, 0]),
+    );
+
+    let target_mask = morphology::erode(
+      &preprocess::alpha_mask(&rotated, preprocess::ALPHA_MASK_THRESHOLD),
+      Norm::LInf,
+      CONTOUR_EROSION,
+    );
+    let target = MatchImage::from_rgba(&rotated, &target_config);
+    let target_edges = preprocess::mask_edges(
+      preprocess::detect_edges(target.gray, target_thresholds, &config),
+      &target_mask,
+    );
+
+    let scores = ncc::match_template_normalized(&background_edges, &target_edges);
+    let confidence = ncc::find_extremes(&scores).max_value as f64;
+
+    // 得分相同时保留较小的角度
+    if confidence > best.confidence {
+      best = RotationResult {
+        angle_deg,
+        confidence,
+      };
+    }
+  }
+
+  Ok(best)
+}
+
+/// 旋转验证码匹配：找出目标图片需要旋转的角度
+///
+/// 以 `angleStepDeg` 为步长把目标图片从 0 度旋转到 360 度，每个角度都在背景图上做边缘模板匹配
+/// （预处理方式与 `slideMatch` 一致，只在灰度图上匹配），返回得分最高的角度。
+///
+/// # 参数
+/// - target: 目标图片 Buffer，旋转后超出原尺寸的部分会被裁掉，建议使用圆形透明背景的图片
+/// - background: 背景图片 Buffer
+/// - angle_step_deg: 可选，角度步长（度），默认 5
+/// - options: 可选配置，见 `SlideMatchConfig`
+#[napi]
+pub fn rotation_match(
+  target: Buffer,
+  background: Buffer,
+  angle_step_deg: Option<f64>,
+  options: Option<SlideMatchConfig>,
+) -> Result<RotationResult, ErrorStatus> {
+  rotation_match_internal(
+    &target,
+    &background,
+    angle_step_deg.unwrap_or(DEFAULT_ANGLE_STEP_DEG),
+    &options.unwrap_or_default(),
+  )
+  .map_err(|e| napi_error(&e, format!("旋转匹配失败: {e}")))
+}
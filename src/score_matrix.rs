@@ -0,0 +1,87 @@
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::error::{napi_error, ErrorStatus};
+use crate::multi::CandidateScores;
+use crate::{validate_config, SlideMatchConfig};
+
+/// 完整的模板匹配得分矩阵（归一化互相关，按行存储）
+///
+/// `data[row * cols + col]` 为目标图片（裁剪透明边框后）左上角位于背景图 (col, row) 时的得分。
+/// 指定 `fixedY` 时只包含搜索的行，第 0 行对应 `y = fixedY - fixedYTolerance`（不小于 0）。
+#[napi]
+pub struct ScoreMatrix {
+  scores: Vec<f32>,
+  /// 行数，即目标图片左上角可取的 y 的个数
+  #[napi(readonly)]
+  pub rows: u32,
+  /// 列数，即目标图片左上角可取的 x 的个数
+  #[napi(readonly)]
+  pub cols: u32,
+}
+
+impl std::fmt::Debug for ScoreMatrix {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("ScoreMatrix")
+      .field("rows", &self.rows)
+      .field("cols", &self.cols)
+      .finish_non_exhaustive()
+  }
+}
+
+#[napi]
+impl ScoreMatrix {
+  /// 按行存储的全部得分，每次读取都会复制一份，需要多次使用时先保存到变量中
+  #[napi(getter)]
+  pub fn data(&self) -> Float32Array {
+    Float32Array::new(self.scores.clone())
+  }
+
+  /// 第 `y` 行、第 `x` 列的得分，超出范围时报错
+  #[napi]
+  pub fn at(&self, x: u32, y: u32) -> Result<f32> {
+    if x >= self.cols || y >= self.rows {
+      return Err(Error::from_reason(format!(
+        "坐标 ({x}, {y}) 超出得分矩阵范围（{} 列 × {} 行）",
+        self.cols, self.rows
+      )));
+    }
+    Ok(self.scores[y as usize * self.cols as usize + x as usize])
+  }
+}
+
+fn slide_match_score_matrix_internal(
+  target_image: &[u8],
+  background_image: &[u8],
+  config: &SlideMatchConfig,
+) -> anyhow::Result<ScoreMatrix> {
+  validate_config(config)?;
+
+  let scores = CandidateScores::new(target_image, background_image, config)?
+    .map
+    .scores;
+  Ok(ScoreMatrix {
+    rows: scores.height(),
+    cols: scores.width(),
+    scores: scores.into_raw(),
+  })
+}
+
+/// 返回完整的模板匹配得分矩阵，供调用方自行实现寻峰逻辑
+///
+/// 预处理方式与 `slideMatch` 一致（裁剪透明区域、固定 Canny 阈值），`slideMatch` 的结果即矩阵中
+/// 得分最大的位置。矩阵坐标为处理尺寸下的像素，不做 `backgroundDisplayWidth` 换算。
+///
+/// # 参数
+/// - target: 目标图片 Buffer
+/// - background: 背景图片 Buffer
+/// - options: 可选配置，见 `SlideMatchConfig`
+#[napi]
+pub fn slide_match_score_matrix(
+  target: Buffer,
+  background: Buffer,
+  options: Option<SlideMatchConfig>,
+) -> Result<ScoreMatrix, ErrorStatus> {
+  slide_match_score_matrix_internal(&target, &background, &options.unwrap_or_default())
+    .map_err(|e| napi_error(&e, format!("计算得分矩阵失败: {e}")))
+}
@@ -0,0 +1,120 @@
+use image::RgbaImage;
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::decode::load_image;
+use crate::error::{napi_error, ErrorStatus};
+use crate::preprocess::ALPHA_MASK_THRESHOLD;
+use crate::SlideMatchConfig;
+
+// 不透明像素占外接矩形面积的比例高于该值视为矩形
+const RECTANGLE_FILL_RATIO: f64 = 0.9;
+// 不透明像素占外接矩形面积的比例低于该值无法识别
+const MIN_SHAPE_FILL_RATIO: f64 = 0.6;
+// 与外接矩形内切椭圆的交并比高于该值视为圆形
+const CIRCLE_ROUNDNESS: f64 = 0.9;
+// 圆形外接矩形允许的宽高比偏差
+const CIRCLE_ASPECT_TOLERANCE: f64 = 0.1;
+
+/// 滑块形状
+#[napi(string_enum = "camelCase")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SliderShape {
+  /// 矩形（几乎没有透明区域）
+  Rectangle,
+  /// 拼图块等不规则形状
+  PuzzlePiece,
+  /// 圆形
+  Circle,
+  /// 无法识别（没有不透明像素或不透明区域过少）
+  Unknown,
+}
+
+/// 不透明像素的外接矩形 (x, y, width, height)
+fn opaque_bounds(img: &RgbaImage) -> Option<(u32, u32, u32, u32)> {
+  let mut bounds: Option<(u32, u32, u32, u32)> = None;
+
+  for (x, y, p) in img.enumerate_pixels() {
+    if p[3] < ALPHA_MASK_THRESHOLD {
+      continue;
+    }
+
+    bounds = Some(match bounds {
+      None => (x, y, x, y),
+      Some((x0, y0, x1, y1)) => (x0.min(x), y0.min(y), x1.max(x), y1.max(y)),
+    });
+  }
+
+  bounds.map(|(x0, y0, x1, y1)| (x0, y0, x1 - x0 + 1, y1 - y0 + 1))
+}
+
+/// 不透明区域与外接矩形内切椭圆的交并比，越接近 1 越圆
+fn roundness(img: &RgbaImage, (x0, y0, width, height): (u32, u32, u32, u32)) -> f64 {
+  let rx = width as f64 / 2.0;
+  let ry = height as f64 / 2.0;
+  let mut intersection = 0u32;
+  let mut union = 0u32;
+
+  for y in y0..y0 + height {
+    for x in x0..x0 + width {
+      let dx = (x - x0) as f64 + 0.5 - rx;
+      let dy = (y - y0) as f64 + 0.5 - ry;
+      let in_ellipse = (dx / rx).powi(2) + (dy / ry).powi(2) <= 1.0;
+      let opaque = img.get_pixel(x, y)[3] >= ALPHA_MASK_THRESHOLD;
+
+      if in_ellipse && opaque {
+        intersection += 1;
+      }
+      if in_ellipse || opaque {
+        union += 1;
+      }
+    }
+  }
+
+  intersection as f64 / union as f64
+}
+
+// 根据不透明像素的分布识别滑块形状
+pub(crate) fn classify_shape(img: &RgbaImage) -> SliderShape {
+  let Some(bounds) = opaque_bounds(img) else {
+    return SliderShape::Unknown;
+  };
+  let (_, _, width, height) = bounds;
+
+  // 不透明像素都在外接矩形内
+  let opaque = img
+    .pixels()
+    .filter(|p| p[3] >= ALPHA_MASK_THRESHOLD)
+    .count();
+  let fill_ratio = opaque as f64 / (width * height) as f64;
+
+  if fill_ratio > RECTANGLE_FILL_RATIO {
+    return SliderShape::Rectangle;
+  }
+  if fill_ratio < MIN_SHAPE_FILL_RATIO {
+    return SliderShape::Unknown;
+  }
+
+  let aspect = width as f64 / height as f64;
+  if (aspect - 1.0).abs() <= CIRCLE_ASPECT_TOLERANCE && roundness(img, bounds) >= CIRCLE_ROUNDNESS {
+    SliderShape::Circle
+  } else {
+    SliderShape::PuzzlePiece
+  }
+}
+
+/// 识别滑块（目标图片）的形状
+///
+/// 以透明度不低于 128 的像素为不透明像素，按其占外接矩形面积的比例分类：
+/// - 大于 90%：矩形
+/// - 60%-90%：拼图块或圆形（外接矩形接近正方形且与内切圆高度重合时为圆形）
+/// - 小于 60%：无法识别
+///
+/// # 参数
+/// - target: 目标图片 Buffer
+#[napi]
+pub fn classify_target_shape(target: Buffer) -> Result<SliderShape, ErrorStatus> {
+  load_image(&target, "目标图片", &SlideMatchConfig::default())
+    .map(|img| classify_shape(&img.to_rgba8()))
+    .map_err(|e| napi_error(&e, format!("滑块形状识别失败: {e}")))
+}
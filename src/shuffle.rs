@@ -0,0 +1,147 @@
+use image::{DynamicImage, GenericImage, GenericImageView, RgbaImage};
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::error::{napi_error, ErrorStatus};
+use crate::pipeline::{ImagePipeline, OutputFormat};
+use crate::{decode, slide_match_decoded, validate_config, SlideBBox, SlideMatchConfig};
+
+// 按切片顺序还原打乱的背景图
+//
+// 图片被切成宽度为 `slice_width` 的竖条，`order[i]` 为还原后第 i 条在打乱图片中的下标。
+// 指定 `split_y` 时上下两部分分别打乱：`order` 前一半对应上半部分（0..split_y 行），
+// 后一半对应下半部分，下标都是在同一部分内的下标。
+pub(crate) fn restore_slices(
+  img: &DynamicImage,
+  order: &[u32],
+  slice_width: u32,
+  split_y: Option<u32>,
+) -> anyhow::Result<DynamicImage> {
+  let (width, height) = (img.width(), img.height());
+
+  anyhow::ensure!(slice_width > 0, "切片宽度必须大于 0");
+  anyhow::ensure!(!order.is_empty(), "切片顺序不能为空");
+
+  // 每一部分的行范围
+  let bands = match split_y {
+    Some(split_y) => {
+      anyhow::ensure!(
+        split_y > 0 && split_y < height,
+        "splitY 必须在 1-{} 范围内（图片高度 {height}）",
+        height.saturating_sub(1)
+      );
+      vec![(0, split_y), (split_y, height - split_y)]
+    }
+    None => vec![(0, height)],
+  };
+
+  // 每一部分都由完整的一组切片组成
+  let expected = width as u64 * bands.len() as u64;
+  anyhow::ensure!(
+    order.len() as u64 * slice_width as u64 == expected,
+    "切片数量与图片宽度不一致：{} 个切片 × 切片宽度 {slice_width} 应等于 {expected}（图片宽度 {width} × {} 部分）",
+    order.len(),
+    bands.len()
+  );
+
+  let slices_per_band = order.len() / bands.len();
+  let slice_count = slices_per_band as u32;
+  if let Some(&index) = order.iter().find(|&&index| index >= slice_count) {
+    anyhow::bail!("切片下标 {index} 超出范围（每部分共 {slice_count} 个切片）");
+  }
+
+  let source = img.to_rgba8();
+  let mut restored = RgbaImage::new(width, height);
+
+  for (&(top, band_height), band_order) in bands.iter().zip(order.chunks(slices_per_band)) {
+    for (position, &index) in band_order.iter().enumerate() {
+      let slice = source.view(index * slice_width, top, slice_width, band_height);
+      restored.copy_from(&*slice, position as u32 * slice_width, top)?;
+    }
+  }
+
+  Ok(DynamicImage::ImageRgba8(restored))
+}
+
+fn restore_background_internal(
+  image: &[u8],
+  slice_order: &[u32],
+  slice_width: u32,
+  split_y: Option<u32>,
+) -> anyhow::Result<Vec<u8>> {
+  let config = SlideMatchConfig::default();
+  let image = decode::load_image(image, "背景图片", &config)?;
+  let restored = restore_slices(&image, slice_order, slice_width, split_y)?;
+
+  ImagePipeline::from_image(restored).to_bytes(OutputFormat::Png)
+}
+
+/// 还原切片打乱的背景图，返回 PNG 图片
+///
+/// 背景图被切成宽度为 `sliceWidth` 的竖条并打乱顺序，`sliceOrder[i]` 为还原后第 i 条在打乱图片中的下标。
+/// 指定 `splitY` 时上下两部分分别打乱：`sliceOrder` 前一半对应上半部分（第 0 到 splitY - 1 行），
+/// 后一半对应下半部分，下标都是同一部分内的下标。
+///
+/// # 参数
+/// - image: 打乱的背景图片 Buffer
+/// - slice_order: 切片顺序
+/// - slice_width: 切片宽度（像素）
+/// - split_y: 可选，上下两部分的分界行
+#[napi]
+pub fn restore_background(
+  image: Buffer,
+  slice_order: Vec<u32>,
+  slice_width: u32,
+  split_y: Option<u32>,
+) -> Result<Buffer, ErrorStatus> {
+  restore_background_internal(&image, &slice_order, slice_width, split_y)
+    .map(Buffer::from)
+    .map_err(|e| napi_error(&e, format!("背景图还原失败: {e}")))
+}
+
+fn slide_match_shuffled_internal(
+  target_image: &[u8],
+  background_image: &[u8],
+  slice_order: &[u32],
+  slice_width: u32,
+  split_y: Option<u32>,
+  config: &SlideMatchConfig,
+) -> anyhow::Result<SlideBBox> {
+  validate_config(config)?;
+
+  let target_image = decode::load_image(target_image, "目标图片", config)?;
+  let background_image = decode::load_background(background_image, config)?;
+  let background_image = restore_slices(&background_image, slice_order, slice_width, split_y)?;
+
+  // 缓存键基于打乱的图片数据，无法区分不同的切片顺序，因此不使用背景缓存
+  slide_match_decoded(target_image, background_image, None, None, config)
+}
+
+/// 还原切片打乱的背景图后执行滑块匹配（带透明背景裁剪），切片参数见 `restoreBackground`
+///
+/// # 参数
+/// - target: 目标图片 Buffer
+/// - background: 打乱的背景图片 Buffer
+/// - slice_order: 切片顺序
+/// - slice_width: 切片宽度（像素）
+/// - split_y: 可选，上下两部分的分界行
+/// - options: 可选配置，见 `SlideMatchConfig`
+#[napi]
+pub fn slide_match_shuffled(
+  target: Buffer,
+  background: Buffer,
+  slice_order: Vec<u32>,
+  slice_width: u32,
+  split_y: Option<u32>,
+  options: Option<SlideMatchConfig>,
+) -> Result<SlideBBox, ErrorStatus> {
+  slide_match_shuffled_internal(
+    &target,
+    &background,
+    &slice_order,
+    slice_width,
+    split_y,
+    &options.unwrap_or_default(),
+  )
+  .map_err(|e| napi_error(&e, format!("滑块匹配失败: {e}")))
+}
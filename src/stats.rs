@@ -0,0 +1,104 @@
+use image::GrayImage;
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::error::{napi_error, ErrorStatus};
+use crate::preprocess::{self, MatchImage};
+use crate::{decode, validate_config, SlideMatchConfig};
+
+/// 灰度图的统计信息
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageStats {
+  /// 灰度均值
+  pub mean: f64,
+  /// 灰度标准差（总体标准差）
+  pub std_dev: f64,
+  /// 最小灰度值
+  pub min: u8,
+  /// 最大灰度值
+  pub max: u8,
+  /// 灰度直方图的香农熵（比特），范围 0-8，纯色图片为 0
+  pub entropy: f64,
+}
+
+impl ImageStats {
+  /// 由灰度直方图计算统计信息，空图片的各项均为 0
+  #[must_use]
+  pub fn from_gray(img: &GrayImage) -> Self {
+    let mut hist = [0u64; 256];
+    for &p in img.iter() {
+      hist[p as usize] += 1;
+    }
+
+    let total = hist.iter().sum::<u64>();
+    if total == 0 {
+      return Self {
+        mean: 0.0,
+        std_dev: 0.0,
+        min: 0,
+        max: 0,
+        entropy: 0.0,
+      };
+    }
+
+    let total = total as f64;
+    let occupied = || hist.iter().enumerate().filter(|(_, &count)| count > 0);
+    let mean = occupied()
+      .map(|(value, &count)| value as f64 * count as f64)
+      .sum::<f64>()
+      / total;
+    let variance = occupied()
+      .map(|(value, &count)| (value as f64 - mean).powi(2) * count as f64)
+      .sum::<f64>()
+      / total;
+    // 归一化直方图的熵：-Σ p·log2(p)
+    let entropy = -occupied()
+      .map(|(_, &count)| {
+        let p = count as f64 / total;
+        p * p.log2()
+      })
+      .sum::<f64>();
+
+    Self {
+      mean,
+      std_dev: variance.sqrt(),
+      min: occupied().map(|(value, _)| value as u8).next().unwrap_or(0),
+      max: occupied()
+        .map(|(value, _)| value as u8)
+        .next_back()
+        .unwrap_or(0),
+      entropy,
+    }
+  }
+}
+
+fn compute_image_stats_internal(
+  image: &[u8],
+  config: &SlideMatchConfig,
+) -> anyhow::Result<ImageStats> {
+  validate_config(config)?;
+
+  let image = decode::load_image(image, "图片", config)?;
+  let config = preprocess::resolve_canny_sigma(config, image.width(), image.height());
+  // 与匹配函数相同的灰度转换和预处理
+  let gray = MatchImage::from_dynamic(image, &config).gray;
+
+  Ok(ImageStats::from_gray(&gray))
+}
+
+/// 计算图片的灰度统计信息（均值、标准差、最值、熵），用于匹配前评估图片质量和调试自适应阈值
+///
+/// 统计的是匹配函数实际使用的灰度图：彩色图片自动转换为灰度图，并按 `config` 执行 CLAHE 等预处理。
+///
+/// # 参数
+/// - image: 图片 Buffer
+/// - config: 可选配置，见 `SlideMatchConfig`
+#[napi]
+pub fn compute_image_stats(
+  image: Buffer,
+  config: Option<SlideMatchConfig>,
+) -> Result<ImageStats, ErrorStatus> {
+  compute_image_stats_internal(&image, &config.unwrap_or_default())
+    .map_err(|e| napi_error(&e, format!("计算图片统计信息失败: {e}")))
+}
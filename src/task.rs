@@ -0,0 +1,388 @@
+use napi::bindgen_prelude::*;
+use napi::threadsafe_function::{ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use napi_derive::napi;
+
+use crate::error::{self, napi_error, ErrorStatus};
+use crate::multi::{stream_matches_internal, MatchCandidate};
+use crate::{
+  decode, improved_simple_slide_match_internal, improved_slide_match_internal, SlideBBox,
+  SlideMatchConfig,
+};
+
+// 解析置信度阈值，默认 0.3，范围 0.0-1.0
+fn confidence_threshold(value: Option<f64>) -> Result<f32> {
+  let threshold = value.unwrap_or(0.3) as f32;
+
+  // 验证置信度阈值范围
+  if !(0.0..=1.0).contains(&threshold) {
+    return Err(Error::from_reason("置信度阈值必须在 0.0-1.0 范围内"));
+  }
+
+  Ok(threshold)
+}
+
+/// 改进版滑块匹配的后台任务
+///
+/// 默认在 napi 的任务队列（libuv 线程池）中执行；启用 `tokio` 特性后改为
+/// `tokio::task::spawn_blocking`，与已经使用 tokio 的项目共用同一个运行时。
+///
+/// 任务直接持有 JS 传入的 `Buffer`（保留引用而不复制数据），匹配完成前 JS 侧不应修改这两个 Buffer。
+pub struct ImprovedSlideMatchTask {
+  target_image: Buffer,
+  background_image: Buffer,
+  confidence_threshold: f32,
+  config: SlideMatchConfig,
+}
+
+impl ImprovedSlideMatchTask {
+  /// 校验参数并创建任务，图片数据不复制
+  pub fn new(
+    target_image: Buffer,
+    background_image: Buffer,
+    confidence_threshold: Option<f64>,
+    options: Option<SlideMatchConfig>,
+  ) -> Result<Self> {
+    Ok(Self {
+      target_image,
+      background_image,
+      confidence_threshold: self::confidence_threshold(confidence_threshold)?,
+      config: options.unwrap_or_default(),
+    })
+  }
+
+  /// 在当前线程执行匹配
+  pub fn run(&self) -> Result<SlideBBox, ErrorStatus> {
+    improved_slide_match_internal(
+      &self.target_image,
+      &self.background_image,
+      self.confidence_threshold,
+      &self.config,
+    )
+    .map_err(|e| napi_error(&e, format!("改进版滑块匹配失败: {e}")))
+  }
+}
+
+// 后台线程只记录匹配结果，回到 JS 线程后再转换异常，以便带上错误码
+#[cfg(not(feature = "tokio"))]
+impl Task for ImprovedSlideMatchTask {
+  type Output = Result<SlideBBox, ErrorStatus>;
+  type JsValue = SlideBBox;
+
+  fn compute(&mut self) -> Result<Self::Output> {
+    Ok(self.run())
+  }
+
+  fn resolve(&mut self, env: Env, output: Self::Output) -> Result<Self::JsValue> {
+    output.map_err(|e| error::to_js_error(&env, e))
+  }
+}
+
+// 在 tokio 的阻塞线程池中执行 `run`，失败时回到 JS 线程以带错误码的异常拒绝
+#[cfg(feature = "tokio")]
+pub(crate) fn spawn_blocking<'env, T: ToNapiValue + Send + 'static>(
+  env: &'env Env,
+  prefix: &'static str,
+  run: impl FnOnce() -> Result<T, ErrorStatus> + Send + 'static,
+) -> Result<PromiseRaw<'env, T>> {
+  env.spawn_future_with_callback(
+    async move {
+      tokio::task::spawn_blocking(run)
+        .await
+        .map_err(|e| Error::from_reason(format!("{prefix}: {e}")))
+    },
+    |env, result| result.map_err(|e| error::to_js_error(env, e)),
+  )
+}
+
+/// 改进版滑块匹配（异步），参数与 `improvedSlideMatch` 相同
+/// 匹配在后台线程中执行，不阻塞 JS 主线程；图片 Buffer 不会被复制，匹配完成前不要修改
+#[cfg(not(feature = "tokio"))]
+#[napi]
+pub fn improved_slide_match_async(
+  target_image: Buffer,
+  background_image: Buffer,
+  confidence_threshold: Option<f64>,
+  options: Option<SlideMatchConfig>,
+) -> Result<AsyncTask<ImprovedSlideMatchTask>> {
+  ImprovedSlideMatchTask::new(
+    target_image,
+    background_image,
+    confidence_threshold,
+    options,
+  )
+  .map(AsyncTask::new)
+}
+
+/// 改进版滑块匹配（异步），参数与 `improvedSlideMatch` 相同
+/// 匹配在 tokio 的阻塞线程池中执行，不阻塞 JS 主线程；图片 Buffer 不会被复制，匹配完成前不要修改
+#[cfg(feature = "tokio")]
+#[napi(ts_return_type = "Promise<SlideBBox>")]
+pub fn improved_slide_match_async<'env>(
+  env: &'env Env,
+  target_image: Buffer,
+  background_image: Buffer,
+  confidence_threshold: Option<f64>,
+  options: Option<SlideMatchConfig>,
+) -> Result<PromiseRaw<'env, SlideBBox>> {
+  let task = ImprovedSlideMatchTask::new(
+    target_image,
+    background_image,
+    confidence_threshold,
+    options,
+  )?;
+
+  spawn_blocking(env, "改进版滑块匹配失败", move || task.run())
+}
+
+// 改进版匹配函数的签名，带或不带透明背景裁剪
+type ImprovedMatcher = fn(&[u8], &[u8], f32, &SlideMatchConfig) -> anyhow::Result<SlideBBox>;
+
+/// 从文件路径执行改进版滑块匹配的后台任务，执行方式与 [`ImprovedSlideMatchTask`] 相同
+///
+/// 读取文件和匹配都在后台线程中进行。读取失败时 JS 错误的 `code` 为 `ErrorCode.ReadFailed`，解码或匹配失败时
+/// 为对应的错误码（如 `ErrorCode.DecodeFailed`），与同步的 `improvedSlideMatchWithPath` 相同，错误信息都包含文件路径。
+pub struct ImprovedSlideMatchWithPathTask {
+  target_path: String,
+  background_path: String,
+  confidence_threshold: f32,
+  config: SlideMatchConfig,
+  matcher: ImprovedMatcher,
+}
+
+impl ImprovedSlideMatchWithPathTask {
+  fn new(
+    target_path: String,
+    background_path: String,
+    confidence_threshold: Option<f64>,
+    options: Option<SlideMatchConfig>,
+    matcher: ImprovedMatcher,
+  ) -> Result<Self> {
+    Ok(Self {
+      target_path,
+      background_path,
+      confidence_threshold: self::confidence_threshold(confidence_threshold)?,
+      config: options.unwrap_or_default(),
+      matcher,
+    })
+  }
+
+  fn run(&self) -> Result<SlideBBox, ErrorStatus> {
+    decode::with_image_files(
+      &self.target_path,
+      &self.background_path,
+      |target, background| {
+        (self.matcher)(target, background, self.confidence_threshold, &self.config)
+      },
+    )
+    .map_err(|e| napi_error(&e, format!("改进版滑块匹配失败: {e:#}")))
+  }
+}
+
+#[cfg(not(feature = "tokio"))]
+impl Task for ImprovedSlideMatchWithPathTask {
+  type Output = Result<SlideBBox, ErrorStatus>;
+  type JsValue = SlideBBox;
+
+  fn compute(&mut self) -> Result<Self::Output> {
+    Ok(self.run())
+  }
+
+  fn resolve(&mut self, env: Env, output: Self::Output) -> Result<Self::JsValue> {
+    output.map_err(|e| error::to_js_error(&env, e))
+  }
+}
+
+/// 改进版滑块匹配 - 从文件路径（异步），参数与 `improvedSlideMatchWithPath` 相同
+/// 读取文件和匹配都在后台线程中执行，不阻塞 JS 主线程；
+/// 读取文件失败时错误的 `code` 为 `ErrorCode.ReadFailed`，解码或匹配失败时为对应的错误码
+#[cfg(not(feature = "tokio"))]
+#[napi]
+pub fn improved_slide_match_with_path_async(
+  target_image_path: String,
+  background_image_path: String,
+  confidence_threshold: Option<f64>,
+  options: Option<SlideMatchConfig>,
+) -> Result<AsyncTask<ImprovedSlideMatchWithPathTask>> {
+  ImprovedSlideMatchWithPathTask::new(
+    target_image_path,
+    background_image_path,
+    confidence_threshold,
+    options,
+    improved_slide_match_internal,
+  )
+  .map(AsyncTask::new)
+}
+
+/// 改进版简单滑块匹配 - 从文件路径（异步），参数与 `improvedSimpleSlideMatchWithPath` 相同
+/// 读取文件和匹配都在后台线程中执行，不阻塞 JS 主线程；错误的 `code` 同 `improvedSlideMatchWithPathAsync`
+#[cfg(not(feature = "tokio"))]
+#[napi]
+pub fn improved_simple_slide_match_with_path_async(
+  target_image_path: String,
+  background_image_path: String,
+  confidence_threshold: Option<f64>,
+  options: Option<SlideMatchConfig>,
+) -> Result<AsyncTask<ImprovedSlideMatchWithPathTask>> {
+  ImprovedSlideMatchWithPathTask::new(
+    target_image_path,
+    background_image_path,
+    confidence_threshold,
+    options,
+    improved_simple_slide_match_internal,
+  )
+  .map(AsyncTask::new)
+}
+
+/// 改进版滑块匹配 - 从文件路径（异步），参数与 `improvedSlideMatchWithPath` 相同
+/// 读取文件和匹配都在 tokio 的阻塞线程池中执行，不阻塞 JS 主线程；
+/// 读取文件失败时错误的 `code` 为 `ErrorCode.ReadFailed`，解码或匹配失败时为对应的错误码
+#[cfg(feature = "tokio")]
+#[napi(ts_return_type = "Promise<SlideBBox>")]
+pub fn improved_slide_match_with_path_async<'env>(
+  env: &'env Env,
+  target_image_path: String,
+  background_image_path: String,
+  confidence_threshold: Option<f64>,
+  options: Option<SlideMatchConfig>,
+) -> Result<PromiseRaw<'env, SlideBBox>> {
+  let task = ImprovedSlideMatchWithPathTask::new(
+    target_image_path,
+    background_image_path,
+    confidence_threshold,
+    options,
+    improved_slide_match_internal,
+  )?;
+
+  spawn_blocking(env, "改进版滑块匹配失败", move || task.run())
+}
+
+/// 改进版简单滑块匹配 - 从文件路径（异步），参数与 `improvedSimpleSlideMatchWithPath` 相同
+/// 读取文件和匹配都在 tokio 的阻塞线程池中执行，不阻塞 JS 主线程；错误的 `code` 同 `improvedSlideMatchWithPathAsync`
+#[cfg(feature = "tokio")]
+#[napi(ts_return_type = "Promise<SlideBBox>")]
+pub fn improved_simple_slide_match_with_path_async<'env>(
+  env: &'env Env,
+  target_image_path: String,
+  background_image_path: String,
+  confidence_threshold: Option<f64>,
+  options: Option<SlideMatchConfig>,
+) -> Result<PromiseRaw<'env, SlideBBox>> {
+  let task = ImprovedSlideMatchWithPathTask::new(
+    target_image_path,
+    background_image_path,
+    confidence_threshold,
+    options,
+    improved_simple_slide_match_internal,
+  )?;
+
+  spawn_blocking(env, "改进版滑块匹配失败", move || task.run())
+}
+
+/// 流式多目标匹配的后台任务，执行方式与 [`ImprovedSlideMatchTask`] 相同
+pub struct StreamMatchesTask {
+  target_image: Buffer,
+  background_image: Buffer,
+  score_threshold: f64,
+  config: SlideMatchConfig,
+  callback: ThreadsafeFunction<MatchCandidate>,
+}
+
+impl StreamMatchesTask {
+  fn new(
+    target_image: Buffer,
+    background_image: Buffer,
+    score_threshold: f64,
+    callback: ThreadsafeFunction<MatchCandidate>,
+    options: Option<SlideMatchConfig>,
+  ) -> Self {
+    Self {
+      target_image,
+      background_image,
+      score_threshold,
+      config: options.unwrap_or_default(),
+      callback,
+    }
+  }
+
+  fn run(&self) -> Result<u32, ErrorStatus> {
+    stream_matches_internal(
+      &self.target_image,
+      &self.background_image,
+      self.score_threshold,
+      &self.config,
+      // 非阻塞调用，回调函数已被释放时停止扫描
+      |candidate| {
+        self
+          .callback
+          .call(Ok(candidate), ThreadsafeFunctionCallMode::NonBlocking)
+          == Status::Ok
+      },
+    )
+    .map_err(|e| napi_error(&e, format!("流式多目标匹配失败: {e}")))
+  }
+}
+
+#[cfg(not(feature = "tokio"))]
+impl Task for StreamMatchesTask {
+  type Output = Result<u32, ErrorStatus>;
+  type JsValue = u32;
+
+  fn compute(&mut self) -> Result<Self::Output> {
+    Ok(self.run())
+  }
+
+  fn resolve(&mut self, env: Env, output: Self::Output) -> Result<Self::JsValue> {
+    output.map_err(|e| error::to_js_error(&env, e))
+  }
+}
+
+/// 流式多目标匹配（异步）
+///
+/// 逐行扫描匹配得分图，每发现一个得分不低于 `threshold` 的局部最大值就以 `(err, candidate)`
+/// 非阻塞地调用一次 `callback`，全部扫描完成后返回结果总数。预处理方式与 `slideMatch` 一致。
+///
+/// # 参数
+/// - target: 目标图片 Buffer，不会被复制，匹配完成前不要修改
+/// - background: 背景图片 Buffer，不会被复制，匹配完成前不要修改
+/// - threshold: 最低匹配得分，范围 0.0-1.0
+/// - callback: 接收每个结果的回调函数
+/// - options: 可选配置，见 `SlideMatchConfig`
+#[cfg(not(feature = "tokio"))]
+#[napi]
+pub fn slide_match_stream_results(
+  target: Buffer,
+  background: Buffer,
+  threshold: f64,
+  callback: ThreadsafeFunction<MatchCandidate>,
+  options: Option<SlideMatchConfig>,
+) -> AsyncTask<StreamMatchesTask> {
+  AsyncTask::new(StreamMatchesTask::new(
+    target, background, threshold, callback, options,
+  ))
+}
+
+/// 流式多目标匹配（异步）
+///
+/// 逐行扫描匹配得分图，每发现一个得分不低于 `threshold` 的局部最大值就以 `(err, candidate)`
+/// 非阻塞地调用一次 `callback`，全部扫描完成后返回结果总数。预处理方式与 `slideMatch` 一致。
+///
+/// # 参数
+/// - target: 目标图片 Buffer，不会被复制，匹配完成前不要修改
+/// - background: 背景图片 Buffer，不会被复制，匹配完成前不要修改
+/// - threshold: 最低匹配得分，范围 0.0-1.0
+/// - callback: 接收每个结果的回调函数
+/// - options: 可选配置，见 `SlideMatchConfig`
+#[cfg(feature = "tokio")]
+#[napi(ts_return_type = "Promise<number>")]
+pub fn slide_match_stream_results<'env>(
+  env: &'env Env,
+  target: Buffer,
+  background: Buffer,
+  threshold: f64,
+  callback: ThreadsafeFunction<MatchCandidate>,
+  options: Option<SlideMatchConfig>,
+) -> Result<PromiseRaw<'env, u32>> {
+  let task = StreamMatchesTask::new(target, background, threshold, callback, options);
+
+  spawn_blocking(env, "流式多目标匹配失败", move || task.run())
+}
@@ -0,0 +1,142 @@
+//! 测试和基准测试用的合成图片
+//!
+//! 仓库中的真实验证码图片数量有限，这里按参数生成背景图和滑块，缺口位置已知，
+//! 可以直接断言匹配结果。仅在测试或启用 `test-utils` 特性时公开，内置的 `benchmark` 同样使用这里生成的图片。
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use image::{DynamicImage, Rgb, RgbImage, Rgba, RgbaImage};
+
+use crate::pipeline::{ImagePipeline, OutputFormat};
+
+// 缺口和滑块边框的宽度（像素）
+const BORDER_WIDTH: u32 = 2;
+// 缺口和滑块边框的亮度
+const BORDER_VALUE: u8 = 245;
+// 滑块边长的最小值（像素）
+pub(crate) const MIN_SLIDER_SIZE: u32 = 8;
+
+// 背景纹理：平缓起伏的灰度，三个通道略有差异
+fn texture(x: u32, y: u32) -> Rgb<u8> {
+  let (x, y) = (x as f32, y as f32);
+  let v = 128.0 + 60.0 * (x / 7.0).sin() * (y / 5.0).cos() + 30.0 * ((x + 2.0 * y) / 11.0).sin();
+  let v = v.clamp(0.0, 255.0) as u8;
+  Rgb([v, v.saturating_add(10), v.saturating_sub(10)])
+}
+
+fn encode_png(image: DynamicImage) -> Vec<u8> {
+  ImagePipeline::from_image(image)
+    .to_bytes(OutputFormat::Png)
+    .expect("PNG 编码失败")
+}
+
+/// 生成一组滑块验证码图片，返回 PNG 编码的 `(滑块, 背景图)`
+///
+/// 背景图尺寸为 `width × height`，在 x = `slider_x` 处有一个垂直居中的正方形缺口
+/// （2 像素亮色边框，内部变暗）；滑块是同一区域的原始纹理加上相同的边框，不含透明边距，
+/// 因此 `slideMatch` 返回的 `x1` 等于 `slider_x`。滑块边长为 `height / 3`（至少 8 像素）。
+///
+/// ```ignore
+/// let (target, background) = generate_slider_pair(320, 160, 200);
+/// let bbox = slide_match_internal(&target, &background, None, &SlideMatchConfig::default())?;
+/// assert_eq!(bbox.x1, 200);
+/// ```
+///
+/// # Panics
+/// 背景图放不下滑块（`height` 小于 8，或 `slider_x + 滑块边长` 超出 `width`）时 panic。
+pub fn generate_slider_pair(width: u32, height: u32, slider_x: u32) -> (Vec<u8>, Vec<u8>) {
+  generate_slider_pair_sized(width, height, slider_x, (height / 3).max(MIN_SLIDER_SIZE))
+}
+
+/// 同 [`generate_slider_pair`]，滑块边长为 `size`
+///
+/// # Panics
+/// 背景图放不下滑块（`size` 大于 `height`，或 `slider_x + size` 超出 `width`）时 panic。
+pub fn generate_slider_pair_sized(
+  width: u32,
+  height: u32,
+  slider_x: u32,
+  size: u32,
+) -> (Vec<u8>, Vec<u8>) {
+  assert!(
+    height >= size && slider_x as u64 + size as u64 <= width as u64,
+    "背景图 {width}x{height} 放不下 x = {slider_x} 处边长 {size} 的滑块"
+  );
+  let slider_y = (height - size) / 2;
+
+  let on_border = |i: u32, j: u32| {
+    i < BORDER_WIDTH || j < BORDER_WIDTH || i >= size - BORDER_WIDTH || j >= size - BORDER_WIDTH
+  };
+
+  let background = RgbImage::from_fn(width, height, |x, y| {
+    let inside =
+      (slider_x..slider_x + size).contains(&x) && (slider_y..slider_y + size).contains(&y);
+    if !inside {
+      return texture(x, y);
+    }
+
+    if on_border(x - slider_x, y - slider_y) {
+      Rgb([BORDER_VALUE; 3])
+    } else {
+      Rgb(texture(x, y).0.map(|v| v / 2))
+    }
+  });
+
+  let target = RgbaImage::from_fn(size, size, |i, j| {
+    if on_border(i, j) {
+      Rgba([BORDER_VALUE, BORDER_VALUE, BORDER_VALUE, u8::MAX])
+    } else {
+      let Rgb([r, g, b]) = texture(slider_x + i, slider_y + j);
+      Rgba([r, g, b, u8::MAX])
+    }
+  });
+
+  (
+    encode_png(DynamicImage::ImageRgba8(target)),
+    encode_png(DynamicImage::ImageRgb8(background)),
+  )
+}
+
+/// 执行匹配时各处理阶段的次数
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StageCounts {
+  /// 转换出参与匹配的灰度图（含反相、CLAHE 等预处理）的次数
+  pub grayscale: usize,
+  /// Canny 边缘检测的次数
+  pub canny: usize,
+}
+
+static GRAYSCALE: AtomicUsize = AtomicUsize::new(0);
+static CANNY: AtomicUsize = AtomicUsize::new(0);
+
+fn stage_counts() -> StageCounts {
+  StageCounts {
+    grayscale: GRAYSCALE.load(Ordering::Relaxed),
+    canny: CANNY.load(Ordering::Relaxed),
+  }
+}
+
+/// 执行 `f` 并返回期间各处理阶段的次数，用于断言某条路径没有重复计算
+///
+/// 计数器是进程级的，同时执行的其他匹配也会计入，调用方需要保证测试串行执行。
+pub fn count_stages<T>(f: impl FnOnce() -> T) -> (T, StageCounts) {
+  let before = stage_counts();
+  let result = f();
+  let after = stage_counts();
+  let counts = StageCounts {
+    grayscale: after.grayscale - before.grayscale,
+    canny: after.canny - before.canny,
+  };
+  (result, counts)
+}
+
+// 只在测试或启用 `test-utils` 特性时计数，正常构建中是空函数
+pub(crate) fn record_grayscale() {
+  #[cfg(any(test, feature = "test-utils"))]
+  GRAYSCALE.fetch_add(1, Ordering::Relaxed);
+}
+
+pub(crate) fn record_canny() {
+  #[cfg(any(test, feature = "test-utils"))]
+  CANNY.fetch_add(1, Ordering::Relaxed);
+}
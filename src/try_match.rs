@@ -0,0 +1,91 @@
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::error::{error_code, ErrorCode};
+use crate::{improved_slide_match_internal, slide_match_internal, SlideBBox, SlideMatchConfig};
+
+/// 不抛出异常的匹配函数（`trySlideMatch` 等）的返回值
+///
+/// `ok` 为 true 时返回 `bbox` 和 `confidence`，否则返回 `code` 和 `message`
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct TrySlideMatchResult {
+  /// 是否匹配成功
+  pub ok: bool,
+  /// 匹配结果，与对应的抛出异常的函数的返回值相同
+  pub bbox: Option<SlideBBox>,
+  /// 匹配得分，即 `bbox.confidence`
+  pub confidence: Option<f64>,
+  /// 失败原因
+  pub code: Option<ErrorCode>,
+  /// 错误信息，与对应的函数抛出的异常信息相同
+  pub message: Option<String>,
+}
+
+// 可预期的失败转换为 `ok: false` 的结果，其余错误（如配置无效）仍抛出异常
+fn to_try_result(result: anyhow::Result<SlideBBox>, prefix: &str) -> Result<TrySlideMatchResult> {
+  match result {
+    Ok(bbox) => Ok(TrySlideMatchResult {
+      ok: true,
+      confidence: bbox.confidence,
+      bbox: Some(bbox),
+      code: None,
+      message: None,
+    }),
+    Err(e) => {
+      let message = format!("{prefix}: {e}");
+      match error_code(&e) {
+        Some(code) => Ok(TrySlideMatchResult {
+          ok: false,
+          bbox: None,
+          confidence: None,
+          code: Some(code),
+          message: Some(message),
+        }),
+        None => Err(Error::from_reason(message)),
+      }
+    }
+  }
+}
+
+/// 不抛出异常的 `slideMatch`，参数与 `slideMatch` 相同
+///
+/// 图片无法解码、目标图片大于背景图片、置信度不高于 `confidenceThreshold` 等可预期的失败不抛出异常，
+/// 而是返回 `ok: false` 和错误码，适合大批量匹配时避免构造异常的开销；
+/// 选项类型错误、配置值无效等调用方的错误仍会抛出异常。
+#[napi]
+pub fn try_slide_match(
+  target_image: Buffer,
+  background_image: Buffer,
+  options: Option<SlideMatchConfig>,
+) -> Result<TrySlideMatchResult> {
+  let config = options.unwrap_or_default();
+
+  to_try_result(
+    slide_match_internal(&target_image, &background_image, None, &config),
+    "滑块匹配失败",
+  )
+}
+
+/// 不抛出异常的 `improvedSlideMatch`，参数与 `improvedSlideMatch` 相同，失败时的处理见 `trySlideMatch`
+///
+/// `onLowConfidence` 为 `error` 时，置信度过低返回 `ok: false` 和 `lowConfidence` 错误码。
+#[napi]
+pub fn try_improved_slide_match(
+  target_image: Buffer,
+  background_image: Buffer,
+  confidence_threshold: Option<f64>,
+  options: Option<SlideMatchConfig>,
+) -> Result<TrySlideMatchResult> {
+  let threshold = confidence_threshold.unwrap_or(0.3) as f32;
+  let config = options.unwrap_or_default();
+
+  if !(0.0..=1.0).contains(&threshold) {
+    return Err(Error::from_reason("置信度阈值必须在 0.0-1.0 范围内"));
+  }
+
+  to_try_result(
+    improved_slide_match_internal(&target_image, &background_image, threshold, &config),
+    "改进版滑块匹配失败",
+  )
+}
@@ -0,0 +1,145 @@
+//! 用计数分配器检查匹配过程中的内存分配
+//!
+//! 全局分配器记录当前存活字节数、峰值和累计分配字节数。计数器是进程级的，
+//! 各测试通过 `MEASURE_LOCK` 串行执行，避免并行的测试互相干扰。
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::io::Cursor;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, PoisonError};
+
+use image::{DynamicImage, ImageFormat, Rgb, RgbImage};
+use napi::bindgen_prelude::Buffer;
+use slide_match::task::ImprovedSlideMatchTask;
+use slide_match::{slide_match_internal, SlideMatchConfig};
+
+struct CountingAllocator;
+
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+static TOTAL: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+  unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+    let ptr = System.alloc(layout);
+    if !ptr.is_null() {
+      let live = LIVE.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+      PEAK.fetch_max(live, Ordering::Relaxed);
+      TOTAL.fetch_add(layout.size(), Ordering::Relaxed);
+    }
+    ptr
+  }
+
+  unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+    System.dealloc(ptr, layout);
+    LIVE.fetch_sub(layout.size(), Ordering::Relaxed);
+  }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+static MEASURE_LOCK: Mutex<()> = Mutex::new(());
+
+// 执行 `f` 期间的分配统计（字节）
+struct Allocations {
+  // 相对开始时的存活字节数峰值
+  peak: usize,
+  // 累计分配的字节数
+  total: usize,
+}
+
+fn measure<T>(f: impl FnOnce() -> T) -> (T, Allocations) {
+  let base = LIVE.load(Ordering::Relaxed);
+  PEAK.store(base, Ordering::Relaxed);
+  let total = TOTAL.load(Ordering::Relaxed);
+  let result = f();
+  let allocations = Allocations {
+    peak: PEAK.load(Ordering::Relaxed) - base,
+    total: TOTAL.load(Ordering::Relaxed) - total,
+  };
+  (result, allocations)
+}
+
+fn encode_png(image: DynamicImage) -> Vec<u8> {
+  let mut bytes = Vec::new();
+  image
+    .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+    .expect("PNG 编码失败");
+  bytes
+}
+
+// 背景图尺寸和滑块边长
+const WIDTH: u32 = 600;
+const HEIGHT: u32 = 400;
+const SLIDER_SIZE: u32 = 48;
+
+// 返回 PNG 编码的 `(滑块, RGB 背景图, 同一背景的灰度图)`；背景为 16 像素的棋盘格，滑块取自 (408, 208)
+fn fixtures() -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+  let background = RgbImage::from_fn(WIDTH, HEIGHT, |x, y| {
+    let v: u8 = if (x / 16 + y / 16) % 2 == 0 { 20 } else { 235 };
+    Rgb([v, v, v.saturating_sub((x % 7) as u8)])
+  });
+  let target =
+    image::imageops::crop_imm(&background, 408, 208, SLIDER_SIZE, SLIDER_SIZE).to_image();
+  let gray = DynamicImage::ImageRgb8(background.clone()).to_luma8();
+
+  (
+    encode_png(DynamicImage::ImageRgb8(target)),
+    encode_png(DynamicImage::ImageRgb8(background)),
+    encode_png(DynamicImage::ImageLuma8(gray)),
+  )
+}
+
+// 背景图解码后立即转为灰度图并释放彩色数据，匹配阶段（峰值所在）不再持有 RGB 缓冲区：
+// RGB 背景的峰值内存与直接传入灰度背景时基本相同，而不是多出一整张 RGB 图片
+#[test]
+fn color_background_is_dropped_before_matching() {
+  let _lock = MEASURE_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+  let (target, rgb_background, gray_background) = fixtures();
+  let config = SlideMatchConfig::default();
+
+  let (rgb, rgb_allocations) =
+    measure(|| slide_match_internal(&target, &rgb_background, None, &config).unwrap());
+  let (gray, gray_allocations) =
+    measure(|| slide_match_internal(&target, &gray_background, None, &config).unwrap());
+
+  assert_eq!((rgb.x1, rgb.y1), (gray.x1, gray.y1));
+  let rgb_buffer = (WIDTH * HEIGHT * 3) as usize;
+  let extra = rgb_allocations.peak.saturating_sub(gray_allocations.peak);
+  assert!(
+    extra < rgb_buffer / 2,
+    "RGB 背景的峰值内存比灰度背景多 {extra} 字节（RGB 缓冲区 {rgb_buffer} 字节）"
+  );
+}
+
+// 异步任务直接持有 JS 传入的 `Buffer`，不复制图片数据：背景图后面附加 64 MB 无关数据时，
+// 创建并执行任务期间的累计分配量与不附加时基本相同
+#[test]
+fn async_task_borrows_input_buffers() {
+  let _lock = MEASURE_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+  let (target, background, _) = fixtures();
+  const PADDING: usize = 64 << 20;
+  let mut padded = background.clone();
+  padded.resize(background.len() + PADDING, 0);
+
+  let run = |background: Vec<u8>| {
+    let (target, background) = (Buffer::from(target.clone()), Buffer::from(background));
+    let (result, allocations) = measure(|| {
+      ImprovedSlideMatchTask::new(target, background, None, None)
+        .unwrap()
+        .run()
+        .unwrap()
+    });
+    (result, allocations.total)
+  };
+  let (plain, plain_total) = run(background);
+  let (padded, padded_total) = run(padded);
+
+  assert_eq!((plain.x1, plain.y1), (padded.x1, padded.y1));
+  let extra = padded_total.saturating_sub(plain_total);
+  assert!(
+    extra < PADDING / 64,
+    "背景图附加 {PADDING} 字节后多分配了 {extra} 字节"
+  );
+}
@@ -0,0 +1,49 @@
+//! 通过 `slide_match_internal` 加载各格式的测试图片
+//!
+//! `synthetic/bg1.avif` 是 `bg1.png` 以 AVIF 重新编码的结果。默认构建不包含 AVIF 解码器
+//! （`image` 的 `avif-native` 特性依赖系统的 libdav1d），传入时应报
+//! [`SlideMatchError::UnsupportedFormat`]，而不是笼统的加载失败。
+
+use std::fs;
+use std::path::PathBuf;
+
+use slide_match::error::SlideMatchError;
+use slide_match::{slide_match_internal, SlideMatchConfig};
+
+fn image(name: &str) -> Vec<u8> {
+  let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+    .join("__test__/images")
+    .join(name);
+  fs::read(&path).unwrap_or_else(|e| panic!("无法读取 {}: {e}", path.display()))
+}
+
+#[test]
+fn png_background_matches() {
+  let bbox = slide_match_internal(
+    &image("cut1.png"),
+    &image("bg1.png"),
+    None,
+    &SlideMatchConfig::default(),
+  )
+  .unwrap();
+
+  assert_eq!((bbox.x1, bbox.y1), (149, 95));
+}
+
+#[test]
+fn avif_background_is_reported_as_unsupported() {
+  let error = slide_match_internal(
+    &image("cut1.png"),
+    &image("synthetic/bg1.avif"),
+    None,
+    &SlideMatchConfig::default(),
+  )
+  .unwrap_err();
+
+  assert_eq!(
+    error.downcast_ref::<SlideMatchError>(),
+    Some(&SlideMatchError::UnsupportedFormat {
+      format: "AVIF".to_string()
+    })
+  );
+}
@@ -0,0 +1,58 @@
+//! `simd` 特性的灰度转换与 image 库逐字节比较，需要启用 `simd` 特性
+//!
+//! 覆盖全部 2^24 种 RGB 颜色；尺寸不是 8 的倍数时尾部像素走标量实现，同样参与比较。
+
+use image::metadata::Cicp;
+use image::{DynamicImage, GrayImage, RgbImage, RgbaImage};
+use slide_match::fast_grayscale::{rgba_to_luma, to_luma8};
+
+// 第 i 个像素的 RGB 为 i 的低 24 位，透明度取高位
+fn all_colors(width: u32, height: u32) -> RgbaImage {
+  RgbaImage::from_fn(width, height, |x, y| {
+    let i = y * width + x;
+    let [r, g, b, a] = i.to_le_bytes();
+    image::Rgba([r, g, b, a.wrapping_mul(97)])
+  })
+}
+
+fn assert_same(actual: &GrayImage, expected: &GrayImage, label: &str) {
+  assert_eq!(actual.dimensions(), expected.dimensions(), "{label}");
+  let mismatches = actual
+    .pixels()
+    .zip(expected.pixels())
+    .filter(|(a, b)| a != b)
+    .count();
+  assert_eq!(mismatches, 0, "{label}: {mismatches} 个像素不同");
+}
+
+#[test]
+fn simd_grayscale_matches_image_crate_on_all_colors() {
+  // 4097 × 4096 > 2^24，覆盖所有 RGB 颜色
+  for (width, height) in [(4097, 4096), (13, 7), (7, 1)] {
+    let rgba = all_colors(width, height);
+    let rgb = DynamicImage::ImageRgba8(rgba.clone()).to_rgb8();
+    let label = format!("{width}x{height}");
+
+    assert_same(
+      &rgba_to_luma(&rgba),
+      &image::imageops::grayscale(&rgba),
+      &format!("{label} grayscale"),
+    );
+
+    let rgba = DynamicImage::ImageRgba8(rgba);
+    assert_same(&to_luma8(&rgba), &rgba.to_luma8(), &format!("{label} RGBA"));
+    let rgb = DynamicImage::ImageRgb8(rgb);
+    assert_same(&to_luma8(&rgb), &rgb.to_luma8(), &format!("{label} RGB"));
+  }
+}
+
+// 非 sRGB 色彩空间的图片使用不同的亮度权重，直接调用 image 库
+#[test]
+fn other_color_spaces_fall_back_to_image_crate() {
+  let mut rgb = DynamicImage::ImageRgb8(RgbImage::from_fn(64, 64, |x, y| {
+    image::Rgb([(x * 4) as u8, (y * 4) as u8, (x + y) as u8])
+  }));
+  rgb.set_color_space(Cicp::DISPLAY_P3).unwrap();
+
+  assert_same(&to_luma8(&rgb), &rgb.to_luma8(), "Display P3");
+}
@@ -0,0 +1,131 @@
+//! 积分图版归一化互相关（`ncc::match_template_normalized`）与 imageproc 的逐窗口实现对比
+//!
+//! 在所有成对的测试图片上分别比较灰度图和 Canny 边缘图（匹配时实际使用的输入）的得分图：
+//! 逐位置相差不超过 1e-4，得分最高的位置相同。
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use image::{GrayImage, Luma};
+use imageproc::definitions::Image;
+use imageproc::edges::canny;
+use imageproc::template_matching::{match_template, MatchTemplateMethod};
+use slide_match::ncc::{find_extremes, match_template_normalized};
+
+const TOLERANCE: f32 = 1e-4;
+
+fn load_gray(path: &Path) -> GrayImage {
+  image::open(path)
+    .unwrap_or_else(|e| panic!("无法读取 {}: {e}", path.display()))
+    .to_luma8()
+}
+
+// 所有成对的 `(滑块, 背景图)`：`cutN.png` / `bgN.png` 和 `synthetic/<名称>_cut*.png` / `synthetic/<名称>_bg.png`
+fn fixture_pairs() -> Vec<(PathBuf, PathBuf)> {
+  let images = Path::new(env!("CARGO_MANIFEST_DIR")).join("__test__/images");
+  let mut pairs: Vec<_> = (1..=4)
+    .map(|i| {
+      (
+        images.join(format!("cut{i}.png")),
+        images.join(format!("bg{i}.png")),
+      )
+    })
+    .collect();
+
+  let synthetic = images.join("synthetic");
+  let mut names: Vec<_> = fs::read_dir(&synthetic)
+    .unwrap()
+    .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+    .collect();
+  names.sort();
+  for name in names {
+    let Some((prefix, _)) = name.split_once("_cut") else {
+      continue;
+    };
+    let background = synthetic.join(format!("{prefix}_bg.png"));
+    if name.ends_with(".png") && background.exists() {
+      pairs.push((synthetic.join(&name), background));
+    }
+  }
+  pairs
+}
+
+// 两张得分图逐位置的最大差值
+fn max_difference(a: &Image<Luma<f32>>, b: &Image<Luma<f32>>) -> f32 {
+  assert_eq!(a.dimensions(), b.dimensions());
+  a.pixels()
+    .zip(b.pixels())
+    .map(|(a, b)| (a[0] - b[0]).abs())
+    .fold(0.0, f32::max)
+}
+
+fn assert_matches_imageproc(image: &GrayImage, template: &GrayImage, label: &str) {
+  let expected = match_template(
+    image,
+    template,
+    MatchTemplateMethod::CrossCorrelationNormalized,
+  );
+  let actual = match_template_normalized(image, template);
+
+  let difference = max_difference(&actual, &expected);
+  assert!(
+    difference <= TOLERANCE,
+    "{label}: 得分最多相差 {difference}"
+  );
+  assert_eq!(
+    find_extremes(&actual).max_value_location,
+    find_extremes(&expected).max_value_location,
+    "{label}: 得分最高的位置不同"
+  );
+}
+
+#[test]
+fn integral_image_matches_imageproc_on_fixtures() {
+  let pairs = fixture_pairs();
+  assert!(pairs.len() > 20);
+
+  for (target, background) in pairs {
+    let label = target.file_name().unwrap().to_string_lossy().into_owned();
+    let (target, background) = (load_gray(&target), load_gray(&background));
+    // `oversize` 的滑块比背景图大，匹配前会先缩放，这里不参与比较
+    if target.width() > background.width() || target.height() > background.height() {
+      continue;
+    }
+
+    assert_matches_imageproc(&background, &target, &format!("{label} 灰度图"));
+    assert_matches_imageproc(
+      &canny(&background, 100.0, 200.0),
+      &canny(&target, 100.0, 200.0),
+      &format!("{label} 边缘图"),
+    );
+  }
+}
+
+// 非零像素超过 u32::MAX / 255² ≈ 66051 个时互相关改用 u64 累加，不会溢出。这样大的模板上 imageproc 的 f32
+// 累加误差已超过 1e-4，改为与 f64 逐窗口计算的精确值比较
+#[test]
+fn large_template_does_not_overflow() {
+  // 128-255 的伪随机纹理：模板的 128000 个像素互相关之和约为 4.6e9，超出 u32
+  let image = GrayImage::from_fn(420, 340, |x, y| {
+    let hash =
+      (x.wrapping_mul(73_856_093) ^ y.wrapping_mul(19_349_663)).wrapping_mul(2_654_435_761);
+    Luma([(hash >> 24) as u8 | 0x80])
+  });
+  let template = image::imageops::crop_imm(&image, 20, 10, 400, 320).to_image();
+
+  let scores = match_template_normalized(&image, &template);
+  let template_sum: f64 = template.pixels().map(|t| t[0] as f64 * t[0] as f64).sum();
+  let exact = Image::from_fn(scores.width(), scores.height(), |x, y| {
+    let (mut correlation, mut window) = (0.0f64, 0.0f64);
+    for (dx, dy, t) in template.enumerate_pixels() {
+      let i = image.get_pixel(x + dx, y + dy)[0] as f64;
+      correlation += i * t[0] as f64;
+      window += i * i;
+    }
+    Luma([(correlation / (window * template_sum).sqrt()) as f32])
+  });
+
+  let difference = max_difference(&scores, &exact);
+  assert!(difference <= 1e-6, "得分最多相差 {difference}");
+  assert_eq!(find_extremes(&scores).max_value_location, (20, 10));
+}
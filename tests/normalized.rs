@@ -0,0 +1,64 @@
+//! `slide_match_normalized_internal` 的 `confidence` 直接取得分峰值，不依赖置信度阈值
+
+use std::fs;
+use std::io::Cursor;
+use std::path::PathBuf;
+
+use image::{ImageFormat, Rgba, RgbaImage};
+use slide_match::error::SlideMatchError;
+use slide_match::{slide_match_internal, slide_match_normalized_internal, SlideMatchConfig};
+
+fn image(name: &str) -> Vec<u8> {
+  let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+    .join("__test__/images")
+    .join(name);
+  fs::read(&path).unwrap_or_else(|e| panic!("无法读取 {}: {e}", path.display()))
+}
+
+#[test]
+fn confidence_is_the_peak_score() {
+  let (target, background) = (image("cut1.png"), image("bg1.png"));
+
+  let normalized =
+    slide_match_normalized_internal(&target, &background, &SlideMatchConfig::default()).unwrap();
+  let config = SlideMatchConfig {
+    confidence_threshold: Some(0.0),
+    ..Default::default()
+  };
+  let bbox = slide_match_internal(&target, &background, None, &config).unwrap();
+
+  assert_eq!(Some(normalized.confidence), bbox.confidence);
+}
+
+#[test]
+fn zero_peak_is_returned_without_a_threshold() {
+  // 与 bg1.png 同样大小的纯色背景没有边缘，所有位置的得分都是 0
+  let (width, height) = image::load_from_memory(&image("bg1.png"))
+    .unwrap()
+    .to_rgba8()
+    .dimensions();
+  let mut background = Vec::new();
+  RgbaImage::from_pixel(width, height, Rgba([90, 120, 150, 255]))
+    .write_to(&mut Cursor::new(&mut background), ImageFormat::Png)
+    .unwrap();
+  let config = SlideMatchConfig {
+    edge_density_threshold: Some(0.0),
+    ..Default::default()
+  };
+
+  let normalized =
+    slide_match_normalized_internal(&image("cut1.png"), &background, &config).unwrap();
+  assert_eq!(normalized.confidence, 0.0);
+
+  // 设置了阈值时仍要求得分高于阈值
+  let config = SlideMatchConfig {
+    confidence_threshold: Some(0.0),
+    ..config
+  };
+  let error =
+    slide_match_normalized_internal(&image("cut1.png"), &background, &config).unwrap_err();
+  assert!(matches!(
+    error.downcast_ref::<SlideMatchError>(),
+    Some(SlideMatchError::LowConfidence { .. })
+  ));
+}
@@ -0,0 +1,40 @@
+//! 互相关逐行累加的 SIMD 实现与标量实现逐位比较，需要启用 `simd` 特性
+
+use slide_match::ncc::row_kernels::{add_scaled_row, add_scaled_row_scalar, simd_available};
+
+// 线性同余生成的伪随机字节序列
+fn bytes(len: usize, seed: u32) -> Vec<u8> {
+  let mut state = seed;
+  (0..len)
+    .map(|_| {
+      state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+      (state >> 24) as u8
+    })
+    .collect()
+}
+
+#[test]
+fn simd_row_accumulation_matches_scalar() {
+  if cfg!(target_arch = "aarch64") {
+    assert!(simd_available());
+  }
+
+  // 覆盖 0、不足一组、整组和整组加尾部的长度，以及 600×300 背景图的整行
+  let lengths = (0..=70).chain([255, 584, 600]);
+  for (i, len) in lengths.enumerate() {
+    let pixels = bytes(len, i as u32);
+    // 已有的累加值不超过 u32::MAX - 255²，再加一次不会溢出
+    let initial: Vec<u32> = bytes(len * 2, !(i as u32))
+      .chunks_exact(2)
+      .map(|pair| u16::from_le_bytes([pair[0], pair[1]]) as u32 * 60_000)
+      .collect();
+
+    for t in [0, 1, 2, 127, 128, 254, 255] {
+      let mut scalar = initial.clone();
+      let mut simd = initial.clone();
+      add_scaled_row_scalar(&mut scalar, &pixels, t);
+      add_scaled_row(&mut simd, &pixels, t);
+      assert_eq!(simd, scalar, "长度 {len}，t = {t}");
+    }
+  }
+}
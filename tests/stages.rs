@@ -0,0 +1,43 @@
+//! 用 `test_utils::count_stages` 检查各处理阶段的执行次数，需要启用 `test-utils` 特性
+//!
+//! 计数器是进程级的，本文件只有一个测试，不会与其他匹配并行执行。
+
+use std::fs;
+use std::path::Path;
+
+use slide_match::test_utils::{count_stages, StageCounts};
+use slide_match::{improved_slide_match_internal, SlideMatchConfig};
+
+// 改进版算法的灰度图只在首次尝试前转换一次；回退边缘图延迟到置信度不达标时才计算，
+// 回退只重新执行两次 Canny 和模板匹配
+#[test]
+fn improved_fallback_only_repeats_edge_detection() {
+  let images = Path::new(env!("CARGO_MANIFEST_DIR")).join("__test__/images");
+  let config = SlideMatchConfig::default();
+
+  for i in 1..=4 {
+    let target = fs::read(images.join(format!("cut{i}.png"))).unwrap();
+    let background = fs::read(images.join(format!("bg{i}.png"))).unwrap();
+    let run = |threshold| {
+      let (bbox, counts) = count_stages(|| {
+        improved_slide_match_internal(&target, &background, threshold, &config).unwrap()
+      });
+      (bbox.metadata.unwrap(), counts)
+    };
+
+    // 置信度阈值为 0 时只有首次尝试得分为 0 才回退，为 1.0 时总是回退
+    for threshold in [0.0, 1.0] {
+      let (metadata, counts) = run(threshold);
+      let expect_fallback = metadata.primary_confidence <= threshold as f64;
+      assert_eq!(metadata.used_fallback, expect_fallback);
+      assert_eq!(
+        counts,
+        StageCounts {
+          grayscale: 2,
+          canny: if expect_fallback { 4 } else { 2 },
+        },
+        "bg{i}.png，置信度阈值 {threshold}"
+      );
+    }
+  }
+}